@@ -0,0 +1,281 @@
+use std::env;
+use std::str::FromStr;
+use std::time::Duration;
+
+use async_graphql::http::ALL_WEBSOCKET_PROTOCOLS;
+use async_graphql::{Data, ServerError};
+use async_graphql_axum::{GraphQLProtocol, GraphQLRequest, GraphQLResponse, GraphQLWebSocket};
+use axum::{
+    Extension, Router,
+    extract::{FromRef, State, WebSocketUpgrade},
+    http::StatusCode,
+    middleware,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+};
+use sqlx::PgPool;
+use thiserror::Error;
+use tower_http::limit::RequestBodyLimitLayer;
+use tracing::warn;
+
+use crate::AppSchema;
+use crate::auth::{AuthContext, JwtAuth, authenticate, authenticate_connection};
+use crate::health::{health, livez, readyz};
+use crate::request_id::{RequestId, request_id};
+use crate::telemetry::metrics;
+
+/// Application state shared across handlers
+#[derive(Clone)]
+pub struct AppState {
+    pub pool: PgPool,
+    pub schema: AppSchema,
+    pub auth: JwtAuth,
+    /// Set the HTTP status from the response's error codes instead of
+    /// always answering 200
+    pub http_status_codes: bool,
+    /// Largest GraphQL request body accepted, in bytes
+    pub max_request_bytes: usize,
+    /// Longest a GraphQL request may run before it fails with `TIMEOUT`
+    pub graphql_timeout: Duration,
+}
+
+/// Default largest GraphQL request body, overridden by `MAX_REQUEST_BYTES`
+pub const DEFAULT_MAX_REQUEST_BYTES: usize = 256 * 1024;
+
+/// Default GraphQL request timeout, overridden by `GRAPHQL_TIMEOUT_MS`
+pub const DEFAULT_GRAPHQL_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Error, Debug, PartialEq)]
+pub enum HttpConfigError {
+    #[error("{name} must be true, false, 1 or 0, got {value:?}")]
+    InvalidFlag { name: &'static str, value: String },
+
+    #[error("{name} must be a positive integer, got {value:?}")]
+    InvalidNumber { name: &'static str, value: String },
+}
+
+/// How the GraphQL endpoint answers, sizes and times requests
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpConfig {
+    /// Set the HTTP status from the response's error codes instead of
+    /// always answering 200
+    pub http_status_codes: bool,
+    /// Largest GraphQL request body accepted, in bytes
+    pub max_request_bytes: usize,
+    /// Longest a GraphQL request may run before it fails with `TIMEOUT`
+    pub graphql_timeout: Duration,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            http_status_codes: false,
+            max_request_bytes: DEFAULT_MAX_REQUEST_BYTES,
+            graphql_timeout: DEFAULT_GRAPHQL_TIMEOUT,
+        }
+    }
+}
+
+impl HttpConfig {
+    /// Read the config from `GRAPHQL_HTTP_STATUS_CODES`, `MAX_REQUEST_BYTES`
+    /// and `GRAPHQL_TIMEOUT_MS`
+    pub fn from_env() -> Result<Self, HttpConfigError> {
+        Self::from_vars(|name| env::var(name).ok())
+    }
+
+    /// Build the config from a variable lookup, using defaults for unset ones
+    pub fn from_vars(var: impl Fn(&str) -> Option<String>) -> Result<Self, HttpConfigError> {
+        let defaults = Self::default();
+
+        let http_status_codes = match var("GRAPHQL_HTTP_STATUS_CODES") {
+            Some(value) => match value.trim().to_ascii_lowercase().as_str() {
+                "true" | "1" => true,
+                "false" | "0" => false,
+                _ => {
+                    return Err(HttpConfigError::InvalidFlag {
+                        name: "GRAPHQL_HTTP_STATUS_CODES",
+                        value,
+                    });
+                }
+            },
+            None => defaults.http_status_codes,
+        };
+        let max_request_bytes =
+            parse_positive(&var, "MAX_REQUEST_BYTES")?.unwrap_or(defaults.max_request_bytes);
+        let graphql_timeout = parse_positive(&var, "GRAPHQL_TIMEOUT_MS")?
+            .map_or(defaults.graphql_timeout, Duration::from_millis);
+
+        Ok(Self {
+            http_status_codes,
+            max_request_bytes,
+            graphql_timeout,
+        })
+    }
+}
+
+fn parse_positive<T: FromStr + Default + PartialOrd>(
+    var: &impl Fn(&str) -> Option<String>,
+    name: &'static str,
+) -> Result<Option<T>, HttpConfigError> {
+    let Some(value) = var(name) else {
+        return Ok(None);
+    };
+
+    match value.trim().parse() {
+        Ok(number) if number > T::default() => Ok(Some(number)),
+        _ => Err(HttpConfigError::InvalidNumber { name, value }),
+    }
+}
+
+impl FromRef<AppState> for PgPool {
+    fn from_ref(state: &AppState) -> Self {
+        state.pool.clone()
+    }
+}
+
+impl FromRef<AppState> for JwtAuth {
+    fn from_ref(state: &AppState) -> Self {
+        state.auth.clone()
+    }
+}
+
+/// Build the HTTP router serving GraphQL, the playground, health checks and metrics
+///
+/// GraphQL requests and subscriptions get a correlation ID before
+/// authentication, so even rejected ones carry it back. Bodies over
+/// `max_request_bytes` are rejected with 413 before they are read.
+pub fn router(state: AppState) -> Router {
+    let graphql = Router::new()
+        .route("/graphql", post(graphql_handler))
+        .route_layer(RequestBodyLimitLayer::new(state.max_request_bytes))
+        .route("/ws", get(graphql_ws_handler))
+        .route_layer(middleware::from_fn_with_state(state.clone(), authenticate))
+        .route_layer(middleware::from_fn(request_id));
+
+    Router::new()
+        .merge(graphql)
+        .route("/playground", get(graphql_playground))
+        .route("/health", get(health))
+        .route("/livez", get(livez))
+        .route("/readyz", get(readyz))
+        .route("/metrics", get(metrics))
+        .with_state(state)
+}
+
+/// GraphQL handler
+///
+/// Passes the caller's `AuthContext`, if any, on to resolvers, and tags every
+/// error with the request's correlation ID as `requestId`. Requests running
+/// longer than `graphql_timeout` are abandoned with a `TIMEOUT` error.
+async fn graphql_handler(
+    State(state): State<AppState>,
+    auth: Option<Extension<AuthContext>>,
+    request_id: Option<Extension<RequestId>>,
+    req: GraphQLRequest,
+) -> Response {
+    let mut req = req.into_inner();
+    if let Some(Extension(auth)) = auth {
+        req = req.data(auth);
+    }
+
+    let mut response = tokio::time::timeout(state.graphql_timeout, state.schema.execute(req))
+        .await
+        .unwrap_or_else(|_| timed_out(state.graphql_timeout));
+    if let Some(Extension(RequestId(id))) = request_id {
+        for error in &mut response.errors {
+            error
+                .extensions
+                .get_or_insert_with(Default::default)
+                .set("requestId", id.as_str());
+        }
+    }
+
+    if state.http_status_codes {
+        StatusCodedResponse(response).into_response()
+    } else {
+        GraphQLResponse::from(response).into_response()
+    }
+}
+
+/// GraphQL subscription handler
+///
+/// Subscriptions run as the caller authenticated on the upgrade request.
+/// Browsers can't set headers on a WebSocket, so a bearer token may instead
+/// be sent as `Authorization` in the `connection_init` payload.
+async fn graphql_ws_handler(
+    State(state): State<AppState>,
+    auth: Option<Extension<AuthContext>>,
+    protocol: GraphQLProtocol,
+    websocket: WebSocketUpgrade,
+) -> Response {
+    let mut data = Data::default();
+    if let Some(Extension(auth)) = auth {
+        data.insert(auth);
+    }
+
+    websocket
+        .protocols(ALL_WEBSOCKET_PROTOCOLS)
+        .on_upgrade(move |stream| {
+            GraphQLWebSocket::new(stream, state.schema, protocol)
+                .with_data(data)
+                .on_connection_init(move |payload| authenticate_connection(state.auth, payload))
+                .serve()
+        })
+}
+
+/// The response for a request cut off after `timeout`
+fn timed_out(timeout: Duration) -> async_graphql::Response {
+    warn!(
+        timeout_ms = timeout.as_millis() as u64,
+        "GraphQL request timed out"
+    );
+
+    let mut error = ServerError::new(
+        format!("Request timed out after {} ms", timeout.as_millis()),
+        None,
+    );
+    error
+        .extensions
+        .get_or_insert_with(Default::default)
+        .set("code", "TIMEOUT");
+    async_graphql::Response::from_errors(vec![error])
+}
+
+/// A GraphQL response whose HTTP status reflects the errors it carries
+///
+/// Any `INTERNAL` error makes it a 500; otherwise any `VALIDATION` or
+/// `NOT_FOUND` error makes it a 400. Everything else stays 200.
+struct StatusCodedResponse(async_graphql::Response);
+
+impl IntoResponse for StatusCodedResponse {
+    fn into_response(self) -> Response {
+        let codes: Vec<&str> = self
+            .0
+            .errors
+            .iter()
+            .filter_map(|e| match e.extensions.as_ref()?.get("code")? {
+                async_graphql::Value::String(code) => Some(code.as_str()),
+                _ => None,
+            })
+            .collect();
+
+        let status = if codes.contains(&"INTERNAL") {
+            StatusCode::INTERNAL_SERVER_ERROR
+        } else if codes.contains(&"VALIDATION") || codes.contains(&"NOT_FOUND") {
+            StatusCode::BAD_REQUEST
+        } else {
+            StatusCode::OK
+        };
+
+        let mut response = GraphQLResponse::from(self.0).into_response();
+        *response.status_mut() = status;
+        response
+    }
+}
+
+/// GraphQL Playground handler
+async fn graphql_playground() -> impl axum::response::IntoResponse {
+    axum::response::Html(async_graphql::http::playground_source(
+        async_graphql::http::GraphQLPlaygroundConfig::new("/graphql").subscription_endpoint("/ws"),
+    ))
+}
@@ -0,0 +1,197 @@
+use std::collections::HashSet;
+use std::env;
+use std::sync::Arc;
+
+use async_graphql::Data;
+use axum::{
+    Json,
+    extract::{Request, State},
+    http::{StatusCode, header::AUTHORIZATION},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation, decode, encode};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use thiserror::Error;
+use time::{Duration, OffsetDateTime};
+use uuid::Uuid;
+
+/// The authenticated caller, available in the GraphQL context when a valid
+/// token was sent
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AuthContext {
+    pub user_id: Uuid,
+    /// Whether the caller may use admin-only fields
+    pub is_admin: bool,
+}
+
+#[derive(Error, Debug)]
+pub enum AuthError {
+    #[error("Invalid authorization header")]
+    InvalidHeader,
+
+    #[error("Invalid token: {0}")]
+    InvalidToken(#[from] jsonwebtoken::errors::Error),
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum AdminConfigError {
+    #[error("ADMIN_USER_IDS contains an invalid user ID: {0:?}")]
+    InvalidUserId(String),
+}
+
+/// Users allowed to call admin-only fields
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AdminAllowlist {
+    user_ids: HashSet<Uuid>,
+}
+
+impl AdminAllowlist {
+    pub fn new(user_ids: impl IntoIterator<Item = Uuid>) -> Self {
+        Self {
+            user_ids: user_ids.into_iter().collect(),
+        }
+    }
+
+    /// Read the allowlist from `ADMIN_USER_IDS` (comma-separated)
+    pub fn from_env() -> Result<Self, AdminConfigError> {
+        Self::from_vars(|name| env::var(name).ok())
+    }
+
+    /// Build the allowlist from a variable lookup; unset means no admins
+    pub fn from_vars(var: impl Fn(&str) -> Option<String>) -> Result<Self, AdminConfigError> {
+        let Some(value) = var("ADMIN_USER_IDS") else {
+            return Ok(Self::default());
+        };
+
+        let user_ids = value
+            .split(',')
+            .map(str::trim)
+            .filter(|id| !id.is_empty())
+            .map(|id| {
+                id.parse()
+                    .map_err(|_| AdminConfigError::InvalidUserId(id.to_string()))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { user_ids })
+    }
+
+    pub fn contains(&self, user_id: Uuid) -> bool {
+        self.user_ids.contains(&user_id)
+    }
+}
+
+/// Claims carried in our tokens
+#[derive(Debug, Serialize, Deserialize)]
+struct Claims {
+    /// The user the token was issued to
+    sub: Uuid,
+    /// Expiry, in seconds since the Unix epoch
+    exp: i64,
+}
+
+/// Issues and validates HS256 tokens signed with a shared secret
+#[derive(Clone)]
+pub struct JwtAuth {
+    encoding: EncodingKey,
+    decoding: DecodingKey,
+    admins: Arc<AdminAllowlist>,
+}
+
+impl JwtAuth {
+    pub fn new(secret: &[u8]) -> Self {
+        Self {
+            encoding: EncodingKey::from_secret(secret),
+            decoding: DecodingKey::from_secret(secret),
+            admins: Arc::default(),
+        }
+    }
+
+    /// Mark callers on `admins` as admins when their tokens are verified
+    pub fn with_admins(mut self, admins: AdminAllowlist) -> Self {
+        self.admins = Arc::new(admins);
+        self
+    }
+
+    /// Issue a token for a user that expires after `ttl`
+    pub fn issue(&self, user_id: Uuid, ttl: Duration) -> Result<String, AuthError> {
+        let claims = Claims {
+            sub: user_id,
+            exp: (OffsetDateTime::now_utc() + ttl).unix_timestamp(),
+        };
+
+        Ok(encode(&Header::default(), &claims, &self.encoding)?)
+    }
+
+    /// Validate a token's signature and expiry
+    pub fn verify(&self, token: &str) -> Result<AuthContext, AuthError> {
+        let data = decode::<Claims>(token, &self.decoding, &Validation::default())?;
+
+        Ok(AuthContext {
+            user_id: data.claims.sub,
+            is_admin: self.admins.contains(data.claims.sub),
+        })
+    }
+}
+
+/// Middleware that authenticates `Authorization: Bearer <token>` requests
+///
+/// Requests without the header pass through anonymously. A valid token adds
+/// an `AuthContext` to the request extensions for the GraphQL handler; a
+/// malformed or invalid one is rejected with 401 before reaching it.
+pub async fn authenticate(State(auth): State<JwtAuth>, mut req: Request, next: Next) -> Response {
+    let Some(header) = req.headers().get(AUTHORIZATION) else {
+        return next.run(req).await;
+    };
+
+    let result = header
+        .to_str()
+        .ok()
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .ok_or(AuthError::InvalidHeader)
+        .and_then(|token| auth.verify(token));
+
+    match result {
+        Ok(context) => {
+            req.extensions_mut().insert(context);
+            next.run(req).await
+        }
+        Err(e) => unauthenticated(e),
+    }
+}
+
+/// Authenticate a subscription from its `connection_init` payload
+///
+/// A payload without `Authorization` leaves the connection as it was; one
+/// with a malformed or invalid bearer token refuses the connection.
+pub async fn authenticate_connection(auth: JwtAuth, payload: Value) -> async_graphql::Result<Data> {
+    let mut data = Data::default();
+    let Some(header) = payload.get("Authorization") else {
+        return Ok(data);
+    };
+
+    let context = header
+        .as_str()
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .ok_or(AuthError::InvalidHeader)
+        .and_then(|token| auth.verify(token))?;
+    data.insert(context);
+    Ok(data)
+}
+
+/// A 401 response shaped like a GraphQL error so clients handle it uniformly
+fn unauthenticated(err: AuthError) -> Response {
+    tracing::debug!("Rejected request: {}", err);
+
+    let body = json!({
+        "data": null,
+        "errors": [{
+            "message": err.to_string(),
+            "extensions": { "code": "UNAUTHENTICATED" },
+        }],
+    });
+
+    (StatusCode::UNAUTHORIZED, Json(body)).into_response()
+}
@@ -0,0 +1,75 @@
+use std::env;
+
+use axum::http::{HeaderValue, Method, header};
+use thiserror::Error;
+use tower_http::cors::{Any, CorsLayer};
+
+#[derive(Error, Debug, PartialEq)]
+pub enum CorsConfigError {
+    #[error("CORS_ALLOWED_ORIGINS contains an invalid origin: {0:?}")]
+    InvalidOrigin(String),
+}
+
+/// Origins other than its own that browsers may call the API from
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum AllowedOrigins {
+    /// No cross-origin access; browsers only allow same-origin requests
+    #[default]
+    SameOrigin,
+    /// Any origin
+    Any,
+    /// Exactly these origins, e.g. `https://app.example.com`
+    List(Vec<HeaderValue>),
+}
+
+impl AllowedOrigins {
+    /// Read the origins from `CORS_ALLOWED_ORIGINS` (comma-separated, or `*`)
+    pub fn from_env() -> Result<Self, CorsConfigError> {
+        Self::from_vars(|name| env::var(name).ok())
+    }
+
+    /// Read the origins from a variable lookup; unset means same-origin only
+    pub fn from_vars(var: impl Fn(&str) -> Option<String>) -> Result<Self, CorsConfigError> {
+        let Some(value) = var("CORS_ALLOWED_ORIGINS") else {
+            return Ok(Self::SameOrigin);
+        };
+        if value.trim() == "*" {
+            return Ok(Self::Any);
+        }
+
+        let origins: Vec<HeaderValue> = value
+            .split(',')
+            .map(str::trim)
+            .filter(|origin| !origin.is_empty())
+            .map(|origin| {
+                let valid = origin.starts_with("http://") || origin.starts_with("https://");
+                HeaderValue::from_str(origin)
+                    .ok()
+                    .filter(|_| valid)
+                    .ok_or_else(|| CorsConfigError::InvalidOrigin(origin.to_string()))
+            })
+            .collect::<Result<_, _>>()?;
+
+        if origins.is_empty() {
+            Ok(Self::SameOrigin)
+        } else {
+            Ok(Self::List(origins))
+        }
+    }
+
+    /// A layer answering preflights and tagging responses for these origins
+    ///
+    /// Allowed origins may `GET` and `POST` with `Content-Type` and
+    /// `Authorization` headers; other origins get no CORS headers at all.
+    pub fn layer(&self) -> CorsLayer {
+        let layer = CorsLayer::new()
+            .allow_methods([Method::GET, Method::POST])
+            .allow_headers([header::CONTENT_TYPE, header::AUTHORIZATION]);
+
+        match self {
+            Self::SameOrigin => layer,
+            Self::Any => layer.allow_origin(Any),
+            Self::List(origins) => layer.allow_origin(origins.clone()),
+        }
+    }
+}
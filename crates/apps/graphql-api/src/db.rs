@@ -0,0 +1,213 @@
+use std::env;
+use std::str::FromStr;
+use std::time::Duration;
+
+use sqlx::postgres::{PgConnectOptions, PgConnection, PgPoolOptions};
+use sqlx::{Connection, Executor, PgPool};
+use thiserror::Error;
+use tracing::warn;
+
+/// Longest wait between connection attempts, however many have failed
+pub const MAX_CONNECT_DELAY: Duration = Duration::from_secs(30);
+
+/// Pool that read-only work such as GraphQL queries runs on
+///
+/// Points at a replica when one is configured, otherwise at the same
+/// database as the primary `PgPool`.
+#[derive(Clone)]
+pub struct ReadPool(pub PgPool);
+
+#[derive(Error, Debug, PartialEq)]
+pub enum DbConfigError {
+    #[error("{name} must be a non-negative integer, got {value:?}")]
+    InvalidNumber { name: &'static str, value: String },
+
+    #[error("{name} must be positive, got {value:?}")]
+    NotPositive { name: &'static str, value: String },
+
+    #[error("DB_MIN_CONNECTIONS ({min}) must be at most DB_MAX_CONNECTIONS ({max})")]
+    ConnectionRange { min: u32, max: u32 },
+}
+
+/// How the server connects to Postgres
+#[derive(Debug, Clone, PartialEq)]
+pub struct DbConfig {
+    /// Longest a single statement may run before Postgres cancels it; zero disables the limit
+    pub statement_timeout: Duration,
+    /// Extra attempts made when the first connection at startup fails
+    pub connect_retries: u32,
+    /// Wait before the first retry; it doubles after each failed attempt
+    pub connect_delay: Duration,
+    /// Most connections the pool opens
+    pub max_connections: u32,
+    /// Connections the pool keeps open even when idle
+    pub min_connections: u32,
+    /// Longest a caller waits for a free connection before giving up
+    pub acquire_timeout: Duration,
+}
+
+impl Default for DbConfig {
+    fn default() -> Self {
+        Self {
+            statement_timeout: Duration::from_secs(5),
+            connect_retries: 5,
+            connect_delay: Duration::from_millis(500),
+            max_connections: 20,
+            min_connections: 0,
+            acquire_timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+impl DbConfig {
+    /// Read the config from `DB_STATEMENT_TIMEOUT_MS`, `DB_CONNECT_RETRIES`,
+    /// `DB_CONNECT_DELAY_MS`, `DB_MAX_CONNECTIONS`, `DB_MIN_CONNECTIONS` and
+    /// `DB_ACQUIRE_TIMEOUT_MS`
+    pub fn from_env() -> Result<Self, DbConfigError> {
+        Self::from_vars(|name| env::var(name).ok())
+    }
+
+    /// Build the config from a variable lookup, using defaults for unset ones
+    pub fn from_vars(var: impl Fn(&str) -> Option<String>) -> Result<Self, DbConfigError> {
+        let defaults = Self::default();
+
+        let statement_timeout = parse_number(&var, "DB_STATEMENT_TIMEOUT_MS")?
+            .map_or(defaults.statement_timeout, Duration::from_millis);
+        let connect_retries =
+            parse_number(&var, "DB_CONNECT_RETRIES")?.unwrap_or(defaults.connect_retries);
+        let connect_delay = parse_number(&var, "DB_CONNECT_DELAY_MS")?
+            .map_or(defaults.connect_delay, Duration::from_millis);
+
+        let max_connections =
+            parse_positive(&var, "DB_MAX_CONNECTIONS")?.unwrap_or(defaults.max_connections);
+        let min_connections =
+            parse_number(&var, "DB_MIN_CONNECTIONS")?.unwrap_or(defaults.min_connections);
+        if min_connections > max_connections {
+            return Err(DbConfigError::ConnectionRange {
+                min: min_connections,
+                max: max_connections,
+            });
+        }
+        let acquire_timeout = parse_positive(&var, "DB_ACQUIRE_TIMEOUT_MS")?
+            .map_or(defaults.acquire_timeout, Duration::from_millis);
+
+        Ok(Self {
+            statement_timeout,
+            connect_retries,
+            connect_delay,
+            max_connections,
+            min_connections,
+            acquire_timeout,
+        })
+    }
+
+    /// Pool options applying this config's sizing and timeouts
+    ///
+    /// Each connection runs `SET statement_timeout`, so a runaway query is
+    /// cancelled by Postgres instead of holding its connection indefinitely.
+    pub fn pool_options(&self) -> PgPoolOptions {
+        let statement_timeout = format!(
+            "SET statement_timeout = {}",
+            self.statement_timeout.as_millis()
+        );
+
+        PgPoolOptions::new()
+            .max_connections(self.max_connections)
+            .min_connections(self.min_connections)
+            .acquire_timeout(self.acquire_timeout)
+            .after_connect(move |conn, _meta| {
+                let statement_timeout = statement_timeout.clone();
+                Box::pin(async move {
+                    conn.execute(statement_timeout.as_str()).await?;
+                    Ok(())
+                })
+            })
+    }
+
+    /// How long to wait after the given failed attempt (0 for the first)
+    ///
+    /// Doubles each time, capped at `MAX_CONNECT_DELAY`.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        2u32.checked_pow(attempt)
+            .and_then(|factor| self.connect_delay.checked_mul(factor))
+            .map_or(MAX_CONNECT_DELAY, |delay| delay.min(MAX_CONNECT_DELAY))
+    }
+}
+
+/// Build a pool from `config`, connecting with `connect`
+pub async fn build_pool(
+    config: &DbConfig,
+    connect: PgConnectOptions,
+) -> Result<PgPool, sqlx::Error> {
+    config.pool_options().connect_with(connect).await
+}
+
+/// Connect a pool to `url`, retrying while Postgres is not reachable yet
+///
+/// Useful when the app starts alongside its database. Gives up after
+/// `connect_retries` retries, or straight away on errors waiting won't fix,
+/// such as bad credentials.
+pub async fn connect_with_retry(url: &str, config: &DbConfig) -> Result<PgPool, sqlx::Error> {
+    let mut attempt = 0;
+
+    // Probe with a single connection: the pool itself keeps retrying until
+    // its acquire timeout, which would hide the error and slow every attempt
+    loop {
+        match PgConnection::connect(url).await {
+            Ok(conn) => {
+                conn.close().await?;
+                return build_pool(config, url.parse()?).await;
+            }
+            Err(e) if attempt < config.connect_retries && is_transient(&e) => {
+                let delay = config.backoff(attempt);
+                attempt += 1;
+                warn!(
+                    error = %e,
+                    attempt,
+                    retries = config.connect_retries,
+                    delay_ms = delay.as_millis() as u64,
+                    "Database not reachable, retrying"
+                );
+                tokio::time::sleep(delay).await;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Whether a connection error may clear up by itself
+fn is_transient(error: &sqlx::Error) -> bool {
+    match error {
+        sqlx::Error::Io(_) | sqlx::Error::PoolTimedOut => true,
+        // 57P03 is cannot_connect_now, sent while the server is starting up
+        sqlx::Error::Database(db) => db.code().as_deref() == Some("57P03"),
+        _ => false,
+    }
+}
+
+fn parse_positive<T: FromStr + Default + PartialOrd>(
+    var: &impl Fn(&str) -> Option<String>,
+    name: &'static str,
+) -> Result<Option<T>, DbConfigError> {
+    match parse_number(var, name)? {
+        Some(number) if number <= T::default() => Err(DbConfigError::NotPositive {
+            name,
+            value: var(name).unwrap_or_default(),
+        }),
+        number => Ok(number),
+    }
+}
+
+fn parse_number<T: FromStr>(
+    var: &impl Fn(&str) -> Option<String>,
+    name: &'static str,
+) -> Result<Option<T>, DbConfigError> {
+    let Some(value) = var(name) else {
+        return Ok(None);
+    };
+
+    match value.trim().parse() {
+        Ok(number) => Ok(Some(number)),
+        Err(_) => Err(DbConfigError::InvalidNumber { name, value }),
+    }
+}
@@ -0,0 +1,103 @@
+use std::time::Duration;
+
+use axum::{Json, extract::State, http::StatusCode};
+use serde::Serialize;
+use sqlx::PgPool;
+
+use crate::MIGRATOR;
+
+/// How long the health check waits for the database before giving up
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Body returned by the health check
+#[derive(Debug, Serialize)]
+pub struct HealthStatus {
+    pub status: &'static str,
+}
+
+/// Liveness handler
+///
+/// Always returns 200 with `{"status":"ok"}`; answering at all shows the
+/// process is up. It deliberately ignores the database so a database outage
+/// doesn't get the process restarted.
+pub async fn livez() -> (StatusCode, Json<HealthStatus>) {
+    (StatusCode::OK, Json(HealthStatus { status: "ok" }))
+}
+
+/// Readiness handler
+///
+/// Returns 200 with `{"status":"ready"}` once the database answers and every
+/// migration this build knows about has been applied, and 503 with
+/// `{"status":"not_ready"}` otherwise.
+pub async fn readyz(State(pool): State<PgPool>) -> (StatusCode, Json<HealthStatus>) {
+    if database_reachable(&pool).await && migrations_applied(&pool).await {
+        (StatusCode::OK, Json(HealthStatus { status: "ready" }))
+    } else {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(HealthStatus {
+                status: "not_ready",
+            }),
+        )
+    }
+}
+
+/// Health check handler
+///
+/// Runs `SELECT 1` against the pool and returns 200 with `{"status":"ok"}`
+/// when it succeeds. If the query fails or takes longer than
+/// `HEALTH_CHECK_TIMEOUT` (including waiting for a connection), it returns
+/// 503 with `{"status":"unhealthy"}` so load balancers stop routing here.
+pub async fn health(State(pool): State<PgPool>) -> (StatusCode, Json<HealthStatus>) {
+    if database_reachable(&pool).await {
+        (StatusCode::OK, Json(HealthStatus { status: "ok" }))
+    } else {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(HealthStatus {
+                status: "unhealthy",
+            }),
+        )
+    }
+}
+
+/// Check the database answers `SELECT 1` within `HEALTH_CHECK_TIMEOUT`
+pub async fn database_reachable(pool: &PgPool) -> bool {
+    let check = sqlx::query("SELECT 1").execute(pool);
+
+    match tokio::time::timeout(HEALTH_CHECK_TIMEOUT, check).await {
+        Ok(Ok(_)) => true,
+        Ok(Err(e)) => {
+            tracing::warn!("Database check failed: {}", e);
+            false
+        }
+        Err(_) => {
+            tracing::warn!("Database check timed out");
+            false
+        }
+    }
+}
+
+/// Check the database has applied the latest migration in `MIGRATOR`
+async fn migrations_applied(pool: &PgPool) -> bool {
+    let Some(expected) = MIGRATOR.iter().map(|m| m.version).max() else {
+        return true;
+    };
+
+    let applied = sqlx::query_scalar::<_, Option<i64>>(
+        "SELECT MAX(version) FROM _sqlx_migrations WHERE success",
+    )
+    .fetch_one(pool);
+
+    match tokio::time::timeout(HEALTH_CHECK_TIMEOUT, applied).await {
+        Ok(Ok(latest)) => latest == Some(expected),
+        Ok(Err(e)) => {
+            tracing::warn!("Migration check failed: {}", e);
+            false
+        }
+        Err(_) => {
+            tracing::warn!("Migration check timed out");
+            false
+        }
+    }
+}
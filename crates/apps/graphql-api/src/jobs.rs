@@ -0,0 +1,160 @@
+use std::env;
+use std::time::Duration;
+
+use sqlx::PgPool;
+use sqlxmq::{JobRegistry, JobRunnerHandle};
+use thiserror::Error;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+use user_feature::{Mailer, UserJobs};
+
+#[derive(Error, Debug, PartialEq)]
+pub enum JobConfigError {
+    #[error("{name} must be a positive integer, got {value:?}")]
+    InvalidNumber { name: &'static str, value: String },
+
+    #[error("JOB_CHANNELS must name at least one channel")]
+    NoChannels,
+
+    #[error("JOB_CONCURRENCY_MAX ({max}) must be at least JOB_CONCURRENCY_MIN ({min})")]
+    ConcurrencyRange { min: usize, max: usize },
+}
+
+/// Which channels the job runner listens on and how many jobs it runs at once
+#[derive(Debug, Clone, PartialEq)]
+pub struct JobRunnerConfig {
+    pub channels: Vec<String>,
+    pub min_concurrency: usize,
+    pub max_concurrency: usize,
+}
+
+impl Default for JobRunnerConfig {
+    fn default() -> Self {
+        Self {
+            channels: vec![
+                "emails".to_string(),
+                "reminders".to_string(),
+                "events".to_string(),
+                "notifications".to_string(),
+            ],
+            min_concurrency: 2,
+            max_concurrency: 10,
+        }
+    }
+}
+
+impl JobRunnerConfig {
+    /// Read the config from `JOB_CHANNELS` (comma-separated),
+    /// `JOB_CONCURRENCY_MIN` and `JOB_CONCURRENCY_MAX`
+    pub fn from_env() -> Result<Self, JobConfigError> {
+        Self::from_vars(|name| env::var(name).ok())
+    }
+
+    /// Build the config from a variable lookup, using defaults for unset ones
+    pub fn from_vars(var: impl Fn(&str) -> Option<String>) -> Result<Self, JobConfigError> {
+        let defaults = Self::default();
+
+        let channels = match var("JOB_CHANNELS") {
+            Some(value) => value
+                .split(',')
+                .map(str::trim)
+                .filter(|c| !c.is_empty())
+                .map(String::from)
+                .collect(),
+            None => defaults.channels,
+        };
+        if channels.is_empty() {
+            return Err(JobConfigError::NoChannels);
+        }
+
+        let min_concurrency =
+            parse_count(&var, "JOB_CONCURRENCY_MIN")?.unwrap_or(defaults.min_concurrency);
+        let max_concurrency =
+            parse_count(&var, "JOB_CONCURRENCY_MAX")?.unwrap_or(defaults.max_concurrency);
+        if max_concurrency < min_concurrency {
+            return Err(JobConfigError::ConcurrencyRange {
+                min: min_concurrency,
+                max: max_concurrency,
+            });
+        }
+
+        Ok(Self {
+            channels,
+            min_concurrency,
+            max_concurrency,
+        })
+    }
+}
+
+fn parse_count(
+    var: &impl Fn(&str) -> Option<String>,
+    name: &'static str,
+) -> Result<Option<usize>, JobConfigError> {
+    let Some(value) = var(name) else {
+        return Ok(None);
+    };
+
+    match value.trim().parse() {
+        Ok(count) if count > 0 => Ok(Some(count)),
+        _ => Err(JobConfigError::InvalidNumber { name, value }),
+    }
+}
+
+/// Registry of every job the application knows how to run
+///
+/// Emails are delivered through `mailer`.
+pub fn job_registry(mailer: Mailer) -> JobRegistry {
+    let mut registry = JobRegistry::new(&[
+        user_feature::send_welcome_email,
+        user_feature::send_welcome_digest,
+        todo_feature::send_due_reminder,
+        todo_feature::notify_todo_created,
+        crate::outbox::handle_domain_event,
+    ]);
+    registry.set_context(mailer);
+    registry
+}
+
+/// Start a job runner for the configured channels
+///
+/// The runner stops when the returned handle is dropped, so keep it for the
+/// lifetime of the process and drain it on shutdown.
+pub async fn start_job_runner(
+    pool: &PgPool,
+    config: &JobRunnerConfig,
+    mailer: Mailer,
+) -> Result<JobRunnerHandle, sqlx::Error> {
+    info!(
+        channels = ?config.channels,
+        min = config.min_concurrency,
+        max = config.max_concurrency,
+        "Starting job runner"
+    );
+
+    let channels: Vec<&str> = config.channels.iter().map(String::as_str).collect();
+
+    job_registry(mailer)
+        .runner(pool)
+        .set_channel_names(&channels)
+        .set_concurrency(config.min_concurrency, config.max_concurrency)
+        .run()
+        .await
+}
+
+/// Queue a welcome email digest every `interval` in a background task
+///
+/// Failures to queue are logged and retried on the next tick. Abort the
+/// returned handle to stop scheduling.
+pub fn spawn_welcome_digest_scheduler(pool: PgPool, interval: Duration) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+
+        loop {
+            ticker.tick().await;
+
+            if let Err(e) = UserJobs::enqueue_welcome_digest(&pool).await {
+                warn!(error = %e, "Failed to queue welcome digest");
+            }
+        }
+    })
+}
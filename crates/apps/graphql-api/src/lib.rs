@@ -1,15 +1,109 @@
+pub mod app;
+pub mod auth;
+pub mod cors;
+pub mod db;
+pub mod health;
+pub mod jobs;
+pub mod outbox;
+pub mod request_id;
 pub mod schema;
+pub mod shutdown;
+pub mod telemetry;
 
-use async_graphql::{EmptySubscription, Schema};
-use schema::{MutationRoot, QueryRoot};
+use std::sync::Arc;
+
+use async_graphql::Schema;
+use async_graphql::dataloader::DataLoader;
+use db::ReadPool;
+use domain::{SharedClock, SystemClock};
+use schema::{
+    DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE, MutationRoot, OpenTodoCountLoader, PageSizes, QueryRoot,
+    SubscriptionRoot, TagLoader, TodoItemLoader, UserLoader,
+};
 use sqlx::PgPool;
+use sqlx::migrate::Migrator;
+use telemetry::{GraphQLMetrics, GraphQLTracing};
+use todo_feature::TodoEvents;
+use user_feature::UserEvents;
+
+/// Database migrations this build expects to have been applied
+pub static MIGRATOR: Migrator = sqlx::migrate!("../../../migrations");
 
 /// The GraphQL schema type
-pub type AppSchema = Schema<QueryRoot, MutationRoot, EmptySubscription>;
+pub type AppSchema = Schema<QueryRoot, MutationRoot, SubscriptionRoot>;
 
 /// Build the GraphQL schema with the given database pool
+///
+/// Subscriptions only receive events once `TodoEvents::listen` and
+/// `UserEvents::listen` are running; use `build_schema_with_events` to share
+/// listening ones.
 pub fn build_schema(pool: PgPool) -> AppSchema {
-    Schema::build(QueryRoot, MutationRoot, EmptySubscription)
+    build_schema_with_replica(pool.clone(), pool)
+}
+
+/// Build the GraphQL schema with queries served from `read_pool` and
+/// mutations from `pool`
+pub fn build_schema_with_replica(pool: PgPool, read_pool: PgPool) -> AppSchema {
+    build_schema_with_events(pool, read_pool, TodoEvents::new(), UserEvents::new())
+}
+
+/// Default maximum query depth, overridden by `GRAPHQL_MAX_DEPTH`
+pub const DEFAULT_MAX_DEPTH: usize = 10;
+
+/// Default maximum query complexity, overridden by `GRAPHQL_MAX_COMPLEXITY`
+pub const DEFAULT_MAX_COMPLEXITY: usize = 1000;
+
+/// Build the GraphQL schema, feeding subscriptions from `events` and
+/// `user_events`
+///
+/// Query resolvers and open todo counts read from `read_pool`, which may be a
+/// replica; mutations and everything else use `pool`. Queries deeper or more complex than the
+/// configured limits are rejected with a GraphQL error before any resolver
+/// runs, paginated fields return pages sized within `PageSizes`, and
+/// malformed IDs are reported as `VALIDATION` errors.
+/// Overdue checks read the time from the `SharedClock` in the context, the
+/// system clock unless a request supplies its own.
+pub fn build_schema_with_events(
+    pool: PgPool,
+    read_pool: PgPool,
+    events: TodoEvents,
+    user_events: UserEvents,
+) -> AppSchema {
+    Schema::build(QueryRoot, MutationRoot, SubscriptionRoot)
+        .limit_depth(env_limit("GRAPHQL_MAX_DEPTH", DEFAULT_MAX_DEPTH))
+        .limit_complexity(env_limit("GRAPHQL_MAX_COMPLEXITY", DEFAULT_MAX_COMPLEXITY))
+        .extension(GraphQLMetrics)
+        .extension(GraphQLTracing)
+        // Mutation payloads select owners, tags and checklists right after
+        // writing them, and role checks must see the latest role, so these
+        // loaders read from the primary to avoid replica lag
+        .data(DataLoader::new(UserLoader::new(pool.clone()), tokio::spawn))
+        .data(DataLoader::new(TagLoader::new(pool.clone()), tokio::spawn))
+        .data(DataLoader::new(
+            TodoItemLoader::new(pool.clone()),
+            tokio::spawn,
+        ))
+        .data(DataLoader::new(
+            OpenTodoCountLoader::new(read_pool.clone()),
+            tokio::spawn,
+        ))
         .data(pool)
+        .data(ReadPool(read_pool))
+        .data(PageSizes::new(
+            env_limit("DEFAULT_PAGE_SIZE", DEFAULT_PAGE_SIZE),
+            env_limit("MAX_PAGE_SIZE", MAX_PAGE_SIZE),
+        ))
+        .data(events)
+        .data(user_events)
+        .data::<SharedClock>(Arc::new(SystemClock))
         .finish()
 }
+
+/// Read a positive limit from the environment, falling back to `default`
+fn env_limit(name: &str, default: usize) -> usize {
+    std::env::var(name)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .filter(|&v| v > 0)
+        .unwrap_or(default)
+}
@@ -0,0 +1,113 @@
+use std::time::Duration;
+
+use domain::{EventRepository, OutboxEvent};
+use metrics::counter;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::PgPool;
+use sqlxmq::{CurrentJob, job};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+/// How often the poller looks for new outbox events
+pub const OUTBOX_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Most events dispatched in one poll
+pub const OUTBOX_BATCH_SIZE: i64 = 100;
+
+/// Arguments for the domain event job
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DomainEventArgs {
+    pub event_id: Uuid,
+    pub event_type: String,
+    pub payload: Value,
+}
+
+/// Handle a domain event dispatched from the outbox
+///
+/// Side effects for new event types hook in here.
+#[job(channel_name = "events")]
+pub async fn handle_domain_event(
+    mut current_job: CurrentJob,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let args: DomainEventArgs = current_job.json()?.expect("job arguments");
+
+    info!(
+        event_id = %args.event_id,
+        event_type = %args.event_type,
+        "Handling domain event"
+    );
+
+    current_job.complete().await?;
+    counter!("events_total", "event_type" => args.event_type).increment(1);
+    Ok(())
+}
+
+/// Dispatch a batch of unprocessed outbox events, returning how many were sent
+///
+/// Each event becomes a `handle_domain_event` job. The jobs are spawned and
+/// the events marked processed in one transaction, so an event is never
+/// dispatched twice or lost.
+pub async fn dispatch_outbox(
+    pool: &PgPool,
+    limit: i64,
+) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+    let mut tx = pool.begin().await?;
+
+    let events = EventRepository::claim_unprocessed(&mut *tx, limit).await?;
+    if events.is_empty() {
+        return Ok(0);
+    }
+
+    for event in &events {
+        enqueue_event(&mut tx, event).await?;
+    }
+
+    let ids: Vec<Uuid> = events.iter().map(|e| e.id).collect();
+    EventRepository::mark_processed(&mut *tx, &ids).await?;
+
+    tx.commit().await?;
+
+    Ok(events.len())
+}
+
+/// Spawn the job that handles an event within a transaction
+async fn enqueue_event(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    event: &OutboxEvent,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let args = DomainEventArgs {
+        event_id: event.id,
+        event_type: event.event_type.clone(),
+        payload: event.payload_json.clone(),
+    };
+
+    handle_domain_event
+        .builder()
+        .set_json(&args)?
+        .spawn(&mut **tx)
+        .await?;
+
+    Ok(())
+}
+
+/// Poll the outbox every `interval` in a background task
+///
+/// Failed polls are logged and retried on the next tick. Abort the returned
+/// handle to stop polling.
+pub fn spawn_outbox_poller(pool: PgPool, interval: Duration) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+
+        loop {
+            ticker.tick().await;
+
+            match dispatch_outbox(&pool, OUTBOX_BATCH_SIZE).await {
+                Ok(0) => {}
+                Ok(count) => debug!(count, "Dispatched outbox events"),
+                Err(e) => warn!(error = %e, "Failed to dispatch outbox events"),
+            }
+        }
+    })
+}
@@ -0,0 +1,42 @@
+use axum::extract::Request;
+use axum::http::HeaderValue;
+use axum::middleware::Next;
+use axum::response::Response;
+use tracing::{Instrument, info_span};
+use uuid::Uuid;
+
+/// Header carrying a request's correlation ID, on the way in and out
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Longest caller-supplied ID that is kept rather than replaced
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+/// Correlation ID of the request being handled
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequestId(pub String);
+
+/// Middleware giving every request a correlation ID
+///
+/// Keeps the caller's `X-Request-Id` when it is printable and at most 128
+/// characters, and generates a UUID otherwise. The ID is added to the request
+/// extensions as a `RequestId`, recorded on a `request` span around the rest
+/// of the handling, and echoed in the response's `X-Request-Id` header.
+pub async fn request_id(mut req: Request, next: Next) -> Response {
+    let id = req
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|id| !id.is_empty() && id.len() <= MAX_REQUEST_ID_LEN)
+        .map(String::from)
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+    req.extensions_mut().insert(RequestId(id.clone()));
+    let span = info_span!("request", request_id = %id);
+    let mut response = next.run(req).instrument(span).await;
+
+    if let Ok(value) = HeaderValue::from_str(&id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
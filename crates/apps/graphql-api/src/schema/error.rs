@@ -0,0 +1,121 @@
+use std::fmt::Display;
+use std::sync::Arc;
+
+use async_graphql::{Error, ErrorExtensionValues, ErrorExtensions, value};
+use domain::DomainError;
+use todo_feature::TodoFeatureError;
+use tracing::error;
+use user_feature::UserFeatureError;
+
+/// A stable, machine-readable code clients can match on
+///
+/// The code is sent in the GraphQL error's `extensions.code`, so clients
+/// don't have to parse messages to tell failures apart.
+pub trait ErrorCode: Display {
+    fn code(&self) -> &'static str;
+
+    /// Add any extensions beyond `code`
+    fn extend(&self, _ext: &mut ErrorExtensionValues) {}
+}
+
+impl ErrorCode for DomainError {
+    fn code(&self) -> &'static str {
+        match self {
+            DomainError::Database(_) => "INTERNAL",
+            DomainError::NotFound(_) => "NOT_FOUND",
+            DomainError::Validation(_) => "VALIDATION",
+            DomainError::Conflict(_) => "CONFLICT",
+        }
+    }
+}
+
+impl ErrorCode for UserFeatureError {
+    fn code(&self) -> &'static str {
+        match self {
+            UserFeatureError::Domain(e) => e.code(),
+            UserFeatureError::Queue(_) => "INTERNAL",
+            UserFeatureError::NotFound(_) => "NOT_FOUND",
+            UserFeatureError::EmailExists(_) => "EMAIL_EXISTS",
+            UserFeatureError::InvalidEmail(_) => "VALIDATION",
+            UserFeatureError::InvalidTimezone(_) => "VALIDATION",
+            UserFeatureError::Validation(_) => "VALIDATION",
+            UserFeatureError::InvalidFields(_) => "VALIDATION",
+            UserFeatureError::InvalidCredentials => "INVALID_CREDENTIALS",
+            UserFeatureError::PasswordHash(_) => "INTERNAL",
+        }
+    }
+
+    /// Lists invalid fields under `extensions.fields` as `{ field, message }`
+    fn extend(&self, ext: &mut ErrorExtensionValues) {
+        if let UserFeatureError::InvalidFields(errors) = self {
+            let fields: Vec<_> = errors
+                .iter()
+                .map(|e| value!({ "field": e.field, "message": e.message }))
+                .collect();
+            ext.set("fields", fields);
+        }
+    }
+}
+
+impl ErrorCode for TodoFeatureError {
+    fn code(&self) -> &'static str {
+        match self {
+            TodoFeatureError::Domain(e) => e.code(),
+            TodoFeatureError::NotFound(_) => "NOT_FOUND",
+            TodoFeatureError::ItemNotFound(_) => "NOT_FOUND",
+            TodoFeatureError::UserNotFound(_) => "USER_NOT_FOUND",
+            TodoFeatureError::Forbidden(_) => "FORBIDDEN",
+            TodoFeatureError::InvalidTag(_) => "VALIDATION",
+            TodoFeatureError::Validation(_) => "VALIDATION",
+            TodoFeatureError::Conflict(_) => "CONFLICT",
+            TodoFeatureError::Queue(_) => "INTERNAL",
+            TodoFeatureError::InvalidTransition { .. } => "INVALID_TRANSITION",
+        }
+    }
+}
+
+// Data loaders share their error between every waiting resolver
+impl<E: ErrorCode> ErrorCode for Arc<E> {
+    fn code(&self) -> &'static str {
+        (**self).code()
+    }
+
+    fn extend(&self, ext: &mut ErrorExtensionValues) {
+        (**self).extend(ext)
+    }
+}
+
+/// Message sent in place of internal failures
+const INTERNAL_MESSAGE: &str = "internal server error";
+
+/// Convert a feature error into a GraphQL error carrying its code
+///
+/// `async_graphql::Error` already converts from anything `Display`, so a
+/// `From` impl isn't possible; resolvers use `.map_err(to_graphql_error)`.
+///
+/// Internal errors are logged in full but reach the client only as a generic
+/// message, since database errors can quote SQL and column names.
+pub fn to_graphql_error<E: ErrorCode>(err: E) -> Error {
+    let code = err.code();
+    let message = if code == "INTERNAL" {
+        error!(error = %err, "Internal error while resolving GraphQL request");
+        INTERNAL_MESSAGE.to_string()
+    } else {
+        err.to_string()
+    };
+
+    Error::new(message).extend_with(|_, ext| {
+        ext.set("code", code);
+        err.extend(ext);
+    })
+}
+
+/// Error for an operation that needs an authenticated caller
+pub fn unauthenticated() -> Error {
+    Error::new("Authentication required").extend_with(|_, ext| ext.set("code", "UNAUTHENTICATED"))
+}
+
+/// Error for a caller acting on another user's data
+pub fn forbidden() -> Error {
+    Error::new("Forbidden").extend_with(|_, ext| ext.set("code", "FORBIDDEN"))
+}
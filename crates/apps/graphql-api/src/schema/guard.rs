@@ -0,0 +1,66 @@
+use async_graphql::dataloader::DataLoader;
+use async_graphql::{Context, Guard, Result};
+use domain::UserRole;
+use uuid::Uuid;
+
+use super::error::{forbidden, to_graphql_error, unauthenticated};
+use super::loaders::UserLoader;
+use crate::auth::AuthContext;
+
+/// The authenticated caller, or an `UNAUTHENTICATED` error
+pub fn current_user(ctx: &Context<'_>) -> Result<AuthContext> {
+    ctx.data_opt::<AuthContext>()
+        .copied()
+        .ok_or_else(unauthenticated)
+}
+
+/// Require the caller to be `user_id`, failing with `FORBIDDEN` otherwise
+pub fn ensure_self(ctx: &Context<'_>, user_id: Uuid) -> Result<()> {
+    if current_user(ctx)?.user_id != user_id {
+        return Err(forbidden());
+    }
+
+    Ok(())
+}
+
+/// Require the caller to be `user_id` or an admin, failing with `FORBIDDEN`
+/// otherwise
+pub async fn ensure_self_or_admin(ctx: &Context<'_>, user_id: Uuid) -> Result<()> {
+    if current_user(ctx)?.user_id == user_id {
+        return Ok(());
+    }
+
+    RequireRole(UserRole::Admin).check(ctx).await
+}
+
+/// Guard requiring the caller to hold a role, failing with `FORBIDDEN` otherwise
+///
+/// Admins hold every role, and callers on the admin allowlist count as admins
+/// whatever their stored role, so the first admins can be bootstrapped.
+///
+/// ```ignore
+/// #[graphql(guard = "RequireRole(UserRole::Admin)")]
+/// ```
+pub struct RequireRole(pub UserRole);
+
+impl Guard for RequireRole {
+    async fn check(&self, ctx: &Context<'_>) -> Result<()> {
+        let auth = current_user(ctx)?;
+        if auth.is_admin {
+            return Ok(());
+        }
+
+        let loader = ctx.data::<DataLoader<UserLoader>>()?;
+        let role = loader
+            .load_one(auth.user_id)
+            .await
+            .map_err(to_graphql_error)?
+            .map(|user| user.role);
+
+        match role {
+            Some(UserRole::Admin) => Ok(()),
+            Some(role) if role == self.0 => Ok(()),
+            _ => Err(forbidden()),
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_graphql::dataloader::Loader;
+use domain::{
+    DomainError, TagRepository, TodoItem, TodoItemRepository, TodoRepository, User, UserRepository,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Batches user lookups so resolving many todo owners costs one query
+pub struct UserLoader {
+    pool: PgPool,
+}
+
+impl UserLoader {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+impl Loader<Uuid> for UserLoader {
+    type Value = User;
+    type Error = Arc<DomainError>;
+
+    async fn load(&self, keys: &[Uuid]) -> Result<HashMap<Uuid, User>, Self::Error> {
+        let users = UserRepository::find_by_ids(&self.pool, keys).await?;
+        Ok(users.into_iter().map(|user| (user.id, user)).collect())
+    }
+}
+
+/// Batches tag lookups so resolving tags for a list of todos costs one query
+pub struct TagLoader {
+    pool: PgPool,
+}
+
+impl TagLoader {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+impl Loader<Uuid> for TagLoader {
+    type Value = Vec<String>;
+    type Error = Arc<DomainError>;
+
+    async fn load(&self, keys: &[Uuid]) -> Result<HashMap<Uuid, Vec<String>>, Self::Error> {
+        let mut tags: HashMap<Uuid, Vec<String>> = HashMap::new();
+        for (todo_id, name) in TagRepository::list_for_todos(&self.pool, keys).await? {
+            tags.entry(todo_id).or_default().push(name);
+        }
+        Ok(tags)
+    }
+}
+
+/// Batches checklist lookups so resolving items for a list of todos costs
+/// one query
+pub struct TodoItemLoader {
+    pool: PgPool,
+}
+
+impl TodoItemLoader {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+impl Loader<Uuid> for TodoItemLoader {
+    type Value = Vec<TodoItem>;
+    type Error = Arc<DomainError>;
+
+    async fn load(&self, keys: &[Uuid]) -> Result<HashMap<Uuid, Vec<TodoItem>>, Self::Error> {
+        let mut items: HashMap<Uuid, Vec<TodoItem>> = HashMap::new();
+        for item in TodoItemRepository::list_for_todos(&self.pool, keys).await? {
+            items.entry(item.todo_id).or_default().push(item);
+        }
+        Ok(items)
+    }
+}
+
+/// Batches open todo counts so listing many users costs one query
+pub struct OpenTodoCountLoader {
+    pool: PgPool,
+}
+
+impl OpenTodoCountLoader {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+impl Loader<Uuid> for OpenTodoCountLoader {
+    type Value = i64;
+    type Error = Arc<DomainError>;
+
+    async fn load(&self, keys: &[Uuid]) -> Result<HashMap<Uuid, i64>, Self::Error> {
+        Ok(TodoRepository::count_open_by_users(&self.pool, keys).await?)
+    }
+}
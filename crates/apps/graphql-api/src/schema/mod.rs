@@ -1,6 +1,14 @@
+mod error;
+mod guard;
+mod loaders;
 mod mutation;
+mod pagination;
 mod query;
+mod subscription;
 mod types;
 
+pub use loaders::{OpenTodoCountLoader, TagLoader, TodoItemLoader, UserLoader};
 pub use mutation::MutationRoot;
+pub use pagination::{DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE, PageSizes};
 pub use query::QueryRoot;
+pub use subscription::SubscriptionRoot;
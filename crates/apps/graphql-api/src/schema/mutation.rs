@@ -1,8 +1,14 @@
 use async_graphql::{Context, Object, Result};
+use domain::{UserRepository, UserRole};
 use sqlx::PgPool;
-use uuid::Uuid;
+use todo_feature::TodoFeatureError;
 
-use super::types::{CreateTodoInput, CreateUserInput, TodoType, UpdateTodoInput, UpdateUserInput, UserType};
+use super::error::to_graphql_error;
+use super::guard::{RequireRole, current_user, ensure_self, ensure_self_or_admin};
+use super::types::{
+    CreateTodoInput, CreateTodoItem, CreateUserInput, DeleteResult, Id, TodoItemType,
+    TodoStatusType, TodoType, UpdateTodoInput, UpdateUserInput, UserType,
+};
 
 pub struct MutationRoot;
 
@@ -16,88 +22,466 @@ impl MutationRoot {
             user_feature::CreateUserInput {
                 email: input.email,
                 name: input.name,
+                timezone: input.timezone,
+                idempotency_key: input.idempotency_key,
             },
         )
-        .await?;
+        .await
+        .map_err(to_graphql_error)?;
         Ok(user.into())
     }
 
-    /// Update a user
+    /// Register several users at once (each is sent a welcome email)
+    ///
+    /// Nothing is created if any of the emails is already taken.
+    async fn register_users(
+        &self,
+        ctx: &Context<'_>,
+        inputs: Vec<CreateUserInput>,
+    ) -> Result<Vec<UserType>> {
+        let pool = ctx.data::<PgPool>()?;
+        let users = user_feature::UserService::register_many(
+            pool,
+            inputs
+                .into_iter()
+                .map(|input| user_feature::CreateUserInput {
+                    email: input.email,
+                    name: input.name,
+                    timezone: input.timezone,
+                    idempotency_key: input.idempotency_key,
+                })
+                .collect(),
+        )
+        .await
+        .map_err(to_graphql_error)?;
+        Ok(users.into_iter().map(Into::into).collect())
+    }
+
+    /// Update a user's name, email or timezone (callers may only update
+    /// themselves)
     async fn update_user(
         &self,
         ctx: &Context<'_>,
-        id: Uuid,
+        id: Id,
         input: UpdateUserInput,
     ) -> Result<UserType> {
+        ensure_self(ctx, id.0)?;
         let pool = ctx.data::<PgPool>()?;
         let user = user_feature::UserService::update(
             pool,
-            id,
-            user_feature::UpdateUserInput { name: input.name },
+            id.0,
+            user_feature::UpdateUserInput {
+                name: input.name,
+                email: input.email,
+                timezone: input.timezone,
+            },
         )
-        .await?;
+        .await
+        .map_err(to_graphql_error)?;
         Ok(user.into())
     }
 
-    /// Delete a user
-    async fn delete_user(&self, ctx: &Context<'_>, id: Uuid) -> Result<bool> {
+    /// Set or replace your password
+    async fn set_password(
+        &self,
+        ctx: &Context<'_>,
+        user_id: Id,
+        #[graphql(secret)] password: String,
+    ) -> Result<bool> {
+        ensure_self(ctx, user_id.0)?;
+        let pool = ctx.data::<PgPool>()?;
+        user_feature::UserService::set_password(pool, user_id.0, &password)
+            .await
+            .map_err(to_graphql_error)?;
+        Ok(true)
+    }
+
+    /// Send the welcome email to a user again (callers may only resend their
+    /// own, unless they are an admin)
+    async fn resend_welcome_email(&self, ctx: &Context<'_>, user_id: Id) -> Result<bool> {
+        ensure_self_or_admin(ctx, user_id.0).await?;
+        let pool = ctx.data::<PgPool>()?;
+        user_feature::UserService::resend_welcome_email(pool, user_id.0)
+            .await
+            .map_err(to_graphql_error)?;
+        Ok(true)
+    }
+
+    /// Delete a user along with their todos (callers may only delete
+    /// themselves)
+    async fn delete_user(&self, ctx: &Context<'_>, id: Id) -> Result<DeleteResult> {
+        ensure_self(ctx, id.0)?;
+        let pool = ctx.data::<PgPool>()?;
+        let deleted = user_feature::UserService::delete(pool, id.0)
+            .await
+            .map_err(to_graphql_error)?;
+        let existed = deleted
+            || UserRepository::find_by_id_including_deleted(pool, id.0)
+                .await
+                .map_err(to_graphql_error)?
+                .is_some();
+        Ok(DeleteResult { deleted, existed })
+    }
+
+    /// Restore a deleted user, returning false if they weren't deleted
+    /// (admins only)
+    #[graphql(guard = "RequireRole(UserRole::Admin)")]
+    async fn restore_user(&self, ctx: &Context<'_>, id: Id) -> Result<bool> {
+        let pool = ctx.data::<PgPool>()?;
+        let auth = current_user(ctx)?;
+        user_feature::UserService::restore(pool, id.0, auth.user_id)
+            .await
+            .map_err(to_graphql_error)
+    }
+
+    /// Scrub a user's personal details, keeping their todos (callers may
+    /// only anonymize themselves)
+    async fn anonymize_user(&self, ctx: &Context<'_>, id: Id) -> Result<bool> {
+        ensure_self(ctx, id.0)?;
         let pool = ctx.data::<PgPool>()?;
-        Ok(user_feature::UserService::delete(pool, id).await?)
+        user_feature::UserService::anonymize(pool, id.0)
+            .await
+            .map_err(to_graphql_error)
     }
 
     /// Create a new todo
     async fn create_todo(&self, ctx: &Context<'_>, input: CreateTodoInput) -> Result<TodoType> {
+        ensure_self(ctx, input.user_id.0)?;
         let pool = ctx.data::<PgPool>()?;
         let todo = todo_feature::TodoService::create(
             pool,
             todo_feature::CreateTodoInput {
-                user_id: input.user_id,
+                user_id: input.user_id.0,
                 title: input.title,
                 description: input.description,
+                due_date: input.due_date,
             },
         )
-        .await?;
+        .await
+        .map_err(to_graphql_error)?;
         Ok(todo.into())
     }
 
+    /// Create several todos for a user at once (all or nothing)
+    async fn create_todos(
+        &self,
+        ctx: &Context<'_>,
+        user_id: Id,
+        items: Vec<CreateTodoItem>,
+    ) -> Result<Vec<TodoType>> {
+        ensure_self(ctx, user_id.0)?;
+        let pool = ctx.data::<PgPool>()?;
+        let todos = todo_feature::TodoService::create_many(
+            pool,
+            user_id.0,
+            items
+                .into_iter()
+                .map(|item| todo_feature::CreateTodoItem {
+                    title: item.title,
+                    description: item.description,
+                    due_date: item.due_date,
+                })
+                .collect(),
+        )
+        .await
+        .map_err(to_graphql_error)?;
+        Ok(todos.into_iter().map(Into::into).collect())
+    }
+
     /// Update a todo
     async fn update_todo(
         &self,
         ctx: &Context<'_>,
-        id: Uuid,
+        id: Id,
         input: UpdateTodoInput,
     ) -> Result<TodoType> {
         let pool = ctx.data::<PgPool>()?;
+        let auth = current_user(ctx)?;
+        todo_feature::TodoService::get_owned(pool, id.0, auth.user_id)
+            .await
+            .map_err(to_graphql_error)?;
         let todo = todo_feature::TodoService::update(
             pool,
-            id,
+            id.0,
             todo_feature::UpdateTodoInput {
                 title: input.title,
                 description: input.description,
                 status: input.status.map(Into::into),
+                expected_updated_at: input.expected_updated_at,
             },
         )
-        .await?;
+        .await
+        .map_err(to_graphql_error)?;
+        Ok(todo.into())
+    }
+
+    /// Set the status of several of the caller's todos at once, returning how
+    /// many changed
+    ///
+    /// IDs of todos owned by someone else are ignored.
+    async fn set_todos_status(
+        &self,
+        ctx: &Context<'_>,
+        ids: Vec<Id>,
+        status: TodoStatusType,
+    ) -> Result<i32> {
+        let auth = current_user(ctx)?;
+        let pool = ctx.data::<PgPool>()?;
+        let count = todo_feature::TodoService::update_status_many(
+            pool,
+            auth.user_id,
+            Id::uuids(ids),
+            status.into(),
+        )
+        .await
+        .map_err(to_graphql_error)?;
+        Ok(count as i32)
+    }
+
+    /// Put a user's todos in the given order, returned in that order
+    async fn reorder_todos(
+        &self,
+        ctx: &Context<'_>,
+        user_id: Id,
+        ordered_ids: Vec<Id>,
+    ) -> Result<Vec<TodoType>> {
+        ensure_self(ctx, user_id.0)?;
+        let pool = ctx.data::<PgPool>()?;
+        let todos = todo_feature::TodoService::reorder(pool, user_id.0, Id::uuids(ordered_ids))
+            .await
+            .map_err(to_graphql_error)?;
+        Ok(todos.into_iter().map(Into::into).collect())
+    }
+
+    /// Copy a todo into a new pending todo
+    async fn duplicate_todo(&self, ctx: &Context<'_>, id: Id) -> Result<TodoType> {
+        let pool = ctx.data::<PgPool>()?;
+        let auth = current_user(ctx)?;
+        todo_feature::TodoService::get_owned(pool, id.0, auth.user_id)
+            .await
+            .map_err(to_graphql_error)?;
+        let todo = todo_feature::TodoService::duplicate(pool, id.0)
+            .await
+            .map_err(to_graphql_error)?;
         Ok(todo.into())
     }
 
     /// Mark a todo as completed
-    async fn complete_todo(&self, ctx: &Context<'_>, id: Uuid) -> Result<TodoType> {
+    async fn complete_todo(&self, ctx: &Context<'_>, id: Id) -> Result<TodoType> {
+        let pool = ctx.data::<PgPool>()?;
+        let auth = current_user(ctx)?;
+        todo_feature::TodoService::get_owned(pool, id.0, auth.user_id)
+            .await
+            .map_err(to_graphql_error)?;
+        let todo = todo_feature::TodoService::complete(pool, id.0)
+            .await
+            .map_err(to_graphql_error)?;
+        Ok(todo.into())
+    }
+
+    /// Mark a todo as in progress and start, or resume, its time tracking
+    async fn start_todo(&self, ctx: &Context<'_>, id: Id) -> Result<TodoType> {
+        let pool = ctx.data::<PgPool>()?;
+        let auth = current_user(ctx)?;
+        todo_feature::TodoService::get_owned(pool, id.0, auth.user_id)
+            .await
+            .map_err(to_graphql_error)?;
+        let todo = todo_feature::TodoService::start(pool, id.0)
+            .await
+            .map_err(to_graphql_error)?;
+        Ok(todo.into())
+    }
+
+    /// Pause a todo's time tracking, keeping it in progress
+    async fn pause_todo(&self, ctx: &Context<'_>, id: Id) -> Result<TodoType> {
+        let pool = ctx.data::<PgPool>()?;
+        let auth = current_user(ctx)?;
+        todo_feature::TodoService::get_owned(pool, id.0, auth.user_id)
+            .await
+            .map_err(to_graphql_error)?;
+        let todo = todo_feature::TodoService::pause(pool, id.0)
+            .await
+            .map_err(to_graphql_error)?;
+        Ok(todo.into())
+    }
+
+    /// Cancel a todo that will not be completed
+    async fn cancel_todo(&self, ctx: &Context<'_>, id: Id) -> Result<TodoType> {
+        let pool = ctx.data::<PgPool>()?;
+        let auth = current_user(ctx)?;
+        todo_feature::TodoService::get_owned(pool, id.0, auth.user_id)
+            .await
+            .map_err(to_graphql_error)?;
+        let todo = todo_feature::TodoService::cancel(pool, id.0)
+            .await
+            .map_err(to_graphql_error)?;
+        Ok(todo.into())
+    }
+
+    /// Move a completed todo back to pending
+    async fn reopen_todo(&self, ctx: &Context<'_>, id: Id) -> Result<TodoType> {
+        let pool = ctx.data::<PgPool>()?;
+        let auth = current_user(ctx)?;
+        todo_feature::TodoService::get_owned(pool, id.0, auth.user_id)
+            .await
+            .map_err(to_graphql_error)?;
+        let todo = todo_feature::TodoService::reopen(pool, id.0)
+            .await
+            .map_err(to_graphql_error)?;
+        Ok(todo.into())
+    }
+
+    /// Hand a todo over to another user
+    async fn reassign_todo(&self, ctx: &Context<'_>, id: Id, new_user_id: Id) -> Result<TodoType> {
+        let pool = ctx.data::<PgPool>()?;
+        let auth = current_user(ctx)?;
+        todo_feature::TodoService::get_owned(pool, id.0, auth.user_id)
+            .await
+            .map_err(to_graphql_error)?;
+        let todo = todo_feature::TodoService::reassign(pool, id.0, new_user_id.0)
+            .await
+            .map_err(to_graphql_error)?;
+        Ok(todo.into())
+    }
+
+    /// Delete all of a user's completed todos, returning how many were removed
+    async fn clear_completed_todos(&self, ctx: &Context<'_>, user_id: Id) -> Result<i32> {
+        ensure_self(ctx, user_id.0)?;
+        let pool = ctx.data::<PgPool>()?;
+        let count = todo_feature::TodoService::clear_completed(pool, user_id.0)
+            .await
+            .map_err(to_graphql_error)?;
+        Ok(count as i32)
+    }
+
+    /// Tag a todo; tags are case-insensitive
+    async fn add_todo_tag(&self, ctx: &Context<'_>, id: Id, tag: String) -> Result<TodoType> {
+        let pool = ctx.data::<PgPool>()?;
+        let auth = current_user(ctx)?;
+        let todo = todo_feature::TodoService::get_owned(pool, id.0, auth.user_id)
+            .await
+            .map_err(to_graphql_error)?;
+        todo_feature::TodoService::add_tag(pool, id.0, &tag)
+            .await
+            .map_err(to_graphql_error)?;
+        Ok(todo.into())
+    }
+
+    /// Remove a tag from a todo
+    async fn remove_todo_tag(&self, ctx: &Context<'_>, id: Id, tag: String) -> Result<TodoType> {
         let pool = ctx.data::<PgPool>()?;
-        let todo = todo_feature::TodoService::complete(pool, id).await?;
+        let auth = current_user(ctx)?;
+        let todo = todo_feature::TodoService::get_owned(pool, id.0, auth.user_id)
+            .await
+            .map_err(to_graphql_error)?;
+        todo_feature::TodoService::remove_tag(pool, id.0, &tag)
+            .await
+            .map_err(to_graphql_error)?;
         Ok(todo.into())
     }
 
-    /// Mark a todo as in progress
-    async fn start_todo(&self, ctx: &Context<'_>, id: Uuid) -> Result<TodoType> {
+    /// Replace a todo's tags with exactly the given list
+    async fn set_todo_tags(
+        &self,
+        ctx: &Context<'_>,
+        todo_id: Id,
+        tags: Vec<String>,
+    ) -> Result<TodoType> {
         let pool = ctx.data::<PgPool>()?;
-        let todo = todo_feature::TodoService::start(pool, id).await?;
+        let auth = current_user(ctx)?;
+        let todo = todo_feature::TodoService::get_owned(pool, todo_id.0, auth.user_id)
+            .await
+            .map_err(to_graphql_error)?;
+        todo_feature::TodoService::set_tags(pool, todo_id.0, &tags)
+            .await
+            .map_err(to_graphql_error)?;
         Ok(todo.into())
     }
 
-    /// Delete a todo
-    async fn delete_todo(&self, ctx: &Context<'_>, id: Uuid) -> Result<bool> {
+    /// Add a checklist item to the end of a todo's items
+    async fn add_todo_item(
+        &self,
+        ctx: &Context<'_>,
+        todo_id: Id,
+        text: String,
+    ) -> Result<TodoItemType> {
+        let pool = ctx.data::<PgPool>()?;
+        let auth = current_user(ctx)?;
+        todo_feature::TodoService::get_owned(pool, todo_id.0, auth.user_id)
+            .await
+            .map_err(to_graphql_error)?;
+        let item = todo_feature::TodoService::add_item(pool, todo_id.0, &text)
+            .await
+            .map_err(to_graphql_error)?;
+        Ok(item.into())
+    }
+
+    /// Mark a checklist item done, or not done if it already was
+    async fn toggle_todo_item(&self, ctx: &Context<'_>, id: Id) -> Result<TodoItemType> {
+        let pool = ctx.data::<PgPool>()?;
+        let auth = current_user(ctx)?;
+        let item = todo_feature::TodoService::get_item(pool, id.0)
+            .await
+            .map_err(to_graphql_error)?;
+        todo_feature::TodoService::get_owned(pool, item.todo_id, auth.user_id)
+            .await
+            .map_err(to_graphql_error)?;
+        let item = todo_feature::TodoService::toggle_item(pool, id.0)
+            .await
+            .map_err(to_graphql_error)?;
+        Ok(item.into())
+    }
+
+    /// Delete a checklist item
+    async fn delete_todo_item(&self, ctx: &Context<'_>, id: Id) -> Result<bool> {
+        let pool = ctx.data::<PgPool>()?;
+        let auth = current_user(ctx)?;
+        let item = todo_feature::TodoService::get_item(pool, id.0)
+            .await
+            .map_err(to_graphql_error)?;
+        todo_feature::TodoService::get_owned(pool, item.todo_id, auth.user_id)
+            .await
+            .map_err(to_graphql_error)?;
+        todo_feature::TodoService::delete_item(pool, id.0)
+            .await
+            .map_err(to_graphql_error)?;
+        Ok(true)
+    }
+
+    /// Delete a todo; it can be brought back with `restoreTodo`
+    async fn delete_todo(&self, ctx: &Context<'_>, id: Id) -> Result<DeleteResult> {
+        let pool = ctx.data::<PgPool>()?;
+        let auth = current_user(ctx)?;
+        match todo_feature::TodoService::get_owned_including_deleted(pool, id.0, auth.user_id).await
+        {
+            Ok(_) => {}
+            Err(TodoFeatureError::NotFound(_)) => {
+                return Ok(DeleteResult {
+                    deleted: false,
+                    existed: false,
+                });
+            }
+            Err(e) => return Err(to_graphql_error(e)),
+        }
+        let deleted = todo_feature::TodoService::delete(pool, id.0)
+            .await
+            .map_err(to_graphql_error)?;
+        Ok(DeleteResult {
+            deleted,
+            existed: true,
+        })
+    }
+
+    /// Restore a deleted todo
+    async fn restore_todo(&self, ctx: &Context<'_>, id: Id) -> Result<TodoType> {
         let pool = ctx.data::<PgPool>()?;
-        Ok(todo_feature::TodoService::delete(pool, id).await?)
+        let auth = current_user(ctx)?;
+        todo_feature::TodoService::get_owned_including_deleted(pool, id.0, auth.user_id)
+            .await
+            .map_err(to_graphql_error)?;
+        let todo = todo_feature::TodoService::restore(pool, id.0)
+            .await
+            .map_err(to_graphql_error)?;
+        Ok(todo.into())
     }
 }
@@ -0,0 +1,50 @@
+use async_graphql::Context;
+
+/// Default page size, overridden by `DEFAULT_PAGE_SIZE`
+pub const DEFAULT_PAGE_SIZE: usize = 20;
+
+/// Largest page size, overridden by `MAX_PAGE_SIZE`
+pub const MAX_PAGE_SIZE: usize = 100;
+
+/// Bounds on how many items a paginated field returns at once
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PageSizes {
+    default: usize,
+    max: usize,
+}
+
+impl PageSizes {
+    /// A default above `max` is lowered to it
+    pub fn new(default: usize, max: usize) -> Self {
+        Self {
+            default: default.min(max),
+            max,
+        }
+    }
+
+    /// The page size to use when `requested` items were asked for
+    ///
+    /// Leaving it out gives the default; asking for more than the maximum
+    /// is clamped to the maximum rather than rejected.
+    pub fn normalize(&self, requested: Option<i32>) -> u64 {
+        let size = match requested {
+            Some(requested) => usize::try_from(requested).unwrap_or(0).min(self.max),
+            None => self.default,
+        };
+        size as u64
+    }
+}
+
+impl Default for PageSizes {
+    fn default() -> Self {
+        Self::new(DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE)
+    }
+}
+
+/// Normalize a requested `first`/`limit` with the schema's `PageSizes`
+pub fn page_size(ctx: &Context<'_>, requested: Option<i32>) -> u64 {
+    ctx.data_opt::<PageSizes>()
+        .copied()
+        .unwrap_or_default()
+        .normalize(requested)
+}
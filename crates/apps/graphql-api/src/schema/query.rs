@@ -1,59 +1,352 @@
-use async_graphql::{Context, Object, Result};
+use async_graphql::{Context, Json, Object, Result};
+use domain::{JobsRepository, SharedClock, UserRole};
 use sqlx::PgPool;
-use uuid::Uuid;
+use time::{Date, OffsetDateTime};
 
-use super::types::{TodoStatusType, TodoType, UserType};
+use super::error::to_graphql_error;
+use super::guard::{RequireRole, current_user, ensure_self};
+use super::pagination::page_size;
+use super::types::{
+    DbPoolStatsType, Email, Id, JobType, RegistrationBucketType, TodoConnection, TodoFilter,
+    TodoPageType, TodoSortType, TodoStatusSummaryType, TodoStatusType, TodoType, UserConnection,
+    UserFilter, UserSortType, UserType,
+};
+use crate::auth::AuthContext;
+use crate::db::ReadPool;
 
 pub struct QueryRoot;
 
 #[Object]
 impl QueryRoot {
-    /// Get a user by ID
-    async fn user(&self, ctx: &Context<'_>, id: Uuid) -> Result<Option<UserType>> {
-        let pool = ctx.data::<PgPool>()?;
-        let user = user_feature::UserService::get(pool, id).await.ok();
-        Ok(user.map(Into::into))
+    /// The authenticated user, or null for anonymous requests
+    async fn me(&self, ctx: &Context<'_>) -> Result<Option<UserType>> {
+        let Some(auth) = ctx.data_opt::<AuthContext>() else {
+            return Ok(None);
+        };
+
+        let pool = &ctx.data::<ReadPool>()?.0;
+        match user_feature::UserService::get(pool, auth.user_id).await {
+            Ok(user) => Ok(Some(user.into())),
+            Err(user_feature::UserFeatureError::NotFound(_)) => Ok(None),
+            Err(e) => Err(to_graphql_error(e)),
+        }
+    }
+
+    /// Get a user by ID, or null if there is none
+    async fn user(&self, ctx: &Context<'_>, id: Id) -> Result<Option<UserType>> {
+        let pool = &ctx.data::<ReadPool>()?.0;
+        match user_feature::UserService::get(pool, id.0).await {
+            Ok(user) => Ok(Some(user.into())),
+            Err(user_feature::UserFeatureError::NotFound(_)) => Ok(None),
+            Err(e) => Err(to_graphql_error(e)),
+        }
+    }
+
+    /// Get several users by ID, in the order given; unknown IDs are skipped
+    async fn users_by_ids(&self, ctx: &Context<'_>, ids: Vec<Id>) -> Result<Vec<UserType>> {
+        let pool = &ctx.data::<ReadPool>()?.0;
+        let users = user_feature::UserService::get_many(pool, &Id::uuids(ids))
+            .await
+            .map_err(to_graphql_error)?;
+        Ok(users.into_iter().map(Into::into).collect())
     }
 
     /// Get a user by email
-    async fn user_by_email(&self, ctx: &Context<'_>, email: String) -> Result<Option<UserType>> {
-        let pool = ctx.data::<PgPool>()?;
-        let user = user_feature::UserService::get_by_email(pool, &email).await?;
+    async fn user_by_email(&self, ctx: &Context<'_>, email: Email) -> Result<Option<UserType>> {
+        let pool = &ctx.data::<ReadPool>()?.0;
+        let user = user_feature::UserService::get_by_email(pool, &email.0)
+            .await
+            .map_err(to_graphql_error)?;
         Ok(user.map(Into::into))
     }
 
-    /// List all users
-    async fn users(&self, ctx: &Context<'_>) -> Result<Vec<UserType>> {
-        let pool = ctx.data::<PgPool>()?;
-        let users = user_feature::UserService::list(pool).await?;
+    /// Everything stored about a user and their todos (callers may only export
+    /// their own data)
+    async fn export_user_data(
+        &self,
+        ctx: &Context<'_>,
+        user_id: Id,
+    ) -> Result<Json<user_feature::UserExport>> {
+        ensure_self(ctx, user_id.0)?;
+        let pool = &ctx.data::<ReadPool>()?.0;
+        let export = user_feature::UserService::export_data(pool, user_id.0)
+            .await
+            .map_err(to_graphql_error)?;
+        Ok(Json(export))
+    }
+
+    /// List all users, optionally only those registered from `createdAfter`
+    /// (inclusive) until `createdBefore` (exclusive)
+    async fn users(
+        &self,
+        ctx: &Context<'_>,
+        created_after: Option<OffsetDateTime>,
+        created_before: Option<OffsetDateTime>,
+    ) -> Result<Vec<UserType>> {
+        let pool = &ctx.data::<ReadPool>()?.0;
+        let users =
+            user_feature::UserService::list_created_between(pool, created_after, created_before)
+                .await
+                .map_err(to_graphql_error)?;
         Ok(users.into_iter().map(Into::into).collect())
     }
 
-    /// Get a todo by ID
-    async fn todo(&self, ctx: &Context<'_>, id: Uuid) -> Result<Option<TodoType>> {
-        let pool = ctx.data::<PgPool>()?;
-        let todo = todo_feature::TodoService::get(pool, id).await.ok();
-        Ok(todo.map(Into::into))
+    /// Get one of the caller's todos by ID, or null if they have none with it
+    async fn todo(&self, ctx: &Context<'_>, id: Id) -> Result<Option<TodoType>> {
+        let auth = current_user(ctx)?;
+        let pool = &ctx.data::<ReadPool>()?.0;
+        match todo_feature::TodoService::get_for_user(pool, id.0, auth.user_id).await {
+            Ok(todo) => Ok(Some(todo.into())),
+            Err(todo_feature::TodoFeatureError::NotFound(_)) => Ok(None),
+            Err(e) => Err(to_graphql_error(e)),
+        }
     }
 
-    /// List todos for a user
-    async fn todos_for_user(&self, ctx: &Context<'_>, user_id: Uuid) -> Result<Vec<TodoType>> {
-        let pool = ctx.data::<PgPool>()?;
-        let todos = todo_feature::TodoService::list_for_user(pool, user_id).await?;
+    /// Get up to 100 of the caller's todos by ID, in the order given; IDs of
+    /// todos they don't have are skipped
+    async fn todos_by_ids(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(validator(max_items = 100))] ids: Vec<Id>,
+    ) -> Result<Vec<TodoType>> {
+        let auth = current_user(ctx)?;
+        let pool = &ctx.data::<ReadPool>()?.0;
+        let todos = todo_feature::TodoService::get_many(pool, auth.user_id, &Id::uuids(ids))
+            .await
+            .map_err(to_graphql_error)?;
+        Ok(todos.into_iter().map(Into::into).collect())
+    }
+
+    /// List todos for a user, with deleted ones only if `includeDeleted`
+    /// (callers may only list their own)
+    async fn todos_for_user(
+        &self,
+        ctx: &Context<'_>,
+        user_id: Id,
+        #[graphql(default = false)] include_deleted: bool,
+        #[graphql(default)] sort: TodoSortType,
+    ) -> Result<Vec<TodoType>> {
+        ensure_self(ctx, user_id.0)?;
+        let pool = &ctx.data::<ReadPool>()?.0;
+        let todos = match sort {
+            TodoSortType::Newest => {
+                todo_feature::TodoService::list_for_user(pool, user_id.0, include_deleted).await
+            }
+            TodoSortType::ByPosition => {
+                todo_feature::TodoService::list_for_user_by_position(
+                    pool,
+                    user_id.0,
+                    include_deleted,
+                )
+                .await
+            }
+        }
+        .map_err(to_graphql_error)?;
+        Ok(todos.into_iter().map(Into::into).collect())
+    }
+
+    /// List a user's todos carrying a tag (callers may only list their own)
+    async fn todos_for_user_by_tag(
+        &self,
+        ctx: &Context<'_>,
+        user_id: Id,
+        tag: String,
+    ) -> Result<Vec<TodoType>> {
+        ensure_self(ctx, user_id.0)?;
+        let pool = &ctx.data::<ReadPool>()?.0;
+        let todos = todo_feature::TodoService::list_for_user_by_tag(pool, user_id.0, &tag)
+            .await
+            .map_err(to_graphql_error)?;
+        Ok(todos.into_iter().map(Into::into).collect())
+    }
+
+    /// List a user's most recently updated todos, latest change first (callers
+    /// may only see their own)
+    async fn recent_todos(
+        &self,
+        ctx: &Context<'_>,
+        user_id: Id,
+        #[graphql(default = 10, validator(minimum = 1, maximum = 100))] limit: i32,
+    ) -> Result<Vec<TodoType>> {
+        ensure_self(ctx, user_id.0)?;
+        let pool = &ctx.data::<ReadPool>()?.0;
+        let todos = todo_feature::TodoService::list_recent(pool, user_id.0, limit as i64)
+            .await
+            .map_err(to_graphql_error)?;
+        Ok(todos.into_iter().map(Into::into).collect())
+    }
+
+    /// List a user's open todos that are past their due date (callers may
+    /// only list their own)
+    async fn overdue_todos(&self, ctx: &Context<'_>, user_id: Id) -> Result<Vec<TodoType>> {
+        ensure_self(ctx, user_id.0)?;
+        let pool = &ctx.data::<ReadPool>()?.0;
+        let clock = ctx.data::<SharedClock>()?;
+        let todos = todo_feature::TodoService::list_overdue(pool, clock.as_ref(), user_id.0)
+            .await
+            .map_err(to_graphql_error)?;
+        Ok(todos.into_iter().map(Into::into).collect())
+    }
+
+    /// List a user's open todos due in `[from, to)`, soonest first
+    async fn todos_due_between(
+        &self,
+        ctx: &Context<'_>,
+        user_id: Id,
+        from: OffsetDateTime,
+        to: OffsetDateTime,
+    ) -> Result<Vec<TodoType>> {
+        ensure_self(ctx, user_id.0)?;
+        let pool = &ctx.data::<ReadPool>()?.0;
+        let todos = todo_feature::TodoService::list_due_between(pool, user_id.0, from, to)
+            .await
+            .map_err(to_graphql_error)?;
         Ok(todos.into_iter().map(Into::into).collect())
     }
 
-    /// List todos for a user filtered by status
+    /// Count a user's todos in each status (callers may only see their own)
+    async fn todo_status_summary(
+        &self,
+        ctx: &Context<'_>,
+        user_id: Id,
+    ) -> Result<TodoStatusSummaryType> {
+        ensure_self(ctx, user_id.0)?;
+        let pool = &ctx.data::<ReadPool>()?.0;
+        let summary = todo_feature::TodoService::status_summary(pool, user_id.0)
+            .await
+            .map_err(to_graphql_error)?;
+        Ok(summary.into())
+    }
+
+    /// List a user's todos matching an optional filter, newest first, with
+    /// pagination (callers may only list their own)
+    async fn todos(
+        &self,
+        ctx: &Context<'_>,
+        user_id: Id,
+        filter: Option<TodoFilter>,
+        #[graphql(default = 0, validator(minimum = 0))] offset: i32,
+        #[graphql(validator(minimum = 1))] limit: Option<i32>,
+    ) -> Result<TodoPageType> {
+        ensure_self(ctx, user_id.0)?;
+        let pool = &ctx.data::<ReadPool>()?.0;
+        let page = todo_feature::TodoService::find(
+            pool,
+            user_id.0,
+            filter.unwrap_or_default().into(),
+            offset as u64,
+            page_size(ctx, limit),
+        )
+        .await
+        .map_err(to_graphql_error)?;
+        Ok(page.into())
+    }
+
+    /// List todos for a user filtered by status, newest first (callers may
+    /// only list their own)
+    ///
+    /// Pass the previous page's `endCursor` as `after` to fetch the next page.
     async fn todos_for_user_by_status(
         &self,
         ctx: &Context<'_>,
-        user_id: Uuid,
+        user_id: Id,
         status: TodoStatusType,
-    ) -> Result<Vec<TodoType>> {
+        #[graphql(validator(minimum = 1))] first: Option<i32>,
+        after: Option<String>,
+    ) -> Result<TodoConnection> {
+        ensure_self(ctx, user_id.0)?;
+        let pool = &ctx.data::<ReadPool>()?.0;
+        let page = todo_feature::TodoService::list_for_user_by_status_paginated(
+            pool,
+            user_id.0,
+            status.into(),
+            after.as_deref(),
+            page_size(ctx, first),
+        )
+        .await
+        .map_err(to_graphql_error)?;
+        Ok(page.into())
+    }
+
+    /// List every user's todos, newest first (admins only)
+    ///
+    /// Pass the previous page's `endCursor` as `after` to fetch the next page.
+    #[graphql(guard = "RequireRole(UserRole::Admin)")]
+    async fn all_todos(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(validator(minimum = 1))] first: Option<i32>,
+        after: Option<String>,
+    ) -> Result<TodoConnection> {
+        let pool = &ctx.data::<ReadPool>()?.0;
+        let page = todo_feature::TodoService::list_all_paginated(
+            pool,
+            after.as_deref(),
+            page_size(ctx, first),
+        )
+        .await
+        .map_err(to_graphql_error)?;
+        Ok(page.into())
+    }
+
+    /// List users matching an optional filter, with pagination (admins only)
+    #[graphql(guard = "RequireRole(UserRole::Admin)")]
+    async fn all_users(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<UserFilter>,
+        #[graphql(default)] sort: UserSortType,
+        #[graphql(default = 0, validator(minimum = 0))] offset: i32,
+        #[graphql(validator(minimum = 1))] limit: Option<i32>,
+    ) -> Result<UserConnection> {
+        let pool = &ctx.data::<ReadPool>()?.0;
+        let page = user_feature::UserService::find(
+            pool,
+            filter.unwrap_or_default().into(),
+            sort.into(),
+            offset as u64,
+            page_size(ctx, limit),
+        )
+        .await
+        .map_err(to_graphql_error)?;
+        Ok(page.into())
+    }
+
+    /// Count registrations per UTC day from `from` to `to`, both inclusive,
+    /// including days with none (admins only)
+    #[graphql(guard = "RequireRole(UserRole::Admin)")]
+    async fn registration_stats(
+        &self,
+        ctx: &Context<'_>,
+        from: Date,
+        to: Date,
+    ) -> Result<Vec<RegistrationBucketType>> {
+        let pool = &ctx.data::<ReadPool>()?.0;
+        let buckets = user_feature::UserService::registrations_by_day(pool, from, to)
+            .await
+            .map_err(to_graphql_error)?;
+        Ok(buckets.into_iter().map(Into::into).collect())
+    }
+
+    /// List jobs queued on a channel, oldest first (admins only)
+    #[graphql(guard = "RequireRole(UserRole::Admin)")]
+    async fn jobs(
+        &self,
+        ctx: &Context<'_>,
+        channel: String,
+        #[graphql(default = 20, validator(minimum = 1, maximum = 100))] limit: i32,
+    ) -> Result<Vec<JobType>> {
+        let pool = &ctx.data::<ReadPool>()?.0;
+        let jobs = JobsRepository::list_by_channel(pool, &channel, limit as i64)
+            .await
+            .map_err(to_graphql_error)?;
+        Ok(jobs.into_iter().map(Into::into).collect())
+    }
+
+    /// Connection usage of the primary database pool (admins only)
+    #[graphql(guard = "RequireRole(UserRole::Admin)")]
+    async fn db_pool_stats(&self, ctx: &Context<'_>) -> Result<DbPoolStatsType> {
         let pool = ctx.data::<PgPool>()?;
-        let todos =
-            todo_feature::TodoService::list_for_user_by_status(pool, user_id, status.into())
-                .await?;
-        Ok(todos.into_iter().map(Into::into).collect())
+        Ok(pool.into())
     }
 }
@@ -0,0 +1,53 @@
+use async_graphql::{Context, Result, Subscription};
+use domain::UserRole;
+use futures_util::{Stream, stream};
+use todo_feature::TodoEvents;
+use tokio::sync::broadcast::error::RecvError;
+use user_feature::UserEvents;
+
+use super::guard::{RequireRole, ensure_self};
+use super::types::{Id, TodoType, UserType};
+
+pub struct SubscriptionRoot;
+
+#[Subscription]
+impl SubscriptionRoot {
+    /// Receive a user's todos whenever their status changes (callers may only
+    /// watch their own)
+    async fn todo_status_changed(
+        &self,
+        ctx: &Context<'_>,
+        user_id: Id,
+    ) -> Result<impl Stream<Item = TodoType>> {
+        ensure_self(ctx, user_id.0)?;
+        let receiver = ctx.data::<TodoEvents>()?.subscribe();
+
+        Ok(stream::unfold(receiver, move |mut receiver| async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(todo) if todo.user_id == user_id.0 => return Some((todo.into(), receiver)),
+                    // Other users' todos, or events dropped while we lagged behind
+                    Ok(_) | Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        }))
+    }
+
+    /// Receive every user as soon as they register (admins only)
+    #[graphql(guard = "RequireRole(UserRole::Admin)")]
+    async fn user_registered(&self, ctx: &Context<'_>) -> Result<impl Stream<Item = UserType>> {
+        let receiver = ctx.data::<UserEvents>()?.subscribe();
+
+        Ok(stream::unfold(receiver, |mut receiver| async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(user) => return Some((user.into(), receiver)),
+                    // Events dropped while we lagged behind
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        }))
+    }
+}
@@ -1,13 +1,30 @@
-use async_graphql::{Enum, InputObject, SimpleObject};
-use time::OffsetDateTime;
+use async_graphql::dataloader::DataLoader;
+use async_graphql::{
+    ComplexObject, Context, Enum, InputObject, InputValueError, InputValueResult, Result, Scalar,
+    ScalarType, SimpleObject, Value,
+};
+use domain::SharedClock;
+use sqlx::PgPool;
+use time::format_description::well_known::Rfc3339;
+use time::{Date, OffsetDateTime};
+use time_tz::OffsetDateTimeExt;
 use uuid::Uuid;
 
+use super::error::to_graphql_error;
+use super::guard::ensure_self;
+use super::loaders::{OpenTodoCountLoader, TagLoader, TodoItemLoader, UserLoader};
+use crate::db::ReadPool;
+
 /// GraphQL representation of a User
 #[derive(SimpleObject)]
+#[graphql(complex)]
 pub struct UserType {
-    pub id: Uuid,
+    pub id: Id,
     pub email: String,
     pub name: String,
+    /// IANA timezone name used for local due dates
+    pub timezone: String,
+    pub role: UserRoleType,
     pub created_at: OffsetDateTime,
     pub updated_at: OffsetDateTime,
 }
@@ -15,37 +32,353 @@ pub struct UserType {
 impl From<domain::User> for UserType {
     fn from(user: domain::User) -> Self {
         Self {
-            id: user.id,
+            id: Id(user.id),
             email: user.email,
             name: user.name,
+            timezone: user.timezone,
+            role: user.role.into(),
             created_at: user.created_at,
             updated_at: user.updated_at,
         }
     }
 }
 
+#[ComplexObject]
+impl UserType {
+    /// The todos this user owns (callers may only see their own)
+    async fn todos(&self, ctx: &Context<'_>) -> Result<Vec<TodoType>> {
+        ensure_self(ctx, self.id.0)?;
+        let pool = &ctx.data::<ReadPool>()?.0;
+        let todos = match todo_feature::TodoService::list_for_user(pool, self.id.0, false).await {
+            Ok(todos) => todos,
+            // A soft-deleted user's todos were removed along with them
+            Err(todo_feature::TodoFeatureError::UserNotFound(_)) => Vec::new(),
+            Err(e) => return Err(to_graphql_error(e)),
+        };
+        Ok(todos.into_iter().map(Into::into).collect())
+    }
+
+    /// How many of this user's todos are neither completed nor cancelled
+    async fn open_todo_count(&self, ctx: &Context<'_>) -> Result<i64> {
+        let loader = ctx.data::<DataLoader<OpenTodoCountLoader>>()?;
+        let count = loader.load_one(self.id.0).await.map_err(to_graphql_error)?;
+        Ok(count.unwrap_or(0))
+    }
+}
+
 /// GraphQL representation of a Todo
 #[derive(SimpleObject)]
+#[graphql(complex)]
 pub struct TodoType {
-    pub id: Uuid,
-    pub user_id: Uuid,
+    pub id: Id,
+    pub user_id: Id,
     pub title: String,
     pub description: Option<String>,
     pub status: TodoStatusType,
+    pub due_date: Option<OffsetDateTime>,
+    #[graphql(skip)]
+    pub started_at: Option<OffsetDateTime>,
+    /// Seconds tracked by finished intervals, not counting a running one
+    pub total_seconds: i64,
     pub created_at: OffsetDateTime,
     pub updated_at: OffsetDateTime,
+    /// When the todo was deleted; only set on todos listed with `includeDeleted`
+    pub deleted_at: Option<OffsetDateTime>,
 }
 
 impl From<domain::Todo> for TodoType {
     fn from(todo: domain::Todo) -> Self {
         Self {
-            id: todo.id,
-            user_id: todo.user_id,
+            id: Id(todo.id),
+            user_id: Id(todo.user_id),
             title: todo.title,
             description: todo.description,
             status: todo.status.into(),
+            due_date: todo.due_date,
+            started_at: todo.started_at,
+            total_seconds: todo.total_seconds,
             created_at: todo.created_at,
             updated_at: todo.updated_at,
+            deleted_at: todo.deleted_at,
+        }
+    }
+}
+
+#[ComplexObject]
+impl TodoType {
+    /// The user who owns this todo
+    async fn owner(&self, ctx: &Context<'_>) -> Result<UserType> {
+        let loader = ctx.data::<DataLoader<UserLoader>>()?;
+        let user = loader
+            .load_one(self.user_id.0)
+            .await
+            .map_err(to_graphql_error)?
+            .ok_or_else(|| format!("User not found: {}", self.user_id.0))?;
+        Ok(user.into())
+    }
+
+    /// The due date in the owner's timezone, as an RFC 3339 string carrying
+    /// their UTC offset
+    ///
+    /// A plain string because `DateTime` values are always sent in UTC.
+    async fn due_date_local(&self, ctx: &Context<'_>) -> Result<Option<String>> {
+        let Some(due_date) = self.due_date else {
+            return Ok(None);
+        };
+
+        let owner = self.owner(ctx).await?;
+        let local = in_timezone(due_date, &owner.timezone).format(&Rfc3339)?;
+        Ok(Some(local))
+    }
+
+    /// The todo's tags, lowercase and in alphabetical order
+    async fn tags(&self, ctx: &Context<'_>) -> Result<Vec<String>> {
+        let loader = ctx.data::<DataLoader<TagLoader>>()?;
+        let tags = loader.load_one(self.id.0).await.map_err(to_graphql_error)?;
+        Ok(tags.unwrap_or_default())
+    }
+
+    /// The todo's checklist, in position order
+    async fn items(&self, ctx: &Context<'_>) -> Result<Vec<TodoItemType>> {
+        let loader = ctx.data::<DataLoader<TodoItemLoader>>()?;
+        let items = loader.load_one(self.id.0).await.map_err(to_graphql_error)?;
+        Ok(items
+            .unwrap_or_default()
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+
+    /// Share of checklist items that are done, from 0 to 1; 0 with no items
+    async fn progress(&self, ctx: &Context<'_>) -> Result<f64> {
+        let items = self.items(ctx).await?;
+        if items.is_empty() {
+            return Ok(0.0);
+        }
+
+        let done = items.iter().filter(|item| item.done).count();
+        Ok(done as f64 / items.len() as f64)
+    }
+
+    /// Whether the todo's time-tracking clock is running
+    async fn is_running(&self) -> bool {
+        self.started_at.is_some()
+    }
+
+    /// Whether the todo is past its due date and still open
+    async fn is_overdue(&self, ctx: &Context<'_>) -> Result<bool> {
+        let now = ctx.data::<SharedClock>()?.now();
+        let status = domain::TodoStatus::from(self.status);
+        Ok(!status.is_closed() && self.due_date.is_some_and(|due| due < now))
+    }
+}
+
+/// Express `at` in the named IANA timezone
+///
+/// Names are validated when saved, so an unknown one leaves `at` unchanged
+/// rather than failing the field.
+fn in_timezone(at: OffsetDateTime, timezone: &str) -> OffsetDateTime {
+    match time_tz::timezones::get_by_name(timezone) {
+        Some(tz) => at.to_timezone(tz),
+        None => at,
+    }
+}
+
+/// GraphQL representation of a checklist item within a todo
+#[derive(SimpleObject)]
+pub struct TodoItemType {
+    pub id: Id,
+    pub todo_id: Id,
+    pub text: String,
+    pub done: bool,
+    pub position: i32,
+}
+
+impl From<domain::TodoItem> for TodoItemType {
+    fn from(item: domain::TodoItem) -> Self {
+        Self {
+            id: Id(item.id),
+            todo_id: Id(item.todo_id),
+            text: item.text,
+            done: item.done,
+            position: item.position,
+        }
+    }
+}
+
+/// Outcome of a delete mutation
+///
+/// `existed` tells a missing entity apart from one that was already deleted,
+/// which comes back as `existed` but not `deleted`.
+#[derive(SimpleObject)]
+pub struct DeleteResult {
+    /// Whether this call deleted the entity
+    pub deleted: bool,
+    /// Whether the entity existed, deleted or not
+    pub existed: bool,
+}
+
+/// A page of todos, fetched by cursor
+#[derive(SimpleObject)]
+pub struct TodoConnection {
+    pub nodes: Vec<TodoType>,
+    pub has_next_page: bool,
+    /// Cursor of the last node, to pass as `after` for the next page
+    pub end_cursor: Option<String>,
+}
+
+/// A page of todos, fetched by offset
+#[derive(SimpleObject)]
+pub struct TodoPageType {
+    pub nodes: Vec<TodoType>,
+    pub has_next_page: bool,
+}
+
+impl From<todo_feature::TodoPage> for TodoPageType {
+    fn from(page: todo_feature::TodoPage) -> Self {
+        Self {
+            nodes: page.todos.into_iter().map(Into::into).collect(),
+            has_next_page: page.has_next_page,
+        }
+    }
+}
+
+impl From<domain::Page<domain::Todo>> for TodoConnection {
+    fn from(page: domain::Page<domain::Todo>) -> Self {
+        Self {
+            end_cursor: page.end_cursor().map(|cursor| cursor.encode()),
+            nodes: page.items.into_iter().map(Into::into).collect(),
+            has_next_page: page.has_next_page,
+        }
+    }
+}
+
+/// A page of users
+#[derive(SimpleObject)]
+pub struct UserConnection {
+    pub nodes: Vec<UserType>,
+    pub has_next_page: bool,
+}
+
+impl From<domain::Page<domain::User>> for UserConnection {
+    fn from(page: domain::Page<domain::User>) -> Self {
+        Self {
+            nodes: page.items.into_iter().map(Into::into).collect(),
+            has_next_page: page.has_next_page,
+        }
+    }
+}
+
+/// How many users registered on one UTC day
+#[derive(SimpleObject)]
+pub struct RegistrationBucketType {
+    pub day: Date,
+    pub count: i64,
+}
+
+impl From<(Date, i64)> for RegistrationBucketType {
+    fn from((day, count): (Date, i64)) -> Self {
+        Self { day, count }
+    }
+}
+
+/// Connection counts for the primary database pool
+#[derive(SimpleObject)]
+pub struct DbPoolStatsType {
+    /// Open connections, idle or checked out
+    pub size: u32,
+    pub idle: u32,
+    pub in_use: u32,
+}
+
+impl From<&PgPool> for DbPoolStatsType {
+    fn from(pool: &PgPool) -> Self {
+        let size = pool.size();
+        // The two counters are read separately, so idle can briefly exceed size
+        let idle = (pool.num_idle() as u32).min(size);
+        Self {
+            size,
+            idle,
+            in_use: size - idle,
+        }
+    }
+}
+
+/// Count of a user's todos in each status
+#[derive(SimpleObject)]
+pub struct TodoStatusSummaryType {
+    pub pending: i64,
+    pub in_progress: i64,
+    pub completed: i64,
+    pub cancelled: i64,
+}
+
+impl From<todo_feature::TodoStatusSummary> for TodoStatusSummaryType {
+    fn from(summary: todo_feature::TodoStatusSummary) -> Self {
+        Self {
+            pending: summary.pending,
+            in_progress: summary.in_progress,
+            completed: summary.completed,
+            cancelled: summary.cancelled,
+        }
+    }
+}
+
+/// A background job waiting in the queue
+#[derive(SimpleObject)]
+pub struct JobType {
+    pub id: Id,
+    pub channel_name: String,
+    pub name: Option<String>,
+    /// Runs left before the job is given up on
+    pub attempts: i32,
+    /// When the job is next due to run; null once it is out of attempts
+    pub scheduled_at: Option<OffsetDateTime>,
+    /// Whether a runner would pick the job up now
+    pub is_runnable: bool,
+}
+
+impl From<domain::Job> for JobType {
+    fn from(job: domain::Job) -> Self {
+        Self {
+            id: Id(job.id),
+            channel_name: job.channel_name,
+            name: job.name,
+            attempts: job.attempts,
+            scheduled_at: job.scheduled_at,
+            is_runnable: job.is_runnable,
+        }
+    }
+}
+
+/// Order to list a user's todos in
+#[derive(Enum, Copy, Clone, Eq, PartialEq, Default)]
+pub enum TodoSortType {
+    /// Newest first
+    #[default]
+    Newest,
+    /// The order set with `reorderTodos`, then newest first
+    ByPosition,
+}
+
+/// Order to list users in
+#[derive(Enum, Copy, Clone, Eq, PartialEq, Default)]
+pub enum UserSortType {
+    /// Most recently registered first
+    #[default]
+    Newest,
+    /// Earliest registered first
+    Oldest,
+    /// Alphabetical by name
+    Name,
+}
+
+impl From<UserSortType> for domain::UserSort {
+    fn from(sort: UserSortType) -> Self {
+        match sort {
+            UserSortType::Newest => domain::UserSort::Newest,
+            UserSortType::Oldest => domain::UserSort::Oldest,
+            UserSortType::Name => domain::UserSort::Name,
         }
     }
 }
@@ -56,6 +389,7 @@ pub enum TodoStatusType {
     Pending,
     InProgress,
     Completed,
+    Cancelled,
 }
 
 impl From<domain::TodoStatus> for TodoStatusType {
@@ -64,6 +398,7 @@ impl From<domain::TodoStatus> for TodoStatusType {
             domain::TodoStatus::Pending => TodoStatusType::Pending,
             domain::TodoStatus::InProgress => TodoStatusType::InProgress,
             domain::TodoStatus::Completed => TodoStatusType::Completed,
+            domain::TodoStatus::Cancelled => TodoStatusType::Cancelled,
         }
     }
 }
@@ -74,29 +409,175 @@ impl From<TodoStatusType> for domain::TodoStatus {
             TodoStatusType::Pending => domain::TodoStatus::Pending,
             TodoStatusType::InProgress => domain::TodoStatus::InProgress,
             TodoStatusType::Completed => domain::TodoStatus::Completed,
+            TodoStatusType::Cancelled => domain::TodoStatus::Cancelled,
         }
     }
 }
 
+/// GraphQL enum for a user's role
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+pub enum UserRoleType {
+    User,
+    Admin,
+}
+
+impl From<domain::UserRole> for UserRoleType {
+    fn from(role: domain::UserRole) -> Self {
+        match role {
+            domain::UserRole::User => UserRoleType::User,
+            domain::UserRole::Admin => UserRoleType::Admin,
+        }
+    }
+}
+
+/// An email address, rejected while the query is parsed if malformed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Email(pub String);
+
+#[Scalar]
+impl ScalarType for Email {
+    fn parse(value: Value) -> InputValueResult<Self> {
+        let Value::String(email) = &value else {
+            return Err(InputValueError::expected_type(value));
+        };
+
+        let email = email.trim();
+        if user_feature::is_valid_email(email) {
+            Ok(Email(email.to_string()))
+        } else {
+            Err(InputValueError::custom(format!(
+                "{email:?} is not a valid email address"
+            )))
+        }
+    }
+
+    fn to_value(&self) -> Value {
+        Value::String(self.0.clone())
+    }
+}
+
+/// Message for an ID argument that doesn't parse
+const INVALID_ID_MESSAGE: &str =
+    "invalid id format: expected a UUID such as \"123e4567-e89b-12d3-a456-426614174000\"";
+
+/// A user, todo, item or job ID
+///
+/// Exposed as the `UUID` scalar. Malformed IDs are rejected with a
+/// `VALIDATION` error while the arguments are parsed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Id(pub Uuid);
+
+impl Id {
+    /// The UUIDs of a list of IDs, in the same order
+    pub fn uuids(ids: Vec<Id>) -> Vec<Uuid> {
+        ids.into_iter().map(|id| id.0).collect()
+    }
+}
+
+#[Scalar(name = "UUID")]
+impl ScalarType for Id {
+    fn parse(value: Value) -> InputValueResult<Self> {
+        let Value::String(id) = &value else {
+            return Err(InputValueError::expected_type(value).with_extension("code", "VALIDATION"));
+        };
+
+        Uuid::parse_str(id).map(Id).map_err(|_| {
+            InputValueError::custom(INVALID_ID_MESSAGE).with_extension("code", "VALIDATION")
+        })
+    }
+
+    fn to_value(&self) -> Value {
+        Value::String(self.0.to_string())
+    }
+}
+
 /// Input for creating a user
 #[derive(InputObject)]
 pub struct CreateUserInput {
+    /// Validated with the other fields, so every invalid one is reported at
+    /// once under `extensions.fields`
     pub email: String,
     pub name: String,
+    /// IANA timezone name, such as `Europe/London`; defaults to UTC
+    pub timezone: Option<String>,
+    /// Repeating a registration with the same key returns the user it created
+    pub idempotency_key: Option<String>,
 }
 
 /// Input for updating a user
 #[derive(InputObject)]
 pub struct UpdateUserInput {
     pub name: Option<String>,
+    pub email: Option<String>,
+    pub timezone: Option<String>,
 }
 
 /// Input for creating a todo
 #[derive(InputObject)]
 pub struct CreateTodoInput {
-    pub user_id: Uuid,
+    pub user_id: Id,
+    pub title: String,
+    pub description: Option<String>,
+    pub due_date: Option<OffsetDateTime>,
+}
+
+/// A single todo in a batch create
+#[derive(InputObject)]
+pub struct CreateTodoItem {
     pub title: String,
     pub description: Option<String>,
+    pub due_date: Option<OffsetDateTime>,
+}
+
+/// Criteria for listing a user's todos; every field set must match
+#[derive(InputObject, Default)]
+pub struct TodoFilter {
+    /// Todos in any of these statuses match
+    pub statuses: Option<Vec<TodoStatusType>>,
+    /// Text matched case-insensitively against title or description
+    pub query: Option<String>,
+    pub tag: Option<String>,
+    pub created_after: Option<OffsetDateTime>,
+    pub created_before: Option<OffsetDateTime>,
+    pub due_before: Option<OffsetDateTime>,
+}
+
+impl From<TodoFilter> for todo_feature::TodoFilter {
+    fn from(filter: TodoFilter) -> Self {
+        Self {
+            statuses: filter
+                .statuses
+                .unwrap_or_default()
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            query: filter.query,
+            tag: filter.tag,
+            created_after: filter.created_after,
+            created_before: filter.created_before,
+            due_before: filter.due_before,
+        }
+    }
+}
+
+/// Criteria for listing users; every field set must match
+#[derive(InputObject, Default)]
+pub struct UserFilter {
+    /// Text matched case-insensitively against name or email
+    pub query: Option<String>,
+    pub created_after: Option<OffsetDateTime>,
+    #[graphql(default)]
+    pub include_deleted: bool,
+}
+
+impl From<UserFilter> for domain::UserFilter {
+    fn from(filter: UserFilter) -> Self {
+        Self {
+            query: filter.query,
+            created_after: filter.created_after,
+            include_deleted: filter.include_deleted,
+        }
+    }
 }
 
 /// Input for updating a todo
@@ -105,4 +586,6 @@ pub struct UpdateTodoInput {
     pub title: Option<String>,
     pub description: Option<String>,
     pub status: Option<TodoStatusType>,
+    /// Reject the update if the todo changed after this time
+    pub expected_updated_at: Option<OffsetDateTime>,
 }
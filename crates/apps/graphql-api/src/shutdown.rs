@@ -0,0 +1,56 @@
+use std::time::Duration;
+
+use sqlxmq::JobRunnerHandle;
+use tracing::{info, warn};
+
+/// How long shutdown waits for in-flight jobs before giving up on them
+pub const JOB_DRAIN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Resolve when the process is asked to stop (Ctrl+C, or SIGTERM on Unix)
+pub async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("Failed to install Ctrl+C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("Failed to install SIGTERM handler")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+
+    info!("Shutdown signal received");
+}
+
+/// Stop a job runner from picking up new work, then wait for its running
+/// jobs to finish
+///
+/// Jobs still running after `timeout` are left to sqlxmq's retry handling,
+/// so they run again on the next start rather than being lost.
+pub async fn drain_job_runner(name: &str, runner: &mut JobRunnerHandle, timeout: Duration) {
+    runner.stop().await;
+
+    let running = runner.num_running_jobs();
+    if running > 0 {
+        info!(runner = name, running, "Waiting for in-flight jobs");
+        runner.wait_jobs_finish(timeout).await;
+    }
+
+    let remaining = runner.num_running_jobs();
+    if remaining > 0 {
+        warn!(runner = name, remaining, "Jobs still running at timeout");
+    } else {
+        info!(runner = name, "Job runner drained");
+    }
+}
@@ -0,0 +1,250 @@
+use std::env;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Instant;
+
+use async_graphql::extensions::{
+    Extension, ExtensionContext, ExtensionFactory, NextExecute, NextParseQuery, NextResolve,
+    ResolveInfo,
+};
+use async_graphql::parser::types::{ExecutableDocument, OperationType};
+use async_graphql::{Response, ServerResult, Value, Variables};
+use axum::extract::State;
+use metrics::{counter, gauge, histogram};
+use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
+use sqlx::PgPool;
+use thiserror::Error;
+use tracing::{Instrument, Subscriber, info_span};
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::{EnvFilter, Layer};
+
+/// Log directives used when `RUST_LOG` is unset
+const DEFAULT_LOG_FILTER: &str =
+    "graphql_api=debug,user_feature=debug,todo_feature=debug,sqlx=warn";
+
+#[derive(Error, Debug, PartialEq)]
+pub enum LogFormatError {
+    #[error("LOG_FORMAT must be \"json\" or \"pretty\", got {0:?}")]
+    Unknown(String),
+}
+
+/// How log lines are written
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum LogFormat {
+    /// Human-readable lines
+    #[default]
+    Pretty,
+    /// One JSON object per line, for log aggregation
+    Json,
+}
+
+impl LogFormat {
+    /// Read the format from `LOG_FORMAT`
+    pub fn from_env() -> Result<Self, LogFormatError> {
+        Self::from_vars(|name| env::var(name).ok())
+    }
+
+    /// Pick the format from a variable lookup, defaulting to `Pretty` when unset
+    pub fn from_vars(var: impl Fn(&str) -> Option<String>) -> Result<Self, LogFormatError> {
+        match var("LOG_FORMAT") {
+            None => Ok(Self::default()),
+            Some(value) => match value.trim().to_ascii_lowercase().as_str() {
+                "pretty" => Ok(Self::Pretty),
+                "json" => Ok(Self::Json),
+                _ => Err(LogFormatError::Unknown(value)),
+            },
+        }
+    }
+}
+
+/// Build the tracing subscriber, writing logs in `format` to `writer`
+///
+/// Events are filtered by `RUST_LOG`, falling back to debug logging for this
+/// workspace's crates.
+pub fn subscriber<W>(format: LogFormat, writer: W) -> impl Subscriber + Send + Sync
+where
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    let filter =
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(DEFAULT_LOG_FILTER));
+    let fmt = match format {
+        LogFormat::Pretty => tracing_subscriber::fmt::layer().with_writer(writer).boxed(),
+        LogFormat::Json => tracing_subscriber::fmt::layer()
+            .json()
+            .with_writer(writer)
+            .boxed(),
+    };
+
+    tracing_subscriber::registry().with(filter).with(fmt)
+}
+
+/// GraphQL operations executed, labelled by `operation` type
+pub const GRAPHQL_REQUESTS: &str = "graphql_requests_total";
+
+/// Time to execute a GraphQL operation, labelled by `operation` type
+pub const GRAPHQL_REQUEST_DURATION: &str = "graphql_request_duration_seconds";
+
+/// Time spent in each resolver, labelled by `field` as `Type.field`
+pub const GRAPHQL_RESOLVER_DURATION: &str = "graphql_resolver_duration_seconds";
+
+/// Connections currently open in the database pool
+pub const DB_POOL_CONNECTIONS: &str = "db_pool_connections";
+
+/// Open connections in the database pool not in use
+pub const DB_POOL_IDLE_CONNECTIONS: &str = "db_pool_idle_connections";
+
+/// Histogram buckets in seconds, from 5ms to 10s
+const DURATION_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+static PROMETHEUS: OnceLock<PrometheusHandle> = OnceLock::new();
+
+/// Install the global Prometheus recorder, returning the handle that renders it
+///
+/// Only the first call installs anything; later calls return the same handle.
+pub fn install_recorder() -> &'static PrometheusHandle {
+    PROMETHEUS.get_or_init(|| {
+        PrometheusBuilder::new()
+            .set_buckets(DURATION_BUCKETS)
+            .expect("Duration buckets are not empty")
+            .install_recorder()
+            .expect("Failed to install Prometheus recorder")
+    })
+}
+
+/// Metrics handler
+///
+/// Renders every recorded metric in the Prometheus text format, refreshing
+/// the pool gauges first.
+pub async fn metrics(State(pool): State<PgPool>) -> String {
+    gauge!(DB_POOL_CONNECTIONS).set(pool.size() as f64);
+    gauge!(DB_POOL_IDLE_CONNECTIONS).set(pool.num_idle() as f64);
+
+    install_recorder().render()
+}
+
+/// Schema extension recording request counts and resolver latency
+///
+/// Requests are labelled by operation type rather than by the name the
+/// client chose, so the number of label values stays bounded.
+pub struct GraphQLMetrics;
+
+impl ExtensionFactory for GraphQLMetrics {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(GraphQLMetricsExtension::default())
+    }
+}
+
+#[derive(Default)]
+struct GraphQLMetricsExtension {
+    /// The type of each operation in the request's document, by name
+    operations: Mutex<Vec<(Option<String>, OperationType)>>,
+}
+
+#[async_graphql::async_trait::async_trait]
+impl Extension for GraphQLMetricsExtension {
+    async fn parse_query(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        query: &str,
+        variables: &Variables,
+        next: NextParseQuery<'_>,
+    ) -> ServerResult<ExecutableDocument> {
+        let document = next.run(ctx, query, variables).await?;
+
+        *self.operations.lock().unwrap() = document
+            .operations
+            .iter()
+            .map(|(name, operation)| (name.map(|name| name.to_string()), operation.node.ty))
+            .collect();
+        Ok(document)
+    }
+
+    async fn execute(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        operation_name: Option<&str>,
+        next: NextExecute<'_>,
+    ) -> Response {
+        let operation = self.operation_type(operation_name);
+        let started = Instant::now();
+
+        let response = next.run(ctx, operation_name).await;
+
+        counter!(GRAPHQL_REQUESTS, "operation" => operation).increment(1);
+        histogram!(GRAPHQL_REQUEST_DURATION, "operation" => operation)
+            .record(started.elapsed().as_secs_f64());
+        response
+    }
+
+    async fn resolve(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        info: ResolveInfo<'_>,
+        next: NextResolve<'_>,
+    ) -> ServerResult<Option<Value>> {
+        if info.is_for_introspection {
+            return next.run(ctx, info).await;
+        }
+
+        let field = format!("{}.{}", info.parent_type, info.name);
+        let started = Instant::now();
+
+        let result = next.run(ctx, info).await;
+
+        histogram!(GRAPHQL_RESOLVER_DURATION, "field" => field)
+            .record(started.elapsed().as_secs_f64());
+        result
+    }
+}
+
+impl GraphQLMetricsExtension {
+    /// The `operation` label for the operation being executed: `query`,
+    /// `mutation` or `subscription`
+    fn operation_type(&self, operation_name: Option<&str>) -> &'static str {
+        let operations = self.operations.lock().unwrap();
+        let ty = match operation_name {
+            Some(wanted) => operations
+                .iter()
+                .find(|(name, _)| name.as_deref() == Some(wanted))
+                .map(|(_, ty)| *ty),
+            None => operations.first().map(|(_, ty)| *ty),
+        };
+
+        match ty {
+            Some(OperationType::Query) => "query",
+            Some(OperationType::Mutation) => "mutation",
+            Some(OperationType::Subscription) => "subscription",
+            None => "unknown",
+        }
+    }
+}
+
+/// Schema extension running each GraphQL operation in a `graphql_request`
+/// span carrying its `operation` name
+pub struct GraphQLTracing;
+
+impl ExtensionFactory for GraphQLTracing {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(GraphQLTracingExtension)
+    }
+}
+
+struct GraphQLTracingExtension;
+
+#[async_graphql::async_trait::async_trait]
+impl Extension for GraphQLTracingExtension {
+    async fn execute(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        operation_name: Option<&str>,
+        next: NextExecute<'_>,
+    ) -> Response {
+        let span = info_span!(
+            "graphql_request",
+            operation = operation_name.unwrap_or("anonymous")
+        );
+        next.run(ctx, operation_name).instrument(span).await
+    }
+}
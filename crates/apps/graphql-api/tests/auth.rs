@@ -0,0 +1,401 @@
+//! Authentication tests
+//!
+//! These send HTTP requests through the full router so the bearer token
+//! middleware runs exactly as it does in production.
+
+use axum::body::{Body, to_bytes};
+use axum::http::{Request, StatusCode, header};
+use domain::UserRole;
+use graphql_api::app::{AppState, DEFAULT_GRAPHQL_TIMEOUT, DEFAULT_MAX_REQUEST_BYTES, router};
+use graphql_api::auth::{AdminAllowlist, AdminConfigError, JwtAuth, authenticate_connection};
+use graphql_api::build_schema;
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use time::Duration;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+const SECRET: &[u8] = b"test-secret";
+
+/// The only user on the admin allowlist
+const ADMIN_ID: Uuid = Uuid::from_u128(0xad);
+
+/// A bearer header for `user_id`
+fn bearer(user_id: Uuid) -> String {
+    let token = JwtAuth::new(SECRET)
+        .issue(user_id, Duration::hours(1))
+        .unwrap();
+    format!("Bearer {}", token)
+}
+
+/// POST a GraphQL query to the router, optionally with an Authorization header
+async fn post_graphql(
+    pool: &PgPool,
+    query: &str,
+    authorization: Option<&str>,
+) -> (StatusCode, Value) {
+    let app = router(AppState {
+        pool: pool.clone(),
+        schema: build_schema(pool.clone()),
+        auth: JwtAuth::new(SECRET).with_admins(AdminAllowlist::new([ADMIN_ID])),
+        http_status_codes: false,
+        max_request_bytes: DEFAULT_MAX_REQUEST_BYTES,
+        graphql_timeout: DEFAULT_GRAPHQL_TIMEOUT,
+    });
+
+    let mut request = Request::post("/graphql").header(header::CONTENT_TYPE, "application/json");
+    if let Some(value) = authorization {
+        request = request.header(header::AUTHORIZATION, value);
+    }
+    let request = request
+        .body(Body::from(json!({ "query": query }).to_string()))
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+async fn create_test_user(pool: &PgPool, email: &str) -> Uuid {
+    user_feature::UserService::register(
+        pool,
+        user_feature::CreateUserInput {
+            email: email.to_string(),
+            name: "Auth User".to_string(),
+            timezone: None,
+            idempotency_key: None,
+        },
+    )
+    .await
+    .expect("Failed to create user")
+    .id
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn me_resolves_with_valid_token(pool: PgPool) {
+    let user_id = create_test_user(&pool, "me@test.com").await;
+    let token = JwtAuth::new(SECRET)
+        .issue(user_id, Duration::hours(1))
+        .unwrap();
+
+    let (status, body) = post_graphql(
+        &pool,
+        "query { me { id email } }",
+        Some(&format!("Bearer {}", token)),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["me"]["id"], user_id.to_string());
+    assert_eq!(body["data"]["me"]["email"], "me@test.com");
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn me_is_null_without_token(pool: PgPool) {
+    let (status, body) = post_graphql(&pool, "query { me { id } }", None).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["errors"].is_null());
+    assert!(body["data"]["me"].is_null());
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn invalid_token_is_rejected(pool: PgPool) {
+    let user_id = create_test_user(&pool, "forged@test.com").await;
+    let forged = JwtAuth::new(b"some-other-secret")
+        .issue(user_id, Duration::hours(1))
+        .unwrap();
+
+    let (status, body) = post_graphql(
+        &pool,
+        "query { me { id } }",
+        Some(&format!("Bearer {}", forged)),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body["errors"][0]["extensions"]["code"], "UNAUTHENTICATED");
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn expired_token_is_rejected(pool: PgPool) {
+    let user_id = create_test_user(&pool, "expired@test.com").await;
+    let expired = JwtAuth::new(SECRET)
+        .issue(user_id, Duration::hours(-1))
+        .unwrap();
+
+    let (status, _) = post_graphql(
+        &pool,
+        "query { me { id } }",
+        Some(&format!("Bearer {}", expired)),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn subscription_upgrade_with_invalid_token_is_rejected(pool: PgPool) {
+    let app = router(AppState {
+        pool: pool.clone(),
+        schema: build_schema(pool.clone()),
+        auth: JwtAuth::new(SECRET),
+        http_status_codes: false,
+        max_request_bytes: DEFAULT_MAX_REQUEST_BYTES,
+        graphql_timeout: DEFAULT_GRAPHQL_TIMEOUT,
+    });
+    let forged = JwtAuth::new(b"some-other-secret")
+        .issue(Uuid::new_v4(), Duration::hours(1))
+        .unwrap();
+
+    let request = Request::get("/ws")
+        .header(header::AUTHORIZATION, format!("Bearer {}", forged))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn connection_init_authenticates_bearer_tokens() {
+    let auth = JwtAuth::new(SECRET);
+
+    // No credentials leaves the connection anonymous
+    assert!(
+        authenticate_connection(auth.clone(), json!({}))
+            .await
+            .is_ok()
+    );
+
+    assert!(
+        authenticate_connection(auth.clone(), json!({ "Authorization": bearer(ADMIN_ID) }))
+            .await
+            .is_ok()
+    );
+
+    // A bad token refuses the connection
+    let forged = JwtAuth::new(b"some-other-secret")
+        .issue(ADMIN_ID, Duration::hours(1))
+        .unwrap();
+    let payload = json!({ "Authorization": format!("Bearer {}", forged) });
+    assert!(
+        authenticate_connection(auth.clone(), payload)
+            .await
+            .is_err()
+    );
+    assert!(
+        authenticate_connection(auth, json!({ "Authorization": "Token abc" }))
+            .await
+            .is_err()
+    );
+}
+
+#[test]
+fn admin_allowlist_is_read_from_comma_separated_ids() {
+    let allowlist = AdminAllowlist::from_vars(|name| {
+        (name == "ADMIN_USER_IDS").then(|| format!(" {ADMIN_ID} ,{},", Uuid::from_u128(2)))
+    })
+    .unwrap();
+
+    assert!(allowlist.contains(ADMIN_ID));
+    assert!(allowlist.contains(Uuid::from_u128(2)));
+    assert!(!allowlist.contains(Uuid::from_u128(3)));
+    assert_eq!(
+        AdminAllowlist::from_vars(|_| None),
+        Ok(AdminAllowlist::default())
+    );
+}
+
+#[test]
+fn admin_allowlist_rejects_invalid_ids() {
+    let result = AdminAllowlist::from_vars(|_| Some("not-a-uuid".to_string()));
+
+    assert_eq!(
+        result,
+        Err(AdminConfigError::InvalidUserId("not-a-uuid".to_string()))
+    );
+}
+
+async fn create_todo(pool: &PgPool, user_id: Uuid, title: &str) {
+    todo_feature::TodoService::create(
+        pool,
+        todo_feature::CreateTodoInput {
+            user_id,
+            title: title.to_string(),
+            description: None,
+            due_date: None,
+        },
+    )
+    .await
+    .expect("Failed to create todo");
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn all_todos_is_forbidden_for_non_admins(pool: PgPool) {
+    // A regular user, with the default role
+    let user_id = create_test_user(&pool, "not-admin@test.com").await;
+
+    let (status, body) = post_graphql(
+        &pool,
+        "query { allTodos { nodes { id } } }",
+        Some(&bearer(user_id)),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["data"].is_null());
+    assert_eq!(body["errors"][0]["extensions"]["code"], "FORBIDDEN");
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn admin_sees_todos_from_every_user(pool: PgPool) {
+    let alice = create_test_user(&pool, "alice@test.com").await;
+    let bob = create_test_user(&pool, "bob@test.com").await;
+    create_todo(&pool, alice, "Alice's task").await;
+    create_todo(&pool, bob, "Bob's task").await;
+
+    let (status, body) = post_graphql(
+        &pool,
+        "query { allTodos(first: 10) { nodes { userId } hasNextPage } }",
+        Some(&bearer(ADMIN_ID)),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["errors"].is_null());
+    let owners: Vec<&str> = body["data"]["allTodos"]["nodes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|todo| todo["userId"].as_str().unwrap())
+        .collect();
+    assert_eq!(owners, [bob.to_string(), alice.to_string()]);
+    assert_eq!(body["data"]["allTodos"]["hasNextPage"], false);
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn all_users_is_admin_only(pool: PgPool) {
+    let user_id = create_test_user(&pool, "not-admin-users@test.com").await;
+    create_test_user(&pool, "other@test.com").await;
+    let query = r#"query { allUsers(filter: { query: "test.com" }, sort: OLDEST, limit: 1) {
+        nodes { email } hasNextPage
+    } }"#;
+
+    let (_, body) = post_graphql(&pool, query, Some(&bearer(user_id))).await;
+    assert_eq!(body["errors"][0]["extensions"]["code"], "FORBIDDEN");
+
+    let (status, body) = post_graphql(&pool, query, Some(&bearer(ADMIN_ID))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["errors"].is_null());
+    assert_eq!(
+        body["data"]["allUsers"]["nodes"][0]["email"],
+        "not-admin-users@test.com"
+    );
+    assert_eq!(body["data"]["allUsers"]["hasNextPage"], true);
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn admin_role_grants_admin_only_fields(pool: PgPool) {
+    // Given a user who is not on the allowlist but has the admin role
+    let user_id = create_test_user(&pool, "promoted@test.com").await;
+    user_feature::UserService::set_role(&pool, user_id, UserRole::Admin)
+        .await
+        .unwrap();
+
+    let (status, body) = post_graphql(
+        &pool,
+        "query { me { role } allTodos { nodes { id } } }",
+        Some(&bearer(user_id)),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["errors"].is_null());
+    assert_eq!(body["data"]["me"]["role"], "ADMIN");
+    assert!(body["data"]["allTodos"]["nodes"].is_array());
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn jobs_is_forbidden_for_non_admins(pool: PgPool) {
+    let user_id = create_test_user(&pool, "not-operator@test.com").await;
+
+    let (status, body) = post_graphql(
+        &pool,
+        r#"query { jobs(channel: "emails") { id } }"#,
+        Some(&bearer(user_id)),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["data"].is_null());
+    assert_eq!(body["errors"][0]["extensions"]["code"], "FORBIDDEN");
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn admin_sees_pending_welcome_email_job(pool: PgPool) {
+    // Registering a user queues their welcome email
+    create_test_user(&pool, "queued@test.com").await;
+
+    let (status, body) = post_graphql(
+        &pool,
+        r#"query {
+            jobs(channel: "emails", limit: 10) {
+                id channelName name attempts scheduledAt isRunnable
+            }
+        }"#,
+        Some(&bearer(ADMIN_ID)),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["errors"].is_null());
+    let jobs = body["data"]["jobs"].as_array().unwrap();
+    assert_eq!(jobs.len(), 1);
+    assert_eq!(jobs[0]["channelName"], "emails");
+    assert!(
+        jobs[0]["name"]
+            .as_str()
+            .unwrap()
+            .ends_with("send_welcome_email")
+    );
+    assert!(jobs[0]["attempts"].as_i64().unwrap() > 0);
+    assert!(jobs[0]["scheduledAt"].is_string());
+    assert_eq!(jobs[0]["isRunnable"], true);
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn db_pool_stats_is_forbidden_for_non_admins(pool: PgPool) {
+    let user_id = create_test_user(&pool, "not-dba@test.com").await;
+
+    let (status, body) = post_graphql(
+        &pool,
+        "query { dbPoolStats { size } }",
+        Some(&bearer(user_id)),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["data"].is_null());
+    assert_eq!(body["errors"][0]["extensions"]["code"], "FORBIDDEN");
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn admin_sees_db_pool_stats(pool: PgPool) {
+    let (status, body) = post_graphql(
+        &pool,
+        "query { dbPoolStats { size idle inUse } }",
+        Some(&bearer(ADMIN_ID)),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["errors"].is_null());
+    let stats = &body["data"]["dbPoolStats"];
+    let size = stats["size"].as_u64().unwrap();
+    let idle = stats["idle"].as_u64().unwrap();
+    let in_use = stats["inUse"].as_u64().unwrap();
+    assert!(size >= idle);
+    assert_eq!(in_use, size - idle);
+}
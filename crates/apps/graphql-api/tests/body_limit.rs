@@ -0,0 +1,50 @@
+//! Request body size limit tests
+//!
+//! GraphQL request bodies larger than `max_request_bytes` are refused with
+//! 413 before any of the query is parsed.
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode, header};
+use graphql_api::app::{AppState, DEFAULT_GRAPHQL_TIMEOUT, DEFAULT_MAX_REQUEST_BYTES, router};
+use graphql_api::auth::JwtAuth;
+use graphql_api::build_schema;
+use serde_json::json;
+use sqlx::PgPool;
+use tower::ServiceExt;
+
+/// POST a GraphQL query padded to at least `padding` bytes, returning the status
+async fn post_padded(pool: &PgPool, padding: usize) -> StatusCode {
+    let app = router(AppState {
+        pool: pool.clone(),
+        schema: build_schema(pool.clone()),
+        auth: JwtAuth::new(b"test-secret"),
+        http_status_codes: false,
+        max_request_bytes: DEFAULT_MAX_REQUEST_BYTES,
+        graphql_timeout: DEFAULT_GRAPHQL_TIMEOUT,
+    });
+
+    // Whitespace is ignored by the GraphQL parser, so only the size changes
+    let query = format!("query {{ users {{ id }} }}{}", " ".repeat(padding));
+    let body = json!({ "query": query }).to_string();
+    let request = Request::post("/graphql")
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(header::CONTENT_LENGTH, body.len())
+        .body(Body::from(body))
+        .unwrap();
+
+    app.oneshot(request).await.unwrap().status()
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn oversized_body_is_payload_too_large(pool: PgPool) {
+    let status = post_padded(&pool, DEFAULT_MAX_REQUEST_BYTES + 1).await;
+
+    assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn normal_sized_body_is_accepted(pool: PgPool) {
+    let status = post_padded(&pool, 1024).await;
+
+    assert_eq!(status, StatusCode::OK);
+}
@@ -7,6 +7,7 @@
 //! contract is correct (right fields, right types, right error format).
 
 use async_graphql::Request;
+use graphql_api::auth::AuthContext;
 use graphql_api::build_schema;
 use serde_json::Value;
 use sqlx::PgPool;
@@ -18,6 +19,33 @@ async fn execute(pool: &PgPool, query: &str) -> Value {
     serde_json::to_value(&response).expect("Failed to serialize response")
 }
 
+/// Execute a GraphQL query authenticated as the given user
+async fn execute_as(pool: &PgPool, user_id: &str, query: &str) -> Value {
+    let schema = build_schema(pool.clone());
+    let auth = AuthContext {
+        user_id: user_id.parse().expect("Invalid user ID"),
+        is_admin: false,
+    };
+    let response = schema.execute(Request::new(query).data(auth)).await;
+    serde_json::to_value(&response).expect("Failed to serialize response")
+}
+
+/// Register a user with the given email, returning their ID
+async fn register_user(pool: &PgPool, email: &str) -> String {
+    let response = execute(
+        pool,
+        &format!(
+            r#"mutation {{ registerUser(input: {{ email: "{}", name: "User" }}) {{ id }} }}"#,
+            email
+        ),
+    )
+    .await;
+    response["data"]["registerUser"]["id"]
+        .as_str()
+        .expect("Failed to register user")
+        .to_string()
+}
+
 /// Assert response has no errors
 fn assert_no_errors(response: &Value) {
     let errors = &response["errors"];
@@ -43,6 +71,7 @@ fn assert_has_errors(response: &Value) {
 
 mod user_mutations {
     use super::*;
+    use serde_json::json;
 
     #[sqlx::test(migrations = "../../../migrations")]
     async fn register_user_returns_user_type(pool: PgPool) {
@@ -79,6 +108,58 @@ mod user_mutations {
         );
     }
 
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn register_user_with_unknown_timezone_is_validation_error(pool: PgPool) {
+        let response = execute(
+            &pool,
+            r#"mutation { registerUser(input: { email: "tz@test.com", name: "User", timezone: "Nowhere/Special" }) { id } }"#,
+        )
+        .await;
+
+        assert_has_errors(&response);
+        assert_eq!(response["errors"][0]["extensions"]["code"], "VALIDATION");
+    }
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn register_user_with_malformed_email_is_validation_error(pool: PgPool) {
+        let response = execute(
+            &pool,
+            r#"mutation { registerUser(input: { email: "bad", name: "User" }) { id } }"#,
+        )
+        .await;
+
+        assert_has_errors(&response);
+        let extensions = &response["errors"][0]["extensions"];
+        assert_eq!(extensions["code"], "VALIDATION");
+        assert_eq!(extensions["fields"][0]["field"], "email");
+
+        let users: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(users, 0);
+    }
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn register_user_reports_every_invalid_field(pool: PgPool) {
+        let response = execute(
+            &pool,
+            r#"mutation { registerUser(input: { email: "bad", name: "  " }) { id } }"#,
+        )
+        .await;
+
+        assert_has_errors(&response);
+        let extensions = &response["errors"][0]["extensions"];
+        assert_eq!(extensions["code"], "VALIDATION");
+        assert_eq!(
+            extensions["fields"],
+            json!([
+                { "field": "email", "message": "must be a valid email address" },
+                { "field": "name", "message": "must not be empty" },
+            ])
+        );
+    }
+
     #[sqlx::test(migrations = "../../../migrations")]
     async fn register_user_error_is_graphql_error(pool: PgPool) {
         // First registration
@@ -101,6 +182,8 @@ mod user_mutations {
         assert!(!errors.is_empty());
         // Each error should have a message field
         assert!(errors[0]["message"].is_string());
+        // And a stable code clients can match on
+        assert_eq!(errors[0]["extensions"]["code"], "EMAIL_EXISTS");
     }
 
     #[sqlx::test(migrations = "../../../migrations")]
@@ -116,8 +199,9 @@ mod user_mutations {
             .unwrap();
 
         // Update
-        let response = execute(
+        let response = execute_as(
             &pool,
+            user_id,
             &format!(
                 r#"mutation {{ updateUser(id: "{}", input: {{ name: "After" }}) {{ id email name }} }}"#,
                 user_id
@@ -133,7 +217,163 @@ mod user_mutations {
     }
 
     #[sqlx::test(migrations = "../../../migrations")]
-    async fn delete_user_returns_boolean(pool: PgPool) {
+    async fn update_user_with_invalid_email_returns_error(pool: PgPool) {
+        let create_response = execute(
+            &pool,
+            r#"mutation { registerUser(input: { email: "bad-email@test.com", name: "User" }) { id } }"#,
+        )
+        .await;
+        let user_id = create_response["data"]["registerUser"]["id"]
+            .as_str()
+            .unwrap();
+
+        let response = execute_as(
+            &pool,
+            user_id,
+            &format!(
+                r#"mutation {{ updateUser(id: "{}", input: {{ email: "nope" }}) {{ id }} }}"#,
+                user_id
+            ),
+        )
+        .await;
+
+        assert_has_errors(&response);
+    }
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn update_another_user_is_forbidden(pool: PgPool) {
+        let victim = register_user(&pool, "update-victim@test.com").await;
+        let caller = register_user(&pool, "update-caller@test.com").await;
+
+        let response = execute_as(
+            &pool,
+            &caller,
+            &format!(
+                r#"mutation {{ updateUser(id: "{}", input: {{ email: "taken-over@test.com" }}) {{ id }} }}"#,
+                victim
+            ),
+        )
+        .await;
+
+        assert_has_errors(&response);
+        assert_eq!(response["errors"][0]["extensions"]["code"], "FORBIDDEN");
+        let user = user_feature::UserService::get(&pool, victim.parse().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(user.email, "update-victim@test.com");
+    }
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn resend_welcome_email_returns_boolean(pool: PgPool) {
+        let create_response = execute(
+            &pool,
+            r#"mutation { registerUser(input: { email: "resend@test.com", name: "User" }) { id } }"#,
+        )
+        .await;
+        let user_id = create_response["data"]["registerUser"]["id"]
+            .as_str()
+            .unwrap();
+
+        let response = execute_as(
+            &pool,
+            user_id,
+            &format!(
+                r#"mutation {{ resendWelcomeEmail(userId: "{}") }}"#,
+                user_id
+            ),
+        )
+        .await;
+
+        assert_no_errors(&response);
+        assert!(response["data"]["resendWelcomeEmail"].is_boolean());
+    }
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn resend_welcome_email_is_limited_to_self_and_admins(pool: PgPool) {
+        let user_id = register_user(&pool, "resend-target@test.com").await;
+        let mutation = format!(
+            r#"mutation {{ resendWelcomeEmail(userId: "{}") }}"#,
+            user_id
+        );
+
+        // Anonymous callers and other users are refused
+        let response = execute(&pool, &mutation).await;
+        assert_eq!(
+            response["errors"][0]["extensions"]["code"],
+            "UNAUTHENTICATED"
+        );
+        let other = register_user(&pool, "resend-other@test.com").await;
+        let response = execute_as(&pool, &other, &mutation).await;
+        assert_eq!(response["errors"][0]["extensions"]["code"], "FORBIDDEN");
+
+        // Admins may resend anyone's
+        let admin = AuthContext {
+            user_id: uuid::Uuid::new_v4(),
+            is_admin: true,
+        };
+        let response = build_schema(pool.clone())
+            .execute(Request::new(mutation).data(admin))
+            .await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+    }
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn set_password_never_exposes_the_hash(pool: PgPool) {
+        let create_response = execute(
+            &pool,
+            r#"mutation { registerUser(input: { email: "password@test.com", name: "User" }) { id } }"#,
+        )
+        .await;
+        let user_id = create_response["data"]["registerUser"]["id"]
+            .as_str()
+            .unwrap();
+
+        let response = execute_as(
+            &pool,
+            user_id,
+            &format!(
+                r#"mutation {{ setPassword(userId: "{}", password: "correct horse battery") }}"#,
+                user_id
+            ),
+        )
+        .await;
+        assert_no_errors(&response);
+        assert_eq!(response["data"]["setPassword"], true);
+
+        // The hash is stored...
+        let hash: Option<String> =
+            sqlx::query_scalar("SELECT password_hash FROM users WHERE id = $1::uuid")
+                .bind(user_id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert!(hash.unwrap().starts_with("$argon2"));
+
+        // ...but the user type has no field for it
+        let response = execute(&pool, r#"{ __type(name: "UserType") { fields { name } } }"#).await;
+        assert_no_errors(&response);
+        let fields: Vec<&str> = response["data"]["__type"]["fields"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|f| f["name"].as_str().unwrap())
+            .collect();
+        assert!(fields.contains(&"email"));
+        assert!(!fields.iter().any(|f| f.to_lowercase().contains("password")));
+
+        // And nothing in a full user response carries it
+        let response = execute_as(
+            &pool,
+            user_id,
+            "{ me { id email name timezone role createdAt updatedAt } }",
+        )
+        .await;
+        assert_no_errors(&response);
+        assert!(!response.to_string().contains("$argon2"));
+    }
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn delete_user_reports_whether_it_existed(pool: PgPool) {
         // Create user
         let create_response = execute(
             &pool,
@@ -145,17 +385,89 @@ mod user_mutations {
             .unwrap();
 
         // Delete
-        let response = execute(
+        let response = execute_as(
             &pool,
-            &format!(r#"mutation {{ deleteUser(id: "{}") }}"#, user_id),
+            user_id,
+            &format!(
+                r#"mutation {{ deleteUser(id: "{}") {{ deleted existed }} }}"#,
+                user_id
+            ),
         )
         .await;
 
         assert_no_errors(&response);
-        assert!(
-            response["data"]["deleteUser"].is_boolean(),
-            "deleteUser should return boolean"
+        assert_eq!(
+            response["data"]["deleteUser"],
+            serde_json::json!({ "deleted": true, "existed": true })
+        );
+
+        // A user that never existed
+        let response = execute_as(
+            &pool,
+            "00000000-0000-0000-0000-000000000000",
+            r#"mutation { deleteUser(id: "00000000-0000-0000-0000-000000000000") { deleted existed } }"#,
+        )
+        .await;
+
+        assert_no_errors(&response);
+        assert_eq!(
+            response["data"]["deleteUser"],
+            serde_json::json!({ "deleted": false, "existed": false })
+        );
+    }
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn delete_another_user_is_forbidden(pool: PgPool) {
+        let victim = register_user(&pool, "delete-victim@test.com").await;
+        let caller = register_user(&pool, "delete-caller@test.com").await;
+        let mutation = format!(
+            r#"mutation {{ deleteUser(id: "{}") {{ deleted existed }} }}"#,
+            victim
         );
+
+        let response = execute_as(&pool, &caller, &mutation).await;
+        assert_has_errors(&response);
+        assert_eq!(response["errors"][0]["extensions"]["code"], "FORBIDDEN");
+
+        let response = execute(&pool, &mutation).await;
+        assert_has_errors(&response);
+        assert_eq!(
+            response["errors"][0]["extensions"]["code"],
+            "UNAUTHENTICATED"
+        );
+
+        // The victim is still there
+        let user = user_feature::UserService::get(&pool, victim.parse().unwrap()).await;
+        assert!(user.is_ok(), "user was deleted: {user:?}");
+    }
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn anonymize_another_user_is_forbidden(pool: PgPool) {
+        let response = execute(
+            &pool,
+            r#"mutation { registerUser(input: { email: "victim@test.com", name: "Victim" }) { id } }"#,
+        )
+        .await;
+        let victim_id = response["data"]["registerUser"]["id"].as_str().unwrap();
+
+        let caller = "00000000-0000-0000-0000-000000000001";
+        let response = execute_as(
+            &pool,
+            caller,
+            &format!(r#"mutation {{ anonymizeUser(id: "{}") }}"#, victim_id),
+        )
+        .await;
+
+        assert_has_errors(&response);
+        assert_eq!(response["errors"][0]["extensions"]["code"], "FORBIDDEN");
+
+        // The victim's details are untouched
+        let email: String = sqlx::query_scalar("SELECT email FROM users WHERE id = $1::uuid")
+            .bind(victim_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(email, "victim@test.com");
     }
 }
 
@@ -183,285 +495,2146 @@ mod user_queries {
     }
 
     #[sqlx::test(migrations = "../../../migrations")]
-    async fn users_query_returns_array(pool: PgPool) {
-        let response = execute(&pool, r#"query { users { id email name } }"#).await;
+    async fn user_query_with_malformed_id_is_validation_error(pool: PgPool) {
+        let response = execute(&pool, r#"query { user(id: "not-a-uuid") { id } }"#).await;
 
-        assert_no_errors(&response);
+        assert_has_errors(&response);
+        assert_eq!(response["errors"][0]["extensions"]["code"], "VALIDATION");
+        let message = response["errors"][0]["message"].as_str().unwrap();
         assert!(
-            response["data"]["users"].is_array(),
-            "users should return an array"
+            message.contains("invalid id format"),
+            "unexpected message: {message}"
         );
-    }
 
-    #[sqlx::test(migrations = "../../../migrations")]
-    async fn user_by_email_returns_user_or_null(pool: PgPool) {
+        // Malformed IDs inside input objects are reported the same way
         let response = execute(
             &pool,
-            r#"query { userByEmail(email: "nonexistent@test.com") { id } }"#,
+            r#"mutation { createTodo(input: { userId: "not-a-uuid", title: "Task" }) { id } }"#,
         )
         .await;
 
-        assert_no_errors(&response);
-        assert!(
-            response["data"]["userByEmail"].is_null(),
-            "Missing user should return null"
-        );
-    }
-}
+        assert_has_errors(&response);
+        assert_eq!(response["errors"][0]["extensions"]["code"], "VALIDATION");
 
-// =============================================================================
-// Todo Mutation Contracts
-// =============================================================================
+        // And so are those in lists
+        let response = execute(&pool, r#"query { usersByIds(ids: ["not-a-uuid"]) { id } }"#).await;
 
-mod todo_mutations {
-    use super::*;
+        assert_has_errors(&response);
+        assert_eq!(response["errors"][0]["extensions"]["code"], "VALIDATION");
+    }
 
     #[sqlx::test(migrations = "../../../migrations")]
-    async fn create_todo_returns_todo_type(pool: PgPool) {
-        // Create user first
-        let user_response = execute(
+    async fn user_query_reports_database_failures(pool: PgPool) {
+        // A closed pool fails every query, unlike a missing user
+        pool.close().await;
+
+        let response = execute(
             &pool,
-            r#"mutation { registerUser(input: { email: "todo@test.com", name: "Todo User" }) { id } }"#,
+            r#"query { user(id: "00000000-0000-0000-0000-000000000000") { id } }"#,
         )
         .await;
-        let user_id = user_response["data"]["registerUser"]["id"]
-            .as_str()
-            .unwrap();
 
-        // Create todo
+        assert_has_errors(&response);
+        assert_eq!(response["errors"][0]["extensions"]["code"], "INTERNAL");
+    }
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn users_by_ids_returns_found_users_in_order(pool: PgPool) {
+        let mut ids = Vec::new();
+        for email in ["first-batch@test.com", "second-batch@test.com"] {
+            let response = execute(
+                &pool,
+                &format!(
+                    r#"mutation {{ registerUser(input: {{ email: "{}", name: "User" }}) {{ id }} }}"#,
+                    email
+                ),
+            )
+            .await;
+            ids.push(
+                response["data"]["registerUser"]["id"]
+                    .as_str()
+                    .unwrap()
+                    .to_string(),
+            );
+        }
+
         let response = execute(
             &pool,
             &format!(
-                r#"mutation {{
-                    createTodo(input: {{ userId: "{}", title: "Contract Todo", description: "Test" }}) {{
-                        id
-                        title
-                        description
-                        status
-                        userId
-                        createdAt
-                        updatedAt
-                    }}
-                }}"#,
-                user_id
+                r#"query {{ usersByIds(ids: ["{}", "00000000-0000-0000-0000-000000000000", "{}"]) {{ id email }} }}"#,
+                ids[1], ids[0]
             ),
         )
         .await;
 
         assert_no_errors(&response);
-        let todo = &response["data"]["createTodo"];
-
-        assert!(todo["id"].is_string(), "id should be a string");
-        assert!(todo["title"].is_string(), "title should be a string");
-        assert!(
-            todo["description"].is_string() || todo["description"].is_null(),
-            "description should be string or null"
-        );
-        assert!(todo["status"].is_string(), "status should be an enum string");
-        assert!(todo["userId"].is_string(), "userId should be a string");
-        assert!(todo["createdAt"].is_string(), "createdAt should be a string");
-        assert!(todo["updatedAt"].is_string(), "updatedAt should be a string");
+        let users = response["data"]["usersByIds"].as_array().unwrap();
+        assert_eq!(users.len(), 2);
+        assert_eq!(users[0]["id"], ids[1].as_str());
+        assert_eq!(users[0]["email"], "second-batch@test.com");
+        assert_eq!(users[1]["id"], ids[0].as_str());
     }
 
     #[sqlx::test(migrations = "../../../migrations")]
-    async fn create_todo_for_invalid_user_returns_error(pool: PgPool) {
+    async fn users_can_be_filtered_by_registration_time(pool: PgPool) {
+        for (email, created_at) in [
+            ("before-cutoff@test.com", "2025-03-01T09:00:00Z"),
+            ("at-cutoff@test.com", "2025-03-01T10:00:00Z"),
+        ] {
+            execute(
+                &pool,
+                &format!(
+                    r#"mutation {{ registerUser(input: {{ email: "{}", name: "User" }}) {{ id }} }}"#,
+                    email
+                ),
+            )
+            .await;
+            sqlx::query("UPDATE users SET created_at = $1::timestamptz WHERE email = $2")
+                .bind(created_at)
+                .bind(email)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        let emails = |response: &Value, field: &str| -> Vec<String> {
+            response["data"][field]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|u| u["email"].as_str().unwrap().to_string())
+                .collect()
+        };
+
         let response = execute(
             &pool,
-            r#"mutation {
-                createTodo(input: { userId: "00000000-0000-0000-0000-000000000000", title: "Orphan" }) {
-                    id
-                }
+            r#"query {
+                after: users(createdAfter: "2025-03-01T10:00:00Z") { email }
+                before: users(createdBefore: "2025-03-01T10:00:00Z") { email }
             }"#,
         )
         .await;
 
-        assert_has_errors(&response);
+        assert_no_errors(&response);
+        assert_eq!(emails(&response, "after"), ["at-cutoff@test.com"]);
+        assert_eq!(emails(&response, "before"), ["before-cutoff@test.com"]);
     }
 
     #[sqlx::test(migrations = "../../../migrations")]
-    async fn update_todo_returns_todo_type(pool: PgPool) {
-        // Setup: create user and todo
+    async fn user_todos_returns_todo_list(pool: PgPool) {
         let user_response = execute(
             &pool,
-            r#"mutation { registerUser(input: { email: "update-todo@test.com", name: "User" }) { id } }"#,
+            r#"mutation { registerUser(input: { email: "user-todos@test.com", name: "Todo Owner" }) { id } }"#,
         )
         .await;
         let user_id = user_response["data"]["registerUser"]["id"]
             .as_str()
             .unwrap();
 
-        let todo_response = execute(
+        execute_as(
             &pool,
+            user_id,
             &format!(
-                r#"mutation {{ createTodo(input: {{ userId: "{}", title: "Before" }}) {{ id }} }}"#,
+                r#"mutation {{ createTodos(userId: "{}", items: [{{ title: "First" }}, {{ title: "Second" }}]) {{ id }} }}"#,
                 user_id
             ),
         )
         .await;
-        let todo_id = todo_response["data"]["createTodo"]["id"].as_str().unwrap();
 
-        // Update todo
-        let response = execute(
+        let response = execute_as(
             &pool,
+            user_id,
             &format!(
-                r#"mutation {{ updateTodo(id: "{}", input: {{ title: "After" }}) {{ id title }} }}"#,
-                todo_id
+                r#"query {{ user(id: "{}") {{ name todos {{ title }} }} }}"#,
+                user_id
             ),
         )
         .await;
 
         assert_no_errors(&response);
-        assert!(response["data"]["updateTodo"]["id"].is_string());
-        assert!(response["data"]["updateTodo"]["title"].is_string());
+        assert_eq!(response["data"]["user"]["name"], "Todo Owner");
+        let mut titles: Vec<&str> = response["data"]["user"]["todos"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["title"].as_str().unwrap())
+            .collect();
+        titles.sort();
+        assert_eq!(titles, ["First", "Second"]);
     }
 
     #[sqlx::test(migrations = "../../../migrations")]
-    async fn status_mutations_return_todo_type(pool: PgPool) {
-        // Setup
-        let user_response = execute(
+    async fn user_todos_of_another_user_are_forbidden(pool: PgPool) {
+        let owner = register_user(&pool, "todos-a@test.com").await;
+        let other = register_user(&pool, "todos-b@test.com").await;
+        execute_as(
             &pool,
-            r#"mutation { registerUser(input: { email: "status@test.com", name: "User" }) { id } }"#,
+            &owner,
+            &format!(
+                r#"mutation {{ createTodo(input: {{ userId: "{}", title: "Private" }}) {{ id }} }}"#,
+                owner
+            ),
         )
         .await;
-        let user_id = user_response["data"]["registerUser"]["id"]
-            .as_str()
-            .unwrap();
 
-        let todo_response = execute(
+        // B asks for A's todos through A's user
+        let response = execute_as(
             &pool,
+            &other,
             &format!(
-                r#"mutation {{ createTodo(input: {{ userId: "{}", title: "Status Test" }}) {{ id }} }}"#,
-                user_id
+                r#"query {{ user(id: "{}") {{ name todos {{ title description }} }} }}"#,
+                owner
+            ),
+        )
+        .await;
+
+        assert_has_errors(&response);
+        assert_eq!(response["errors"][0]["extensions"]["code"], "FORBIDDEN");
+        assert!(
+            !response.to_string().contains("Private"),
+            "todos leaked: {response}"
+        );
+    }
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn users_query_returns_array(pool: PgPool) {
+        let response = execute(&pool, r#"query { users { id email name } }"#).await;
+
+        assert_no_errors(&response);
+        assert!(
+            response["data"]["users"].is_array(),
+            "users should return an array"
+        );
+    }
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn users_query_counts_open_todos_per_user(pool: PgPool) {
+        let mut ids = Vec::new();
+        for (email, todo_count) in [("busy@test.com", 4), ("idle@test.com", 1)] {
+            let user_response = execute(
+                &pool,
+                &format!(
+                    r#"mutation {{ registerUser(input: {{ email: "{}", name: "User" }}) {{ id }} }}"#,
+                    email
+                ),
+            )
+            .await;
+            let user_id = user_response["data"]["registerUser"]["id"]
+                .as_str()
+                .unwrap()
+                .to_string();
+
+            let items = vec![r#"{ title: "Todo" }"#; todo_count].join(", ");
+            let todos_response = execute_as(
+                &pool,
+                &user_id,
+                &format!(
+                    r#"mutation {{ createTodos(userId: "{}", items: [{}]) {{ id }} }}"#,
+                    user_id, items
+                ),
+            )
+            .await;
+            assert_no_errors(&todos_response);
+            ids.push((user_id, todos_response["data"]["createTodos"].clone()));
+        }
+
+        // Completed and cancelled todos aren't counted
+        let (busy_id, busy_todos) = &ids[0];
+        let complete_response = execute_as(
+            &pool,
+            busy_id,
+            &format!(
+                r#"mutation {{ completeTodo(id: "{}") {{ id }} }}"#,
+                busy_todos[0]["id"].as_str().unwrap()
+            ),
+        )
+        .await;
+        assert_no_errors(&complete_response);
+        let cancel_response = execute_as(
+            &pool,
+            busy_id,
+            &format!(
+                r#"mutation {{ cancelTodo(id: "{}") {{ id }} }}"#,
+                busy_todos[1]["id"].as_str().unwrap()
+            ),
+        )
+        .await;
+        assert_no_errors(&cancel_response);
+
+        let response = execute(&pool, r#"query { users { email openTodoCount } }"#).await;
+
+        assert_no_errors(&response);
+        let mut counts: Vec<(&str, i64)> = response["data"]["users"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|u| {
+                (
+                    u["email"].as_str().unwrap(),
+                    u["openTodoCount"].as_i64().unwrap(),
+                )
+            })
+            .collect();
+        counts.sort();
+        assert_eq!(counts, [("busy@test.com", 2), ("idle@test.com", 1)]);
+    }
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn user_by_email_returns_user_or_null(pool: PgPool) {
+        let response = execute(
+            &pool,
+            r#"query { userByEmail(email: "nonexistent@test.com") { id } }"#,
+        )
+        .await;
+
+        assert_no_errors(&response);
+        assert!(
+            response["data"]["userByEmail"].is_null(),
+            "Missing user should return null"
+        );
+    }
+}
+
+// =============================================================================
+// Todo Mutation Contracts
+// =============================================================================
+
+mod todo_mutations {
+    use super::*;
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn create_todo_returns_todo_type(pool: PgPool) {
+        // Create user first
+        let user_response = execute(
+            &pool,
+            r#"mutation { registerUser(input: { email: "todo@test.com", name: "Todo User" }) { id } }"#,
+        )
+        .await;
+        let user_id = user_response["data"]["registerUser"]["id"]
+            .as_str()
+            .unwrap();
+
+        // Create todo
+        let response = execute_as(
+            &pool,
+            user_id,
+            &format!(
+                r#"mutation {{
+                    createTodo(input: {{ userId: "{}", title: "Contract Todo", description: "Test" }}) {{
+                        id
+                        title
+                        description
+                        status
+                        userId
+                        createdAt
+                        updatedAt
+                    }}
+                }}"#,
+                user_id
+            ),
+        )
+        .await;
+
+        assert_no_errors(&response);
+        let todo = &response["data"]["createTodo"];
+
+        assert!(todo["id"].is_string(), "id should be a string");
+        assert!(todo["title"].is_string(), "title should be a string");
+        assert!(
+            todo["description"].is_string() || todo["description"].is_null(),
+            "description should be string or null"
+        );
+        assert!(todo["status"].is_string(), "status should be an enum string");
+        assert!(todo["userId"].is_string(), "userId should be a string");
+        assert!(todo["createdAt"].is_string(), "createdAt should be a string");
+        assert!(todo["updatedAt"].is_string(), "updatedAt should be a string");
+    }
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn create_todo_for_invalid_user_returns_error(pool: PgPool) {
+        let response = execute_as(
+            &pool,
+            "00000000-0000-0000-0000-000000000000",
+            r#"mutation {
+                createTodo(input: { userId: "00000000-0000-0000-0000-000000000000", title: "Orphan" }) {
+                    id
+                }
+            }"#,
+        )
+        .await;
+
+        assert_has_errors(&response);
+        assert_eq!(
+            response["errors"][0]["extensions"]["code"],
+            "USER_NOT_FOUND"
+        );
+    }
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn create_todo_for_another_user_is_forbidden(pool: PgPool) {
+        let owner = register_user(&pool, "create-owner@test.com").await;
+        let caller = register_user(&pool, "create-caller@test.com").await;
+
+        let response = execute_as(
+            &pool,
+            &caller,
+            &format!(
+                r#"mutation {{ createTodo(input: {{ userId: "{}", title: "Planted" }}) {{ id }} }}"#,
+                owner
+            ),
+        )
+        .await;
+        assert_has_errors(&response);
+        assert_eq!(response["errors"][0]["extensions"]["code"], "FORBIDDEN");
+
+        let response = execute_as(
+            &pool,
+            &caller,
+            &format!(
+                r#"mutation {{ createTodos(userId: "{}", items: [{{ title: "Planted" }}]) {{ id }} }}"#,
+                owner
+            ),
+        )
+        .await;
+        assert_has_errors(&response);
+        assert_eq!(response["errors"][0]["extensions"]["code"], "FORBIDDEN");
+
+        // Nothing was created for the owner
+        let todos = todo_feature::TodoService::list_for_user(&pool, owner.parse().unwrap(), false)
+            .await
+            .unwrap();
+        assert!(todos.is_empty());
+    }
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn create_todos_returns_todo_list(pool: PgPool) {
+        let user_response = execute(
+            &pool,
+            r#"mutation { registerUser(input: { email: "batch@test.com", name: "User" }) { id } }"#,
+        )
+        .await;
+        let user_id = user_response["data"]["registerUser"]["id"]
+            .as_str()
+            .unwrap();
+
+        let response = execute_as(
+            &pool,
+            user_id,
+            &format!(
+                r#"mutation {{
+                    createTodos(userId: "{}", items: [{{ title: "One" }}, {{ title: "Two", description: "Second", dueDate: "2030-01-01T00:00:00Z" }}]) {{
+                        id
+                        title
+                        status
+                        dueDate
+                    }}
+                }}"#,
+                user_id
+            ),
+        )
+        .await;
+
+        assert_no_errors(&response);
+        let todos = response["data"]["createTodos"].as_array().unwrap();
+        assert_eq!(todos.len(), 2);
+        assert!(todos[0]["id"].is_string());
+        assert!(todos[0]["status"].is_string());
+        assert!(todos[0]["dueDate"].is_null());
+        assert!(todos[1]["dueDate"].is_string());
+    }
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn update_todo_returns_todo_type(pool: PgPool) {
+        // Setup: create user and todo
+        let user_response = execute(
+            &pool,
+            r#"mutation { registerUser(input: { email: "update-todo@test.com", name: "User" }) { id } }"#,
+        )
+        .await;
+        let user_id = user_response["data"]["registerUser"]["id"]
+            .as_str()
+            .unwrap();
+
+        let todo_response = execute_as(
+            &pool,
+            user_id,
+            &format!(
+                r#"mutation {{ createTodo(input: {{ userId: "{}", title: "Before" }}) {{ id }} }}"#,
+                user_id
+            ),
+        )
+        .await;
+        let todo_id = todo_response["data"]["createTodo"]["id"].as_str().unwrap();
+
+        // Update todo
+        let response = execute_as(
+            &pool,
+            user_id,
+            &format!(
+                r#"mutation {{ updateTodo(id: "{}", input: {{ title: "After" }}) {{ id title }} }}"#,
+                todo_id
+            ),
+        )
+        .await;
+
+        assert_no_errors(&response);
+        assert!(response["data"]["updateTodo"]["id"].is_string());
+        assert!(response["data"]["updateTodo"]["title"].is_string());
+    }
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn reassign_todo_moves_it_to_the_new_user(pool: PgPool) {
+        // Setup: two users, the first owning a todo
+        let mut user_ids = Vec::new();
+        for email in ["giver@test.com", "taker@test.com"] {
+            let response = execute(
+                &pool,
+                &format!(
+                    r#"mutation {{ registerUser(input: {{ email: "{}", name: "User" }}) {{ id }} }}"#,
+                    email
+                ),
+            )
+            .await;
+            user_ids.push(
+                response["data"]["registerUser"]["id"]
+                    .as_str()
+                    .unwrap()
+                    .to_string(),
+            );
+        }
+        let (giver, taker) = (&user_ids[0], &user_ids[1]);
+
+        let todo_response = execute_as(
+            &pool,
+            giver,
+            &format!(
+                r#"mutation {{ createTodo(input: {{ userId: "{}", title: "Pass it on" }}) {{ id }} }}"#,
+                giver
+            ),
+        )
+        .await;
+        let todo_id = todo_response["data"]["createTodo"]["id"].as_str().unwrap();
+
+        // Reassign to a user who doesn't exist
+        let response = execute_as(
+            &pool,
+            giver,
+            &format!(
+                r#"mutation {{ reassignTodo(id: "{}", newUserId: "{}") {{ id }} }}"#,
+                todo_id,
+                uuid::Uuid::new_v4()
+            ),
+        )
+        .await;
+        assert_has_errors(&response);
+        assert_eq!(
+            response["errors"][0]["extensions"]["code"],
+            "USER_NOT_FOUND"
+        );
+
+        // Reassign to the second user
+        let response = execute_as(
+            &pool,
+            giver,
+            &format!(
+                r#"mutation {{ reassignTodo(id: "{}", newUserId: "{}") {{ id userId }} }}"#,
+                todo_id, taker
+            ),
+        )
+        .await;
+        assert_no_errors(&response);
+        assert_eq!(response["data"]["reassignTodo"]["userId"], taker.as_str());
+    }
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn status_mutations_return_todo_type(pool: PgPool) {
+        // Setup
+        let user_response = execute(
+            &pool,
+            r#"mutation { registerUser(input: { email: "status@test.com", name: "User" }) { id } }"#,
+        )
+        .await;
+        let user_id = user_response["data"]["registerUser"]["id"]
+            .as_str()
+            .unwrap();
+
+        let todo_response = execute_as(
+            &pool,
+            user_id,
+            &format!(
+                r#"mutation {{ createTodo(input: {{ userId: "{}", title: "Status Test" }}) {{ id }} }}"#,
+                user_id
+            ),
+        )
+        .await;
+        let todo_id = todo_response["data"]["createTodo"]["id"].as_str().unwrap();
+
+        // startTodo returns Todo
+        let start_response = execute_as(
+            &pool,
+            user_id,
+            &format!(
+                r#"mutation {{ startTodo(id: "{}") {{ id status isRunning }} }}"#,
+                todo_id
+            ),
+        )
+        .await;
+        assert_no_errors(&start_response);
+        assert!(start_response["data"]["startTodo"]["status"].is_string());
+        assert_eq!(start_response["data"]["startTodo"]["isRunning"], true);
+
+        // pauseTodo returns Todo
+        let pause_response = execute_as(
+            &pool,
+            user_id,
+            &format!(
+                r#"mutation {{ pauseTodo(id: "{}") {{ id isRunning totalSeconds }} }}"#,
+                todo_id
+            ),
+        )
+        .await;
+        assert_no_errors(&pause_response);
+        assert_eq!(pause_response["data"]["pauseTodo"]["isRunning"], false);
+        assert!(pause_response["data"]["pauseTodo"]["totalSeconds"].is_i64());
+
+        // completeTodo returns Todo
+        let complete_response = execute_as(
+            &pool,
+            user_id,
+            &format!(
+                r#"mutation {{ completeTodo(id: "{}") {{ id status }} }}"#,
+                todo_id
+            ),
+        )
+        .await;
+        assert_no_errors(&complete_response);
+        assert!(complete_response["data"]["completeTodo"]["status"].is_string());
+
+        // reopenTodo returns Todo
+        let reopen_response = execute_as(
+            &pool,
+            user_id,
+            &format!(
+                r#"mutation {{ reopenTodo(id: "{}") {{ id status }} }}"#,
+                todo_id
+            ),
+        )
+        .await;
+        assert_no_errors(&reopen_response);
+        assert_eq!(reopen_response["data"]["reopenTodo"]["status"], "PENDING");
+
+        // cancelTodo returns Todo
+        let cancel_response = execute_as(
+            &pool,
+            user_id,
+            &format!(
+                r#"mutation {{ cancelTodo(id: "{}") {{ id status }} }}"#,
+                todo_id
+            ),
+        )
+        .await;
+        assert_no_errors(&cancel_response);
+        assert_eq!(cancel_response["data"]["cancelTodo"]["status"], "CANCELLED");
+    }
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn update_todo_cannot_reset_a_completed_todo_to_pending(pool: PgPool) {
+        let user_response = execute(
+            &pool,
+            r#"mutation { registerUser(input: { email: "no-reset@test.com", name: "User" }) { id } }"#,
+        )
+        .await;
+        let user_id = user_response["data"]["registerUser"]["id"]
+            .as_str()
+            .unwrap();
+
+        let todo_response = execute_as(
+            &pool,
+            user_id,
+            &format!(
+                r#"mutation {{ createTodo(input: {{ userId: "{}", title: "Done" }}) {{ id }} }}"#,
+                user_id
+            ),
+        )
+        .await;
+        let todo_id = todo_response["data"]["createTodo"]["id"].as_str().unwrap();
+
+        let complete_response = execute_as(
+            &pool,
+            user_id,
+            &format!(r#"mutation {{ completeTodo(id: "{}") {{ id }} }}"#, todo_id),
+        )
+        .await;
+        assert_no_errors(&complete_response);
+
+        // Setting the status directly follows the same rules as the
+        // dedicated mutations
+        let response = execute_as(
+            &pool,
+            user_id,
+            &format!(
+                r#"mutation {{ updateTodo(id: "{}", input: {{ status: PENDING }}) {{ id status }} }}"#,
+                todo_id
+            ),
+        )
+        .await;
+
+        assert_has_errors(&response);
+        assert_eq!(
+            response["errors"][0]["extensions"]["code"],
+            "INVALID_TRANSITION"
+        );
+
+        let todo = execute_as(
+            &pool,
+            user_id,
+            &format!(r#"query {{ todo(id: "{}") {{ status }} }}"#, todo_id),
+        )
+        .await;
+        assert_eq!(todo["data"]["todo"]["status"], "COMPLETED");
+    }
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn set_todos_status_returns_count(pool: PgPool) {
+        let user_response = execute(
+            &pool,
+            r#"mutation { registerUser(input: { email: "bulk@test.com", name: "User" }) { id } }"#,
+        )
+        .await;
+        let user_id = user_response["data"]["registerUser"]["id"]
+            .as_str()
+            .unwrap();
+
+        let todos_response = execute_as(
+            &pool,
+            user_id,
+            &format!(
+                r#"mutation {{ createTodos(userId: "{}", items: [{{ title: "A" }}, {{ title: "B" }}]) {{ id }} }}"#,
+                user_id
+            ),
+        )
+        .await;
+        let ids: Vec<&str> = todos_response["data"]["createTodos"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["id"].as_str().unwrap())
+            .collect();
+
+        let response = execute_as(
+            &pool,
+            user_id,
+            &format!(
+                r#"mutation {{ setTodosStatus(ids: ["{}", "{}"], status: COMPLETED) }}"#,
+                ids[0], ids[1]
+            ),
+        )
+        .await;
+
+        assert_no_errors(&response);
+        assert_eq!(response["data"]["setTodosStatus"], 2);
+    }
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn set_todos_status_leaves_other_users_todos_alone(pool: PgPool) {
+        // User B owns a todo; user A tries to complete it in bulk
+        let a_response = execute(
+            &pool,
+            r#"mutation { registerUser(input: { email: "a@test.com", name: "A" }) { id } }"#,
+        )
+        .await;
+        let a_id = a_response["data"]["registerUser"]["id"].as_str().unwrap();
+        let b_response = execute(
+            &pool,
+            r#"mutation { registerUser(input: { email: "b@test.com", name: "B" }) { id } }"#,
+        )
+        .await;
+        let b_id = b_response["data"]["registerUser"]["id"].as_str().unwrap();
+
+        let todo_response = execute_as(
+            &pool,
+            b_id,
+            &format!(
+                r#"mutation {{ createTodo(input: {{ userId: "{}", title: "B's" }}) {{ id }} }}"#,
+                b_id
+            ),
+        )
+        .await;
+        let todo_id = todo_response["data"]["createTodo"]["id"].as_str().unwrap();
+
+        let response = execute_as(
+            &pool,
+            a_id,
+            &format!(
+                r#"mutation {{ setTodosStatus(ids: ["{}"], status: COMPLETED) }}"#,
+                todo_id
+            ),
+        )
+        .await;
+
+        assert_no_errors(&response);
+        assert_eq!(response["data"]["setTodosStatus"], 0);
+        let status: String = sqlx::query_scalar("SELECT status FROM todos WHERE id = $1::uuid")
+            .bind(todo_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(status, "pending");
+    }
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn set_todos_status_requires_authentication(pool: PgPool) {
+        let response = execute(
+            &pool,
+            r#"mutation { setTodosStatus(ids: ["00000000-0000-0000-0000-000000000001"], status: COMPLETED) }"#,
+        )
+        .await;
+
+        assert_has_errors(&response);
+        assert_eq!(
+            response["errors"][0]["extensions"]["code"],
+            "UNAUTHENTICATED"
+        );
+    }
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn delete_todo_reports_whether_it_existed(pool: PgPool) {
+        // Setup
+        let user_response = execute(
+            &pool,
+            r#"mutation { registerUser(input: { email: "del-todo@test.com", name: "User" }) { id } }"#,
+        )
+        .await;
+        let user_id = user_response["data"]["registerUser"]["id"]
+            .as_str()
+            .unwrap();
+
+        let todo_response = execute_as(
+            &pool,
+            user_id,
+            &format!(
+                r#"mutation {{ createTodo(input: {{ userId: "{}", title: "Delete Me" }}) {{ id }} }}"#,
+                user_id
+            ),
+        )
+        .await;
+        let todo_id = todo_response["data"]["createTodo"]["id"].as_str().unwrap();
+
+        // Delete
+        let response = execute_as(
+            &pool,
+            user_id,
+            &format!(
+                r#"mutation {{ deleteTodo(id: "{}") {{ deleted existed }} }}"#,
+                todo_id
+            ),
+        )
+        .await;
+
+        assert_no_errors(&response);
+        assert_eq!(
+            response["data"]["deleteTodo"],
+            serde_json::json!({ "deleted": true, "existed": true })
+        );
+
+        // Deleting it again finds it already deleted
+        let response = execute_as(
+            &pool,
+            user_id,
+            &format!(
+                r#"mutation {{ deleteTodo(id: "{}") {{ deleted existed }} }}"#,
+                todo_id
+            ),
+        )
+        .await;
+
+        assert_no_errors(&response);
+        assert_eq!(
+            response["data"]["deleteTodo"],
+            serde_json::json!({ "deleted": false, "existed": true })
+        );
+
+        // A todo that never existed
+        let response = execute_as(
+            &pool,
+            user_id,
+            r#"mutation { deleteTodo(id: "00000000-0000-0000-0000-000000000000") { deleted existed } }"#,
+        )
+        .await;
+
+        assert_no_errors(&response);
+        assert_eq!(
+            response["data"]["deleteTodo"],
+            serde_json::json!({ "deleted": false, "existed": false })
+        );
+    }
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn deleted_todo_is_listed_on_request_and_can_be_restored(pool: PgPool) {
+        let user_response = execute(
+            &pool,
+            r#"mutation { registerUser(input: { email: "restore-todo@test.com", name: "User" }) { id } }"#,
+        )
+        .await;
+        let user_id = user_response["data"]["registerUser"]["id"]
+            .as_str()
+            .unwrap();
+
+        let todo_response = execute_as(
+            &pool,
+            user_id,
+            &format!(
+                r#"mutation {{ createTodo(input: {{ userId: "{}", title: "Restore Me" }}) {{ id }} }}"#,
+                user_id
+            ),
+        )
+        .await;
+        let todo_id = todo_response["data"]["createTodo"]["id"].as_str().unwrap();
+        execute_as(
+            &pool,
+            user_id,
+            &format!(
+                r#"mutation {{ deleteTodo(id: "{}") {{ deleted existed }} }}"#,
+                todo_id
+            ),
+        )
+        .await;
+
+        // Hidden by default, listed with a deletedAt when asked for
+        let list = |include: bool| {
+            format!(
+                r#"query {{ todosForUser(userId: "{}", includeDeleted: {}) {{ id deletedAt }} }}"#,
+                user_id, include
+            )
+        };
+        let response = execute_as(&pool, user_id, &list(false)).await;
+        assert_no_errors(&response);
+        assert!(
+            response["data"]["todosForUser"]
+                .as_array()
+                .unwrap()
+                .is_empty()
+        );
+        let response = execute_as(&pool, user_id, &list(true)).await;
+        assert_no_errors(&response);
+        assert!(response["data"]["todosForUser"][0]["deletedAt"].is_string());
+
+        // restoreTodo returns Todo
+        let response = execute_as(
+            &pool,
+            user_id,
+            &format!(
+                r#"mutation {{ restoreTodo(id: "{}") {{ id deletedAt }} }}"#,
+                todo_id
+            ),
+        )
+        .await;
+        assert_no_errors(&response);
+        assert!(response["data"]["restoreTodo"]["deletedAt"].is_null());
+
+        let response = execute_as(&pool, user_id, &list(false)).await;
+        assert_eq!(response["data"]["todosForUser"][0]["id"], todo_id);
+    }
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn duplicate_todo_returns_new_todo(pool: PgPool) {
+        let user_response = execute(
+            &pool,
+            r#"mutation { registerUser(input: { email: "duplicate@test.com", name: "User" }) { id } }"#,
+        )
+        .await;
+        let user_id = user_response["data"]["registerUser"]["id"]
+            .as_str()
+            .unwrap();
+        let todo_response = execute_as(
+            &pool,
+            user_id,
+            &format!(
+                r#"mutation {{ createTodo(input: {{ userId: "{}", title: "Template" }}) {{ id }} }}"#,
+                user_id
+            ),
+        )
+        .await;
+        let todo_id = todo_response["data"]["createTodo"]["id"].as_str().unwrap();
+
+        let response = execute_as(
+            &pool,
+            user_id,
+            &format!(
+                r#"mutation {{ duplicateTodo(id: "{}") {{ id title status }} }}"#,
+                todo_id
+            ),
+        )
+        .await;
+
+        assert_no_errors(&response);
+        let copy = &response["data"]["duplicateTodo"];
+        assert_ne!(copy["id"], todo_id);
+        assert_eq!(copy["title"], "Template");
+        assert_eq!(copy["status"], "PENDING");
+    }
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn clear_completed_todos_returns_count(pool: PgPool) {
+        let user_response = execute(
+            &pool,
+            r#"mutation { registerUser(input: { email: "clear@test.com", name: "User" }) { id } }"#,
+        )
+        .await;
+        let user_id = user_response["data"]["registerUser"]["id"]
+            .as_str()
+            .unwrap();
+
+        let response = execute_as(
+            &pool,
+            user_id,
+            &format!(
+                r#"mutation {{ clearCompletedTodos(userId: "{}") }}"#,
+                user_id
+            ),
+        )
+        .await;
+
+        assert_no_errors(&response);
+        assert_eq!(response["data"]["clearCompletedTodos"], 0);
+    }
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn todo_tag_mutations_return_todo_with_tags(pool: PgPool) {
+        let user_response = execute(
+            &pool,
+            r#"mutation { registerUser(input: { email: "tags@test.com", name: "User" }) { id } }"#,
+        )
+        .await;
+        let user_id = user_response["data"]["registerUser"]["id"]
+            .as_str()
+            .unwrap();
+        let todo_response = execute_as(
+            &pool,
+            user_id,
+            &format!(
+                r#"mutation {{ createTodo(input: {{ userId: "{}", title: "Tag me" }}) {{ id }} }}"#,
+                user_id
+            ),
+        )
+        .await;
+        let todo_id = todo_response["data"]["createTodo"]["id"].as_str().unwrap();
+
+        let response = execute_as(
+            &pool,
+            user_id,
+            &format!(
+                r#"mutation {{
+                    a: addTodoTag(id: "{id}", tag: "Work") {{ id }}
+                    b: addTodoTag(id: "{id}", tag: "home") {{ id }}
+                    c: removeTodoTag(id: "{id}", tag: "home") {{ tags }}
+                }}"#,
+                id = todo_id
+            ),
+        )
+        .await;
+
+        assert_no_errors(&response);
+        assert_eq!(response["data"]["c"]["tags"], serde_json::json!(["work"]));
+
+        let response = execute_as(
+            &pool,
+            user_id,
+            &format!(
+                r#"query {{ todosForUserByTag(userId: "{}", tag: "WORK") {{ id tags }} }}"#,
+                user_id
+            ),
+        )
+        .await;
+
+        assert_no_errors(&response);
+        let todos = response["data"]["todosForUserByTag"].as_array().unwrap();
+        assert_eq!(todos.len(), 1);
+        assert_eq!(todos[0]["id"], todo_id);
+    }
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn todo_items_report_progress(pool: PgPool) {
+        let user_response = execute(
+            &pool,
+            r#"mutation { registerUser(input: { email: "items@test.com", name: "User" }) { id } }"#,
+        )
+        .await;
+        let user_id = user_response["data"]["registerUser"]["id"]
+            .as_str()
+            .unwrap();
+        let todo_response = execute_as(
+            &pool,
+            user_id,
+            &format!(
+                r#"mutation {{ createTodo(input: {{ userId: "{}", title: "Pack" }}) {{ id progress }} }}"#,
+                user_id
+            ),
+        )
+        .await;
+        assert_no_errors(&todo_response);
+        let todo = &todo_response["data"]["createTodo"];
+        assert_eq!(todo["progress"], 0.0);
+        let todo_id = todo["id"].as_str().unwrap();
+
+        let response = execute_as(
+            &pool,
+            user_id,
+            &format!(
+                r#"mutation {{
+                    a: addTodoItem(todoId: "{id}", text: "Socks") {{ id position done }}
+                    b: addTodoItem(todoId: "{id}", text: "Passport") {{ id position }}
+                }}"#,
+                id = todo_id
+            ),
+        )
+        .await;
+        assert_no_errors(&response);
+        assert_eq!(response["data"]["a"]["position"], 0);
+        assert_eq!(response["data"]["a"]["done"], false);
+        assert_eq!(response["data"]["b"]["position"], 1);
+        let item_id = response["data"]["a"]["id"].as_str().unwrap();
+
+        let response = execute_as(
+            &pool,
+            user_id,
+            &format!(
+                r#"mutation {{ toggleTodoItem(id: "{}") {{ done }} }}"#,
+                item_id
+            ),
+        )
+        .await;
+        assert_no_errors(&response);
+        assert_eq!(response["data"]["toggleTodoItem"]["done"], true);
+
+        let response = execute_as(
+            &pool,
+            user_id,
+            &format!(
+                r#"query {{ todo(id: "{}") {{ progress items {{ text done }} }} }}"#,
+                todo_id
+            ),
+        )
+        .await;
+        assert_no_errors(&response);
+        let todo = &response["data"]["todo"];
+        assert_eq!(todo["progress"], 0.5);
+        assert_eq!(todo["items"][0]["text"], "Socks");
+        assert_eq!(todo["items"][1]["text"], "Passport");
+    }
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn todo_mutations_reject_other_users(pool: PgPool) {
+        // User B owns a todo; user A tries to touch it
+        let a_response = execute(
+            &pool,
+            r#"mutation { registerUser(input: { email: "a@test.com", name: "A" }) { id } }"#,
+        )
+        .await;
+        let a_id = a_response["data"]["registerUser"]["id"].as_str().unwrap();
+        let b_response = execute(
+            &pool,
+            r#"mutation { registerUser(input: { email: "b@test.com", name: "B" }) { id } }"#,
+        )
+        .await;
+        let b_id = b_response["data"]["registerUser"]["id"].as_str().unwrap();
+
+        let todo_response = execute_as(
+            &pool,
+            b_id,
+            &format!(
+                r#"mutation {{ createTodo(input: {{ userId: "{}", title: "B's" }}) {{ id }} }}"#,
+                b_id
+            ),
+        )
+        .await;
+        let todo_id = todo_response["data"]["createTodo"]["id"].as_str().unwrap();
+
+        let complete_response = execute_as(
+            &pool,
+            a_id,
+            &format!(r#"mutation {{ completeTodo(id: "{}") {{ id }} }}"#, todo_id),
+        )
+        .await;
+        assert_has_errors(&complete_response);
+        assert_eq!(
+            complete_response["errors"][0]["extensions"]["code"],
+            "FORBIDDEN"
+        );
+
+        let delete_response = execute_as(
+            &pool,
+            a_id,
+            &format!(
+                r#"mutation {{ deleteTodo(id: "{}") {{ deleted existed }} }}"#,
+                todo_id
+            ),
+        )
+        .await;
+        assert_has_errors(&delete_response);
+        assert_eq!(
+            delete_response["errors"][0]["extensions"]["code"],
+            "FORBIDDEN"
+        );
+
+        // Anonymous callers are turned away before ownership is checked
+        let anonymous_response = execute(
+            &pool,
+            &format!(
+                r#"mutation {{ deleteTodo(id: "{}") {{ deleted existed }} }}"#,
+                todo_id
+            ),
+        )
+        .await;
+        assert_eq!(
+            anonymous_response["errors"][0]["extensions"]["code"],
+            "UNAUTHENTICATED"
+        );
+
+        // B's todo is untouched
+        let todo = todo_feature::TodoService::get(&pool, todo_id.parse().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(todo.status, domain::TodoStatus::Pending);
+    }
+}
+
+// =============================================================================
+// Todo Query Contracts
+// =============================================================================
+
+mod todo_queries {
+    use super::*;
+    use domain::{FixedClock, SharedClock};
+    use std::sync::Arc;
+    use time::OffsetDateTime;
+    use time::format_description::well_known::Rfc3339;
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn todo_query_returns_todo_or_null(pool: PgPool) {
+        let response = execute_as(
+            &pool,
+            "00000000-0000-0000-0000-000000000001",
+            r#"query { todo(id: "00000000-0000-0000-0000-000000000000") { id } }"#,
+        )
+        .await;
+
+        assert_no_errors(&response);
+        assert!(response["data"]["todo"].is_null());
+    }
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn todo_query_hides_other_users_todos(pool: PgPool) {
+        let owner_response = execute(
+            &pool,
+            r#"mutation { registerUser(input: { email: "owner@test.com", name: "Owner" }) { id } }"#,
+        )
+        .await;
+        let owner_id = owner_response["data"]["registerUser"]["id"]
+            .as_str()
+            .unwrap();
+        let todo_response = execute_as(
+            &pool,
+            owner_id,
+            &format!(
+                r#"mutation {{ createTodo(input: {{ userId: "{}", title: "Private" }}) {{ id }} }}"#,
+                owner_id
+            ),
+        )
+        .await;
+        let todo_id = todo_response["data"]["createTodo"]["id"].as_str().unwrap();
+        let query = format!(r#"query {{ todo(id: "{}") {{ id }} }}"#, todo_id);
+
+        // The owner sees it...
+        let response = execute_as(&pool, owner_id, &query).await;
+        assert_no_errors(&response);
+        assert_eq!(response["data"]["todo"]["id"], todo_id);
+
+        // ...anyone else gets null, as for a todo that doesn't exist
+        let response = execute_as(&pool, "00000000-0000-0000-0000-000000000001", &query).await;
+        assert_no_errors(&response);
+        assert!(response["data"]["todo"].is_null());
+    }
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn todo_query_reports_database_failures(pool: PgPool) {
+        // A closed pool fails every query, unlike a missing todo
+        pool.close().await;
+
+        let response = execute_as(
+            &pool,
+            "00000000-0000-0000-0000-000000000001",
+            r#"query { todo(id: "00000000-0000-0000-0000-000000000000") { id } }"#,
+        )
+        .await;
+
+        assert_has_errors(&response);
+        assert_eq!(response["errors"][0]["extensions"]["code"], "INTERNAL");
+    }
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn todos_by_ids_skips_unknown_ids(pool: PgPool) {
+        let response = execute_as(
+            &pool,
+            "00000000-0000-0000-0000-000000000001",
+            r#"query { todosByIds(ids: ["00000000-0000-0000-0000-000000000000"]) { id } }"#,
+        )
+        .await;
+
+        assert_no_errors(&response);
+        assert_eq!(response["data"]["todosByIds"], serde_json::json!([]));
+    }
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn todos_by_ids_skips_other_users_todos(pool: PgPool) {
+        let owner_response = execute(
+            &pool,
+            r#"mutation { registerUser(input: { email: "owner@test.com", name: "Owner" }) { id } }"#,
+        )
+        .await;
+        let owner_id = owner_response["data"]["registerUser"]["id"]
+            .as_str()
+            .unwrap();
+        let todo_response = execute_as(
+            &pool,
+            owner_id,
+            &format!(
+                r#"mutation {{ createTodo(input: {{ userId: "{}", title: "Private" }}) {{ id }} }}"#,
+                owner_id
             ),
         )
         .await;
         let todo_id = todo_response["data"]["createTodo"]["id"].as_str().unwrap();
 
-        // startTodo returns Todo
-        let start_response = execute(
+        let response = execute_as(
+            &pool,
+            "00000000-0000-0000-0000-000000000001",
+            &format!(r#"query {{ todosByIds(ids: ["{}"]) {{ id }} }}"#, todo_id),
+        )
+        .await;
+
+        assert_no_errors(&response);
+        assert_eq!(response["data"]["todosByIds"], serde_json::json!([]));
+    }
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn todos_by_ids_rejects_too_many_ids(pool: PgPool) {
+        let ids: Vec<String> = (0..101)
+            .map(|_| format!(r#""{}""#, uuid::Uuid::new_v4()))
+            .collect();
+
+        let response = execute_as(
+            &pool,
+            "00000000-0000-0000-0000-000000000001",
+            &format!("query {{ todosByIds(ids: [{}]) {{ id }} }}", ids.join(", ")),
+        )
+        .await;
+
+        assert_has_errors(&response);
+        assert!(response["data"].is_null());
+    }
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn todos_for_user_returns_array(pool: PgPool) {
+        // Create user
+        let user_response = execute(
+            &pool,
+            r#"mutation { registerUser(input: { email: "list-todos@test.com", name: "User" }) { id } }"#,
+        )
+        .await;
+        let user_id = user_response["data"]["registerUser"]["id"]
+            .as_str()
+            .unwrap();
+
+        let response = execute_as(
+            &pool,
+            user_id,
+            &format!(
+                r#"query {{ todosForUser(userId: "{}") {{ id title }} }}"#,
+                user_id
+            ),
+        )
+        .await;
+
+        assert_no_errors(&response);
+        assert!(response["data"]["todosForUser"].is_array());
+    }
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn todos_for_another_user_is_forbidden(pool: PgPool) {
+        let caller = "00000000-0000-0000-0000-000000000001";
+        let response = execute_as(
+            &pool,
+            caller,
+            r#"query { todosForUser(userId: "00000000-0000-0000-0000-000000000002") { id } }"#,
+        )
+        .await;
+
+        assert_has_errors(&response);
+        assert_eq!(response["errors"][0]["extensions"]["code"], "FORBIDDEN");
+    }
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn todos_for_user_by_status_returns_connection(pool: PgPool) {
+        // Create user
+        let user_response = execute(
+            &pool,
+            r#"mutation { registerUser(input: { email: "status-list@test.com", name: "User" }) { id } }"#,
+        )
+        .await;
+        let user_id = user_response["data"]["registerUser"]["id"]
+            .as_str()
+            .unwrap();
+
+        let response = execute_as(
+            &pool,
+            user_id,
+            &format!(
+                r#"query {{ todosForUserByStatus(userId: "{}", status: PENDING, first: 10) {{
+                    nodes {{ id }}
+                    hasNextPage
+                    endCursor
+                }} }}"#,
+                user_id
+            ),
+        )
+        .await;
+
+        assert_no_errors(&response);
+        let connection = &response["data"]["todosForUserByStatus"];
+        assert!(connection["nodes"].is_array());
+        assert_eq!(connection["hasNextPage"], false);
+        assert!(connection["endCursor"].is_null());
+    }
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn todos_for_user_by_status_rejects_malformed_cursor(pool: PgPool) {
+        let response = execute_as(
+            &pool,
+            "00000000-0000-0000-0000-000000000001",
+            r#"query { todosForUserByStatus(
+                userId: "00000000-0000-0000-0000-000000000001",
+                status: PENDING,
+                after: "not-a-cursor"
+            ) { nodes { id } } }"#,
+        )
+        .await;
+
+        assert_has_errors(&response);
+        assert_eq!(response["errors"][0]["extensions"]["code"], "VALIDATION");
+    }
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn todos_for_user_by_status_for_another_user_is_forbidden(pool: PgPool) {
+        let response = execute_as(
+            &pool,
+            "00000000-0000-0000-0000-000000000001",
+            r#"query { todosForUserByStatus(
+                userId: "00000000-0000-0000-0000-000000000002",
+                status: PENDING
+            ) { nodes { id } } }"#,
+        )
+        .await;
+
+        assert_has_errors(&response);
+        assert_eq!(response["errors"][0]["extensions"]["code"], "FORBIDDEN");
+    }
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn offset_todos_page_has_no_cursor(pool: PgPool) {
+        // Only cursor-paged queries hand out an endCursor to continue from
+        let response = execute(
+            &pool,
+            r#"{ __type(name: "TodoPageType") { fields { name } } }"#,
+        )
+        .await;
+
+        assert_no_errors(&response);
+        let fields: Vec<&str> = response["data"]["__type"]["fields"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|f| f["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(fields, ["nodes", "hasNextPage"]);
+    }
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn todo_status_summary_returns_counts(pool: PgPool) {
+        let user_response = execute(
+            &pool,
+            r#"mutation { registerUser(input: { email: "summary@test.com", name: "User" }) { id } }"#,
+        )
+        .await;
+        let user_id = user_response["data"]["registerUser"]["id"]
+            .as_str()
+            .unwrap();
+
+        execute_as(
+            &pool,
+            user_id,
+            &format!(
+                r#"mutation {{ createTodos(userId: "{}", items: [{{ title: "One" }}, {{ title: "Two" }}]) {{ id }} }}"#,
+                user_id
+            ),
+        )
+        .await;
+
+        let response = execute_as(
+            &pool,
+            user_id,
+            &format!(
+                r#"query {{ todoStatusSummary(userId: "{}") {{ pending inProgress completed cancelled }} }}"#,
+                user_id
+            ),
+        )
+        .await;
+
+        assert_no_errors(&response);
+        let summary = &response["data"]["todoStatusSummary"];
+        assert_eq!(summary["pending"], 2);
+        assert_eq!(summary["inProgress"], 0);
+        assert_eq!(summary["completed"], 0);
+        assert_eq!(summary["cancelled"], 0);
+    }
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn export_user_data_returns_json(pool: PgPool) {
+        let user_response = execute(
+            &pool,
+            r#"mutation { registerUser(input: { email: "export@test.com", name: "User" }) { id } }"#,
+        )
+        .await;
+        let user_id = user_response["data"]["registerUser"]["id"]
+            .as_str()
+            .unwrap();
+
+        execute_as(
+            &pool,
+            user_id,
+            &format!(
+                r#"mutation {{ createTodos(userId: "{}", items: [{{ title: "One" }}, {{ title: "Two" }}]) {{ id }} }}"#,
+                user_id
+            ),
+        )
+        .await;
+
+        let response = execute_as(
+            &pool,
+            user_id,
+            &format!(r#"query {{ exportUserData(userId: "{}") }}"#, user_id),
+        )
+        .await;
+
+        assert_no_errors(&response);
+        let export = &response["data"]["exportUserData"];
+        assert_eq!(export["email"], "export@test.com");
+        assert_eq!(export["todos"].as_array().unwrap().len(), 2);
+    }
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn todos_search_returns_connection(pool: PgPool) {
+        // Create user
+        let user_response = execute(
+            &pool,
+            r#"mutation { registerUser(input: { email: "search@test.com", name: "User" }) { id } }"#,
+        )
+        .await;
+        let user_id = user_response["data"]["registerUser"]["id"]
+            .as_str()
+            .unwrap();
+
+        let response = execute_as(
+            &pool,
+            user_id,
+            &format!(
+                r#"query {{
+                    todos(userId: "{}", filter: {{ query: "milk" }}, offset: 0, limit: 10) {{
+                        nodes {{ id title }}
+                        hasNextPage
+                    }}
+                }}"#,
+                user_id
+            ),
+        )
+        .await;
+
+        assert_no_errors(&response);
+        assert!(response["data"]["todos"]["nodes"].is_array());
+        assert!(response["data"]["todos"]["hasNextPage"].is_boolean());
+    }
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn todos_filter_returns_connection(pool: PgPool) {
+        // Create user
+        let user_response = execute(
+            &pool,
+            r#"mutation { registerUser(input: { email: "search@test.com", name: "User" }) { id } }"#,
+        )
+        .await;
+        let user_id = user_response["data"]["registerUser"]["id"]
+            .as_str()
+            .unwrap();
+
+        let response = execute_as(
             &pool,
+            user_id,
             &format!(
-                r#"mutation {{ startTodo(id: "{}") {{ id status }} }}"#,
-                todo_id
+                r#"query {{
+                    todos(
+                        userId: "{}",
+                        filter: {{ statuses: [PENDING, IN_PROGRESS], query: "milk", tag: "groceries", dueBefore: "2030-01-01T00:00:00Z" }},
+                        offset: 0,
+                        limit: 10
+                    ) {{
+                        nodes {{ id title }}
+                        hasNextPage
+                    }}
+                }}"#,
+                user_id
             ),
         )
         .await;
-        assert_no_errors(&start_response);
-        assert!(start_response["data"]["startTodo"]["status"].is_string());
 
-        // completeTodo returns Todo
-        let complete_response = execute(
+        assert_no_errors(&response);
+        assert!(response["data"]["todos"]["nodes"].is_array());
+        assert!(response["data"]["todos"]["hasNextPage"].is_boolean());
+    }
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn todos_for_another_user_is_forbidden(pool: PgPool) {
+        let owner = register_user(&pool, "todos-owner@test.com").await;
+        let other = register_user(&pool, "todos-other@test.com").await;
+
+        let query = format!(
+            r#"query {{ todos(userId: "{}") {{ nodes {{ id }} }} }}"#,
+            owner
+        );
+
+        // Other users are refused
+        let response = execute_as(&pool, &other, &query).await;
+        assert_has_errors(&response);
+        assert_eq!(response["errors"][0]["extensions"]["code"], "FORBIDDEN");
+
+        // And so are anonymous callers
+        let response = execute(&pool, &query).await;
+        assert_has_errors(&response);
+        assert_eq!(
+            response["errors"][0]["extensions"]["code"],
+            "UNAUTHENTICATED"
+        );
+    }
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn overdue_todos_returns_array(pool: PgPool) {
+        let user_response = execute(
+            &pool,
+            r#"mutation { registerUser(input: { email: "overdue@test.com", name: "User" }) { id } }"#,
+        )
+        .await;
+        let user_id = user_response["data"]["registerUser"]["id"]
+            .as_str()
+            .unwrap();
+
+        execute_as(
             &pool,
+            user_id,
             &format!(
-                r#"mutation {{ completeTodo(id: "{}") {{ id status }} }}"#,
-                todo_id
+                r#"mutation {{ createTodo(input: {{ userId: "{}", title: "Late", dueDate: "2020-01-01T00:00:00Z" }}) {{ id }} }}"#,
+                user_id
             ),
         )
         .await;
-        assert_no_errors(&complete_response);
-        assert!(complete_response["data"]["completeTodo"]["status"].is_string());
+
+        let response = execute_as(
+            &pool,
+            user_id,
+            &format!(
+                r#"query {{ overdueTodos(userId: "{}") {{ id title dueDate }} }}"#,
+                user_id
+            ),
+        )
+        .await;
+
+        assert_no_errors(&response);
+        let todos = response["data"]["overdueTodos"].as_array().unwrap();
+        assert_eq!(todos.len(), 1);
+        assert!(todos[0]["dueDate"].is_string());
     }
 
     #[sqlx::test(migrations = "../../../migrations")]
-    async fn delete_todo_returns_boolean(pool: PgPool) {
-        // Setup
+    async fn overdue_todos_for_another_user_is_forbidden(pool: PgPool) {
+        let owner = register_user(&pool, "overdue-owner@test.com").await;
+        let other = register_user(&pool, "overdue-other@test.com").await;
+
+        let response = execute_as(
+            &pool,
+            &other,
+            &format!(r#"query {{ overdueTodos(userId: "{}") {{ id }} }}"#, owner),
+        )
+        .await;
+
+        assert_has_errors(&response);
+        assert_eq!(response["errors"][0]["extensions"]["code"], "FORBIDDEN");
+    }
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn todo_is_overdue_returns_boolean(pool: PgPool) {
         let user_response = execute(
             &pool,
-            r#"mutation { registerUser(input: { email: "del-todo@test.com", name: "User" }) { id } }"#,
+            r#"mutation { registerUser(input: { email: "is-overdue@test.com", name: "User" }) { id } }"#,
         )
         .await;
         let user_id = user_response["data"]["registerUser"]["id"]
             .as_str()
             .unwrap();
 
-        let todo_response = execute(
+        let todo_response = execute_as(
             &pool,
+            user_id,
             &format!(
-                r#"mutation {{ createTodo(input: {{ userId: "{}", title: "Delete Me" }}) {{ id }} }}"#,
+                r#"mutation {{ createTodo(input: {{ userId: "{}", title: "Late", dueDate: "2020-01-01T00:00:00Z" }}) {{ id isOverdue }} }}"#,
                 user_id
             ),
         )
         .await;
+        assert_no_errors(&todo_response);
+        assert_eq!(todo_response["data"]["createTodo"]["isOverdue"], true);
         let todo_id = todo_response["data"]["createTodo"]["id"].as_str().unwrap();
 
-        // Delete
-        let response = execute(
+        // Completed todos are never overdue
+        let response = execute_as(
             &pool,
-            &format!(r#"mutation {{ deleteTodo(id: "{}") }}"#, todo_id),
+            user_id,
+            &format!(
+                r#"mutation {{ completeTodo(id: "{}") {{ isOverdue }} }}"#,
+                todo_id
+            ),
         )
         .await;
 
         assert_no_errors(&response);
-        assert!(response["data"]["deleteTodo"].is_boolean());
+        assert_eq!(response["data"]["completeTodo"]["isOverdue"], false);
     }
-}
 
-// =============================================================================
-// Todo Query Contracts
-// =============================================================================
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn todo_is_overdue_follows_the_request_clock(pool: PgPool) {
+        let user_response = execute(
+            &pool,
+            r#"mutation { registerUser(input: { email: "overdue-clock@test.com", name: "User" }) { id } }"#,
+        )
+        .await;
+        let user_id = user_response["data"]["registerUser"]["id"]
+            .as_str()
+            .unwrap();
+        let todo_response = execute_as(
+            &pool,
+            user_id,
+            &format!(
+                r#"mutation {{ createTodo(input: {{ userId: "{}", title: "Noon", dueDate: "2030-06-01T12:00:00Z" }}) {{ id }} }}"#,
+                user_id
+            ),
+        )
+        .await;
+        let todo_id = todo_response["data"]["createTodo"]["id"].as_str().unwrap();
 
-mod todo_queries {
-    use super::*;
+        // Due at exactly noon, the todo only becomes overdue after it
+        let schema = build_schema(pool.clone());
+        for (now, expected) in [
+            ("2030-06-01T12:00:00Z", false),
+            ("2030-06-01T12:00:01Z", true),
+        ] {
+            let clock: SharedClock =
+                Arc::new(FixedClock(OffsetDateTime::parse(now, &Rfc3339).unwrap()));
+            let query = format!(r#"query {{ todo(id: "{}") {{ isOverdue }} }}"#, todo_id);
+            let auth = AuthContext {
+                user_id: user_id.parse().unwrap(),
+                is_admin: false,
+            };
+            let response = schema
+                .execute(Request::new(query).data(clock).data(auth))
+                .await;
+            let response = serde_json::to_value(&response).unwrap();
+
+            assert_no_errors(&response);
+            assert_eq!(response["data"]["todo"]["isOverdue"], expected, "at {now}");
+        }
+    }
 
     #[sqlx::test(migrations = "../../../migrations")]
-    async fn todo_query_returns_todo_or_null(pool: PgPool) {
-        let response = execute(
+    async fn due_date_local_uses_owner_timezone(pool: PgPool) {
+        let user_response = execute(
             &pool,
-            r#"query { todo(id: "00000000-0000-0000-0000-000000000000") { id } }"#,
+            r#"mutation { registerUser(input: { email: "tokyo@test.com", name: "User", timezone: "Asia/Tokyo" }) { id timezone } }"#,
+        )
+        .await;
+        assert_no_errors(&user_response);
+        assert_eq!(user_response["data"]["registerUser"]["timezone"], "Asia/Tokyo");
+        let user_id = user_response["data"]["registerUser"]["id"]
+            .as_str()
+            .unwrap();
+
+        let response = execute_as(
+            &pool,
+            user_id,
+            &format!(
+                r#"mutation {{ createTodo(input: {{ userId: "{}", title: "Call", dueDate: "2030-01-15T00:00:00Z" }}) {{ dueDate dueDateLocal }} }}"#,
+                user_id
+            ),
         )
         .await;
 
         assert_no_errors(&response);
-        assert!(response["data"]["todo"].is_null());
+        let todo = &response["data"]["createTodo"];
+        assert_eq!(todo["dueDate"], "2030-01-15T00:00:00Z");
+        assert_eq!(todo["dueDateLocal"], "2030-01-15T09:00:00+09:00");
     }
 
     #[sqlx::test(migrations = "../../../migrations")]
-    async fn todos_for_user_returns_array(pool: PgPool) {
-        // Create user
+    async fn todo_owner_returns_user_type(pool: PgPool) {
         let user_response = execute(
             &pool,
-            r#"mutation { registerUser(input: { email: "list-todos@test.com", name: "User" }) { id } }"#,
+            r#"mutation { registerUser(input: { email: "owner@test.com", name: "Owner" }) { id } }"#,
         )
         .await;
         let user_id = user_response["data"]["registerUser"]["id"]
             .as_str()
             .unwrap();
 
-        let response = execute(
+        execute_as(
             &pool,
+            user_id,
             &format!(
-                r#"query {{ todosForUser(userId: "{}") {{ id title }} }}"#,
+                r#"mutation {{ createTodos(userId: "{}", items: [{{ title: "A" }}, {{ title: "B" }}]) {{ id }} }}"#,
+                user_id
+            ),
+        )
+        .await;
+
+        let response = execute_as(
+            &pool,
+            user_id,
+            &format!(
+                r#"query {{ todosForUser(userId: "{}") {{ id owner {{ id name }} }} }}"#,
                 user_id
             ),
         )
         .await;
 
         assert_no_errors(&response);
-        assert!(response["data"]["todosForUser"].is_array());
+        let todos = response["data"]["todosForUser"].as_array().unwrap();
+        assert_eq!(todos.len(), 2);
+        for todo in todos {
+            assert_eq!(todo["owner"]["id"], user_id);
+            assert_eq!(todo["owner"]["name"], "Owner");
+        }
     }
 
     #[sqlx::test(migrations = "../../../migrations")]
-    async fn todos_for_user_by_status_returns_array(pool: PgPool) {
-        // Create user
+    async fn database_errors_are_masked(pool: PgPool) {
+        // Break the schema so the next todo query fails inside Postgres
+        sqlx::query("ALTER TABLE todos RENAME COLUMN title TO renamed_title")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let user_id = "00000000-0000-0000-0000-000000000000";
+        let response = execute_as(
+            &pool,
+            user_id,
+            &format!(
+                r#"query {{ todosForUser(userId: "{}") {{ id }} }}"#,
+                user_id
+            ),
+        )
+        .await;
+
+        assert_has_errors(&response);
+        let error = &response["errors"][0];
+        assert_eq!(error["message"], "internal server error");
+        assert_eq!(error["extensions"]["code"], "INTERNAL");
+        let message = error["message"].as_str().unwrap().to_lowercase();
+        for keyword in ["select", "column", "title", "todos"] {
+            assert!(!message.contains(keyword), "leaked {keyword}: {message}");
+        }
+    }
+}
+
+// =============================================================================
+// Subscription Contracts
+// =============================================================================
+
+mod subscriptions {
+    use super::*;
+    use futures_util::{FutureExt, StreamExt};
+    use graphql_api::build_schema_with_events;
+    use std::time::Duration;
+    use todo_feature::TodoEvents;
+    use user_feature::UserEvents;
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn todo_status_changed_streams_todo_type(pool: PgPool) {
+        // Setup
         let user_response = execute(
             &pool,
-            r#"mutation { registerUser(input: { email: "status-list@test.com", name: "User" }) { id } }"#,
+            r#"mutation { registerUser(input: { email: "subscribe@test.com", name: "User" }) { id } }"#,
+        )
+        .await;
+        let user_id = user_response["data"]["registerUser"]["id"]
+            .as_str()
+            .unwrap();
+
+        let todo_response = execute_as(
+            &pool,
+            user_id,
+            &format!(
+                r#"mutation {{ createTodo(input: {{ userId: "{}", title: "Watch Me" }}) {{ id }} }}"#,
+                user_id
+            ),
+        )
+        .await;
+        let todo_id = todo_response["data"]["createTodo"]["id"].as_str().unwrap();
+
+        let events = TodoEvents::new();
+        let listener = events.listen(&pool).await.unwrap();
+        let schema =
+            build_schema_with_events(pool.clone(), pool.clone(), events, UserEvents::new());
+
+        // Subscribe as the owner; the first poll registers the subscriber
+        let auth = AuthContext {
+            user_id: user_id.parse().unwrap(),
+            is_admin: false,
+        };
+        let mut stream = schema.execute_stream(
+            Request::new(format!(
+                r#"subscription {{ todoStatusChanged(userId: "{}") {{ id status }} }}"#,
+                user_id
+            ))
+            .data(auth),
+        );
+        assert!(stream.next().now_or_never().is_none());
+
+        // completeTodo publishes an event
+        let complete_response = execute_as(
+            &pool,
+            user_id,
+            &format!(r#"mutation {{ completeTodo(id: "{}") {{ id }} }}"#, todo_id),
+        )
+        .await;
+        assert_no_errors(&complete_response);
+
+        let response = tokio::time::timeout(Duration::from_secs(5), stream.next())
+            .await
+            .expect("Timed out waiting for subscription event")
+            .expect("Subscription ended");
+        let response = serde_json::to_value(&response).unwrap();
+
+        assert_no_errors(&response);
+        let todo = &response["data"]["todoStatusChanged"];
+        assert_eq!(todo["id"], todo_id);
+        assert_eq!(todo["status"], "COMPLETED");
+
+        listener.abort();
+    }
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn todo_status_changed_for_another_user_is_forbidden(pool: PgPool) {
+        let schema = build_schema(pool.clone());
+        let auth = AuthContext {
+            user_id: "00000000-0000-0000-0000-000000000001".parse().unwrap(),
+            is_admin: false,
+        };
+        let mut stream = schema.execute_stream(
+            Request::new(
+                r#"subscription { todoStatusChanged(userId: "00000000-0000-0000-0000-000000000002") { id } }"#,
+            )
+            .data(auth),
+        );
+
+        let response = tokio::time::timeout(Duration::from_secs(5), stream.next())
+            .await
+            .expect("Timed out waiting for subscription response")
+            .expect("Subscription ended without a response");
+        let response = serde_json::to_value(&response).unwrap();
+
+        assert_has_errors(&response);
+        assert_eq!(response["errors"][0]["extensions"]["code"], "FORBIDDEN");
+    }
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn user_registered_streams_new_users(pool: PgPool) {
+        // Setup
+        let user_events = UserEvents::new();
+        let listener = user_events.listen(&pool).await.unwrap();
+        let schema =
+            build_schema_with_events(pool.clone(), pool.clone(), TodoEvents::new(), user_events);
+
+        // Subscribe as an admin; the first poll registers the subscriber
+        let admin = AuthContext {
+            user_id: uuid::Uuid::new_v4(),
+            is_admin: true,
+        };
+        let mut stream = schema.execute_stream(
+            Request::new("subscription { userRegistered { id email } }").data(admin),
+        );
+        assert!(stream.next().now_or_never().is_none());
+
+        // registerUser publishes an event
+        let register_response = execute(
+            &pool,
+            r#"mutation { registerUser(input: { email: "newcomer@test.com", name: "New" }) { id } }"#,
+        )
+        .await;
+        assert_no_errors(&register_response);
+
+        let response = tokio::time::timeout(Duration::from_secs(5), stream.next())
+            .await
+            .expect("Timed out waiting for subscription event")
+            .expect("Subscription ended");
+        let response = serde_json::to_value(&response).unwrap();
+
+        assert_no_errors(&response);
+        let user = &response["data"]["userRegistered"];
+        assert_eq!(user["email"], "newcomer@test.com");
+        assert_eq!(user["id"], register_response["data"]["registerUser"]["id"]);
+
+        listener.abort();
+    }
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn user_registered_is_refused_for_non_admins(pool: PgPool) {
+        let user_response = execute(
+            &pool,
+            r#"mutation { registerUser(input: { email: "snoop@test.com", name: "Snoop" }) { id } }"#,
         )
         .await;
         let user_id = user_response["data"]["registerUser"]["id"]
             .as_str()
             .unwrap();
 
+        let schema = build_schema(pool.clone());
+        let auth = AuthContext {
+            user_id: user_id.parse().unwrap(),
+            is_admin: false,
+        };
+        let mut stream = schema.execute_stream(
+            Request::new("subscription { userRegistered { id email } }").data(auth),
+        );
+
+        let response = tokio::time::timeout(Duration::from_secs(5), stream.next())
+            .await
+            .expect("Timed out waiting for subscription response")
+            .expect("Subscription ended without a response");
+        let response = serde_json::to_value(&response).unwrap();
+
+        assert_has_errors(&response);
+        assert_eq!(response["errors"][0]["extensions"]["code"], "FORBIDDEN");
+    }
+}
+
+// =============================================================================
+// Query Limit Contracts
+// =============================================================================
+
+mod query_limits {
+    use super::*;
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn overly_deep_query_is_rejected(pool: PgPool) {
+        // user -> todos -> owner repeated well past the default depth limit
+        let mut selection = String::from("id");
+        for _ in 0..6 {
+            selection = format!("id todos {{ id owner {{ {} }} }}", selection);
+        }
+        let query = format!(
+            r#"query {{ user(id: "00000000-0000-0000-0000-000000000000") {{ {} }} }}"#,
+            selection
+        );
+
+        let response = execute(&pool, &query).await;
+
+        assert_has_errors(&response);
+        assert!(response["data"].is_null(), "query should not have run");
+    }
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn nested_query_within_limits_succeeds(pool: PgPool) {
+        let response = execute(
+            &pool,
+            r#"query {
+                user(id: "00000000-0000-0000-0000-000000000000") {
+                    id
+                    todos { id owner { id } }
+                }
+            }"#,
+        )
+        .await;
+
+        assert_no_errors(&response);
+    }
+
+    /// Register a user with `count` pending todos, returning the user's ID
+    async fn user_with_todos(pool: &PgPool, count: i32) -> String {
         let response = execute(
+            pool,
+            r#"mutation { registerUser(input: { email: "pages@test.com", name: "User" }) { id } }"#,
+        )
+        .await;
+        let user_id = response["data"]["registerUser"]["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        sqlx::query(
+            "INSERT INTO todos (id, user_id, title) \
+             SELECT gen_random_uuid(), $1, 'Todo ' || n FROM generate_series(1, $2) AS n",
+        )
+        .bind(user_id.parse::<uuid::Uuid>().unwrap())
+        .bind(count)
+        .execute(pool)
+        .await
+        .unwrap();
+
+        user_id
+    }
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn omitted_page_size_uses_the_default(pool: PgPool) {
+        let user_id = user_with_todos(&pool, 30).await;
+
+        let response = execute_as(
+            &pool,
+            &user_id,
+            &format!(
+                r#"query {{ todosForUserByStatus(userId: "{}", status: PENDING) {{ nodes {{ id }} hasNextPage }} }}"#,
+                user_id
+            ),
+        )
+        .await;
+
+        assert_no_errors(&response);
+        let connection = &response["data"]["todosForUserByStatus"];
+        assert_eq!(connection["nodes"].as_array().unwrap().len(), 20);
+        assert_eq!(connection["hasNextPage"], true);
+    }
+
+    #[sqlx::test(migrations = "../../../migrations")]
+    async fn oversized_page_is_capped_at_the_maximum(pool: PgPool) {
+        let user_id = user_with_todos(&pool, 120).await;
+
+        let response = execute_as(
             &pool,
+            &user_id,
             &format!(
-                r#"query {{ todosForUserByStatus(userId: "{}", status: PENDING) {{ id }} }}"#,
+                r#"query {{ todosForUserByStatus(userId: "{}", status: PENDING, first: 1000) {{ nodes {{ id }} hasNextPage }} }}"#,
                 user_id
             ),
         )
         .await;
 
         assert_no_errors(&response);
-        assert!(response["data"]["todosForUserByStatus"].is_array());
+        let connection = &response["data"]["todosForUserByStatus"];
+        assert_eq!(connection["nodes"].as_array().unwrap().len(), 100);
+        assert_eq!(connection["hasNextPage"], true);
     }
 }
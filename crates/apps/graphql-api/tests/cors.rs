@@ -0,0 +1,117 @@
+//! CORS tests
+//!
+//! Browsers send an `OPTIONS` preflight before a cross-origin GraphQL POST;
+//! only configured origins may get an `Access-Control-Allow-Origin` back.
+
+use axum::body::Body;
+use axum::http::{HeaderValue, Method, Request, StatusCode, header};
+use axum::response::Response;
+use graphql_api::app::{AppState, DEFAULT_GRAPHQL_TIMEOUT, DEFAULT_MAX_REQUEST_BYTES, router};
+use graphql_api::auth::JwtAuth;
+use graphql_api::build_schema;
+use graphql_api::cors::{AllowedOrigins, CorsConfigError};
+use sqlx::PgPool;
+use tower::ServiceExt;
+
+const APP_ORIGIN: &str = "https://app.example.com";
+
+/// Send a preflight for a GraphQL POST from `origin`
+async fn preflight(pool: &PgPool, origins: &AllowedOrigins, origin: &str) -> Response {
+    let app = router(AppState {
+        pool: pool.clone(),
+        schema: build_schema(pool.clone()),
+        auth: JwtAuth::new(b"test-secret"),
+        http_status_codes: false,
+        max_request_bytes: DEFAULT_MAX_REQUEST_BYTES,
+        graphql_timeout: DEFAULT_GRAPHQL_TIMEOUT,
+    })
+    .layer(origins.layer());
+
+    let request = Request::builder()
+        .method(Method::OPTIONS)
+        .uri("/graphql")
+        .header(header::ORIGIN, origin)
+        .header(header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+        .header(
+            header::ACCESS_CONTROL_REQUEST_HEADERS,
+            "content-type,authorization",
+        )
+        .body(Body::empty())
+        .unwrap();
+
+    app.oneshot(request).await.unwrap()
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn preflight_from_an_allowed_origin_is_allowed(pool: PgPool) {
+    let origins = AllowedOrigins::List(vec![HeaderValue::from_static(APP_ORIGIN)]);
+
+    let response = preflight(&pool, &origins, APP_ORIGIN).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let headers = response.headers();
+    assert_eq!(headers[header::ACCESS_CONTROL_ALLOW_ORIGIN], APP_ORIGIN);
+    let methods = headers[header::ACCESS_CONTROL_ALLOW_METHODS]
+        .to_str()
+        .unwrap();
+    assert!(methods.contains("POST"));
+    let allowed_headers = headers[header::ACCESS_CONTROL_ALLOW_HEADERS]
+        .to_str()
+        .unwrap();
+    assert!(allowed_headers.contains("authorization"));
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn preflight_from_another_origin_gets_no_allow_origin(pool: PgPool) {
+    let origins = AllowedOrigins::List(vec![HeaderValue::from_static(APP_ORIGIN)]);
+
+    let listed = preflight(&pool, &origins, "https://evil.example.com").await;
+    let same_origin = preflight(&pool, &AllowedOrigins::SameOrigin, APP_ORIGIN).await;
+
+    assert!(
+        !listed
+            .headers()
+            .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+    );
+    assert!(
+        !same_origin
+            .headers()
+            .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+    );
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn wildcard_allows_any_origin(pool: PgPool) {
+    let response = preflight(&pool, &AllowedOrigins::Any, "https://anywhere.example.com").await;
+
+    assert_eq!(response.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN], "*");
+}
+
+#[test]
+fn allowed_origins_are_read_from_the_environment() {
+    let parse = |value: &str| {
+        let value = value.to_string();
+        AllowedOrigins::from_vars(move |name| {
+            (name == "CORS_ALLOWED_ORIGINS").then(|| value.clone())
+        })
+    };
+
+    assert_eq!(
+        AllowedOrigins::from_vars(|_| None),
+        Ok(AllowedOrigins::SameOrigin)
+    );
+    assert_eq!(parse(" * "), Ok(AllowedOrigins::Any));
+    assert_eq!(
+        parse("https://a.example.com, http://localhost:5173,"),
+        Ok(AllowedOrigins::List(vec![
+            HeaderValue::from_static("https://a.example.com"),
+            HeaderValue::from_static("http://localhost:5173"),
+        ]))
+    );
+    assert_eq!(
+        parse("app.example.com"),
+        Err(CorsConfigError::InvalidOrigin(
+            "app.example.com".to_string()
+        ))
+    );
+}
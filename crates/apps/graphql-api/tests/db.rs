@@ -0,0 +1,148 @@
+//! Database connection tests
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use graphql_api::db::{DbConfig, DbConfigError, MAX_CONNECT_DELAY, build_pool, connect_with_retry};
+use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+
+/// Parse a config from the given variables only
+fn parse(vars: &[(&str, &str)]) -> Result<DbConfig, DbConfigError> {
+    let vars: HashMap<String, String> = vars
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    DbConfig::from_vars(|name| vars.get(name).cloned())
+}
+
+#[test]
+fn statement_timeout_defaults_to_five_seconds() {
+    assert_eq!(
+        parse(&[]).unwrap().statement_timeout,
+        Duration::from_secs(5)
+    );
+}
+
+#[test]
+fn statement_timeout_is_read_in_milliseconds() {
+    let config = parse(&[("DB_STATEMENT_TIMEOUT_MS", "250")]).unwrap();
+
+    assert_eq!(config.statement_timeout, Duration::from_millis(250));
+}
+
+#[test]
+fn non_numeric_statement_timeout_is_rejected() {
+    assert!(matches!(
+        parse(&[("DB_STATEMENT_TIMEOUT_MS", "5s")]),
+        Err(DbConfigError::InvalidNumber {
+            name: "DB_STATEMENT_TIMEOUT_MS",
+            ..
+        })
+    ));
+}
+
+#[test]
+fn connect_retry_settings_are_read() {
+    let config = parse(&[("DB_CONNECT_RETRIES", "3"), ("DB_CONNECT_DELAY_MS", "200")]).unwrap();
+
+    assert_eq!(config.connect_retries, 3);
+    assert_eq!(config.connect_delay, Duration::from_millis(200));
+}
+
+#[test]
+fn pool_size_defaults_to_twenty_connections() {
+    let config = parse(&[]).unwrap();
+
+    assert_eq!((config.min_connections, config.max_connections), (0, 20));
+    assert_eq!(config.acquire_timeout, Duration::from_secs(30));
+}
+
+#[test]
+fn pool_settings_are_read() {
+    let config = parse(&[
+        ("DB_MAX_CONNECTIONS", "50"),
+        ("DB_MIN_CONNECTIONS", "5"),
+        ("DB_ACQUIRE_TIMEOUT_MS", "1500"),
+    ])
+    .unwrap();
+
+    assert_eq!((config.min_connections, config.max_connections), (5, 50));
+    assert_eq!(config.acquire_timeout, Duration::from_millis(1500));
+}
+
+#[test]
+fn min_connections_above_max_is_rejected() {
+    assert_eq!(
+        parse(&[("DB_MAX_CONNECTIONS", "4"), ("DB_MIN_CONNECTIONS", "8")]),
+        Err(DbConfigError::ConnectionRange { min: 8, max: 4 })
+    );
+}
+
+#[test]
+fn zero_max_connections_is_rejected() {
+    assert_eq!(
+        parse(&[("DB_MAX_CONNECTIONS", "0")]),
+        Err(DbConfigError::NotPositive {
+            name: "DB_MAX_CONNECTIONS",
+            value: "0".to_string(),
+        })
+    );
+}
+
+#[test]
+fn connect_backoff_doubles_after_each_attempt() {
+    let config = DbConfig {
+        connect_delay: Duration::from_millis(500),
+        ..DbConfig::default()
+    };
+
+    let schedule: Vec<Duration> = (0..4).map(|attempt| config.backoff(attempt)).collect();
+
+    assert_eq!(schedule, [500, 1000, 2000, 4000].map(Duration::from_millis));
+}
+
+#[test]
+fn connect_backoff_is_capped() {
+    let config = DbConfig::default();
+
+    assert_eq!(config.backoff(10), MAX_CONNECT_DELAY);
+    assert_eq!(config.backoff(u32::MAX), MAX_CONNECT_DELAY);
+}
+
+#[tokio::test]
+async fn connect_gives_up_after_retries() {
+    let config = DbConfig {
+        connect_retries: 2,
+        connect_delay: Duration::from_millis(10),
+        ..DbConfig::default()
+    };
+
+    // Nothing listens on port 1, so every attempt fails to connect
+    let result = connect_with_retry("postgres://postgres@127.0.0.1:1/none", &config).await;
+
+    assert!(matches!(result, Err(sqlx::Error::Io(_))));
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn slow_statements_are_cancelled(_: PgPoolOptions, connect: PgConnectOptions) {
+    let config = DbConfig {
+        statement_timeout: Duration::from_millis(100),
+        ..DbConfig::default()
+    };
+    let pool = build_pool(&config, connect)
+        .await
+        .expect("Failed to connect");
+
+    let result = sqlx::query("SELECT pg_sleep(2)").execute(&pool).await;
+
+    // 57014 is query_canceled, raised when statement_timeout fires
+    let error = result.expect_err("Slow query should time out");
+    let code = error.as_database_error().and_then(|e| e.code());
+    assert_eq!(code.as_deref(), Some("57014"));
+
+    // Fast statements on the same pool still work
+    sqlx::query("SELECT 1")
+        .execute(&pool)
+        .await
+        .expect("Fast query should succeed");
+}
@@ -0,0 +1,94 @@
+//! HTTP endpoint configuration tests
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use graphql_api::app::{
+    DEFAULT_GRAPHQL_TIMEOUT, DEFAULT_MAX_REQUEST_BYTES, HttpConfig, HttpConfigError,
+};
+
+/// Parse a config from the given variables only
+fn parse(vars: &[(&str, &str)]) -> Result<HttpConfig, HttpConfigError> {
+    let vars: HashMap<String, String> = vars
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    HttpConfig::from_vars(|name| vars.get(name).cloned())
+}
+
+#[test]
+fn unset_variables_use_defaults() {
+    let config = parse(&[]).unwrap();
+
+    assert_eq!(config, HttpConfig::default());
+    assert!(!config.http_status_codes);
+    assert_eq!(config.max_request_bytes, DEFAULT_MAX_REQUEST_BYTES);
+    assert_eq!(config.graphql_timeout, DEFAULT_GRAPHQL_TIMEOUT);
+}
+
+#[test]
+fn variables_override_defaults() {
+    let config = parse(&[
+        ("GRAPHQL_HTTP_STATUS_CODES", " TRUE "),
+        ("MAX_REQUEST_BYTES", "1024"),
+        ("GRAPHQL_TIMEOUT_MS", "2500"),
+    ])
+    .unwrap();
+
+    assert!(config.http_status_codes);
+    assert_eq!(config.max_request_bytes, 1024);
+    assert_eq!(config.graphql_timeout, Duration::from_millis(2500));
+}
+
+#[test]
+fn status_codes_flag_accepts_one_and_zero() {
+    assert!(
+        parse(&[("GRAPHQL_HTTP_STATUS_CODES", "1")])
+            .unwrap()
+            .http_status_codes
+    );
+    assert!(
+        !parse(&[("GRAPHQL_HTTP_STATUS_CODES", "0")])
+            .unwrap()
+            .http_status_codes
+    );
+}
+
+#[test]
+fn unrecognised_status_codes_flag_is_rejected() {
+    let result = parse(&[("GRAPHQL_HTTP_STATUS_CODES", "yes")]);
+
+    assert_eq!(
+        result,
+        Err(HttpConfigError::InvalidFlag {
+            name: "GRAPHQL_HTTP_STATUS_CODES",
+            value: "yes".to_string(),
+        })
+    );
+}
+
+#[test]
+fn non_numeric_request_size_is_rejected() {
+    let result = parse(&[("MAX_REQUEST_BYTES", "1mb")]);
+
+    assert!(matches!(
+        result,
+        Err(HttpConfigError::InvalidNumber {
+            name: "MAX_REQUEST_BYTES",
+            ..
+        })
+    ));
+}
+
+#[test]
+fn zero_timeout_is_rejected() {
+    let result = parse(&[("GRAPHQL_TIMEOUT_MS", "0")]);
+
+    assert!(matches!(
+        result,
+        Err(HttpConfigError::InvalidNumber {
+            name: "GRAPHQL_TIMEOUT_MS",
+            ..
+        })
+    ));
+}
@@ -0,0 +1,85 @@
+//! Job runner configuration tests
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use graphql_api::jobs::{JobConfigError, JobRunnerConfig, start_job_runner};
+use sqlx::PgPool;
+use user_feature::{LogEmailSender, Mailer};
+
+/// Parse a config from the given variables only
+fn parse(vars: &[(&str, &str)]) -> Result<JobRunnerConfig, JobConfigError> {
+    let vars: HashMap<String, String> = vars
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    JobRunnerConfig::from_vars(|name| vars.get(name).cloned())
+}
+
+#[test]
+fn unset_variables_use_defaults() {
+    let config = parse(&[]).unwrap();
+
+    assert_eq!(config, JobRunnerConfig::default());
+    assert_eq!(
+        config.channels,
+        vec!["emails", "reminders", "events", "notifications"]
+    );
+    assert_eq!((config.min_concurrency, config.max_concurrency), (2, 10));
+}
+
+#[test]
+fn variables_override_defaults() {
+    let config = parse(&[
+        ("JOB_CHANNELS", "emails, reminders ,reports"),
+        ("JOB_CONCURRENCY_MIN", "4"),
+        ("JOB_CONCURRENCY_MAX", "16"),
+    ])
+    .unwrap();
+
+    assert_eq!(config.channels, vec!["emails", "reminders", "reports"]);
+    assert_eq!(config.min_concurrency, 4);
+    assert_eq!(config.max_concurrency, 16);
+}
+
+#[test]
+fn max_below_min_is_rejected() {
+    let result = parse(&[("JOB_CONCURRENCY_MIN", "8"), ("JOB_CONCURRENCY_MAX", "4")]);
+
+    assert_eq!(
+        result,
+        Err(JobConfigError::ConcurrencyRange { min: 8, max: 4 })
+    );
+}
+
+#[test]
+fn non_numeric_concurrency_is_rejected() {
+    let result = parse(&[("JOB_CONCURRENCY_MAX", "lots")]);
+
+    assert!(matches!(
+        result,
+        Err(JobConfigError::InvalidNumber {
+            name: "JOB_CONCURRENCY_MAX",
+            ..
+        })
+    ));
+}
+
+#[test]
+fn empty_channel_list_is_rejected() {
+    assert_eq!(
+        parse(&[("JOB_CHANNELS", " , ")]),
+        Err(JobConfigError::NoChannels)
+    );
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn job_runner_starts_with_config(pool: PgPool) {
+    let mailer: Mailer = Arc::new(LogEmailSender);
+    let mut runner = start_job_runner(&pool, &JobRunnerConfig::default(), mailer)
+        .await
+        .expect("Failed to start job runner");
+
+    assert_eq!(runner.num_running_jobs(), 0);
+    runner.stop().await;
+}
@@ -0,0 +1,146 @@
+//! Log format and request span tests
+
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+
+use async_graphql::Request;
+use graphql_api::build_schema;
+use graphql_api::telemetry::{LogFormat, LogFormatError, subscriber};
+use sqlx::PgPool;
+use tracing::field::{Field, Visit};
+use tracing::instrument::WithSubscriber;
+use tracing::span::{Attributes, Id};
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::{Layer, Registry};
+
+/// Parse the format from the given variables only
+fn parse(vars: &[(&str, &str)]) -> Result<LogFormat, LogFormatError> {
+    let vars: HashMap<String, String> = vars
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    LogFormat::from_vars(|name| vars.get(name).cloned())
+}
+
+/// Collects everything written to it
+#[derive(Clone, Default)]
+struct Output(Arc<Mutex<Vec<u8>>>);
+
+impl Output {
+    fn contents(&self) -> String {
+        String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+    }
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a> MakeWriter<'a> for Output {
+    type Writer = Self;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self.clone()
+    }
+}
+
+/// Log one event in `format`, returning what was written
+fn log_line(format: LogFormat) -> String {
+    let output = Output::default();
+    tracing::subscriber::with_default(subscriber(format, output.clone()), || {
+        tracing::info!(target: "graphql_api", "Logging works");
+    });
+    output.contents()
+}
+
+/// Records the `operation` field of every `graphql_request` span opened
+#[derive(Clone, Default)]
+struct RequestSpans(Arc<Mutex<Vec<String>>>);
+
+impl<S: tracing::Subscriber> Layer<S> for RequestSpans {
+    fn on_new_span(&self, attrs: &Attributes<'_>, _id: &Id, _ctx: Context<'_, S>) {
+        if attrs.metadata().name() != "graphql_request" {
+            return;
+        }
+
+        let mut field = OperationField::default();
+        attrs.record(&mut field);
+        if let Some(operation) = field.0 {
+            self.0.lock().unwrap().push(operation);
+        }
+    }
+}
+
+/// Picks the `operation` field out of a span
+#[derive(Default)]
+struct OperationField(Option<String>);
+
+impl Visit for OperationField {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "operation" {
+            self.0 = Some(value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, _field: &Field, _value: &dyn std::fmt::Debug) {}
+}
+
+#[test]
+fn unset_format_is_pretty() {
+    assert_eq!(parse(&[]), Ok(LogFormat::Pretty));
+}
+
+#[test]
+fn format_is_case_insensitive() {
+    assert_eq!(parse(&[("LOG_FORMAT", " JSON ")]), Ok(LogFormat::Json));
+    assert_eq!(parse(&[("LOG_FORMAT", "Pretty")]), Ok(LogFormat::Pretty));
+}
+
+#[test]
+fn unknown_format_is_rejected() {
+    assert_eq!(
+        parse(&[("LOG_FORMAT", "xml")]),
+        Err(LogFormatError::Unknown("xml".to_string()))
+    );
+}
+
+#[test]
+fn pretty_format_writes_plain_lines() {
+    let output = log_line(LogFormat::Pretty);
+
+    assert!(output.contains("Logging works"), "got {output:?}");
+    assert!(serde_json::from_str::<serde_json::Value>(output.trim()).is_err());
+}
+
+#[test]
+fn json_format_writes_json_lines() {
+    let output = log_line(LogFormat::Json);
+
+    let line: serde_json::Value = serde_json::from_str(output.trim()).unwrap();
+    assert_eq!(line["fields"]["message"], "Logging works");
+    assert_eq!(line["level"], "INFO");
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn graphql_request_span_carries_operation_name(pool: PgPool) {
+    let schema = build_schema(pool);
+    let spans = RequestSpans::default();
+
+    for query in ["query Named { __typename }", "{ __typename }"] {
+        schema
+            .execute(Request::new(query))
+            .with_subscriber(Registry::default().with(spans.clone()))
+            .await;
+    }
+
+    assert_eq!(*spans.0.lock().unwrap(), ["Named", "anonymous"]);
+}
@@ -0,0 +1,95 @@
+//! Metrics endpoint tests
+
+use std::sync::Arc;
+
+use axum::Router;
+use axum::body::{Body, to_bytes};
+use axum::http::{Request, StatusCode, header};
+use graphql_api::app::{AppState, DEFAULT_GRAPHQL_TIMEOUT, DEFAULT_MAX_REQUEST_BYTES, router};
+use graphql_api::auth::JwtAuth;
+use graphql_api::build_schema;
+use graphql_api::telemetry::install_recorder;
+use serde_json::json;
+use sqlx::PgPool;
+use tower::ServiceExt;
+use user_feature::{CreateUserInput, LogEmailSender, Mailer, UserJobs, UserService};
+
+fn app(pool: &PgPool) -> Router {
+    router(AppState {
+        pool: pool.clone(),
+        schema: build_schema(pool.clone()),
+        auth: JwtAuth::new(b"test-secret"),
+        http_status_codes: false,
+        max_request_bytes: DEFAULT_MAX_REQUEST_BYTES,
+        graphql_timeout: DEFAULT_GRAPHQL_TIMEOUT,
+    })
+}
+
+async fn post_graphql(app: &Router, query: &str) {
+    let request = Request::post("/graphql")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(json!({ "query": query }).to_string()))
+        .unwrap();
+
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn metrics_endpoint_reports_requests_pool_and_jobs(pool: PgPool) {
+    install_recorder();
+    let app = app(&pool);
+
+    // A few GraphQL queries and a mutation, some of them named
+    post_graphql(&app, "query { users { id } }").await;
+    post_graphql(&app, "query ListUsers { users { id } }").await;
+    post_graphql(
+        &app,
+        r#"mutation Register { registerUser(input: { email: "label@test.com", name: "Label" }) { id } }"#,
+    )
+    .await;
+
+    // And a job run
+    UserService::register(
+        &pool,
+        CreateUserInput {
+            email: "metrics@test.com".to_string(),
+            name: "Metrics".to_string(),
+            timezone: None,
+            idempotency_key: None,
+        },
+    )
+    .await
+    .unwrap();
+    let mailer: Mailer = Arc::new(LogEmailSender);
+    UserJobs::registry(mailer)
+        .runner(&pool)
+        .set_channel_names(&["emails"])
+        .test_one()
+        .await
+        .unwrap();
+
+    let response = app
+        .oneshot(Request::get("/metrics").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let body = String::from_utf8(body.to_vec()).unwrap();
+
+    for expected in [
+        r#"graphql_requests_total{operation="query"}"#,
+        r#"graphql_requests_total{operation="mutation"}"#,
+        "graphql_request_duration_seconds_bucket",
+        r#"graphql_resolver_duration_seconds_count{field="QueryRoot.users"}"#,
+        "db_pool_connections",
+        "db_pool_idle_connections",
+        r#"jobs_total{job="send_welcome_email",outcome="success"}"#,
+    ] {
+        assert!(body.contains(expected), "missing {expected} in:\n{body}");
+    }
+
+    // Client-chosen operation names never become label values
+    assert!(!body.contains("ListUsers"), "unexpected label in:\n{body}");
+    assert!(!body.contains("Register"), "unexpected label in:\n{body}");
+}
@@ -0,0 +1,86 @@
+//! Outbox dispatch tests
+
+use domain::EventRepository;
+use graphql_api::outbox::{dispatch_outbox, handle_domain_event};
+use serde_json::json;
+use sqlx::PgPool;
+use sqlxmq::JobRegistry;
+
+async fn event_job_count(pool: &PgPool) -> i64 {
+    sqlx::query_scalar(
+        "SELECT COUNT(*) FROM mq_msgs WHERE channel_name = 'events' AND id != uuid_nil()",
+    )
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn dispatch_turns_events_into_jobs_once(pool: PgPool) {
+    // Given two recorded events
+    let first = EventRepository::record(&pool, "user.registered", &json!({ "n": 1 }))
+        .await
+        .unwrap();
+    EventRepository::record(&pool, "todo.completed", &json!({ "n": 2 }))
+        .await
+        .unwrap();
+
+    // When the outbox is dispatched
+    assert_eq!(dispatch_outbox(&pool, 10).await.unwrap(), 2);
+
+    // Then each event became a job and was marked processed
+    assert_eq!(event_job_count(&pool).await, 2);
+    assert!(
+        EventRepository::claim_unprocessed(&pool, 10)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    // And dispatching again sends nothing new
+    assert_eq!(dispatch_outbox(&pool, 10).await.unwrap(), 0);
+    assert_eq!(event_job_count(&pool).await, 2);
+
+    // And the jobs carry the event
+    let payload: serde_json::Value = sqlx::query_scalar(
+        "SELECT p.payload_json::jsonb FROM mq_payloads p
+         JOIN mq_msgs m ON p.id = m.id
+         WHERE m.channel_name = 'events' AND p.payload_json::jsonb->>'event_id' = $1",
+    )
+    .bind(first.to_string())
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(payload["event_type"], "user.registered");
+    assert_eq!(payload["payload"], json!({ "n": 1 }));
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn dispatch_respects_batch_size(pool: PgPool) {
+    for n in 0..3 {
+        EventRepository::record(&pool, "todo.completed", &json!({ "n": n }))
+            .await
+            .unwrap();
+    }
+
+    assert_eq!(dispatch_outbox(&pool, 2).await.unwrap(), 2);
+    assert_eq!(dispatch_outbox(&pool, 2).await.unwrap(), 1);
+    assert_eq!(event_job_count(&pool).await, 3);
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn dispatched_event_job_completes(pool: PgPool) {
+    EventRepository::record(&pool, "user.registered", &json!({}))
+        .await
+        .unwrap();
+    dispatch_outbox(&pool, 10).await.unwrap();
+
+    JobRegistry::new(&[handle_domain_event])
+        .runner(&pool)
+        .set_channel_names(&["events"])
+        .test_one()
+        .await
+        .unwrap();
+
+    assert_eq!(event_job_count(&pool).await, 0);
+}
@@ -0,0 +1,63 @@
+//! Running with separate pools for queries and mutations
+//!
+//! Both pools point at the same test database; these tests check that
+//! resolvers still find the pool they need when the two are split.
+
+use async_graphql::Request;
+use graphql_api::build_schema_with_replica;
+use serde_json::Value;
+use sqlx::PgPool;
+
+/// A second pool on the same database, standing in for a replica
+async fn replica_of(pool: &PgPool) -> PgPool {
+    PgPool::connect_with((*pool.connect_options()).clone())
+        .await
+        .expect("Failed to connect replica pool")
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn queries_and_mutations_succeed_with_two_pools(pool: PgPool) {
+    let read_pool = replica_of(&pool).await;
+    let schema = build_schema_with_replica(pool.clone(), read_pool);
+
+    // A mutation writes through the primary pool
+    let response = schema
+        .execute(Request::new(
+            r#"mutation { registerUser(input: { email: "replica@test.com", name: "Replica" }) { id } }"#,
+        ))
+        .await;
+    let response: Value = serde_json::to_value(&response).unwrap();
+    assert!(response["errors"].is_null(), "{response}");
+    let user_id = response["data"]["registerUser"]["id"].as_str().unwrap();
+
+    // And a query reads it back through the read pool
+    let response = schema
+        .execute(Request::new(format!(
+            r#"query {{ user(id: "{user_id}") {{ email }} }}"#
+        )))
+        .await;
+    let response: Value = serde_json::to_value(&response).unwrap();
+    assert!(response["errors"].is_null(), "{response}");
+    assert_eq!(response["data"]["user"]["email"], "replica@test.com");
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn open_todo_counts_come_from_the_read_pool(pool: PgPool) {
+    // With the read pool unavailable
+    let read_pool = replica_of(&pool).await;
+    read_pool.close().await;
+    let schema = build_schema_with_replica(pool.clone(), read_pool);
+
+    // A mutation still writes through the primary pool, but its open todo
+    // count can't be loaded
+    let response = schema
+        .execute(Request::new(
+            r#"mutation { registerUser(input: { email: "count@test.com", name: "Count" }) { id openTodoCount } }"#,
+        ))
+        .await;
+    let response: Value = serde_json::to_value(&response).unwrap();
+    assert_eq!(
+        response["errors"][0]["path"][1], "openTodoCount",
+        "{response}"
+    );
+}
@@ -0,0 +1,79 @@
+//! Request correlation ID tests
+
+use axum::body::{Body, to_bytes};
+use axum::http::{Request, StatusCode, header};
+use graphql_api::app::{AppState, DEFAULT_GRAPHQL_TIMEOUT, DEFAULT_MAX_REQUEST_BYTES, router};
+use graphql_api::auth::JwtAuth;
+use graphql_api::build_schema;
+use graphql_api::request_id::REQUEST_ID_HEADER;
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use time::Duration;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+/// POST a GraphQL query as the nil user, optionally with an `X-Request-Id`,
+/// returning the response's request ID and body
+async fn post_graphql(pool: &PgPool, request_id: Option<&str>, query: &str) -> (String, Value) {
+    let auth = JwtAuth::new(b"test-secret");
+    let token = auth.issue(Uuid::nil(), Duration::hours(1)).unwrap();
+    let app = router(AppState {
+        pool: pool.clone(),
+        schema: build_schema(pool.clone()),
+        auth,
+        http_status_codes: false,
+        max_request_bytes: DEFAULT_MAX_REQUEST_BYTES,
+        graphql_timeout: DEFAULT_GRAPHQL_TIMEOUT,
+    });
+
+    let mut request = Request::post("/graphql")
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token));
+    if let Some(id) = request_id {
+        request = request.header(REQUEST_ID_HEADER, id);
+    }
+    let request = request
+        .body(Body::from(json!({ "query": query }).to_string()))
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let id = response.headers()[REQUEST_ID_HEADER]
+        .to_str()
+        .unwrap()
+        .to_string();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (id, serde_json::from_slice(&body).unwrap())
+}
+
+const NOT_FOUND: &str =
+    r#"mutation { resendWelcomeEmail(userId: "00000000-0000-0000-0000-000000000000") }"#;
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn missing_request_id_is_generated(pool: PgPool) {
+    let (id, body) = post_graphql(&pool, None, "query { users { id } }").await;
+
+    assert!(body["errors"].is_null());
+    assert!(Uuid::parse_str(&id).is_ok(), "expected a UUID, got {id:?}");
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn supplied_request_id_is_echoed(pool: PgPool) {
+    let (id, _) = post_graphql(&pool, Some("support-ticket-42"), "query { users { id } }").await;
+
+    assert_eq!(id, "support-ticket-42");
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn graphql_errors_carry_the_request_id(pool: PgPool) {
+    let (_, body) = post_graphql(&pool, Some("support-ticket-42"), NOT_FOUND).await;
+    assert_eq!(body["errors"][0]["extensions"]["code"], "NOT_FOUND");
+    assert_eq!(
+        body["errors"][0]["extensions"]["requestId"],
+        "support-ticket-42"
+    );
+
+    // A generated ID is reported the same way
+    let (id, body) = post_graphql(&pool, None, NOT_FOUND).await;
+    assert_eq!(body["errors"][0]["extensions"]["requestId"], id.as_str());
+}
@@ -0,0 +1,53 @@
+//! Shutdown tests
+//!
+//! These check that draining a job runner lets in-flight jobs finish instead
+//! of cutting them off.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use graphql_api::shutdown::drain_job_runner;
+use sqlx::PgPool;
+use sqlxmq::{JobBuilder, JobRunnerOptions};
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn drain_waits_for_in_flight_jobs(pool: PgPool) {
+    // A runner whose only job takes a while to finish
+    let finished = Arc::new(AtomicBool::new(false));
+    let job_finished = finished.clone();
+    let mut runner = JobRunnerOptions::new(&pool, move |mut job| {
+        let finished = job_finished.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            job.complete().await.expect("Failed to complete job");
+            finished.store(true, Ordering::SeqCst);
+        });
+    })
+    .set_channel_names(&["drain-test"])
+    .run()
+    .await
+    .expect("Failed to start runner");
+
+    JobBuilder::new("slow_job")
+        .set_channel_name("drain-test")
+        .spawn(&pool)
+        .await
+        .expect("Failed to spawn job");
+
+    // Wait for the runner to pick the job up
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while runner.num_running_jobs() == 0 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("Job was never picked up");
+
+    // When draining
+    drain_job_runner("drain-test", &mut runner, Duration::from_secs(5)).await;
+
+    // Then the job ran to completion rather than being aborted
+    assert!(finished.load(Ordering::SeqCst));
+    assert_eq!(runner.num_running_jobs(), 0);
+}
@@ -6,6 +6,7 @@
 //! Keep these minimal - detailed behavior testing happens at the feature layer.
 
 use async_graphql::Request;
+use graphql_api::auth::AuthContext;
 use graphql_api::build_schema;
 use serde_json::Value;
 use sqlx::PgPool;
@@ -17,6 +18,17 @@ async fn execute(pool: &PgPool, query: &str) -> Value {
     serde_json::to_value(&response).expect("Failed to serialize response")
 }
 
+/// Execute a GraphQL query authenticated as the given user
+async fn execute_as(pool: &PgPool, user_id: &str, query: &str) -> Value {
+    let schema = build_schema(pool.clone());
+    let auth = AuthContext {
+        user_id: user_id.parse().expect("Invalid user ID"),
+        is_admin: false,
+    };
+    let response = schema.execute(Request::new(query).data(auth)).await;
+    serde_json::to_value(&response).expect("Failed to serialize response")
+}
+
 /// Assert response has no errors
 fn assert_no_errors(response: &Value) {
     let errors = &response["errors"];
@@ -88,7 +100,7 @@ async fn smoke_test_complete_user_and_todo_workflow(pool: PgPool) {
         "#,
         user_id
     );
-    let todo_response = execute(&pool, &create_todo_query).await;
+    let todo_response = execute_as(&pool, &user_id, &create_todo_query).await;
     assert_no_errors(&todo_response);
     let todo_id = get_string(&todo_response, &["data", "createTodo", "id"]);
     assert_eq!(
@@ -101,7 +113,7 @@ async fn smoke_test_complete_user_and_todo_workflow(pool: PgPool) {
         r#"mutation {{ startTodo(id: "{}") {{ status }} }}"#,
         todo_id
     );
-    let start_response = execute(&pool, &start_query).await;
+    let start_response = execute_as(&pool, &user_id, &start_query).await;
     assert_no_errors(&start_response);
     assert_eq!(
         start_response["data"]["startTodo"]["status"],
@@ -113,7 +125,7 @@ async fn smoke_test_complete_user_and_todo_workflow(pool: PgPool) {
         r#"mutation {{ completeTodo(id: "{}") {{ status }} }}"#,
         todo_id
     );
-    let complete_response = execute(&pool, &complete_query).await;
+    let complete_response = execute_as(&pool, &user_id, &complete_query).await;
     assert_no_errors(&complete_response);
     assert_eq!(
         complete_response["data"]["completeTodo"]["status"],
@@ -125,7 +137,7 @@ async fn smoke_test_complete_user_and_todo_workflow(pool: PgPool) {
         r#"query {{ todosForUser(userId: "{}") {{ title status }} }}"#,
         user_id
     );
-    let list_response = execute(&pool, &list_query).await;
+    let list_response = execute_as(&pool, &user_id, &list_query).await;
     assert_no_errors(&list_response);
     let todos = list_response["data"]["todosForUser"].as_array().unwrap();
     assert_eq!(todos.len(), 1);
@@ -137,8 +149,9 @@ async fn smoke_test_error_handling(pool: PgPool) {
     // Verify that errors from the feature layer are properly propagated as GraphQL errors
 
     // Try to create a todo for non-existent user
-    let response = execute(
+    let response = execute_as(
         &pool,
+        "00000000-0000-0000-0000-000000000000",
         r#"
         mutation {
             createTodo(input: {
@@ -183,8 +196,9 @@ async fn smoke_test_user_crud(pool: PgPool) {
     assert_eq!(read_response["data"]["user"]["name"], "CRUD User");
 
     // Update
-    let update_response = execute(
+    let update_response = execute_as(
         &pool,
+        &user_id,
         &format!(
             r#"mutation {{ updateUser(id: "{}", input: {{ name: "Updated User" }}) {{ name }} }}"#,
             user_id
@@ -198,13 +212,17 @@ async fn smoke_test_user_crud(pool: PgPool) {
     );
 
     // Delete
-    let delete_response = execute(
+    let delete_response = execute_as(
         &pool,
-        &format!(r#"mutation {{ deleteUser(id: "{}") }}"#, user_id),
+        &user_id,
+        &format!(
+            r#"mutation {{ deleteUser(id: "{}") {{ deleted existed }} }}"#,
+            user_id
+        ),
     )
     .await;
     assert_no_errors(&delete_response);
-    assert_eq!(delete_response["data"]["deleteUser"], true);
+    assert_eq!(delete_response["data"]["deleteUser"]["deleted"], true);
 
     // Verify deleted
     let verify_response = execute(
@@ -227,8 +245,9 @@ async fn smoke_test_todo_crud(pool: PgPool) {
     let user_id = get_string(&user_response, &["data", "registerUser", "id"]);
 
     // Create
-    let create_response = execute(
+    let create_response = execute_as(
         &pool,
+        &user_id,
         &format!(
             r#"mutation {{ createTodo(input: {{ userId: "{}", title: "CRUD Todo" }}) {{ id title }} }}"#,
             user_id
@@ -240,8 +259,9 @@ async fn smoke_test_todo_crud(pool: PgPool) {
     assert_eq!(create_response["data"]["createTodo"]["title"], "CRUD Todo");
 
     // Read
-    let read_response = execute(
+    let read_response = execute_as(
         &pool,
+        &user_id,
         &format!(r#"query {{ todo(id: "{}") {{ title }} }}"#, todo_id),
     )
     .await;
@@ -249,8 +269,9 @@ async fn smoke_test_todo_crud(pool: PgPool) {
     assert_eq!(read_response["data"]["todo"]["title"], "CRUD Todo");
 
     // Update
-    let update_response = execute(
+    let update_response = execute_as(
         &pool,
+        &user_id,
         &format!(
             r#"mutation {{ updateTodo(id: "{}", input: {{ title: "Updated Todo" }}) {{ title }} }}"#,
             todo_id
@@ -264,17 +285,22 @@ async fn smoke_test_todo_crud(pool: PgPool) {
     );
 
     // Delete
-    let delete_response = execute(
+    let delete_response = execute_as(
         &pool,
-        &format!(r#"mutation {{ deleteTodo(id: "{}") }}"#, todo_id),
+        &user_id,
+        &format!(
+            r#"mutation {{ deleteTodo(id: "{}") {{ deleted existed }} }}"#,
+            todo_id
+        ),
     )
     .await;
     assert_no_errors(&delete_response);
-    assert_eq!(delete_response["data"]["deleteTodo"], true);
+    assert_eq!(delete_response["data"]["deleteTodo"]["deleted"], true);
 
     // Verify deleted
-    let verify_response = execute(
+    let verify_response = execute_as(
         &pool,
+        &user_id,
         &format!(r#"query {{ todo(id: "{}") {{ id }} }}"#, todo_id),
     )
     .await;
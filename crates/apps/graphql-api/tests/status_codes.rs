@@ -0,0 +1,98 @@
+//! HTTP status code tests
+//!
+//! With `http_status_codes` on, the GraphQL endpoint answers with a status
+//! derived from the error codes in the response.
+
+use axum::body::{Body, to_bytes};
+use axum::http::{Request, StatusCode, header};
+use graphql_api::app::{AppState, DEFAULT_GRAPHQL_TIMEOUT, DEFAULT_MAX_REQUEST_BYTES, router};
+use graphql_api::auth::JwtAuth;
+use graphql_api::build_schema;
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use time::Duration;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+/// POST a GraphQL query to a router with status codes switched on or off
+///
+/// Requests are made as the nil user, who has no account, so acting on it
+/// reports `NOT_FOUND`.
+async fn post_graphql(pool: &PgPool, http_status_codes: bool, query: &str) -> (StatusCode, Value) {
+    let auth = JwtAuth::new(b"test-secret");
+    let token = auth.issue(Uuid::nil(), Duration::hours(1)).unwrap();
+    let app = router(AppState {
+        pool: pool.clone(),
+        schema: build_schema(pool.clone()),
+        auth,
+        http_status_codes,
+        max_request_bytes: DEFAULT_MAX_REQUEST_BYTES,
+        graphql_timeout: DEFAULT_GRAPHQL_TIMEOUT,
+    });
+
+    let request = Request::post("/graphql")
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::from(json!({ "query": query }).to_string()))
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+const INVALID_TIMEZONE: &str = r#"mutation {
+    registerUser(input: { email: "tz@test.com", name: "User", timezone: "Nowhere/Special" }) { id }
+}"#;
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn success_is_ok(pool: PgPool) {
+    let (status, body) = post_graphql(&pool, true, "query { users { id } }").await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["errors"].is_null());
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn validation_error_is_bad_request(pool: PgPool) {
+    let (status, body) = post_graphql(&pool, true, INVALID_TIMEZONE).await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["errors"][0]["extensions"]["code"], "VALIDATION");
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn not_found_error_is_bad_request(pool: PgPool) {
+    let (status, body) = post_graphql(
+        &pool,
+        true,
+        r#"mutation { resendWelcomeEmail(userId: "00000000-0000-0000-0000-000000000000") }"#,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["errors"][0]["extensions"]["code"], "NOT_FOUND");
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn internal_error_is_server_error(pool: PgPool) {
+    // Break the users query underneath the resolver
+    sqlx::query("ALTER TABLE users RENAME TO users_moved")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let (status, body) = post_graphql(&pool, true, "query { users { id } }").await;
+
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(body["errors"][0]["extensions"]["code"], "INTERNAL");
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn errors_stay_ok_when_switched_off(pool: PgPool) {
+    let (status, body) = post_graphql(&pool, false, INVALID_TIMEZONE).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["errors"][0]["extensions"]["code"], "VALIDATION");
+}
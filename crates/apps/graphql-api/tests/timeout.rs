@@ -0,0 +1,62 @@
+//! GraphQL request timeout tests
+//!
+//! A request still running after `graphql_timeout` is abandoned with a
+//! `TIMEOUT` error instead of holding the connection open.
+
+use std::time::Duration;
+
+use axum::body::{Body, to_bytes};
+use axum::http::{Request, StatusCode, header};
+use graphql_api::app::{AppState, DEFAULT_MAX_REQUEST_BYTES, router};
+use graphql_api::auth::JwtAuth;
+use graphql_api::build_schema;
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use tower::ServiceExt;
+
+/// POST a GraphQL query to a router with the given timeout
+async fn post_graphql(pool: &PgPool, graphql_timeout: Duration, query: &str) -> Value {
+    let app = router(AppState {
+        pool: pool.clone(),
+        schema: build_schema(pool.clone()),
+        auth: JwtAuth::new(b"test-secret"),
+        http_status_codes: false,
+        max_request_bytes: DEFAULT_MAX_REQUEST_BYTES,
+        graphql_timeout,
+    });
+
+    let request = Request::post("/graphql")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(json!({ "query": query }).to_string()))
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    serde_json::from_slice(&body).unwrap()
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn slow_request_times_out(pool: PgPool) {
+    // Hold a lock on users so the `users` resolver blocks until it is released
+    let mut tx = pool.begin().await.unwrap();
+    sqlx::query("LOCK TABLE users IN ACCESS EXCLUSIVE MODE")
+        .execute(&mut *tx)
+        .await
+        .unwrap();
+
+    let body = post_graphql(&pool, Duration::from_millis(200), "query { users { id } }").await;
+
+    assert_eq!(body["errors"][0]["extensions"]["code"], "TIMEOUT");
+    assert!(body["data"].is_null());
+
+    tx.rollback().await.unwrap();
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn fast_request_finishes_within_the_timeout(pool: PgPool) {
+    let body = post_graphql(&pool, Duration::from_secs(10), "query { users { id } }").await;
+
+    assert!(body["errors"].is_null());
+    assert_eq!(body["data"]["users"], json!([]));
+}
@@ -0,0 +1,34 @@
+use std::sync::Arc;
+
+use time::OffsetDateTime;
+
+/// A source of the current time
+///
+/// Time-dependent logic asks a `Clock` for "now" instead of reading the
+/// system time itself, so tests can pin it to a chosen instant.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> OffsetDateTime;
+}
+
+/// A clock shared between the tasks that need it
+pub type SharedClock = Arc<dyn Clock>;
+
+/// The real time, in UTC
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> OffsetDateTime {
+        OffsetDateTime::now_utc()
+    }
+}
+
+/// A clock stopped at one instant
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedClock(pub OffsetDateTime);
+
+impl Clock for FixedClock {
+    fn now(&self) -> OffsetDateTime {
+        self.0
+    }
+}
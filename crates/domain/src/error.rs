@@ -10,4 +10,7 @@ pub enum DomainError {
 
     #[error("Validation error: {0}")]
     Validation(String),
+
+    #[error("Conflict: {0}")]
+    Conflict(String),
 }
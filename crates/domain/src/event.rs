@@ -0,0 +1,99 @@
+use serde_json::Value;
+use sqlx::{Execute, Executor, Postgres};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::DomainError;
+use crate::slow_query::timed_query;
+
+/// A domain event waiting in the outbox to be dispatched
+#[derive(Debug, Clone)]
+pub struct OutboxEvent {
+    pub id: Uuid,
+    pub event_type: String,
+    pub payload_json: Value,
+    pub created_at: OffsetDateTime,
+    pub processed_at: Option<OffsetDateTime>,
+}
+
+/// Repository for the domain event outbox
+///
+/// Events are recorded in the same transaction as the change they describe,
+/// so they exist exactly when that change was committed.
+pub struct EventRepository;
+
+impl EventRepository {
+    /// Record an event, returning its ID
+    pub async fn record<'e, E>(
+        executor: E,
+        event_type: &str,
+        payload_json: &Value,
+    ) -> Result<Uuid, DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let id = Uuid::new_v4();
+
+        let query = sqlx::query!(
+            r#"
+            INSERT INTO outbox (id, event_type, payload_json)
+            VALUES ($1, $2, $3)
+            "#,
+            id,
+            event_type,
+            payload_json
+        );
+        timed_query(query.sql(), query.execute(executor)).await?;
+
+        Ok(id)
+    }
+
+    /// Lock up to `limit` unprocessed events, oldest first
+    ///
+    /// Events locked by another transaction are skipped, so several pollers
+    /// can run at once without dispatching an event twice.
+    pub async fn claim_unprocessed<'e, E>(
+        executor: E,
+        limit: i64,
+    ) -> Result<Vec<OutboxEvent>, DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let query = sqlx::query_as!(
+            OutboxEvent,
+            r#"
+            SELECT id, event_type, payload_json, created_at, processed_at
+            FROM outbox
+            WHERE processed_at IS NULL
+            ORDER BY created_at
+            LIMIT $1
+            FOR UPDATE SKIP LOCKED
+            "#,
+            limit
+        );
+        let events = timed_query(query.sql(), query.fetch_all(executor)).await?;
+
+        Ok(events)
+    }
+
+    /// Mark events as processed
+    pub async fn mark_processed<'e, E>(executor: E, ids: &[Uuid]) -> Result<u64, DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let now = OffsetDateTime::now_utc();
+
+        let query = sqlx::query!(
+            r#"
+            UPDATE outbox
+            SET processed_at = $1
+            WHERE id = ANY($2) AND processed_at IS NULL
+            "#,
+            now,
+            ids
+        );
+        let result = timed_query(query.sql(), query.execute(executor)).await?;
+
+        Ok(result.rows_affected())
+    }
+}
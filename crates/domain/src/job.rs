@@ -0,0 +1,81 @@
+use sqlx::{Execute, Executor, Postgres};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::DomainError;
+use crate::slow_query::timed_query;
+
+/// A background job waiting in the sqlxmq queue
+#[derive(Debug, Clone)]
+pub struct Job {
+    pub id: Uuid,
+    pub channel_name: String,
+    /// The job function's name, if the payload is still stored
+    pub name: Option<String>,
+    /// Runs left before sqlxmq gives up on the job
+    pub attempts: i32,
+    /// When the job is next due to run; `None` once it is out of attempts
+    pub scheduled_at: Option<OffsetDateTime>,
+    /// Whether a runner would pick the job up now
+    pub is_runnable: bool,
+}
+
+/// Repository for inspecting the sqlxmq job queue
+pub struct JobsRepository;
+
+impl JobsRepository {
+    /// List up to `limit` jobs queued on a channel, oldest first
+    ///
+    /// Skips the sentinel row sqlxmq keeps at `uuid_nil()`. A job is runnable
+    /// once its scheduled time has passed and any job it waits on is done.
+    pub async fn list_by_channel<'e, E>(
+        executor: E,
+        channel: &str,
+        limit: i64,
+    ) -> Result<Vec<Job>, DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let query = sqlx::query_as!(
+            Job,
+            r#"
+            SELECT
+                m.id,
+                m.channel_name,
+                p.name AS "name?",
+                m.attempts,
+                m.attempt_at AS scheduled_at,
+                COALESCE(
+                    m.attempt_at <= NOW() AND NOT mq_uuid_exists(m.after_message_id),
+                    FALSE
+                ) AS "is_runnable!"
+            FROM mq_msgs m
+            LEFT JOIN mq_payloads p ON p.id = m.id
+            WHERE m.channel_name = $1 AND m.id != uuid_nil()
+            ORDER BY m.created_at, m.id
+            LIMIT $2
+            "#,
+            channel,
+            limit
+        );
+        let jobs = timed_query(query.sql(), query.fetch_all(executor)).await?;
+
+        Ok(jobs)
+    }
+
+    /// Remove queued jobs, skipping any that have already run
+    pub async fn delete<'e, E>(executor: E, ids: &[Uuid]) -> Result<(), DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        if ids.is_empty() {
+            return Ok(());
+        }
+
+        // mq_delete returns void, which the query macros can't describe
+        let query = sqlx::query("SELECT mq_delete($1)").bind(ids);
+        timed_query(query.sql(), query.execute(executor)).await?;
+
+        Ok(())
+    }
+}
@@ -1,7 +1,27 @@
+pub mod clock;
 pub mod error;
+pub mod pagination;
 pub mod user;
 pub mod todo;
+pub mod todo_item;
+pub mod tag;
+pub mod event;
+pub mod job;
+pub mod limits;
+pub mod retry;
+pub mod slow_query;
 
+pub use clock::{Clock, FixedClock, SharedClock, SystemClock};
 pub use error::DomainError;
-pub use user::{User, UserRepository};
-pub use todo::{Todo, TodoRepository, TodoStatus};
\ No newline at end of file
+pub use pagination::{Cursor, Keyset, Page};
+pub use user::{normalize_email, User, UserFilter, UserRepository, UserRole, UserSort};
+pub use todo::{Todo, TodoFilter, TodoRepository, TodoStatus};
+pub use todo_item::{TodoItem, TodoItemRepository};
+pub use tag::TagRepository;
+pub use event::{EventRepository, OutboxEvent};
+pub use job::{Job, JobsRepository};
+pub use limits::{parse_limit, LimitsError};
+pub use retry::{
+    retry_on_transient, retry_on_transient_with, RetryConfig, RetryConfigError, Transient,
+};
+pub use slow_query::{timed_query, SlowQueryConfig, SlowQueryConfigError};
\ No newline at end of file
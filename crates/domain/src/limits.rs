@@ -0,0 +1,24 @@
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum LimitsError {
+    #[error("{name} must be a positive integer, got {value:?}")]
+    InvalidNumber { name: &'static str, value: String },
+}
+
+/// Read the limit `name` from a variable lookup, or `None` if it is unset
+///
+/// Shared by the features' length limits, which must all be positive.
+pub fn parse_limit(
+    var: impl Fn(&str) -> Option<String>,
+    name: &'static str,
+) -> Result<Option<usize>, LimitsError> {
+    let Some(value) = var(name) else {
+        return Ok(None);
+    };
+
+    match value.trim().parse() {
+        Ok(max) if max > 0 => Ok(Some(max)),
+        _ => Err(LimitsError::InvalidNumber { name, value }),
+    }
+}
@@ -0,0 +1,125 @@
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use sea_query::{Expr, IntoColumnRef, Order, SelectStatement};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::DomainError;
+
+/// Position of a row in newest-first `(created_at, id)` order
+///
+/// Clients get it as an opaque string and pass it back to fetch the rows
+/// after it. The `id` breaks ties between rows created at the same instant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cursor {
+    pub created_at: OffsetDateTime,
+    pub id: Uuid,
+}
+
+impl Cursor {
+    /// Encode as URL-safe base64
+    pub fn encode(&self) -> String {
+        let raw = format!("{}:{}", self.created_at.unix_timestamp_nanos(), self.id);
+        URL_SAFE_NO_PAD.encode(raw)
+    }
+
+    /// Decode a cursor produced by `encode`
+    ///
+    /// Returns `DomainError::Validation` for anything else.
+    pub fn decode(encoded: &str) -> Result<Self, DomainError> {
+        let invalid = || DomainError::Validation(format!("Invalid cursor: {}", encoded));
+
+        let raw = URL_SAFE_NO_PAD.decode(encoded).map_err(|_| invalid())?;
+        let raw = String::from_utf8(raw).map_err(|_| invalid())?;
+        let (nanos, id) = raw.split_once(':').ok_or_else(invalid)?;
+
+        let nanos = nanos.parse().map_err(|_| invalid())?;
+        Ok(Self {
+            created_at: OffsetDateTime::from_unix_timestamp_nanos(nanos).map_err(|_| invalid())?,
+            id: id.parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+/// Entities that can be paged through by `Cursor`
+pub trait Keyset {
+    fn cursor(&self) -> Cursor;
+}
+
+/// One page of rows in `(created_at, id)` order
+#[derive(Debug, Clone, PartialEq)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub has_next_page: bool,
+}
+
+impl<T> Page<T> {
+    /// Build a page from rows fetched by a query using `apply_keyset`
+    ///
+    /// The extra row fetched beyond `limit` only signals that another page
+    /// follows and is dropped.
+    pub fn from_rows(mut rows: Vec<T>, limit: u64) -> Self {
+        let has_next_page = rows.len() as u64 > limit;
+        rows.truncate(limit as usize);
+
+        Self {
+            items: rows,
+            has_next_page,
+        }
+    }
+
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Page<U> {
+        Page {
+            items: self.items.into_iter().map(f).collect(),
+            has_next_page: self.has_next_page,
+        }
+    }
+
+    /// Like `map`, failing the whole page on the first error
+    pub fn try_map<U, E>(self, f: impl FnMut(T) -> Result<U, E>) -> Result<Page<U>, E> {
+        Ok(Page {
+            items: self.items.into_iter().map(f).collect::<Result<_, _>>()?,
+            has_next_page: self.has_next_page,
+        })
+    }
+}
+
+impl<T: Keyset> Page<T> {
+    /// Cursor of the last row, to request the following page with
+    pub fn end_cursor(&self) -> Option<Cursor> {
+        self.items.last().map(Keyset::cursor)
+    }
+}
+
+/// Page `select` newest first by `(created_at, id)`
+///
+/// Only rows strictly after `after` are kept, so rows inserted while a
+/// client pages never shift later pages. One row beyond `limit` is fetched;
+/// pass the result to `Page::from_rows`.
+pub fn apply_keyset<C>(
+    select: &mut SelectStatement,
+    created_at: C,
+    id: C,
+    after: Option<&Cursor>,
+    limit: u64,
+) where
+    C: IntoColumnRef + Clone,
+{
+    if let Some(after) = after {
+        select.and_where(
+            Expr::tuple([
+                Expr::col(created_at.clone()).into(),
+                Expr::col(id.clone()).into(),
+            ])
+            .lt(Expr::tuple([
+                Expr::val(after.created_at).into(),
+                Expr::val(after.id).into(),
+            ])),
+        );
+    }
+
+    select
+        .order_by(created_at, Order::Desc)
+        .order_by(id, Order::Desc)
+        .limit(limit + 1);
+}
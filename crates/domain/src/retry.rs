@@ -0,0 +1,137 @@
+use std::env;
+use std::future::Future;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use thiserror::Error;
+use tracing::warn;
+
+use crate::DomainError;
+
+/// SQLSTATE of a transaction that lost a serialization conflict
+const SERIALIZATION_FAILURE: &str = "40001";
+
+/// SQLSTATE of a transaction chosen as a deadlock victim
+const DEADLOCK_DETECTED: &str = "40P01";
+
+#[derive(Error, Debug, PartialEq)]
+pub enum RetryConfigError {
+    #[error("{name} must be a non-negative integer, got {value:?}")]
+    InvalidNumber { name: &'static str, value: String },
+}
+
+/// How often work failing with a transient database error is retried
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryConfig {
+    /// Retries after the first attempt; zero disables retrying
+    pub retries: u32,
+    /// Wait before the first retry, doubling before each one after
+    pub base_delay: Duration,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            retries: 3,
+            base_delay: Duration::from_millis(10),
+        }
+    }
+}
+
+static CONFIG: OnceLock<RetryConfig> = OnceLock::new();
+
+impl RetryConfig {
+    /// Read the config from `DB_TRANSIENT_RETRIES`
+    pub fn from_env() -> Result<Self, RetryConfigError> {
+        Self::from_vars(|name| env::var(name).ok())
+    }
+
+    /// Build the config from a variable lookup, using defaults for unset ones
+    pub fn from_vars(var: impl Fn(&str) -> Option<String>) -> Result<Self, RetryConfigError> {
+        let name = "DB_TRANSIENT_RETRIES";
+        let retries = match var(name) {
+            Some(value) => match value.trim().parse() {
+                Ok(retries) => retries,
+                Err(_) => return Err(RetryConfigError::InvalidNumber { name, value }),
+            },
+            None => Self::default().retries,
+        };
+
+        Ok(Self {
+            retries,
+            ..Self::default()
+        })
+    }
+
+    /// Make this the config `retry_on_transient` follows
+    ///
+    /// Only the first call has any effect.
+    pub fn install(self) {
+        let _ = CONFIG.set(self);
+    }
+
+    /// The config in force: the installed one, or the default
+    pub fn current() -> Self {
+        CONFIG.get().copied().unwrap_or_default()
+    }
+}
+
+/// An error that may go away if the work is simply tried again
+pub trait Transient {
+    fn is_transient(&self) -> bool;
+}
+
+impl Transient for sqlx::Error {
+    /// Serialization failures and deadlocks are transient
+    fn is_transient(&self) -> bool {
+        self.as_database_error()
+            .and_then(|db| db.code())
+            .is_some_and(|code| code == SERIALIZATION_FAILURE || code == DEADLOCK_DETECTED)
+    }
+}
+
+impl Transient for DomainError {
+    fn is_transient(&self) -> bool {
+        match self {
+            DomainError::Database(e) => e.is_transient(),
+            _ => false,
+        }
+    }
+}
+
+/// Run `op`, running it again while it fails with a transient error
+///
+/// Follows the installed `RetryConfig`. Each attempt must be complete on its
+/// own, so `op` should begin and commit its own transaction: once a
+/// statement fails, the transaction it ran in can only be rolled back.
+pub async fn retry_on_transient<T, E, F, Fut>(op: F) -> Result<T, E>
+where
+    E: Transient + std::fmt::Display,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    retry_on_transient_with(RetryConfig::current(), op).await
+}
+
+/// Like `retry_on_transient`, following `config` instead
+pub async fn retry_on_transient_with<T, E, F, Fut>(config: RetryConfig, mut op: F) -> Result<T, E>
+where
+    E: Transient + std::fmt::Display,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let mut delay = config.base_delay;
+
+    for attempt in 1..=config.retries {
+        match op().await {
+            Err(e) if e.is_transient() => {
+                warn!(attempt, error = %e, "Retrying after transient database error");
+                tokio::time::sleep(delay).await;
+                delay *= 2;
+            }
+            result => return result,
+        }
+    }
+
+    op().await
+}
@@ -0,0 +1,85 @@
+use std::env;
+use std::future::Future;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+use thiserror::Error;
+use tracing::warn;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum SlowQueryConfigError {
+    #[error("{name} must be a non-negative integer, got {value:?}")]
+    InvalidNumber { name: &'static str, value: String },
+}
+
+/// How long a repository query may take before it is logged as slow
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SlowQueryConfig {
+    pub threshold: Duration,
+}
+
+impl Default for SlowQueryConfig {
+    fn default() -> Self {
+        Self {
+            threshold: Duration::from_millis(200),
+        }
+    }
+}
+
+static CONFIG: OnceLock<SlowQueryConfig> = OnceLock::new();
+
+impl SlowQueryConfig {
+    /// Read the config from `SLOW_QUERY_MS`
+    pub fn from_env() -> Result<Self, SlowQueryConfigError> {
+        Self::from_vars(|name| env::var(name).ok())
+    }
+
+    /// Build the config from a variable lookup, using defaults for unset ones
+    pub fn from_vars(var: impl Fn(&str) -> Option<String>) -> Result<Self, SlowQueryConfigError> {
+        let name = "SLOW_QUERY_MS";
+        let threshold = match var(name) {
+            Some(value) => match value.trim().parse() {
+                Ok(ms) => Duration::from_millis(ms),
+                Err(_) => return Err(SlowQueryConfigError::InvalidNumber { name, value }),
+            },
+            None => Self::default().threshold,
+        };
+
+        Ok(Self { threshold })
+    }
+
+    /// Make this the config every repository query is timed against
+    ///
+    /// Only the first call has any effect.
+    pub fn install(self) {
+        let _ = CONFIG.set(self);
+    }
+
+    /// The config in force: the installed one, or the default
+    pub fn current() -> Self {
+        CONFIG.get().copied().unwrap_or_default()
+    }
+}
+
+/// Run a query, warning with its SQL if it takes longer than the threshold
+///
+/// Repositories route every statement through here so slow ones show up in
+/// the logs whichever repository issued them.
+pub async fn timed_query<T, E>(
+    sql: &str,
+    query: impl Future<Output = Result<T, E>>,
+) -> Result<T, E> {
+    let started = Instant::now();
+    let result = query.await;
+    let elapsed = started.elapsed();
+
+    if elapsed > SlowQueryConfig::current().threshold {
+        warn!(
+            sql = sql.trim(),
+            elapsed_ms = elapsed.as_millis() as u64,
+            "Slow query"
+        );
+    }
+
+    result
+}
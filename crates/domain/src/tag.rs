@@ -0,0 +1,186 @@
+use sea_query::Iden;
+use sqlx::{Execute, Executor, Postgres};
+use uuid::Uuid;
+
+use crate::DomainError;
+use crate::slow_query::timed_query;
+
+/// Tags table identifiers for SeaQuery
+#[derive(Iden)]
+pub(crate) enum Tags {
+    Table,
+    Id,
+    Name,
+}
+
+/// Todo tags join table identifiers for SeaQuery
+#[derive(Iden)]
+pub(crate) enum TodoTags {
+    Table,
+    TodoId,
+    TagId,
+}
+
+/// Repository for tagging todos
+///
+/// Tag names are stored as given; callers normalize them first.
+pub struct TagRepository;
+
+impl TagRepository {
+    /// Tag a todo, creating the tag if it doesn't exist yet
+    ///
+    /// Attaching a tag the todo already has does nothing.
+    pub async fn attach<'e, E>(executor: E, todo_id: Uuid, name: &str) -> Result<(), DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let query = sqlx::query!(
+            r#"
+            WITH tag AS (
+                INSERT INTO tags (id, name)
+                VALUES ($1, $2)
+                ON CONFLICT (name) DO UPDATE SET name = EXCLUDED.name
+                RETURNING id
+            )
+            INSERT INTO todo_tags (todo_id, tag_id)
+            SELECT $3, id FROM tag
+            ON CONFLICT DO NOTHING
+            "#,
+            Uuid::new_v4(),
+            name,
+            todo_id
+        );
+        timed_query(query.sql(), query.execute(executor))
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::Database(db) if db.is_foreign_key_violation() => {
+                    DomainError::NotFound(format!("Todo not found: {}", todo_id))
+                }
+                e => e.into(),
+            })?;
+
+        Ok(())
+    }
+
+    /// Remove a tag from a todo, returning whether it had the tag
+    pub async fn detach<'e, E>(executor: E, todo_id: Uuid, name: &str) -> Result<bool, DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let query = sqlx::query!(
+            r#"
+            DELETE FROM todo_tags
+            USING tags
+            WHERE todo_tags.tag_id = tags.id
+              AND todo_tags.todo_id = $1
+              AND tags.name = $2
+            "#,
+            todo_id,
+            name
+        );
+        let result = timed_query(query.sql(), query.execute(executor)).await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Replace a todo's tags with exactly `names`, creating any that don't
+    /// exist yet
+    ///
+    /// Runs as a single statement, so the old set is swapped for the new one
+    /// atomically. An empty `names` removes every tag.
+    pub async fn set_tags<'e, E>(
+        executor: E,
+        todo_id: Uuid,
+        names: &[String],
+    ) -> Result<(), DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let ids: Vec<Uuid> = names.iter().map(|_| Uuid::new_v4()).collect();
+
+        let query = sqlx::query!(
+            r#"
+            WITH wanted AS (
+                SELECT DISTINCT ON (name) id, name
+                FROM UNNEST($2::uuid[], $3::text[]) AS t(id, name)
+            ),
+            tag AS (
+                INSERT INTO tags (id, name)
+                SELECT id, name FROM wanted
+                ON CONFLICT (name) DO UPDATE SET name = EXCLUDED.name
+                RETURNING id
+            ),
+            removed AS (
+                DELETE FROM todo_tags
+                WHERE todo_id = $1 AND tag_id NOT IN (SELECT id FROM tag)
+            )
+            INSERT INTO todo_tags (todo_id, tag_id)
+            SELECT $1, id FROM tag
+            ON CONFLICT DO NOTHING
+            "#,
+            todo_id,
+            &ids,
+            names
+        );
+        timed_query(query.sql(), query.execute(executor))
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::Database(db) if db.is_foreign_key_violation() => {
+                    DomainError::NotFound(format!("Todo not found: {}", todo_id))
+                }
+                e => e.into(),
+            })?;
+
+        Ok(())
+    }
+
+    /// List a todo's tags in alphabetical order
+    pub async fn list_for_todo<'e, E>(
+        executor: E,
+        todo_id: Uuid,
+    ) -> Result<Vec<String>, DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let query = sqlx::query_scalar!(
+            r#"
+            SELECT tags.name
+            FROM tags
+            JOIN todo_tags ON todo_tags.tag_id = tags.id
+            WHERE todo_tags.todo_id = $1
+            ORDER BY tags.name
+            "#,
+            todo_id
+        );
+        let names = timed_query(query.sql(), query.fetch_all(executor)).await?;
+
+        Ok(names)
+    }
+
+    /// List the tags of several todos at once as `(todo_id, name)` pairs,
+    /// alphabetical within each todo
+    pub async fn list_for_todos<'e, E>(
+        executor: E,
+        todo_ids: &[Uuid],
+    ) -> Result<Vec<(Uuid, String)>, DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let query = sqlx::query!(
+            r#"
+            SELECT todo_tags.todo_id, tags.name
+            FROM tags
+            JOIN todo_tags ON todo_tags.tag_id = tags.id
+            WHERE todo_tags.todo_id = ANY($1)
+            ORDER BY tags.name
+            "#,
+            todo_ids
+        );
+        let rows = timed_query(query.sql(), query.fetch_all(executor)).await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.todo_id, row.name))
+            .collect())
+    }
+}
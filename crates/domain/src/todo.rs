@@ -1,8 +1,16 @@
-use sqlx::{Executor, FromRow, Postgres};
+use std::collections::HashMap;
+
+use sea_query::extension::postgres::PgExpr;
+use sea_query::{Cond, Expr, Iden, Order, PostgresQueryBuilder, Query, SelectStatement};
+use sea_query_binder::SqlxBinder;
+use sqlx::{Execute, Executor, FromRow, Postgres};
 use time::OffsetDateTime;
 use uuid::Uuid;
 
 use crate::DomainError;
+use crate::pagination::{Cursor, Keyset, Page, apply_keyset};
+use crate::slow_query::timed_query;
+use crate::tag::{Tags, TodoTags};
 
 /// Todo status enum
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -10,27 +18,107 @@ pub enum TodoStatus {
     Pending,
     InProgress,
     Completed,
+    Cancelled,
 }
 
 impl TodoStatus {
+    /// Every status, in lifecycle order
+    pub const ALL: [TodoStatus; 4] = [
+        TodoStatus::Pending,
+        TodoStatus::InProgress,
+        TodoStatus::Completed,
+        TodoStatus::Cancelled,
+    ];
+
     pub fn as_str(&self) -> &'static str {
         match self {
             TodoStatus::Pending => "pending",
             TodoStatus::InProgress => "in_progress",
             TodoStatus::Completed => "completed",
+            TodoStatus::Cancelled => "cancelled",
         }
     }
 
-    pub fn from_str(s: &str) -> Option<Self> {
+    /// Whether a todo in this status may move to `next`
+    ///
+    /// Open todos (`Pending` or `InProgress`) may be completed or cancelled,
+    /// and a pending todo may be started. `Completed` and `Cancelled` are final.
+    pub fn can_transition_to(&self, next: TodoStatus) -> bool {
+        matches!(
+            (self, next),
+            (TodoStatus::Pending, TodoStatus::InProgress)
+                | (TodoStatus::Pending, TodoStatus::Completed)
+                | (TodoStatus::Pending, TodoStatus::Cancelled)
+                | (TodoStatus::InProgress, TodoStatus::Completed)
+                | (TodoStatus::InProgress, TodoStatus::Cancelled)
+        )
+    }
+
+    /// Whether the todo is finished with, either completed or cancelled
+    pub fn is_closed(&self) -> bool {
+        matches!(self, TodoStatus::Completed | TodoStatus::Cancelled)
+    }
+
+    /// Parse a status read from the database
+    ///
+    /// An unknown value is an error, so a corrupt row fails the read instead
+    /// of passing for a pending todo.
+    pub fn from_db(s: &str) -> Result<Self, DomainError> {
         match s {
-            "pending" => Some(TodoStatus::Pending),
-            "in_progress" => Some(TodoStatus::InProgress),
-            "completed" => Some(TodoStatus::Completed),
-            _ => None,
+            "pending" => Ok(TodoStatus::Pending),
+            "in_progress" => Ok(TodoStatus::InProgress),
+            "completed" => Ok(TodoStatus::Completed),
+            "cancelled" => Ok(TodoStatus::Cancelled),
+            _ => Err(DomainError::Validation(format!(
+                "Unknown todo status: {:?}",
+                s
+            ))),
         }
     }
 }
 
+impl From<TodoStatus> for sea_query::Value {
+    fn from(status: TodoStatus) -> Self {
+        status.as_str().into()
+    }
+}
+
+/// Optional criteria for `TodoRepository::find`
+///
+/// Every field that is set must match; unset fields don't filter.
+#[derive(Debug, Clone, Default)]
+pub struct TodoFilter {
+    /// Any of these statuses matches; empty means every status
+    pub statuses: Vec<TodoStatus>,
+    /// Case-insensitive text matched against title or description
+    pub query: Option<String>,
+    /// Tag name, already normalized
+    pub tag: Option<String>,
+    /// Inclusive lower bound on when the todo was created
+    pub created_after: Option<OffsetDateTime>,
+    /// Exclusive upper bound on when the todo was created
+    pub created_before: Option<OffsetDateTime>,
+    /// Exclusive upper bound on the due date
+    pub due_before: Option<OffsetDateTime>,
+}
+
+/// Todos table identifiers for SeaQuery
+#[derive(Iden, Clone, Copy)]
+enum Todos {
+    Table,
+    Id,
+    UserId,
+    Title,
+    Description,
+    Status,
+    DueDate,
+    StartedAt,
+    TotalSeconds,
+    CreatedAt,
+    UpdatedAt,
+    DeletedAt,
+}
+
 /// Raw todo row from database
 #[derive(Debug, Clone, FromRow)]
 struct TodoRow {
@@ -39,8 +127,12 @@ struct TodoRow {
     pub title: String,
     pub description: Option<String>,
     pub status: String,
+    pub due_date: Option<OffsetDateTime>,
+    pub started_at: Option<OffsetDateTime>,
+    pub total_seconds: i64,
     pub created_at: OffsetDateTime,
     pub updated_at: OffsetDateTime,
+    pub deleted_at: Option<OffsetDateTime>,
 }
 
 /// Todo entity
@@ -51,20 +143,49 @@ pub struct Todo {
     pub title: String,
     pub description: Option<String>,
     pub status: TodoStatus,
+    pub due_date: Option<OffsetDateTime>,
+    /// When the running time-tracking interval began; `None` when not running
+    pub started_at: Option<OffsetDateTime>,
+    /// Seconds tracked by finished intervals
+    pub total_seconds: i64,
     pub created_at: OffsetDateTime,
     pub updated_at: OffsetDateTime,
+    /// When the todo was soft-deleted; deleted todos are hidden unless asked for
+    pub deleted_at: Option<OffsetDateTime>,
 }
 
-impl From<TodoRow> for Todo {
-    fn from(row: TodoRow) -> Self {
-        Todo {
+impl TryFrom<TodoRow> for Todo {
+    type Error = DomainError;
+
+    fn try_from(row: TodoRow) -> Result<Self, DomainError> {
+        Ok(Todo {
             id: row.id,
             user_id: row.user_id,
             title: row.title,
             description: row.description,
-            status: TodoStatus::from_str(&row.status).unwrap_or(TodoStatus::Pending),
+            status: TodoStatus::from_db(&row.status)?,
+            due_date: row.due_date,
+            started_at: row.started_at,
+            total_seconds: row.total_seconds,
             created_at: row.created_at,
             updated_at: row.updated_at,
+            deleted_at: row.deleted_at,
+        })
+    }
+}
+
+impl Todo {
+    /// Whether the todo is still open and its due date is before `now`
+    pub fn is_overdue(&self, now: OffsetDateTime) -> bool {
+        !self.status.is_closed() && self.due_date.is_some_and(|due| due < now)
+    }
+}
+
+impl Keyset for Todo {
+    fn cursor(&self) -> Cursor {
+        Cursor {
+            created_at: self.created_at,
+            id: self.id,
         }
     }
 }
@@ -74,11 +195,15 @@ pub struct TodoRepository;
 
 impl TodoRepository {
     /// Create a new todo
+    ///
+    /// Runs as a single statement that only inserts when the user exists and
+    /// is active. Returns `DomainError::Conflict` otherwise.
     pub async fn create<'e, E>(
         executor: E,
         user_id: Uuid,
         title: &str,
         description: Option<&str>,
+        due_date: Option<OffsetDateTime>,
     ) -> Result<Todo, DomainError>
     where
         E: Executor<'e, Database = Postgres>,
@@ -88,12 +213,16 @@ impl TodoRepository {
         let status = TodoStatus::Pending.as_str();
         let description_string = description.map(|s| s.to_string());
 
-        let row = sqlx::query_as!(
+        let query = sqlx::query_as!(
             TodoRow,
             r#"
-            INSERT INTO todos (id, user_id, title, description, status, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7)
-            RETURNING id, user_id, title, description, status, created_at, updated_at
+            INSERT INTO todos (id, user_id, title, description, status, due_date, created_at, updated_at)
+            SELECT $1::uuid, users.id, $3::text, $4::text, $5::text, $7::timestamptz,
+                   $6::timestamptz, $6::timestamptz
+            FROM users
+            WHERE users.id = $2 AND users.deleted_at IS NULL
+            RETURNING id, user_id, title, description, status, due_date, started_at,
+                      total_seconds, created_at, updated_at, deleted_at
             "#,
             id,
             user_id,
@@ -101,12 +230,82 @@ impl TodoRepository {
             description_string,
             status,
             now,
+            due_date
+        );
+        let row = timed_query(query.sql(), query.fetch_optional(executor))
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::Database(db) if db.is_foreign_key_violation() => {
+                    DomainError::Conflict(format!("User does not exist: {}", user_id))
+                }
+                e => e.into(),
+            })?
+            .ok_or_else(|| DomainError::Conflict(format!("User does not exist: {}", user_id)))?;
+
+        row.try_into()
+    }
+
+    /// Create several todos for a user with a single multi-row INSERT
+    ///
+    /// Each item is a `(title, description, due_date)` tuple. Because it is one
+    /// statement, either every row is inserted or none are. Like `create`, rows
+    /// are only inserted when the user exists and is active; returns
+    /// `DomainError::Conflict` otherwise.
+    pub async fn create_many<'e, E>(
+        executor: E,
+        user_id: Uuid,
+        items: &[(&str, Option<&str>, Option<OffsetDateTime>)],
+    ) -> Result<Vec<Todo>, DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        if items.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let now = OffsetDateTime::now_utc();
+        let status = TodoStatus::Pending.as_str();
+        let ids: Vec<Uuid> = items.iter().map(|_| Uuid::new_v4()).collect();
+        let titles: Vec<String> = items.iter().map(|(t, _, _)| String::from(*t)).collect();
+        let descriptions: Vec<Option<String>> =
+            items.iter().map(|(_, d, _)| d.map(String::from)).collect();
+        let due_dates: Vec<Option<OffsetDateTime>> = items.iter().map(|(_, _, d)| *d).collect();
+
+        let query = sqlx::query_as!(
+            TodoRow,
+            r#"
+            INSERT INTO todos (id, user_id, title, description, status, due_date, created_at, updated_at)
+            SELECT id, $1, title, description, $6, due_date, $7, $7
+            FROM UNNEST($2::uuid[], $3::text[], $4::text[], $5::timestamptz[])
+                 AS t(id, title, description, due_date)
+            WHERE EXISTS (SELECT 1 FROM users WHERE users.id = $1 AND users.deleted_at IS NULL)
+            RETURNING id, user_id, title, description, status, due_date, started_at,
+                      total_seconds, created_at, updated_at, deleted_at
+            "#,
+            user_id,
+            &ids,
+            &titles,
+            &descriptions as &[Option<String>],
+            &due_dates as &[Option<OffsetDateTime>],
+            status,
             now
-        )
-        .fetch_one(executor)
-        .await?;
+        );
+        let rows = timed_query(query.sql(), query.fetch_all(executor))
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::Database(db) if db.is_foreign_key_violation() => {
+                    DomainError::Conflict(format!("User does not exist: {}", user_id))
+                }
+                e => e.into(),
+            })?;
+        if rows.is_empty() {
+            return Err(DomainError::Conflict(format!(
+                "User does not exist: {}",
+                user_id
+            )));
+        }
 
-        Ok(row.into())
+        rows.into_iter().map(Todo::try_from).collect()
     }
 
     /// Find a todo by ID
@@ -114,43 +313,180 @@ impl TodoRepository {
     where
         E: Executor<'e, Database = Postgres>,
     {
-        let row = sqlx::query_as!(
+        let query = sqlx::query_as!(
             TodoRow,
             r#"
-            SELECT id, user_id, title, description, status, created_at, updated_at
+            SELECT id, user_id, title, description, status, due_date, started_at,
+                   total_seconds, created_at, updated_at, deleted_at
+            FROM todos
+            WHERE id = $1 AND deleted_at IS NULL
+            "#,
+            id
+        );
+        let row = timed_query(query.sql(), query.fetch_optional(executor)).await?;
+
+        row.map(Todo::try_from).transpose()
+    }
+
+    /// Find a todo by ID, even if it has been deleted
+    pub async fn find_by_id_including_deleted<'e, E>(
+        executor: E,
+        id: Uuid,
+    ) -> Result<Option<Todo>, DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let query = sqlx::query_as!(
+            TodoRow,
+            r#"
+            SELECT id, user_id, title, description, status, due_date, started_at,
+                   total_seconds, created_at, updated_at, deleted_at
             FROM todos
             WHERE id = $1
             "#,
             id
-        )
-        .fetch_optional(executor)
-        .await?;
+        );
+        let row = timed_query(query.sql(), query.fetch_optional(executor)).await?;
 
-        Ok(row.map(Into::into))
+        row.map(Todo::try_from).transpose()
     }
 
-    /// List todos for a user
-    pub async fn list_by_user<'e, E>(executor: E, user_id: Uuid) -> Result<Vec<Todo>, DomainError>
+    /// Find several todos by ID in one query, in the order the IDs are given
+    ///
+    /// IDs with no matching todo are simply absent from the result.
+    pub async fn find_by_ids<'e, E>(executor: E, ids: &[Uuid]) -> Result<Vec<Todo>, DomainError>
     where
         E: Executor<'e, Database = Postgres>,
     {
-        let rows = sqlx::query_as!(
+        let query = sqlx::query_as!(
             TodoRow,
             r#"
-            SELECT id, user_id, title, description, status, created_at, updated_at
+            SELECT id, user_id, title, description, status, due_date, started_at,
+                   total_seconds, created_at, updated_at, deleted_at
             FROM todos
-            WHERE user_id = $1
-            ORDER BY created_at DESC
+            WHERE id = ANY($1) AND deleted_at IS NULL
+            ORDER BY array_position($1, id)
             "#,
-            user_id
+            ids
+        );
+        let rows = timed_query(query.sql(), query.fetch_all(executor)).await?;
+
+        rows.into_iter().map(Todo::try_from).collect()
+    }
+
+    /// List todos for a user, newest first, with deleted ones only if
+    /// `include_deleted`
+    ///
+    /// Todos created at the same instant are ordered by ID, so the order is
+    /// stable across reads.
+    pub async fn list_by_user<'e, E>(
+        executor: E,
+        user_id: Uuid,
+        include_deleted: bool,
+    ) -> Result<Vec<Todo>, DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let query = sqlx::query_as!(
+            TodoRow,
+            r#"
+            SELECT id, user_id, title, description, status, due_date, started_at,
+                   total_seconds, created_at, updated_at, deleted_at
+            FROM todos
+            WHERE user_id = $1 AND ($2 OR deleted_at IS NULL)
+            ORDER BY created_at DESC, id DESC
+            "#,
+            user_id,
+            include_deleted
+        );
+        let rows = timed_query(query.sql(), query.fetch_all(executor)).await?;
+
+        rows.into_iter().map(Todo::try_from).collect()
+    }
+
+    /// List todos for a user in the order set by `set_positions`
+    ///
+    /// Todos without a position follow the positioned ones, newest first.
+    pub async fn list_by_user_by_position<'e, E>(
+        executor: E,
+        user_id: Uuid,
+        include_deleted: bool,
+    ) -> Result<Vec<Todo>, DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let query = sqlx::query_as!(
+            TodoRow,
+            r#"
+            SELECT id, user_id, title, description, status, due_date, started_at,
+                   total_seconds, created_at, updated_at, deleted_at
+            FROM todos
+            WHERE user_id = $1 AND ($2 OR deleted_at IS NULL)
+            ORDER BY position ASC NULLS LAST, created_at DESC, id DESC
+            "#,
+            user_id,
+            include_deleted
+        );
+        let rows = timed_query(query.sql(), query.fetch_all(executor)).await?;
+
+        rows.into_iter().map(Todo::try_from).collect()
+    }
+
+    /// Give a user's todos positions following the order of `ids`
+    ///
+    /// The first ID gets position 0. IDs not belonging to `user_id` are
+    /// skipped; returns how many todos were updated.
+    pub async fn set_positions<'e, E>(
+        executor: E,
+        user_id: Uuid,
+        ids: &[Uuid],
+    ) -> Result<u64, DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let now = OffsetDateTime::now_utc();
+
+        let query = sqlx::query!(
+            r#"
+            UPDATE todos
+            SET position = (t.ordinality - 1)::int, updated_at = $3
+            FROM UNNEST($2::uuid[]) WITH ORDINALITY AS t(id, ordinality)
+            WHERE todos.id = t.id AND todos.user_id = $1
+            "#,
+            user_id,
+            ids,
+            now
+        );
+        let result = timed_query(query.sql(), query.execute(executor)).await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// List every user's todos a page at a time, newest first
+    ///
+    /// Not scoped to an owner, so only for admin tooling.
+    pub async fn list_all_paginated<'e, E>(
+        executor: E,
+        after: Option<&Cursor>,
+        limit: u64,
+    ) -> Result<Page<Todo>, DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let mut select = select_todos();
+        apply_keyset(&mut select, Todos::CreatedAt, Todos::Id, after, limit);
+
+        let (sql, values) = select.build_sqlx(PostgresQueryBuilder);
+        let rows = timed_query(
+            &sql,
+            sqlx::query_as_with::<_, TodoRow, _>(&sql, values).fetch_all(executor),
         )
-        .fetch_all(executor)
         .await?;
 
-        Ok(rows.into_iter().map(Into::into).collect())
+        Page::from_rows(rows, limit).try_map(Todo::try_from)
     }
 
-    /// List todos by status for a user
+    /// List todos by status for a user, newest first with ties broken by ID
     pub async fn list_by_user_and_status<'e, E>(
         executor: E,
         user_id: Uuid,
@@ -160,28 +496,308 @@ impl TodoRepository {
         E: Executor<'e, Database = Postgres>,
     {
         let status_str = status.as_str();
-        let rows = sqlx::query_as!(
+        let query = sqlx::query_as!(
             TodoRow,
             r#"
-            SELECT id, user_id, title, description, status, created_at, updated_at
+            SELECT id, user_id, title, description, status, due_date, started_at,
+                   total_seconds, created_at, updated_at, deleted_at
             FROM todos
-            WHERE user_id = $1 AND status = $2
-            ORDER BY created_at DESC
+            WHERE user_id = $1 AND status = $2 AND deleted_at IS NULL
+            ORDER BY created_at DESC, id DESC
             "#,
             user_id,
             status_str
+        );
+        let rows = timed_query(query.sql(), query.fetch_all(executor)).await?;
+
+        rows.into_iter().map(Todo::try_from).collect()
+    }
+
+    /// List a user's todos in one status a page at a time, newest first
+    pub async fn list_by_user_and_status_paginated<'e, E>(
+        executor: E,
+        user_id: Uuid,
+        status: TodoStatus,
+        after: Option<&Cursor>,
+        limit: u64,
+    ) -> Result<Page<Todo>, DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let mut select = select_todos();
+        select
+            .and_where(Expr::col(Todos::UserId).eq(user_id))
+            .and_where(Expr::col(Todos::Status).eq(status));
+        apply_keyset(&mut select, Todos::CreatedAt, Todos::Id, after, limit);
+
+        let (sql, values) = select.build_sqlx(PostgresQueryBuilder);
+        let rows = timed_query(
+            &sql,
+            sqlx::query_as_with::<_, TodoRow, _>(&sql, values).fetch_all(executor),
+        )
+        .await?;
+
+        Page::from_rows(rows, limit).try_map(Todo::try_from)
+    }
+
+    /// List a user's todos carrying the given tag
+    pub async fn list_by_user_and_tag<'e, E>(
+        executor: E,
+        user_id: Uuid,
+        tag: &str,
+    ) -> Result<Vec<Todo>, DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let query = sqlx::query_as!(
+            TodoRow,
+            r#"
+            SELECT todos.id, todos.user_id, todos.title, todos.description, todos.status,
+                   todos.due_date, todos.started_at, todos.total_seconds, todos.created_at,
+                   todos.updated_at, todos.deleted_at
+            FROM todos
+            JOIN todo_tags ON todo_tags.todo_id = todos.id
+            JOIN tags ON tags.id = todo_tags.tag_id
+            WHERE todos.user_id = $1 AND tags.name = $2 AND todos.deleted_at IS NULL
+            ORDER BY todos.created_at DESC, todos.id DESC
+            "#,
+            user_id,
+            tag
+        );
+        let rows = timed_query(query.sql(), query.fetch_all(executor)).await?;
+
+        rows.into_iter().map(Todo::try_from).collect()
+    }
+
+    /// Find a user's todos matching every criterion set in `filter`, newest
+    /// first, with offset pagination
+    pub async fn find<'e, E>(
+        executor: E,
+        user_id: Uuid,
+        filter: &TodoFilter,
+        offset: u64,
+        limit: u64,
+    ) -> Result<Vec<Todo>, DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let mut select = select_todos();
+        select.and_where(Expr::col(Todos::UserId).eq(user_id));
+
+        if !filter.statuses.is_empty() {
+            select.and_where(Expr::col(Todos::Status).is_in(filter.statuses.iter().copied()));
+        }
+
+        if let Some(query) = &filter.query {
+            let pattern = format!("%{}%", escape_like(query));
+            select.cond_where(
+                Cond::any()
+                    .add(Expr::col(Todos::Title).ilike(&pattern))
+                    .add(Expr::col(Todos::Description).ilike(&pattern)),
+            );
+        }
+
+        if let Some(tag) = &filter.tag {
+            select.and_where(
+                Expr::col(Todos::Id).in_subquery(
+                    Query::select()
+                        .column((TodoTags::Table, TodoTags::TodoId))
+                        .from(TodoTags::Table)
+                        .inner_join(
+                            Tags::Table,
+                            Expr::col((Tags::Table, Tags::Id))
+                                .equals((TodoTags::Table, TodoTags::TagId)),
+                        )
+                        .and_where(Expr::col((Tags::Table, Tags::Name)).eq(tag.as_str()))
+                        .to_owned(),
+                ),
+            );
+        }
+
+        if let Some(after) = filter.created_after {
+            select.and_where(Expr::col(Todos::CreatedAt).gte(after));
+        }
+        if let Some(before) = filter.created_before {
+            select.and_where(Expr::col(Todos::CreatedAt).lt(before));
+        }
+        if let Some(before) = filter.due_before {
+            select.and_where(Expr::col(Todos::DueDate).lt(before));
+        }
+
+        let (sql, values) = select
+            .order_by(Todos::CreatedAt, Order::Desc)
+            .order_by(Todos::Id, Order::Desc)
+            .offset(offset)
+            .limit(limit)
+            .build_sqlx(PostgresQueryBuilder);
+
+        let rows = timed_query(
+            &sql,
+            sqlx::query_as_with::<_, TodoRow, _>(&sql, values).fetch_all(executor),
         )
-        .fetch_all(executor)
         .await?;
 
-        Ok(rows.into_iter().map(Into::into).collect())
+        rows.into_iter().map(Todo::try_from).collect()
+    }
+
+    /// List a user's most recently updated todos, latest change first
+    pub async fn list_recently_updated<'e, E>(
+        executor: E,
+        user_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<Todo>, DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let query = sqlx::query_as!(
+            TodoRow,
+            r#"
+            SELECT id, user_id, title, description, status, due_date, started_at,
+                   total_seconds, created_at, updated_at, deleted_at
+            FROM todos
+            WHERE user_id = $1 AND deleted_at IS NULL
+            ORDER BY updated_at DESC, id DESC
+            LIMIT $2
+            "#,
+            user_id,
+            limit
+        );
+        let rows = timed_query(query.sql(), query.fetch_all(executor)).await?;
+
+        rows.into_iter().map(Todo::try_from).collect()
+    }
+
+    /// List a user's open todos whose due date is before `now`, soonest first
+    ///
+    /// Completed and cancelled todos are never overdue.
+    pub async fn list_overdue<'e, E>(
+        executor: E,
+        user_id: Uuid,
+        now: OffsetDateTime,
+    ) -> Result<Vec<Todo>, DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let query = sqlx::query_as!(
+            TodoRow,
+            r#"
+            SELECT id, user_id, title, description, status, due_date, started_at,
+                   total_seconds, created_at, updated_at, deleted_at
+            FROM todos
+            WHERE user_id = $1
+              AND due_date < $2
+              AND status NOT IN ('completed', 'cancelled')
+              AND deleted_at IS NULL
+            ORDER BY due_date ASC
+            "#,
+            user_id,
+            now
+        );
+        let rows = timed_query(query.sql(), query.fetch_all(executor)).await?;
+
+        rows.into_iter().map(Todo::try_from).collect()
+    }
+
+    /// List a user's open todos due in `[from, to)`, soonest first
+    ///
+    /// Completed and cancelled todos are left out.
+    pub async fn list_due_between<'e, E>(
+        executor: E,
+        user_id: Uuid,
+        from: OffsetDateTime,
+        to: OffsetDateTime,
+    ) -> Result<Vec<Todo>, DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let query = sqlx::query_as!(
+            TodoRow,
+            r#"
+            SELECT id, user_id, title, description, status, due_date, started_at,
+                   total_seconds, created_at, updated_at, deleted_at
+            FROM todos
+            WHERE user_id = $1
+              AND due_date >= $2
+              AND due_date < $3
+              AND status NOT IN ('completed', 'cancelled')
+              AND deleted_at IS NULL
+            ORDER BY due_date ASC
+            "#,
+            user_id,
+            from,
+            to
+        );
+        let rows = timed_query(query.sql(), query.fetch_all(executor)).await?;
+
+        rows.into_iter().map(Todo::try_from).collect()
+    }
+
+    /// Count a user's todos in each status
+    ///
+    /// Statuses the user has no todos in are left out.
+    pub async fn count_by_status<'e, E>(
+        executor: E,
+        user_id: Uuid,
+    ) -> Result<Vec<(TodoStatus, i64)>, DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let query = sqlx::query!(
+            r#"
+            SELECT status, COUNT(*) AS "count!"
+            FROM todos
+            WHERE user_id = $1 AND deleted_at IS NULL
+            GROUP BY status
+            "#,
+            user_id
+        );
+        let rows = timed_query(query.sql(), query.fetch_all(executor)).await?;
+
+        rows.into_iter()
+            .map(|row| Ok((TodoStatus::from_db(&row.status)?, row.count)))
+            .collect()
+    }
+
+    /// Count the open todos, those neither completed nor cancelled, for each
+    /// of several users
+    ///
+    /// Users with no such todos are left out.
+    pub async fn count_open_by_users<'e, E>(
+        executor: E,
+        user_ids: &[Uuid],
+    ) -> Result<HashMap<Uuid, i64>, DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let query = sqlx::query!(
+            r#"
+            SELECT user_id, COUNT(*) AS "count!"
+            FROM todos
+            WHERE user_id = ANY($1)
+              AND status NOT IN ('completed', 'cancelled')
+              AND deleted_at IS NULL
+            GROUP BY user_id
+            "#,
+            user_ids
+        );
+        let rows = timed_query(query.sql(), query.fetch_all(executor)).await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.user_id, row.count))
+            .collect())
     }
 
     /// Update a todo's status
+    ///
+    /// With `expected_updated_at`, the todo is only changed if it was last
+    /// updated at exactly that time; otherwise `None` is returned as if it
+    /// didn't exist.
     pub async fn update_status<'e, E>(
         executor: E,
         id: Uuid,
         status: TodoStatus,
+        expected_updated_at: Option<OffsetDateTime>,
     ) -> Result<Option<Todo>, DomainError>
     where
         E: Executor<'e, Database = Postgres>,
@@ -189,30 +805,74 @@ impl TodoRepository {
         let now = OffsetDateTime::now_utc();
         let status_str = status.as_str();
 
-        let row = sqlx::query_as!(
+        let query = sqlx::query_as!(
             TodoRow,
             r#"
             UPDATE todos
             SET status = $1, updated_at = $2
             WHERE id = $3
-            RETURNING id, user_id, title, description, status, created_at, updated_at
+              AND deleted_at IS NULL
+              AND ($4::timestamptz IS NULL OR updated_at = $4)
+            RETURNING id, user_id, title, description, status, due_date, started_at,
+                      total_seconds, created_at, updated_at, deleted_at
             "#,
             status_str,
             now,
-            id
-        )
-        .fetch_optional(executor)
-        .await?;
+            id,
+            expected_updated_at
+        );
+        let row = timed_query(query.sql(), query.fetch_optional(executor)).await?;
 
-        Ok(row.map(Into::into))
+        row.map(Todo::try_from).transpose()
+    }
+
+    /// Set the status of several of a user's todos in one statement
+    ///
+    /// Only todos owned by `user_id` and currently in one of the `from`
+    /// statuses are changed. Returns the todos that were updated.
+    pub async fn update_status_many<'e, E>(
+        executor: E,
+        user_id: Uuid,
+        ids: &[Uuid],
+        status: TodoStatus,
+        from: &[TodoStatus],
+    ) -> Result<Vec<Todo>, DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let now = OffsetDateTime::now_utc();
+        let status_str = status.as_str();
+        let from_strs: Vec<String> = from.iter().map(|s| s.as_str().to_owned()).collect();
+
+        let query = sqlx::query_as!(
+            TodoRow,
+            r#"
+            UPDATE todos
+            SET status = $1, updated_at = $2
+            WHERE user_id = $3 AND id = ANY($4) AND status = ANY($5) AND deleted_at IS NULL
+            RETURNING id, user_id, title, description, status, due_date, started_at,
+                      total_seconds, created_at, updated_at, deleted_at
+            "#,
+            status_str,
+            now,
+            user_id,
+            ids,
+            &from_strs
+        );
+        let rows = timed_query(query.sql(), query.fetch_all(executor)).await?;
+
+        rows.into_iter().map(Todo::try_from).collect()
     }
 
     /// Update a todo's title and description
+    ///
+    /// `expected_updated_at` guards the write as in `update_status`.
     pub async fn update_content<'e, E>(
         executor: E,
         id: Uuid,
         title: &str,
         description: Option<&str>,
+        expected_updated_at: Option<OffsetDateTime>,
     ) -> Result<Option<Todo>, DomainError>
     where
         E: Executor<'e, Database = Postgres>,
@@ -220,40 +880,322 @@ impl TodoRepository {
         let now = OffsetDateTime::now_utc();
         let description_string = description.map(|s| s.to_string());
 
-        let row = sqlx::query_as!(
+        let query = sqlx::query_as!(
             TodoRow,
             r#"
             UPDATE todos
             SET title = $1, description = $2, updated_at = $3
             WHERE id = $4
-            RETURNING id, user_id, title, description, status, created_at, updated_at
+              AND deleted_at IS NULL
+              AND ($5::timestamptz IS NULL OR updated_at = $5)
+            RETURNING id, user_id, title, description, status, due_date, started_at,
+                      total_seconds, created_at, updated_at, deleted_at
             "#,
             title,
             description_string,
             now,
+            id,
+            expected_updated_at
+        );
+        let row = timed_query(query.sql(), query.fetch_optional(executor)).await?;
+
+        row.map(Todo::try_from).transpose()
+    }
+
+    /// Start a todo's time-tracking clock
+    ///
+    /// Returns `None` if the todo doesn't exist or its clock is already
+    /// running.
+    pub async fn start_timer<'e, E>(executor: E, id: Uuid) -> Result<Option<Todo>, DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let now = OffsetDateTime::now_utc();
+
+        let query = sqlx::query_as!(
+            TodoRow,
+            r#"
+            UPDATE todos
+            SET started_at = $1, updated_at = $1
+            WHERE id = $2 AND started_at IS NULL AND deleted_at IS NULL
+            RETURNING id, user_id, title, description, status, due_date, started_at,
+                      total_seconds, created_at, updated_at, deleted_at
+            "#,
+            now,
             id
-        )
-        .fetch_optional(executor)
-        .await?;
+        );
+        let row = timed_query(query.sql(), query.fetch_optional(executor)).await?;
+
+        row.map(Todo::try_from).transpose()
+    }
+
+    /// Stop a todo's running clock, adding the elapsed whole seconds to its
+    /// total
+    ///
+    /// Returns `None` if the todo doesn't exist or its clock isn't running.
+    pub async fn stop_timer<'e, E>(executor: E, id: Uuid) -> Result<Option<Todo>, DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let now = OffsetDateTime::now_utc();
+
+        let query = sqlx::query_as!(
+            TodoRow,
+            r#"
+            UPDATE todos
+            SET total_seconds = total_seconds + FLOOR(EXTRACT(EPOCH FROM $1 - started_at))::bigint,
+                started_at = NULL,
+                updated_at = $1
+            WHERE id = $2 AND started_at IS NOT NULL
+            RETURNING id, user_id, title, description, status, due_date, started_at,
+                      total_seconds, created_at, updated_at, deleted_at
+            "#,
+            now,
+            id
+        );
+        let row = timed_query(query.sql(), query.fetch_optional(executor)).await?;
 
-        Ok(row.map(Into::into))
+        row.map(Todo::try_from).transpose()
     }
 
-    /// Delete a todo
+    /// Record the reminder job scheduled for a todo
+    pub async fn set_reminder_job_id<'e, E>(
+        executor: E,
+        id: Uuid,
+        job_id: Option<Uuid>,
+    ) -> Result<(), DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let query = sqlx::query!(
+            r#"
+            UPDATE todos
+            SET reminder_job_id = $1
+            WHERE id = $2
+            "#,
+            job_id,
+            id
+        );
+        timed_query(query.sql(), query.execute(executor)).await?;
+
+        Ok(())
+    }
+
+    /// Clear a todo's reminder job, returning the ID it held
+    ///
+    /// The row is locked while it is read, so two callers cannot both take
+    /// the same job.
+    pub async fn take_reminder_job_id<'e, E>(
+        executor: E,
+        id: Uuid,
+    ) -> Result<Option<Uuid>, DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let query = sqlx::query_scalar!(
+            r#"
+            UPDATE todos
+            SET reminder_job_id = NULL
+            FROM (
+                SELECT id, reminder_job_id FROM todos WHERE id = $1 FOR UPDATE
+            ) AS previous
+            WHERE todos.id = previous.id AND previous.reminder_job_id IS NOT NULL
+            RETURNING previous.reminder_job_id AS "reminder_job_id!"
+            "#,
+            id
+        );
+        let job_id = timed_query(query.sql(), query.fetch_optional(executor)).await?;
+
+        Ok(job_id)
+    }
+
+    /// Soft-delete a todo
+    ///
+    /// Sets `deleted_at` rather than removing the row, so the todo drops out
+    /// of normal queries but can be restored. Returns `false` if it doesn't
+    /// exist or is already deleted.
     pub async fn delete<'e, E>(executor: E, id: Uuid) -> Result<bool, DomainError>
     where
         E: Executor<'e, Database = Postgres>,
     {
-        let result = sqlx::query!(
+        let now = OffsetDateTime::now_utc();
+
+        let query = sqlx::query!(
             r#"
-            DELETE FROM todos
-            WHERE id = $1
+            UPDATE todos
+            SET deleted_at = $1, updated_at = $1
+            WHERE id = $2 AND deleted_at IS NULL
             "#,
+            now,
             id
-        )
-        .execute(executor)
-        .await?;
+        );
+        let result = timed_query(query.sql(), query.execute(executor)).await?;
 
         Ok(result.rows_affected() > 0)
     }
-}
\ No newline at end of file
+
+    /// Bring back a soft-deleted todo
+    ///
+    /// Returns `None` if the todo doesn't exist or isn't deleted.
+    pub async fn restore<'e, E>(executor: E, id: Uuid) -> Result<Option<Todo>, DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let now = OffsetDateTime::now_utc();
+
+        let query = sqlx::query_as!(
+            TodoRow,
+            r#"
+            UPDATE todos
+            SET deleted_at = NULL, updated_at = $1
+            WHERE id = $2 AND deleted_at IS NOT NULL
+            RETURNING id, user_id, title, description, status, due_date, started_at,
+                      total_seconds, created_at, updated_at, deleted_at
+            "#,
+            now,
+            id
+        );
+        let row = timed_query(query.sql(), query.fetch_optional(executor)).await?;
+
+        row.map(Todo::try_from).transpose()
+    }
+
+    /// Move a todo to another user, returning None if there is no such todo
+    ///
+    /// The todo's position is cleared, since it ordered the todo among its
+    /// old owner's. A missing `user_id` is a `Conflict`; callers should
+    /// check the user exists first.
+    pub async fn reassign<'e, E>(
+        executor: E,
+        id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<Todo>, DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let now = OffsetDateTime::now_utc();
+
+        let query = sqlx::query_as!(
+            TodoRow,
+            r#"
+            UPDATE todos
+            SET user_id = $1, position = NULL, updated_at = $2
+            WHERE id = $3 AND deleted_at IS NULL
+            RETURNING id, user_id, title, description, status, due_date, started_at,
+                      total_seconds, created_at, updated_at, deleted_at
+            "#,
+            user_id,
+            now,
+            id
+        );
+        let row = timed_query(query.sql(), query.fetch_optional(executor))
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::Database(db) if db.is_foreign_key_violation() => {
+                    DomainError::Conflict(format!("User does not exist: {}", user_id))
+                }
+                e => e.into(),
+            })?;
+
+        row.map(Todo::try_from).transpose()
+    }
+
+    /// Delete a user's completed todos, returning how many were removed
+    pub async fn delete_completed_by_user<'e, E>(
+        executor: E,
+        user_id: Uuid,
+    ) -> Result<u64, DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let status = TodoStatus::Completed.as_str();
+        let query = sqlx::query!(
+            r#"
+            DELETE FROM todos
+            WHERE user_id = $1 AND status = $2 AND deleted_at IS NULL
+            "#,
+            user_id,
+            status
+        );
+        let result = timed_query(query.sql(), query.execute(executor)).await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Clear the reminder jobs of all of a user's todos, returning the IDs
+    /// they held
+    ///
+    /// The rows are locked as in `take_reminder_job_id`.
+    pub async fn take_reminder_job_ids_by_user<'e, E>(
+        executor: E,
+        user_id: Uuid,
+    ) -> Result<Vec<Uuid>, DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let query = sqlx::query_scalar!(
+            r#"
+            UPDATE todos
+            SET reminder_job_id = NULL
+            FROM (
+                SELECT id, reminder_job_id FROM todos
+                WHERE user_id = $1 AND reminder_job_id IS NOT NULL
+                FOR UPDATE
+            ) AS previous
+            WHERE todos.id = previous.id
+            RETURNING previous.reminder_job_id AS "reminder_job_id!"
+            "#,
+            user_id
+        );
+        let job_ids = timed_query(query.sql(), query.fetch_all(executor)).await?;
+
+        Ok(job_ids)
+    }
+
+    /// Delete all todos belonging to a user, returning how many were removed
+    pub async fn delete_by_user<'e, E>(executor: E, user_id: Uuid) -> Result<u64, DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let query = sqlx::query!(
+            r#"
+            DELETE FROM todos
+            WHERE user_id = $1
+            "#,
+            user_id
+        );
+        let result = timed_query(query.sql(), query.execute(executor)).await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+/// Select every `TodoRow` column from todos that aren't deleted
+fn select_todos() -> SelectStatement {
+    let mut select = Query::select();
+    select
+        .columns([
+            Todos::Id,
+            Todos::UserId,
+            Todos::Title,
+            Todos::Description,
+            Todos::Status,
+            Todos::DueDate,
+            Todos::StartedAt,
+            Todos::TotalSeconds,
+            Todos::CreatedAt,
+            Todos::UpdatedAt,
+            Todos::DeletedAt,
+        ])
+        .from(Todos::Table)
+        .and_where(Expr::col(Todos::DeletedAt).is_null());
+    select
+}
+
+/// Escape LIKE wildcards so user input is matched literally
+pub(crate) fn escape_like(input: &str) -> String {
+    input
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
@@ -0,0 +1,125 @@
+use sqlx::{Execute, Executor, Postgres};
+use uuid::Uuid;
+
+use crate::DomainError;
+use crate::slow_query::timed_query;
+
+/// A checklist entry within a todo
+#[derive(Debug, Clone, PartialEq)]
+pub struct TodoItem {
+    pub id: Uuid,
+    pub todo_id: Uuid,
+    pub text: String,
+    pub done: bool,
+    /// Where the item sits in its todo's checklist, lowest first
+    pub position: i32,
+}
+
+/// Repository for todo checklist items
+pub struct TodoItemRepository;
+
+impl TodoItemRepository {
+    /// Add an item to the end of a todo's checklist
+    ///
+    /// Returns `DomainError::NotFound` when the todo doesn't exist.
+    pub async fn add<'e, E>(executor: E, todo_id: Uuid, text: &str) -> Result<TodoItem, DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let query = sqlx::query_as!(
+            TodoItem,
+            r#"
+            INSERT INTO todo_items (id, todo_id, text, position)
+            SELECT $1, $2, $3, COALESCE(MAX(position) + 1, 0)
+            FROM todo_items
+            WHERE todo_id = $2
+            RETURNING id, todo_id, text, done, position
+            "#,
+            Uuid::new_v4(),
+            todo_id,
+            text
+        );
+        timed_query(query.sql(), query.fetch_one(executor))
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::Database(db) if db.is_foreign_key_violation() => {
+                    DomainError::NotFound(format!("Todo not found: {}", todo_id))
+                }
+                e => e.into(),
+            })
+    }
+
+    /// Find an item by ID
+    pub async fn find_by_id<'e, E>(executor: E, id: Uuid) -> Result<Option<TodoItem>, DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let query = sqlx::query_as!(
+            TodoItem,
+            r#"
+            SELECT id, todo_id, text, done, position
+            FROM todo_items
+            WHERE id = $1
+            "#,
+            id
+        );
+        let item = timed_query(query.sql(), query.fetch_optional(executor)).await?;
+
+        Ok(item)
+    }
+
+    /// Flip an item between done and not done, returning it afterwards
+    pub async fn toggle<'e, E>(executor: E, id: Uuid) -> Result<Option<TodoItem>, DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let query = sqlx::query_as!(
+            TodoItem,
+            r#"
+            UPDATE todo_items
+            SET done = NOT done
+            WHERE id = $1
+            RETURNING id, todo_id, text, done, position
+            "#,
+            id
+        );
+        let item = timed_query(query.sql(), query.fetch_optional(executor)).await?;
+
+        Ok(item)
+    }
+
+    /// Delete an item, returning whether it existed
+    pub async fn delete<'e, E>(executor: E, id: Uuid) -> Result<bool, DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let query = sqlx::query!("DELETE FROM todo_items WHERE id = $1", id);
+        let result = timed_query(query.sql(), query.execute(executor)).await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// List the items of several todos at once, in position order within
+    /// each todo
+    pub async fn list_for_todos<'e, E>(
+        executor: E,
+        todo_ids: &[Uuid],
+    ) -> Result<Vec<TodoItem>, DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let query = sqlx::query_as!(
+            TodoItem,
+            r#"
+            SELECT id, todo_id, text, done, position
+            FROM todo_items
+            WHERE todo_id = ANY($1)
+            ORDER BY position
+            "#,
+            todo_ids
+        );
+        let items = timed_query(query.sql(), query.fetch_all(executor)).await?;
+
+        Ok(items)
+    }
+}
@@ -1,17 +1,135 @@
-use sqlx::{Executor, FromRow, Postgres};
-use time::OffsetDateTime;
+use std::collections::HashMap;
+
+use sea_query::extension::postgres::PgExpr;
+use sea_query::{Cond, Expr, Iden, Order, PostgresQueryBuilder, Query, SelectStatement};
+use sea_query_binder::SqlxBinder;
+use sqlx::{Execute, Executor, FromRow, Postgres};
+use time::{Date, OffsetDateTime};
 use uuid::Uuid;
 
 use crate::DomainError;
+use crate::slow_query::timed_query;
+use crate::todo::escape_like;
+
+/// What a user is allowed to do
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UserRole {
+    User,
+    Admin,
+}
+
+impl UserRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UserRole::User => "user",
+            UserRole::Admin => "admin",
+        }
+    }
+
+    /// Parse a role read from the database
+    ///
+    /// An unknown value is an error rather than quietly becoming `User`.
+    pub fn from_db(s: &str) -> Result<Self, DomainError> {
+        match s {
+            "user" => Ok(UserRole::User),
+            "admin" => Ok(UserRole::Admin),
+            _ => Err(DomainError::Validation(format!(
+                "Unknown user role: {:?}",
+                s
+            ))),
+        }
+    }
+}
+
+/// Optional criteria for `UserRepository::find`
+///
+/// Every field that is set must match; unset fields don't filter.
+#[derive(Debug, Clone, Default)]
+pub struct UserFilter {
+    /// Case-insensitive text matched against name or email
+    pub query: Option<String>,
+    /// Inclusive lower bound on when the user registered
+    pub created_after: Option<OffsetDateTime>,
+    /// Whether soft-deleted users are included
+    pub include_deleted: bool,
+}
+
+/// Order for `UserRepository::find` to return users in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UserSort {
+    /// Most recently registered first
+    #[default]
+    Newest,
+    /// Earliest registered first
+    Oldest,
+    /// Alphabetical by name
+    Name,
+}
+
+/// Canonical form of an email address: trimmed and lowercased
+///
+/// `UserRepository` applies this to every email it stores or looks up, so
+/// addresses that differ only in case or surrounding whitespace match.
+pub fn normalize_email(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+/// Raw user row from database
+#[derive(Debug, Clone, FromRow)]
+struct UserRow {
+    pub id: Uuid,
+    pub email: String,
+    pub name: String,
+    pub timezone: String,
+    pub role: String,
+    pub created_at: OffsetDateTime,
+    pub updated_at: OffsetDateTime,
+    pub deleted_at: Option<OffsetDateTime>,
+}
 
 /// User entity
-#[derive(Debug, Clone, FromRow, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct User {
     pub id: Uuid,
     pub email: String,
     pub name: String,
+    /// IANA timezone name, such as `Europe/London`
+    pub timezone: String,
+    pub role: UserRole,
     pub created_at: OffsetDateTime,
     pub updated_at: OffsetDateTime,
+    pub deleted_at: Option<OffsetDateTime>,
+}
+
+impl TryFrom<UserRow> for User {
+    type Error = DomainError;
+
+    fn try_from(row: UserRow) -> Result<Self, DomainError> {
+        Ok(User {
+            id: row.id,
+            email: row.email,
+            name: row.name,
+            timezone: row.timezone,
+            role: UserRole::from_db(&row.role)?,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+            deleted_at: row.deleted_at,
+        })
+    }
+}
+
+/// Users table identifiers for SeaQuery
+#[derive(Iden, Clone, Copy)]
+enum Users {
+    Table,
+    Id,
+    Email,
+    Name,
+    Timezone,
+    Role,
+    CreatedAt,
+    UpdatedAt,
+    DeletedAt,
 }
 
 /// Repository for User operations
@@ -19,34 +137,90 @@ pub struct UserRepository;
 
 impl UserRepository {
     /// Create a new user within a transaction
+    ///
+    /// Returns `DomainError::Conflict` if an active user already has the email.
     pub async fn create<'e, E>(
         executor: E,
         email: &str,
         name: &str,
+        timezone: &str,
     ) -> Result<User, DomainError>
     where
         E: Executor<'e, Database = Postgres>,
     {
+        let email = normalize_email(email);
         let id = Uuid::new_v4();
         let now = OffsetDateTime::now_utc();
 
-        let user = sqlx::query_as!(
-            User,
+        let query = sqlx::query_as!(
+            UserRow,
             r#"
-            INSERT INTO users (id, email, name, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, $5)
-            RETURNING id, email, name, created_at, updated_at
+            INSERT INTO users (id, email, name, timezone, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id, email, name, timezone, role, created_at, updated_at, deleted_at
             "#,
             id,
             email,
             name,
+            timezone,
             now,
             now
-        )
-        .fetch_one(executor)
-        .await?;
+        );
+        let row = timed_query(query.sql(), query.fetch_one(executor))
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::Database(db) if db.is_unique_violation() => {
+                    DomainError::Conflict(format!("Email already exists: {}", email))
+                }
+                e => e.into(),
+            })?;
+
+        row.try_into()
+    }
+
+    /// Create several users with a single multi-row INSERT
+    ///
+    /// Each item is an `(email, name, timezone)` triple. Users whose email is
+    /// already taken, by an active user or an earlier item, are skipped rather
+    /// than failing the statement; the rest are returned in input order, so
+    /// callers can tell which ones collided.
+    pub async fn create_many<'e, E>(
+        executor: E,
+        users: &[(&str, &str, &str)],
+    ) -> Result<Vec<User>, DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let now = OffsetDateTime::now_utc();
+        let ids: Vec<Uuid> = users.iter().map(|_| Uuid::new_v4()).collect();
+        let emails: Vec<String> = users.iter().map(|(e, _, _)| normalize_email(e)).collect();
+        let names: Vec<String> = users.iter().map(|(_, n, _)| String::from(*n)).collect();
+        let timezones: Vec<String> = users.iter().map(|(_, _, t)| String::from(*t)).collect();
+
+        let query = sqlx::query_as!(
+            UserRow,
+            r#"
+            INSERT INTO users (id, email, name, timezone, created_at, updated_at)
+            SELECT id, email, name, timezone, $5, $5
+            FROM UNNEST($1::uuid[], $2::text[], $3::text[], $4::text[])
+                AS t(id, email, name, timezone)
+            ON CONFLICT DO NOTHING
+            RETURNING id, email, name, timezone, role, created_at, updated_at, deleted_at
+            "#,
+            &ids,
+            &emails,
+            &names,
+            &timezones,
+            now
+        );
+        let rows = timed_query(query.sql(), query.fetch_all(executor)).await?;
 
-        Ok(user)
+        // RETURNING order is unspecified, so put the rows back in input order
+        let mut created = rows
+            .into_iter()
+            .map(|row| Ok((row.id, User::try_from(row)?)))
+            .collect::<Result<HashMap<Uuid, User>, DomainError>>()?;
+        Ok(ids.iter().filter_map(|id| created.remove(id)).collect())
     }
 
     /// Find a user by ID
@@ -54,19 +228,62 @@ impl UserRepository {
     where
         E: Executor<'e, Database = Postgres>,
     {
-        let user = sqlx::query_as!(
-            User,
+        let query = sqlx::query_as!(
+            UserRow,
+            r#"
+            SELECT id, email, name, timezone, role, created_at, updated_at, deleted_at
+            FROM users
+            WHERE id = $1 AND deleted_at IS NULL
+            "#,
+            id
+        );
+        let row = timed_query(query.sql(), query.fetch_optional(executor)).await?;
+
+        row.map(User::try_from).transpose()
+    }
+
+    /// Find several users by ID in one query, in the order the IDs are given
+    ///
+    /// IDs with no matching active user are simply absent from the result.
+    pub async fn find_by_ids<'e, E>(executor: E, ids: &[Uuid]) -> Result<Vec<User>, DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let query = sqlx::query_as!(
+            UserRow,
+            r#"
+            SELECT id, email, name, timezone, role, created_at, updated_at, deleted_at
+            FROM users
+            WHERE id = ANY($1) AND deleted_at IS NULL
+            ORDER BY array_position($1, id)
+            "#,
+            ids
+        );
+        let rows = timed_query(query.sql(), query.fetch_all(executor)).await?;
+
+        rows.into_iter().map(User::try_from).collect()
+    }
+
+    /// Find a user by ID, including soft-deleted users (for admin use)
+    pub async fn find_by_id_including_deleted<'e, E>(
+        executor: E,
+        id: Uuid,
+    ) -> Result<Option<User>, DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let query = sqlx::query_as!(
+            UserRow,
             r#"
-            SELECT id, email, name, created_at, updated_at
+            SELECT id, email, name, timezone, role, created_at, updated_at, deleted_at
             FROM users
             WHERE id = $1
             "#,
             id
-        )
-        .fetch_optional(executor)
-        .await?;
+        );
+        let row = timed_query(query.sql(), query.fetch_optional(executor)).await?;
 
-        Ok(user)
+        row.map(User::try_from).transpose()
     }
 
     /// Find a user by email
@@ -77,38 +294,182 @@ impl UserRepository {
     where
         E: Executor<'e, Database = Postgres>,
     {
-        let user = sqlx::query_as!(
-            User,
+        let email = normalize_email(email);
+        let query = sqlx::query_as!(
+            UserRow,
             r#"
-            SELECT id, email, name, created_at, updated_at
+            SELECT id, email, name, timezone, role, created_at, updated_at, deleted_at
             FROM users
-            WHERE email = $1
+            WHERE email = $1 AND deleted_at IS NULL
             "#,
             email
-        )
-        .fetch_optional(executor)
-        .await?;
+        );
+        let row = timed_query(query.sql(), query.fetch_optional(executor)).await?;
+
+        row.map(User::try_from).transpose()
+    }
+
+    /// Hold a lock on an email address until the transaction ends
+    ///
+    /// No row exists to lock with `SELECT ... FOR UPDATE` before a user is
+    /// created, so this takes a transaction-scoped advisory lock keyed on the
+    /// address instead. Registrations that take it before checking
+    /// `find_by_email` can't both see the address as free.
+    pub async fn lock_email<'e, E>(executor: E, email: &str) -> Result<(), DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let email = normalize_email(email);
+        let query = sqlx::query!("SELECT pg_advisory_xact_lock(hashtext($1))", email);
+        timed_query(query.sql(), query.execute(executor)).await?;
 
-        Ok(user)
+        Ok(())
     }
 
-    /// List all users
+    /// List all users, newest first with ties broken by ID
     pub async fn list<'e, E>(executor: E) -> Result<Vec<User>, DomainError>
     where
         E: Executor<'e, Database = Postgres>,
     {
-        let users = sqlx::query_as!(
-            User,
+        let query = sqlx::query_as!(
+            UserRow,
             r#"
-            SELECT id, email, name, created_at, updated_at
+            SELECT id, email, name, timezone, role, created_at, updated_at, deleted_at
             FROM users
-            ORDER BY created_at DESC
+            WHERE deleted_at IS NULL
+            ORDER BY created_at DESC, id DESC
             "#
+        );
+        let rows = timed_query(query.sql(), query.fetch_all(executor)).await?;
+
+        rows.into_iter().map(User::try_from).collect()
+    }
+
+    /// List active users registered in `[after, before)`, newest first
+    ///
+    /// The lower bound is inclusive and the upper bound exclusive, so
+    /// consecutive ranges never count a user twice. Unset bounds don't
+    /// filter.
+    pub async fn list_created_between<'e, E>(
+        executor: E,
+        after: Option<OffsetDateTime>,
+        before: Option<OffsetDateTime>,
+    ) -> Result<Vec<User>, DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let mut select = select_users();
+        select.and_where(Expr::col(Users::DeletedAt).is_null());
+
+        if let Some(after) = after {
+            select.and_where(Expr::col(Users::CreatedAt).gte(after));
+        }
+        if let Some(before) = before {
+            select.and_where(Expr::col(Users::CreatedAt).lt(before));
+        }
+
+        let (sql, values) = select
+            .order_by(Users::CreatedAt, Order::Desc)
+            .order_by(Users::Id, Order::Desc)
+            .build_sqlx(PostgresQueryBuilder);
+
+        let rows = timed_query(
+            &sql,
+            sqlx::query_as_with::<_, UserRow, _>(&sql, values).fetch_all(executor),
         )
-        .fetch_all(executor)
         .await?;
 
-        Ok(users)
+        rows.into_iter().map(User::try_from).collect()
+    }
+
+    /// Find users matching every criterion set in `filter`, in `sort` order,
+    /// with offset pagination
+    ///
+    /// Ties are broken by ID so pages never overlap.
+    pub async fn find<'e, E>(
+        executor: E,
+        filter: &UserFilter,
+        sort: UserSort,
+        offset: u64,
+        limit: u64,
+    ) -> Result<Vec<User>, DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let mut select = select_users();
+
+        if !filter.include_deleted {
+            select.and_where(Expr::col(Users::DeletedAt).is_null());
+        }
+
+        if let Some(query) = &filter.query {
+            let pattern = format!("%{}%", escape_like(query));
+            select.cond_where(
+                Cond::any()
+                    .add(Expr::col(Users::Name).ilike(&pattern))
+                    .add(Expr::col(Users::Email).ilike(&pattern)),
+            );
+        }
+
+        if let Some(after) = filter.created_after {
+            select.and_where(Expr::col(Users::CreatedAt).gte(after));
+        }
+
+        match sort {
+            UserSort::Newest => select
+                .order_by(Users::CreatedAt, Order::Desc)
+                .order_by(Users::Id, Order::Desc),
+            UserSort::Oldest => select
+                .order_by(Users::CreatedAt, Order::Asc)
+                .order_by(Users::Id, Order::Asc),
+            UserSort::Name => select
+                .order_by(Users::Name, Order::Asc)
+                .order_by(Users::Id, Order::Asc),
+        };
+
+        let (sql, values) = select
+            .offset(offset)
+            .limit(limit)
+            .build_sqlx(PostgresQueryBuilder);
+
+        let rows = timed_query(
+            &sql,
+            sqlx::query_as_with::<_, UserRow, _>(&sql, values).fetch_all(executor),
+        )
+        .await?;
+
+        rows.into_iter().map(User::try_from).collect()
+    }
+
+    /// Count the users registered on each UTC day from `from` to `to`,
+    /// both inclusive, oldest day first
+    ///
+    /// Days without registrations are left out. Users who have since been
+    /// deleted still count towards the day they registered.
+    pub async fn registrations_by_day<'e, E>(
+        executor: E,
+        from: Date,
+        to: Date,
+    ) -> Result<Vec<(Date, i64)>, DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let query = sqlx::query!(
+            r#"
+            SELECT date_trunc('day', created_at AT TIME ZONE 'UTC')::date AS "day!",
+                   COUNT(*) AS "count!"
+            FROM users
+            WHERE created_at >= $1::date AT TIME ZONE 'UTC'
+              AND created_at < ($2::date + 1) AT TIME ZONE 'UTC'
+            GROUP BY 1
+            ORDER BY 1
+            "#,
+            from,
+            to
+        );
+        let rows = timed_query(query.sql(), query.fetch_all(executor)).await?;
+
+        Ok(rows.into_iter().map(|row| (row.day, row.count)).collect())
     }
 
     /// Update a user's name
@@ -122,39 +483,343 @@ impl UserRepository {
     {
         let now = OffsetDateTime::now_utc();
 
-        let user = sqlx::query_as!(
-            User,
+        let query = sqlx::query_as!(
+            UserRow,
             r#"
             UPDATE users
             SET name = $1, updated_at = $2
-            WHERE id = $3
-            RETURNING id, email, name, created_at, updated_at
+            WHERE id = $3 AND deleted_at IS NULL
+            RETURNING id, email, name, timezone, role, created_at, updated_at, deleted_at
             "#,
             name,
             now,
             id
-        )
-        .fetch_optional(executor)
-        .await?;
+        );
+        let row = timed_query(query.sql(), query.fetch_optional(executor)).await?;
+
+        row.map(User::try_from).transpose()
+    }
+
+    /// Update a user's email
+    ///
+    /// Returns `DomainError::Conflict` if another active user has the email.
+    pub async fn update_email<'e, E>(
+        executor: E,
+        id: Uuid,
+        email: &str,
+    ) -> Result<Option<User>, DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let email = normalize_email(email);
+        let now = OffsetDateTime::now_utc();
+
+        let query = sqlx::query_as!(
+            UserRow,
+            r#"
+            UPDATE users
+            SET email = $1, updated_at = $2
+            WHERE id = $3 AND deleted_at IS NULL
+            RETURNING id, email, name, timezone, role, created_at, updated_at, deleted_at
+            "#,
+            email,
+            now,
+            id
+        );
+        let row = timed_query(query.sql(), query.fetch_optional(executor))
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::Database(db) if db.is_unique_violation() => {
+                    DomainError::Conflict(format!("Email already exists: {}", email))
+                }
+                e => e.into(),
+            })?;
+
+        row.map(User::try_from).transpose()
+    }
+
+    /// Update a user's timezone
+    ///
+    /// The name is stored as given; callers validate it first.
+    pub async fn update_timezone<'e, E>(
+        executor: E,
+        id: Uuid,
+        timezone: &str,
+    ) -> Result<Option<User>, DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let now = OffsetDateTime::now_utc();
+
+        let query = sqlx::query_as!(
+            UserRow,
+            r#"
+            UPDATE users
+            SET timezone = $1, updated_at = $2
+            WHERE id = $3 AND deleted_at IS NULL
+            RETURNING id, email, name, timezone, role, created_at, updated_at, deleted_at
+            "#,
+            timezone,
+            now,
+            id
+        );
+        let row = timed_query(query.sql(), query.fetch_optional(executor)).await?;
+
+        row.map(User::try_from).transpose()
+    }
+
+    /// Change a user's role
+    pub async fn set_role<'e, E>(
+        executor: E,
+        id: Uuid,
+        role: UserRole,
+    ) -> Result<Option<User>, DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let now = OffsetDateTime::now_utc();
+
+        let query = sqlx::query_as!(
+            UserRow,
+            r#"
+            UPDATE users
+            SET role = $1, updated_at = $2
+            WHERE id = $3 AND deleted_at IS NULL
+            RETURNING id, email, name, timezone, role, created_at, updated_at, deleted_at
+            "#,
+            role.as_str(),
+            now,
+            id
+        );
+        let row = timed_query(query.sql(), query.fetch_optional(executor)).await?;
+
+        row.map(User::try_from).transpose()
+    }
+
+    /// Store a user's password hash, returning false if there is no such
+    /// active user
+    ///
+    /// The hash is deliberately not part of `User`; only
+    /// `find_password_hash` reads it back.
+    pub async fn set_password_hash<'e, E>(
+        executor: E,
+        id: Uuid,
+        password_hash: &str,
+    ) -> Result<bool, DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let now = OffsetDateTime::now_utc();
+
+        let query = sqlx::query!(
+            r#"
+            UPDATE users
+            SET password_hash = $1, updated_at = $2
+            WHERE id = $3 AND deleted_at IS NULL
+            "#,
+            password_hash,
+            now,
+            id
+        );
+        let result = timed_query(query.sql(), query.execute(executor)).await?;
 
-        Ok(user)
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Get an active user's password hash, or None if they have no password
+    pub async fn find_password_hash<'e, E>(
+        executor: E,
+        id: Uuid,
+    ) -> Result<Option<String>, DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let query = sqlx::query_scalar!(
+            r#"
+            SELECT password_hash
+            FROM users
+            WHERE id = $1 AND deleted_at IS NULL
+            "#,
+            id
+        );
+        let hash = timed_query(query.sql(), query.fetch_optional(executor)).await?;
+
+        Ok(hash.flatten())
     }
 
-    /// Delete a user by ID
+    /// Find the active user created under an idempotency key
+    pub async fn find_by_idempotency_key<'e, E>(
+        executor: E,
+        key: &str,
+    ) -> Result<Option<User>, DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let query = sqlx::query_as!(
+            UserRow,
+            r#"
+            SELECT u.id, u.email, u.name, u.timezone, u.role, u.created_at, u.updated_at, u.deleted_at
+            FROM idempotency_keys k
+            JOIN users u ON u.id = k.user_id
+            WHERE k.key = $1 AND u.deleted_at IS NULL
+            "#,
+            key
+        );
+        let row = timed_query(query.sql(), query.fetch_optional(executor)).await?;
+
+        row.map(User::try_from).transpose()
+    }
+
+    /// Record that `user_id` was created under an idempotency key
+    ///
+    /// Returns `DomainError::Conflict` if the key has already been used.
+    pub async fn record_idempotency_key<'e, E>(
+        executor: E,
+        key: &str,
+        user_id: Uuid,
+    ) -> Result<(), DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let query = sqlx::query!(
+            r#"
+            INSERT INTO idempotency_keys (key, user_id)
+            VALUES ($1, $2)
+            "#,
+            key,
+            user_id
+        );
+        timed_query(query.sql(), query.execute(executor))
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::Database(db) if db.is_unique_violation() => {
+                    DomainError::Conflict(format!("Idempotency key already used: {}", key))
+                }
+                e => e.into(),
+            })?;
+
+        Ok(())
+    }
+
+    /// Free the idempotency keys a user was created under, so they can be
+    /// used for a new registration
+    pub async fn release_idempotency_keys<'e, E>(
+        executor: E,
+        user_id: Uuid,
+    ) -> Result<(), DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let query = sqlx::query!(
+            r#"
+            DELETE FROM idempotency_keys
+            WHERE user_id = $1
+            "#,
+            user_id
+        );
+        timed_query(query.sql(), query.execute(executor)).await?;
+
+        Ok(())
+    }
+
+    /// Soft-delete a user by ID
+    ///
+    /// Sets `deleted_at` rather than removing the row, so the user no longer
+    /// appears in normal queries but their history is kept.
     pub async fn delete<'e, E>(executor: E, id: Uuid) -> Result<bool, DomainError>
     where
         E: Executor<'e, Database = Postgres>,
     {
-        let result = sqlx::query!(
+        let now = OffsetDateTime::now_utc();
+
+        let query = sqlx::query!(
             r#"
-            DELETE FROM users
-            WHERE id = $1
+            UPDATE users
+            SET deleted_at = $1
+            WHERE id = $2 AND deleted_at IS NULL
             "#,
+            now,
             id
-        )
-        .execute(executor)
-        .await?;
+        );
+        let result = timed_query(query.sql(), query.execute(executor)).await?;
 
         Ok(result.rows_affected() > 0)
     }
+
+    /// Bring back a soft-deleted user, returning whether one was restored
+    ///
+    /// Returns `DomainError::Conflict` if another active user has taken the
+    /// email address in the meantime.
+    pub async fn restore<'e, E>(executor: E, id: Uuid) -> Result<bool, DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let now = OffsetDateTime::now_utc();
+
+        let query = sqlx::query!(
+            r#"
+            UPDATE users
+            SET deleted_at = NULL, updated_at = $1
+            WHERE id = $2 AND deleted_at IS NOT NULL
+            "#,
+            now,
+            id
+        );
+        let result = timed_query(query.sql(), query.execute(executor))
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::Database(db) if db.is_unique_violation() => {
+                    DomainError::Conflict(format!("Email of user {} is taken by another user", id))
+                }
+                e => e.into(),
+            })?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Scrub a user's personal details and soft-delete them, returning whether
+    /// a user was changed
+    ///
+    /// The email becomes a unique placeholder and the name and password hash
+    /// are blanked in the same UPDATE that sets `deleted_at`. Rows that
+    /// reference the user, such as their todos, are left in place.
+    pub async fn anonymize<'e, E>(executor: E, id: Uuid) -> Result<bool, DomainError>
+    where
+        E: Executor<'e, Database = Postgres>,
+    {
+        let now = OffsetDateTime::now_utc();
+        let email = format!("deleted-{id}@example.invalid");
+
+        let query = sqlx::query!(
+            r#"
+            UPDATE users
+            SET email = $1, name = '', password_hash = NULL, deleted_at = $2, updated_at = $2
+            WHERE id = $3 AND deleted_at IS NULL
+            "#,
+            email,
+            now,
+            id
+        );
+        let result = timed_query(query.sql(), query.execute(executor)).await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+/// Select every `UserRow` column from users, deleted ones included
+fn select_users() -> SelectStatement {
+    let mut select = Query::select();
+    select
+        .columns([
+            Users::Id,
+            Users::Email,
+            Users::Name,
+            Users::Timezone,
+            Users::Role,
+            Users::CreatedAt,
+            Users::UpdatedAt,
+            Users::DeletedAt,
+        ])
+        .from(Users::Table);
+    select
 }
@@ -0,0 +1,68 @@
+use domain::{DomainError, EventRepository};
+use serde_json::json;
+use sqlx::PgPool;
+
+#[sqlx::test(migrations = "../../migrations")]
+async fn test_record_and_claim(pool: PgPool) -> Result<(), DomainError> {
+    let first = EventRepository::record(&pool, "user.registered", &json!({ "n": 1 })).await?;
+    let second = EventRepository::record(&pool, "todo.completed", &json!({ "n": 2 })).await?;
+
+    let events = EventRepository::claim_unprocessed(&pool, 10).await?;
+
+    let ids: Vec<_> = events.iter().map(|e| e.id).collect();
+    assert_eq!(ids, vec![first, second]);
+    assert_eq!(events[0].event_type, "user.registered");
+    assert_eq!(events[1].payload_json, json!({ "n": 2 }));
+    assert!(events.iter().all(|e| e.processed_at.is_none()));
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../migrations")]
+async fn test_claim_respects_limit(pool: PgPool) -> Result<(), DomainError> {
+    for n in 0..3 {
+        EventRepository::record(&pool, "todo.completed", &json!({ "n": n })).await?;
+    }
+
+    assert_eq!(EventRepository::claim_unprocessed(&pool, 2).await?.len(), 2);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../migrations")]
+async fn test_processed_events_are_not_claimed_again(pool: PgPool) -> Result<(), DomainError> {
+    let done = EventRepository::record(&pool, "user.registered", &json!({})).await?;
+    let pending = EventRepository::record(&pool, "user.registered", &json!({})).await?;
+
+    assert_eq!(EventRepository::mark_processed(&pool, &[done]).await?, 1);
+    // Marking it again changes nothing
+    assert_eq!(EventRepository::mark_processed(&pool, &[done]).await?, 0);
+
+    let events = EventRepository::claim_unprocessed(&pool, 10).await?;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].id, pending);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../migrations")]
+async fn test_claimed_events_are_skipped_by_other_transactions(
+    pool: PgPool,
+) -> Result<(), DomainError> {
+    EventRepository::record(&pool, "user.registered", &json!({})).await?;
+
+    let mut tx = pool.begin().await?;
+    assert_eq!(
+        EventRepository::claim_unprocessed(&mut *tx, 10)
+            .await?
+            .len(),
+        1
+    );
+
+    // While the first transaction holds the event, another poller sees nothing
+    assert!(
+        EventRepository::claim_unprocessed(&pool, 10)
+            .await?
+            .is_empty()
+    );
+
+    tx.rollback().await?;
+    Ok(())
+}
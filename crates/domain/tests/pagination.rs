@@ -0,0 +1,93 @@
+use std::collections::HashSet;
+
+use domain::{Cursor, DomainError, TodoRepository, TodoStatus, UserRepository};
+use sqlx::PgPool;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+#[test]
+fn test_cursor_round_trips() {
+    let cursor = Cursor {
+        created_at: OffsetDateTime::from_unix_timestamp_nanos(1_764_844_200_123_456_000).unwrap(),
+        id: Uuid::new_v4(),
+    };
+
+    let decoded = Cursor::decode(&cursor.encode()).unwrap();
+
+    assert_eq!(decoded, cursor);
+}
+
+#[test]
+fn test_cursor_is_url_safe() {
+    let cursor = Cursor {
+        created_at: OffsetDateTime::from_unix_timestamp(1_764_844_200).unwrap(),
+        id: Uuid::new_v4(),
+    };
+
+    let encoded = cursor.encode();
+
+    assert!(
+        encoded
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    );
+}
+
+#[test]
+fn test_invalid_cursor_is_rejected() {
+    for bad in ["", "not base64!", "bm8tY29sb24", "MTIzOm5vdC1hLXV1aWQ"] {
+        assert!(
+            matches!(Cursor::decode(bad), Err(DomainError::Validation(_))),
+            "{bad:?} should be rejected"
+        );
+    }
+}
+
+#[sqlx::test(migrations = "../../migrations")]
+async fn test_todo_pages_are_stable_across_inserts(pool: PgPool) -> Result<(), DomainError> {
+    let user = UserRepository::create(&pool, "pages@example.com", "Test User", "UTC").await?;
+    let mut created = HashSet::new();
+    for i in 0..5 {
+        let todo = TodoRepository::create(&pool, user.id, &format!("Task {i}"), None, None).await?;
+        created.insert(todo.id);
+    }
+
+    // First page
+    let first = TodoRepository::list_by_user_and_status_paginated(
+        &pool,
+        user.id,
+        TodoStatus::Pending,
+        None,
+        2,
+    )
+    .await?;
+    assert_eq!(first.items.len(), 2);
+    assert!(first.has_next_page);
+
+    // A todo added mid-way lands before the first page, not in later ones
+    let late = TodoRepository::create(&pool, user.id, "Late", None, None).await?;
+
+    let mut seen: Vec<Uuid> = first.items.iter().map(|t| t.id).collect();
+    let mut after = first.end_cursor();
+    loop {
+        let page = TodoRepository::list_by_user_and_status_paginated(
+            &pool,
+            user.id,
+            TodoStatus::Pending,
+            after.as_ref(),
+            2,
+        )
+        .await?;
+        seen.extend(page.items.iter().map(|t| t.id));
+        if !page.has_next_page {
+            break;
+        }
+        after = page.end_cursor();
+    }
+
+    // Every original todo exactly once, and not the late one
+    assert_eq!(seen.len(), 5);
+    assert_eq!(seen.iter().copied().collect::<HashSet<_>>(), created);
+    assert!(!seen.contains(&late.id));
+    Ok(())
+}
@@ -0,0 +1,118 @@
+//! Transient error retry tests
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Duration;
+
+use domain::{RetryConfig, RetryConfigError, Transient, retry_on_transient_with};
+use sqlx::PgPool;
+
+/// Parse the config from the given variables only
+fn parse(vars: &[(&str, &str)]) -> Result<RetryConfig, RetryConfigError> {
+    let vars: HashMap<String, String> = vars
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    RetryConfig::from_vars(|name| vars.get(name).cloned())
+}
+
+/// Retry up to `retries` times without waiting in between
+fn config(retries: u32) -> RetryConfig {
+    RetryConfig {
+        retries,
+        base_delay: Duration::ZERO,
+    }
+}
+
+/// Run a statement, forcing it to fail with `errcode` while `fail` is true
+async fn execute(pool: &PgPool, fail: bool, errcode: &str) -> Result<i32, sqlx::Error> {
+    if fail {
+        let sql =
+            format!("DO $$ BEGIN RAISE EXCEPTION 'forced' USING ERRCODE = '{errcode}'; END $$");
+        sqlx::query(&sql).execute(pool).await?;
+    }
+    sqlx::query_scalar("SELECT 1").fetch_one(pool).await
+}
+
+#[test]
+fn unset_retries_uses_default() {
+    let config = parse(&[]).unwrap();
+
+    assert_eq!(config.retries, 3);
+}
+
+#[test]
+fn retries_can_be_overridden() {
+    let config = parse(&[("DB_TRANSIENT_RETRIES", " 0 ")]).unwrap();
+
+    assert_eq!(config.retries, 0);
+}
+
+#[test]
+fn invalid_retries_are_rejected() {
+    assert_eq!(
+        parse(&[("DB_TRANSIENT_RETRIES", "-1")]),
+        Err(RetryConfigError::InvalidNumber {
+            name: "DB_TRANSIENT_RETRIES",
+            value: "-1".to_string(),
+        })
+    );
+}
+
+#[sqlx::test(migrations = "../../migrations")]
+async fn serialization_failures_are_retried_until_success(pool: PgPool) -> Result<(), sqlx::Error> {
+    let attempts = AtomicU32::new(0);
+
+    let value = retry_on_transient_with(config(3), || {
+        let attempt = attempts.fetch_add(1, Ordering::SeqCst) + 1;
+        execute(&pool, attempt <= 2, "40001")
+    })
+    .await?;
+
+    assert_eq!(value, 1);
+    assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../migrations")]
+async fn deadlocks_are_retried(pool: PgPool) -> Result<(), sqlx::Error> {
+    let attempts = AtomicU32::new(0);
+
+    retry_on_transient_with(config(3), || {
+        let attempt = attempts.fetch_add(1, Ordering::SeqCst) + 1;
+        execute(&pool, attempt == 1, "40P01")
+    })
+    .await?;
+
+    assert_eq!(attempts.load(Ordering::SeqCst), 2);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../migrations")]
+async fn retrying_stops_after_the_configured_count(pool: PgPool) {
+    let attempts = AtomicU32::new(0);
+
+    let result = retry_on_transient_with(config(2), || {
+        attempts.fetch_add(1, Ordering::SeqCst);
+        execute(&pool, true, "40001")
+    })
+    .await;
+
+    assert!(result.is_err_and(|e| e.is_transient()));
+    assert_eq!(attempts.load(Ordering::SeqCst), 3);
+}
+
+#[sqlx::test(migrations = "../../migrations")]
+async fn other_errors_are_not_retried(pool: PgPool) {
+    let attempts = AtomicU32::new(0);
+
+    let result = retry_on_transient_with(config(3), || {
+        attempts.fetch_add(1, Ordering::SeqCst);
+        // unique_violation
+        execute(&pool, true, "23505")
+    })
+    .await;
+
+    assert!(result.is_err_and(|e| !e.is_transient()));
+    assert_eq!(attempts.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,95 @@
+//! Slow query logging tests
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use domain::{SlowQueryConfig, SlowQueryConfigError, timed_query};
+use sqlx::PgPool;
+use tracing::instrument::WithSubscriber;
+use tracing::{Level, field::Field, field::Visit};
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::{Layer, Registry};
+
+/// Parse the config from the given variables only
+fn parse(vars: &[(&str, &str)]) -> Result<SlowQueryConfig, SlowQueryConfigError> {
+    let vars: HashMap<String, String> = vars
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    SlowQueryConfig::from_vars(|name| vars.get(name).cloned())
+}
+
+/// Records the `sql` field of every warning logged while a future runs
+#[derive(Clone, Default)]
+struct WarnedSql(Arc<Mutex<Vec<String>>>);
+
+impl<S: tracing::Subscriber> Layer<S> for WarnedSql {
+    fn on_event(&self, event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+        if *event.metadata().level() != Level::WARN {
+            return;
+        }
+
+        let mut field = SqlField::default();
+        event.record(&mut field);
+        if let Some(sql) = field.0 {
+            self.0.lock().unwrap().push(sql);
+        }
+    }
+}
+
+/// Picks the `sql` field out of an event
+#[derive(Default)]
+struct SqlField(Option<String>);
+
+impl Visit for SqlField {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "sql" {
+            self.0 = Some(value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, _field: &Field, _value: &dyn std::fmt::Debug) {}
+}
+
+#[test]
+fn unset_threshold_uses_default() {
+    let config = parse(&[]).unwrap();
+
+    assert_eq!(config.threshold, Duration::from_millis(200));
+}
+
+#[test]
+fn threshold_can_be_overridden() {
+    let config = parse(&[("SLOW_QUERY_MS", " 50 ")]).unwrap();
+
+    assert_eq!(config.threshold, Duration::from_millis(50));
+}
+
+#[test]
+fn invalid_threshold_is_rejected() {
+    assert_eq!(
+        parse(&[("SLOW_QUERY_MS", "soon")]),
+        Err(SlowQueryConfigError::InvalidNumber {
+            name: "SLOW_QUERY_MS",
+            value: "soon".to_string(),
+        })
+    );
+}
+
+#[sqlx::test(migrations = "../../migrations")]
+async fn query_slower_than_threshold_is_logged(pool: PgPool) -> Result<(), sqlx::Error> {
+    SlowQueryConfig {
+        threshold: Duration::from_millis(1),
+    }
+    .install();
+
+    let sql = "SELECT pg_sleep(0.05)";
+    let warned = WarnedSql::default();
+    timed_query(sql, sqlx::query(sql).execute(&pool))
+        .with_subscriber(Registry::default().with(warned.clone()))
+        .await?;
+
+    assert_eq!(*warned.0.lock().unwrap(), [sql]);
+    Ok(())
+}
@@ -0,0 +1,110 @@
+use domain::{DomainError, TagRepository, TodoRepository, UserRepository};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Helper to create a todo to tag
+async fn create_test_todo(pool: &PgPool, email: &str) -> Result<Uuid, DomainError> {
+    let user = UserRepository::create(pool, email, "Test User", "UTC").await?;
+    let todo = TodoRepository::create(pool, user.id, "Tagged Task", None, None).await?;
+    Ok(todo.id)
+}
+
+#[sqlx::test(migrations = "../../migrations")]
+async fn test_attach_and_list_for_todo(pool: PgPool) -> Result<(), DomainError> {
+    let todo_id = create_test_todo(&pool, "attach@example.com").await?;
+
+    TagRepository::attach(&pool, todo_id, "work").await?;
+    TagRepository::attach(&pool, todo_id, "errands").await?;
+
+    let tags = TagRepository::list_for_todo(&pool, todo_id).await?;
+    assert_eq!(tags, vec!["errands", "work"]);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../migrations")]
+async fn test_attach_twice_is_a_no_op(pool: PgPool) -> Result<(), DomainError> {
+    let todo_id = create_test_todo(&pool, "attach-twice@example.com").await?;
+
+    TagRepository::attach(&pool, todo_id, "work").await?;
+    TagRepository::attach(&pool, todo_id, "work").await?;
+
+    assert_eq!(
+        TagRepository::list_for_todo(&pool, todo_id).await?,
+        vec!["work"]
+    );
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../migrations")]
+async fn test_attach_to_missing_todo_fails(pool: PgPool) -> Result<(), DomainError> {
+    let result = TagRepository::attach(&pool, Uuid::new_v4(), "work").await;
+
+    assert!(matches!(result, Err(DomainError::NotFound(_))));
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../migrations")]
+async fn test_set_tags_replaces_the_whole_set(pool: PgPool) -> Result<(), DomainError> {
+    let todo_id = create_test_todo(&pool, "set-tags@example.com").await?;
+
+    TagRepository::set_tags(&pool, todo_id, &["a".into(), "b".into()]).await?;
+    TagRepository::set_tags(&pool, todo_id, &["b".into(), "c".into()]).await?;
+
+    assert_eq!(
+        TagRepository::list_for_todo(&pool, todo_id).await?,
+        vec!["b", "c"]
+    );
+
+    TagRepository::set_tags(&pool, todo_id, &[]).await?;
+    assert!(
+        TagRepository::list_for_todo(&pool, todo_id)
+            .await?
+            .is_empty()
+    );
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../migrations")]
+async fn test_detach(pool: PgPool) -> Result<(), DomainError> {
+    let todo_id = create_test_todo(&pool, "detach@example.com").await?;
+    TagRepository::attach(&pool, todo_id, "work").await?;
+
+    assert!(TagRepository::detach(&pool, todo_id, "work").await?);
+    assert!(!TagRepository::detach(&pool, todo_id, "work").await?);
+    assert!(
+        TagRepository::list_for_todo(&pool, todo_id)
+            .await?
+            .is_empty()
+    );
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../migrations")]
+async fn test_list_for_todos(pool: PgPool) -> Result<(), DomainError> {
+    let first = create_test_todo(&pool, "batch-1@example.com").await?;
+    let second = create_test_todo(&pool, "batch-2@example.com").await?;
+    TagRepository::attach(&pool, first, "work").await?;
+    TagRepository::attach(&pool, second, "home").await?;
+
+    let mut tags = TagRepository::list_for_todos(&pool, &[first, second]).await?;
+    tags.sort();
+
+    let mut expected = vec![(first, "work".to_string()), (second, "home".to_string())];
+    expected.sort();
+    assert_eq!(tags, expected);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../migrations")]
+async fn test_list_by_user_and_tag(pool: PgPool) -> Result<(), DomainError> {
+    let user = UserRepository::create(&pool, "by-tag@example.com", "Test User", "UTC").await?;
+    let tagged = TodoRepository::create(&pool, user.id, "Tagged", None, None).await?;
+    TodoRepository::create(&pool, user.id, "Untagged", None, None).await?;
+    TagRepository::attach(&pool, tagged.id, "work").await?;
+
+    let todos = TodoRepository::list_by_user_and_tag(&pool, user.id, "work").await?;
+
+    assert_eq!(todos.len(), 1);
+    assert_eq!(todos[0].id, tagged.id);
+    Ok(())
+}
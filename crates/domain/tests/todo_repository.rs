@@ -1,11 +1,11 @@
-use domain::{DomainError, TodoRepository, TodoStatus, UserRepository};
+use domain::{DomainError, TodoFilter, TodoRepository, TodoStatus, UserRepository};
 use sqlx::PgPool;
 use uuid::Uuid;
 
 /// Helper to create a user for todo tests (todos require a valid user_id)
 async fn create_test_user(pool: &PgPool, email: &str) -> Result<Uuid, DomainError> {
     let mut tx = pool.begin().await?;
-    let user = UserRepository::create(&mut *tx, email, "Test User").await?;
+    let user = UserRepository::create(&mut *tx, email, "Test User", "UTC").await?;
     tx.commit().await?;
     Ok(user.id)
 }
@@ -14,7 +14,8 @@ async fn create_test_user(pool: &PgPool, email: &str) -> Result<Uuid, DomainErro
 async fn test_create_todo(pool: PgPool) -> Result<(), DomainError> {
     let user_id = create_test_user(&pool, "todo@example.com").await?;
 
-    let todo = TodoRepository::create(&pool, user_id, "My Task", Some("A description")).await?;
+    let todo =
+        TodoRepository::create(&pool, user_id, "My Task", Some("A description"), None).await?;
 
     assert_eq!(todo.user_id, user_id);
     assert_eq!(todo.title, "My Task");
@@ -27,7 +28,7 @@ async fn test_create_todo(pool: PgPool) -> Result<(), DomainError> {
 async fn test_create_todo_without_description(pool: PgPool) -> Result<(), DomainError> {
     let user_id = create_test_user(&pool, "todo2@example.com").await?;
 
-    let todo = TodoRepository::create(&pool, user_id, "Simple Task", None).await?;
+    let todo = TodoRepository::create(&pool, user_id, "Simple Task", None, None).await?;
 
     assert_eq!(todo.title, "Simple Task");
     assert!(todo.description.is_none());
@@ -38,16 +39,16 @@ async fn test_create_todo_without_description(pool: PgPool) -> Result<(), Domain
 async fn test_create_todo_invalid_user_fails(pool: PgPool) -> Result<(), DomainError> {
     let fake_user_id = Uuid::new_v4();
 
-    let result = TodoRepository::create(&pool, fake_user_id, "Task", None).await;
+    let result = TodoRepository::create(&pool, fake_user_id, "Task", None, None).await;
 
-    assert!(result.is_err());
+    assert!(matches!(result, Err(DomainError::Conflict(_))));
     Ok(())
 }
 
 #[sqlx::test(migrations = "../../migrations")]
 async fn test_find_by_id(pool: PgPool) -> Result<(), DomainError> {
     let user_id = create_test_user(&pool, "find-todo@example.com").await?;
-    let created = TodoRepository::create(&pool, user_id, "Find Me", None).await?;
+    let created = TodoRepository::create(&pool, user_id, "Find Me", None, None).await?;
 
     let found = TodoRepository::find_by_id(&pool, created.id).await?;
 
@@ -70,11 +71,11 @@ async fn test_list_by_user(pool: PgPool) -> Result<(), DomainError> {
     let user_id = create_test_user(&pool, "list-todos@example.com").await?;
 
     // Create multiple todos
-    TodoRepository::create(&pool, user_id, "Task 1", None).await?;
-    TodoRepository::create(&pool, user_id, "Task 2", None).await?;
-    TodoRepository::create(&pool, user_id, "Task 3", None).await?;
+    TodoRepository::create(&pool, user_id, "Task 1", None, None).await?;
+    TodoRepository::create(&pool, user_id, "Task 2", None, None).await?;
+    TodoRepository::create(&pool, user_id, "Task 3", None, None).await?;
 
-    let todos = TodoRepository::list_by_user(&pool, user_id).await?;
+    let todos = TodoRepository::list_by_user(&pool, user_id, false).await?;
 
     assert_eq!(todos.len(), 3);
     // Should be ordered by created_at DESC
@@ -88,7 +89,7 @@ async fn test_list_by_user(pool: PgPool) -> Result<(), DomainError> {
 async fn test_list_by_user_empty(pool: PgPool) -> Result<(), DomainError> {
     let user_id = create_test_user(&pool, "empty-todos@example.com").await?;
 
-    let todos = TodoRepository::list_by_user(&pool, user_id).await?;
+    let todos = TodoRepository::list_by_user(&pool, user_id, false).await?;
 
     assert!(todos.is_empty());
     Ok(())
@@ -99,11 +100,11 @@ async fn test_list_by_user_isolates_users(pool: PgPool) -> Result<(), DomainErro
     let user1 = create_test_user(&pool, "user1-todos@example.com").await?;
     let user2 = create_test_user(&pool, "user2-todos@example.com").await?;
 
-    TodoRepository::create(&pool, user1, "User 1 Task", None).await?;
-    TodoRepository::create(&pool, user2, "User 2 Task", None).await?;
+    TodoRepository::create(&pool, user1, "User 1 Task", None, None).await?;
+    TodoRepository::create(&pool, user2, "User 2 Task", None, None).await?;
 
-    let user1_todos = TodoRepository::list_by_user(&pool, user1).await?;
-    let user2_todos = TodoRepository::list_by_user(&pool, user2).await?;
+    let user1_todos = TodoRepository::list_by_user(&pool, user1, false).await?;
+    let user2_todos = TodoRepository::list_by_user(&pool, user2, false).await?;
 
     assert_eq!(user1_todos.len(), 1);
     assert_eq!(user1_todos[0].title, "User 1 Task");
@@ -117,12 +118,12 @@ async fn test_list_by_user_and_status(pool: PgPool) -> Result<(), DomainError> {
     let user_id = create_test_user(&pool, "status-filter@example.com").await?;
 
     // Create todos and update some statuses
-    let todo1 = TodoRepository::create(&pool, user_id, "Pending Task", None).await?;
-    let todo2 = TodoRepository::create(&pool, user_id, "In Progress Task", None).await?;
-    let todo3 = TodoRepository::create(&pool, user_id, "Completed Task", None).await?;
+    let todo1 = TodoRepository::create(&pool, user_id, "Pending Task", None, None).await?;
+    let todo2 = TodoRepository::create(&pool, user_id, "In Progress Task", None, None).await?;
+    let todo3 = TodoRepository::create(&pool, user_id, "Completed Task", None, None).await?;
 
-    TodoRepository::update_status(&pool, todo2.id, TodoStatus::InProgress).await?;
-    TodoRepository::update_status(&pool, todo3.id, TodoStatus::Completed).await?;
+    TodoRepository::update_status(&pool, todo2.id, TodoStatus::InProgress, None).await?;
+    TodoRepository::update_status(&pool, todo3.id, TodoStatus::Completed, None).await?;
 
     // Filter by status
     let pending =
@@ -141,22 +142,149 @@ async fn test_list_by_user_and_status(pool: PgPool) -> Result<(), DomainError> {
     Ok(())
 }
 
+#[sqlx::test(migrations = "../../migrations")]
+async fn test_list_by_user_breaks_timestamp_ties_by_id(pool: PgPool) -> Result<(), DomainError> {
+    let user_id = create_test_user(&pool, "tie-break@example.com").await?;
+    let mut ids = Vec::new();
+    for title in ["A", "B", "C", "D"] {
+        let todo = TodoRepository::create(&pool, user_id, title, None, None).await?;
+        ids.push(todo.id);
+    }
+
+    // Give every todo the same creation time
+    sqlx::query("UPDATE todos SET created_at = '2025-01-01T00:00:00Z' WHERE user_id = $1")
+        .bind(user_id)
+        .execute(&pool)
+        .await?;
+
+    // Ties are ordered by ID, descending, on every read
+    ids.sort_unstable_by(|a, b| b.cmp(a));
+    for _ in 0..3 {
+        let listed = TodoRepository::list_by_user(&pool, user_id, false).await?;
+        let listed: Vec<Uuid> = listed.iter().map(|t| t.id).collect();
+        assert_eq!(listed, ids);
+
+        let pending =
+            TodoRepository::list_by_user_and_status(&pool, user_id, TodoStatus::Pending).await?;
+        let pending: Vec<Uuid> = pending.iter().map(|t| t.id).collect();
+        assert_eq!(pending, ids);
+    }
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../migrations")]
+async fn test_count_by_status(pool: PgPool) -> Result<(), DomainError> {
+    let user_id = create_test_user(&pool, "count-status@example.com").await?;
+
+    let todo1 = TodoRepository::create(&pool, user_id, "Done", None, None).await?;
+    TodoRepository::create(&pool, user_id, "Waiting", None, None).await?;
+    TodoRepository::create(&pool, user_id, "Also Waiting", None, None).await?;
+    TodoRepository::update_status(&pool, todo1.id, TodoStatus::Completed, None).await?;
+
+    let mut counts = TodoRepository::count_by_status(&pool, user_id).await?;
+    counts.sort_by_key(|(_, count)| *count);
+
+    assert_eq!(
+        counts,
+        vec![(TodoStatus::Completed, 1), (TodoStatus::Pending, 2)]
+    );
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../migrations")]
+async fn test_find_combines_statuses_and_query(pool: PgPool) -> Result<(), DomainError> {
+    let user_id = create_test_user(&pool, "search-filter@example.com").await?;
+
+    let groceries = TodoRepository::create(&pool, user_id, "Buy groceries", None, None).await?;
+    let report =
+        TodoRepository::create(&pool, user_id, "Write report", Some("Buy paper"), None).await?;
+    let shoes = TodoRepository::create(&pool, user_id, "Buy shoes", None, None).await?;
+    TodoRepository::update_status(&pool, report.id, TodoStatus::InProgress, None).await?;
+    TodoRepository::update_status(&pool, shoes.id, TodoStatus::Completed, None).await?;
+
+    // The search term alone matches titles and descriptions
+    let mut filter = TodoFilter {
+        query: Some("buy".to_string()),
+        ..Default::default()
+    };
+    let by_query = TodoRepository::find(&pool, user_id, &filter, 0, 10).await?;
+    assert_eq!(by_query.len(), 3);
+
+    // Combined with a status filter, the result narrows
+    filter.statuses = vec![TodoStatus::Pending, TodoStatus::InProgress];
+    let combined = TodoRepository::find(&pool, user_id, &filter, 0, 10).await?;
+    assert_eq!(combined.len(), 2);
+    assert!(combined.iter().any(|t| t.id == groceries.id));
+    assert!(combined.iter().any(|t| t.id == report.id));
+
+    let filter = TodoFilter {
+        statuses: vec![TodoStatus::Pending],
+        query: Some("groceries".to_string()),
+        ..Default::default()
+    };
+    let narrowed = TodoRepository::find(&pool, user_id, &filter, 0, 10).await?;
+    assert_eq!(narrowed.len(), 1);
+    assert_eq!(narrowed[0].id, groceries.id);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../migrations")]
+async fn test_find_pages(pool: PgPool) -> Result<(), DomainError> {
+    let user_id = create_test_user(&pool, "search-pages@example.com").await?;
+
+    TodoRepository::create(&pool, user_id, "Task 1", None, None).await?;
+    TodoRepository::create(&pool, user_id, "Task 2", None, None).await?;
+    TodoRepository::create(&pool, user_id, "Task 3", None, None).await?;
+
+    let filter = TodoFilter::default();
+    let first = TodoRepository::find(&pool, user_id, &filter, 0, 2).await?;
+    let second = TodoRepository::find(&pool, user_id, &filter, 2, 2).await?;
+
+    // Ordered by created_at DESC, like the unfiltered list
+    assert_eq!(first.len(), 2);
+    assert_eq!(first[0].title, "Task 3");
+    assert_eq!(first[1].title, "Task 2");
+    assert_eq!(second.len(), 1);
+    assert_eq!(second[0].title, "Task 1");
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../migrations")]
+async fn test_find_treats_wildcards_literally(pool: PgPool) -> Result<(), DomainError> {
+    let user_id = create_test_user(&pool, "search-wildcard@example.com").await?;
+
+    TodoRepository::create(&pool, user_id, "Raise prices 10%", None, None).await?;
+    TodoRepository::create(&pool, user_id, "Raise prices 100", None, None).await?;
+
+    let filter = TodoFilter {
+        query: Some("10%".to_string()),
+        ..Default::default()
+    };
+    let found = TodoRepository::find(&pool, user_id, &filter, 0, 10).await?;
+
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].title, "Raise prices 10%");
+    Ok(())
+}
+
 #[sqlx::test(migrations = "../../migrations")]
 async fn test_update_status(pool: PgPool) -> Result<(), DomainError> {
     let user_id = create_test_user(&pool, "update-status@example.com").await?;
-    let created = TodoRepository::create(&pool, user_id, "Status Task", None).await?;
+    let created = TodoRepository::create(&pool, user_id, "Status Task", None, None).await?;
 
     assert_eq!(created.status, TodoStatus::Pending);
 
     // Update to InProgress
-    let updated = TodoRepository::update_status(&pool, created.id, TodoStatus::InProgress).await?;
+    let updated =
+        TodoRepository::update_status(&pool, created.id, TodoStatus::InProgress, None).await?;
     assert!(updated.is_some());
     let updated = updated.unwrap();
     assert_eq!(updated.status, TodoStatus::InProgress);
     assert!(updated.updated_at > created.updated_at);
 
     // Update to Completed
-    let completed = TodoRepository::update_status(&pool, created.id, TodoStatus::Completed).await?;
+    let completed =
+        TodoRepository::update_status(&pool, created.id, TodoStatus::Completed, None).await?;
     assert!(completed.is_some());
     assert_eq!(completed.unwrap().status, TodoStatus::Completed);
     Ok(())
@@ -165,20 +293,67 @@ async fn test_update_status(pool: PgPool) -> Result<(), DomainError> {
 #[sqlx::test(migrations = "../../migrations")]
 async fn test_update_status_not_found(pool: PgPool) -> Result<(), DomainError> {
     let updated =
-        TodoRepository::update_status(&pool, Uuid::new_v4(), TodoStatus::Completed).await?;
+        TodoRepository::update_status(&pool, Uuid::new_v4(), TodoStatus::Completed, None).await?;
     assert!(updated.is_none());
     Ok(())
 }
 
+#[sqlx::test(migrations = "../../migrations")]
+async fn test_update_status_with_stale_timestamp(pool: PgPool) -> Result<(), DomainError> {
+    let user_id = create_test_user(&pool, "stale-status@example.com").await?;
+    let created = TodoRepository::create(&pool, user_id, "Contested", None, None).await?;
+
+    // Someone else changes the todo first
+    TodoRepository::update_status(&pool, created.id, TodoStatus::InProgress, None).await?;
+
+    // So a write expecting the original timestamp matches nothing
+    let stale = TodoRepository::update_status(
+        &pool,
+        created.id,
+        TodoStatus::Cancelled,
+        Some(created.updated_at),
+    )
+    .await?;
+    assert!(stale.is_none());
+
+    let current = TodoRepository::find_by_id(&pool, created.id)
+        .await?
+        .unwrap();
+    assert_eq!(current.status, TodoStatus::InProgress);
+
+    // While one expecting the current timestamp goes through
+    let fresh = TodoRepository::update_content(
+        &pool,
+        created.id,
+        "Settled",
+        None,
+        Some(current.updated_at),
+    )
+    .await?;
+    assert_eq!(fresh.unwrap().title, "Settled");
+    Ok(())
+}
+
 #[sqlx::test(migrations = "../../migrations")]
 async fn test_update_content(pool: PgPool) -> Result<(), DomainError> {
     let user_id = create_test_user(&pool, "update-content@example.com").await?;
-    let created =
-        TodoRepository::create(&pool, user_id, "Original Title", Some("Original desc")).await?;
-
-    let updated =
-        TodoRepository::update_content(&pool, created.id, "New Title", Some("New description"))
-            .await?;
+    let created = TodoRepository::create(
+        &pool,
+        user_id,
+        "Original Title",
+        Some("Original desc"),
+        None,
+    )
+    .await?;
+
+    let updated = TodoRepository::update_content(
+        &pool,
+        created.id,
+        "New Title",
+        Some("New description"),
+        None,
+    )
+    .await?;
 
     assert!(updated.is_some());
     let updated = updated.unwrap();
@@ -192,9 +367,11 @@ async fn test_update_content(pool: PgPool) -> Result<(), DomainError> {
 async fn test_update_content_remove_description(pool: PgPool) -> Result<(), DomainError> {
     let user_id = create_test_user(&pool, "remove-desc@example.com").await?;
     let created =
-        TodoRepository::create(&pool, user_id, "Has Description", Some("Description")).await?;
+        TodoRepository::create(&pool, user_id, "Has Description", Some("Description"), None)
+            .await?;
 
-    let updated = TodoRepository::update_content(&pool, created.id, "No Description", None).await?;
+    let updated =
+        TodoRepository::update_content(&pool, created.id, "No Description", None, None).await?;
 
     assert!(updated.is_some());
     let updated = updated.unwrap();
@@ -204,7 +381,8 @@ async fn test_update_content_remove_description(pool: PgPool) -> Result<(), Doma
 
 #[sqlx::test(migrations = "../../migrations")]
 async fn test_update_content_not_found(pool: PgPool) -> Result<(), DomainError> {
-    let updated = TodoRepository::update_content(&pool, Uuid::new_v4(), "Title", None).await?;
+    let updated =
+        TodoRepository::update_content(&pool, Uuid::new_v4(), "Title", None, None).await?;
     assert!(updated.is_none());
     Ok(())
 }
@@ -212,7 +390,7 @@ async fn test_update_content_not_found(pool: PgPool) -> Result<(), DomainError>
 #[sqlx::test(migrations = "../../migrations")]
 async fn test_delete_todo(pool: PgPool) -> Result<(), DomainError> {
     let user_id = create_test_user(&pool, "delete-todo@example.com").await?;
-    let created = TodoRepository::create(&pool, user_id, "Delete Me", None).await?;
+    let created = TodoRepository::create(&pool, user_id, "Delete Me", None, None).await?;
 
     let deleted = TodoRepository::delete(&pool, created.id).await?;
     assert!(deleted);
@@ -229,3 +407,63 @@ async fn test_delete_todo_not_found(pool: PgPool) -> Result<(), DomainError> {
     assert!(!deleted);
     Ok(())
 }
+
+#[sqlx::test(migrations = "../../migrations")]
+async fn test_delete_by_user(pool: PgPool) -> Result<(), DomainError> {
+    let user_id = create_test_user(&pool, "delete-by-user@example.com").await?;
+    let other_id = create_test_user(&pool, "delete-by-user-other@example.com").await?;
+    TodoRepository::create(&pool, user_id, "Mine 1", None, None).await?;
+    TodoRepository::create(&pool, user_id, "Mine 2", None, None).await?;
+    TodoRepository::create(&pool, other_id, "Theirs", None, None).await?;
+
+    let removed = TodoRepository::delete_by_user(&pool, user_id).await?;
+
+    assert_eq!(removed, 2);
+    let remaining = TodoRepository::list_by_user(&pool, user_id, false).await?;
+    assert!(remaining.is_empty());
+    // Other users' todos are untouched
+    let others = TodoRepository::list_by_user(&pool, other_id, false).await?;
+    assert_eq!(others.len(), 1);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../migrations")]
+async fn test_create_todo_in_rolled_back_transaction(pool: PgPool) -> Result<(), DomainError> {
+    let user_id = create_test_user(&pool, "tx-todo@example.com").await?;
+
+    let mut tx = pool.begin().await?;
+    let todo = TodoRepository::create(&mut *tx, user_id, "Transient", None, None).await?;
+
+    // Visible inside the transaction
+    let found = TodoRepository::find_by_id(&mut *tx, todo.id).await?;
+    assert!(found.is_some());
+
+    tx.rollback().await?;
+
+    // Gone once the transaction is rolled back
+    let found = TodoRepository::find_by_id(&pool, todo.id).await?;
+    assert!(found.is_none());
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../migrations")]
+async fn test_unknown_status_fails_the_read(pool: PgPool) -> Result<(), DomainError> {
+    let user_id = create_test_user(&pool, "corrupt-status@example.com").await?;
+    let todo = TodoRepository::create(&pool, user_id, "Corrupt", None, None).await?;
+
+    // Simulate a bad row that got past the CHECK constraint
+    sqlx::query("ALTER TABLE todos DROP CONSTRAINT todos_status_check")
+        .execute(&pool)
+        .await?;
+    sqlx::query("UPDATE todos SET status = 'bogus' WHERE id = $1")
+        .bind(todo.id)
+        .execute(&pool)
+        .await?;
+
+    let result = TodoRepository::find_by_id(&pool, todo.id).await;
+    assert!(matches!(result, Err(DomainError::Validation(_))));
+
+    let result = TodoRepository::list_by_user(&pool, user_id).await;
+    assert!(matches!(result, Err(DomainError::Validation(_))));
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use domain::{DomainError, UserRepository};
+use domain::{DomainError, UserRepository, UserRole, normalize_email};
 use sqlx::PgPool;
 use uuid::Uuid;
 
@@ -6,7 +6,7 @@ use uuid::Uuid;
 async fn test_create_user(pool: PgPool) -> Result<(), DomainError> {
     let mut tx = pool.begin().await?;
 
-    let user = UserRepository::create(&mut *tx, "test@example.com", "Test User").await?;
+    let user = UserRepository::create(&mut *tx, "test@example.com", "Test User", "UTC").await?;
 
     assert_eq!(user.email, "test@example.com");
     assert_eq!(user.name, "Test User");
@@ -21,13 +21,13 @@ async fn test_create_user_duplicate_email_fails(pool: PgPool) -> Result<(), Doma
     let mut tx = pool.begin().await?;
     
     // Create first user
-    UserRepository::create(&mut *tx, "duplicate@example.com", "First").await?;
+    UserRepository::create(&mut *tx, "duplicate@example.com", "First", "UTC").await?;
     
     // Try to create second user with same email in same transaction (should fail)
     // Note: This poisons the transaction, which is fine as we end the test.
-    let result = UserRepository::create(&mut *tx, "duplicate@example.com", "Second").await;
+    let result = UserRepository::create(&mut *tx, "duplicate@example.com", "Second", "UTC").await;
 
-    assert!(result.is_err());
+    assert!(matches!(result, Err(DomainError::Conflict(_))));
     Ok(())
 }
 
@@ -36,7 +36,7 @@ async fn test_find_by_id(pool: PgPool) -> Result<(), DomainError> {
     let mut tx = pool.begin().await?;
     
     // Create a user
-    let created = UserRepository::create(&mut *tx, "find@example.com", "Find Me").await?;
+    let created = UserRepository::create(&mut *tx, "find@example.com", "Find Me", "UTC").await?;
 
     // Find by ID using SAME transaction
     let found = UserRepository::find_by_id(&mut *tx, created.id).await?;
@@ -63,7 +63,7 @@ async fn test_find_by_email(pool: PgPool) -> Result<(), DomainError> {
     let mut tx = pool.begin().await?;
     
     // Create a user
-    let created = UserRepository::create(&mut *tx, "email@example.com", "Email User").await?;
+    let created = UserRepository::create(&mut *tx, "email@example.com", "Email User", "UTC").await?;
 
     // Find by email using SAME transaction
     let found = UserRepository::find_by_email(&mut *tx, "email@example.com").await?;
@@ -77,6 +77,33 @@ async fn test_find_by_email(pool: PgPool) -> Result<(), DomainError> {
     Ok(())
 }
 
+#[test]
+fn test_normalize_email_trims_and_lowercases() {
+    assert_eq!(normalize_email(" Foo@Example.COM\n"), "foo@example.com");
+}
+
+#[test]
+fn test_normalize_email_keeps_normalized_email() {
+    assert_eq!(normalize_email("foo@example.com"), "foo@example.com");
+}
+
+#[sqlx::test(migrations = "../../migrations")]
+async fn test_email_is_stored_and_found_normalized(pool: PgPool) -> Result<(), DomainError> {
+    let mut tx = pool.begin().await?;
+
+    let created = UserRepository::create(&mut *tx, " Foo@Example.com ", "Foo", "UTC").await?;
+    assert_eq!(created.email, "foo@example.com");
+
+    // Either spelling finds the same user
+    for email in ["foo@example.com", " Foo@Example.com "] {
+        let found = UserRepository::find_by_email(&mut *tx, email).await?;
+        assert_eq!(found.map(|u| u.id), Some(created.id));
+    }
+
+    tx.rollback().await?;
+    Ok(())
+}
+
 #[sqlx::test(migrations = "../../migrations")]
 async fn test_find_by_email_not_found(pool: PgPool) -> Result<(), DomainError> {
     let mut tx = pool.begin().await?;
@@ -90,9 +117,9 @@ async fn test_list_users(pool: PgPool) -> Result<(), DomainError> {
     let mut tx = pool.begin().await?;
     
     // Create multiple users in same transaction
-    UserRepository::create(&mut *tx, "user1@example.com", "User 1").await?;
-    UserRepository::create(&mut *tx, "user2@example.com", "User 2").await?;
-    UserRepository::create(&mut *tx, "user3@example.com", "User 3").await?;
+    UserRepository::create(&mut *tx, "user1@example.com", "User 1", "UTC").await?;
+    UserRepository::create(&mut *tx, "user2@example.com", "User 2", "UTC").await?;
+    UserRepository::create(&mut *tx, "user3@example.com", "User 3", "UTC").await?;
 
     // List all using SAME transaction
     let users = UserRepository::list(&mut *tx).await?;
@@ -107,6 +134,28 @@ async fn test_list_users(pool: PgPool) -> Result<(), DomainError> {
     Ok(())
 }
 
+#[sqlx::test(migrations = "../../migrations")]
+async fn test_list_users_breaks_timestamp_ties_by_id(pool: PgPool) -> Result<(), DomainError> {
+    let mut ids = Vec::new();
+    for email in ["tie1@example.com", "tie2@example.com", "tie3@example.com"] {
+        ids.push(UserRepository::create(&pool, email, "Tie", "UTC").await?.id);
+    }
+
+    // Give every user the same registration time
+    sqlx::query("UPDATE users SET created_at = '2025-01-01T00:00:00Z'")
+        .execute(&pool)
+        .await?;
+
+    // Ties are ordered by ID, descending, on every read
+    ids.sort_unstable_by(|a, b| b.cmp(a));
+    for _ in 0..3 {
+        let users = UserRepository::list(&pool).await?;
+        let listed: Vec<Uuid> = users.iter().map(|u| u.id).collect();
+        assert_eq!(listed, ids);
+    }
+    Ok(())
+}
+
 #[sqlx::test(migrations = "../../migrations")]
 async fn test_list_users_empty(pool: PgPool) -> Result<(), DomainError> {
     let mut tx = pool.begin().await?;
@@ -120,7 +169,7 @@ async fn test_update_name(pool: PgPool) -> Result<(), DomainError> {
     let mut tx = pool.begin().await?;
     
     // Create a user
-    let created = UserRepository::create(&mut *tx, "update@example.com", "Original Name").await?;
+    let created = UserRepository::create(&mut *tx, "update@example.com", "Original Name", "UTC").await?;
 
     // Update the name using SAME transaction
     let updated = UserRepository::update_name(&mut *tx, created.id, "New Name").await?;
@@ -144,12 +193,39 @@ async fn test_update_name_not_found(pool: PgPool) -> Result<(), DomainError> {
     Ok(())
 }
 
+#[sqlx::test(migrations = "../../migrations")]
+async fn test_set_role(pool: PgPool) -> Result<(), DomainError> {
+    let mut tx = pool.begin().await?;
+
+    // New users get the least privileged role
+    let created = UserRepository::create(&mut *tx, "role@example.com", "Role", "UTC").await?;
+    assert_eq!(created.role, UserRole::User);
+
+    let updated = UserRepository::set_role(&mut *tx, created.id, UserRole::Admin)
+        .await?
+        .unwrap();
+    assert_eq!(updated.role, UserRole::Admin);
+
+    let found = UserRepository::find_by_id(&mut *tx, created.id).await?.unwrap();
+    assert_eq!(found.role, UserRole::Admin);
+
+    tx.rollback().await?;
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../migrations")]
+async fn test_set_role_not_found(pool: PgPool) -> Result<(), DomainError> {
+    let updated = UserRepository::set_role(&pool, Uuid::new_v4(), UserRole::Admin).await?;
+    assert!(updated.is_none());
+    Ok(())
+}
+
 #[sqlx::test(migrations = "../../migrations")]
 async fn test_delete_user(pool: PgPool) -> Result<(), DomainError> {
     let mut tx = pool.begin().await?;
     
     // Create a user
-    let created = UserRepository::create(&mut *tx, "delete@example.com", "Delete Me").await?;
+    let created = UserRepository::create(&mut *tx, "delete@example.com", "Delete Me", "UTC").await?;
 
     // Delete the user using SAME transaction
     let deleted = UserRepository::delete(&mut *tx, created.id).await?;
@@ -169,4 +245,79 @@ async fn test_delete_user_not_found(pool: PgPool) -> Result<(), DomainError> {
     let deleted = UserRepository::delete(&mut *tx, Uuid::new_v4()).await?;
     assert!(!deleted);
     Ok(())
-}
\ No newline at end of file
+}
+
+#[sqlx::test(migrations = "../../migrations")]
+async fn test_deleted_user_hidden_from_normal_queries(pool: PgPool) -> Result<(), DomainError> {
+    let mut tx = pool.begin().await?;
+
+    let created = UserRepository::create(&mut *tx, "soft@example.com", "Soft Delete", "UTC").await?;
+    UserRepository::delete(&mut *tx, created.id).await?;
+
+    // Normal lookups no longer see the user
+    assert!(UserRepository::find_by_id(&mut *tx, created.id).await?.is_none());
+    assert!(
+        UserRepository::find_by_email(&mut *tx, "soft@example.com")
+            .await?
+            .is_none()
+    );
+    assert!(UserRepository::list(&mut *tx).await?.is_empty());
+
+    // But the row is still there for admin use
+    let found = UserRepository::find_by_id_including_deleted(&mut *tx, created.id).await?;
+    assert!(found.is_some());
+    let found = found.unwrap();
+    assert_eq!(found.email, "soft@example.com");
+    assert!(found.deleted_at.is_some());
+
+    tx.rollback().await?;
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../migrations")]
+async fn test_delete_user_twice_returns_false(pool: PgPool) -> Result<(), DomainError> {
+    let mut tx = pool.begin().await?;
+
+    let created = UserRepository::create(&mut *tx, "twice@example.com", "Twice", "UTC").await?;
+
+    assert!(UserRepository::delete(&mut *tx, created.id).await?);
+    assert!(!UserRepository::delete(&mut *tx, created.id).await?);
+
+    tx.rollback().await?;
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../migrations")]
+async fn test_deleted_user_email_can_be_reused(pool: PgPool) -> Result<(), DomainError> {
+    let mut tx = pool.begin().await?;
+
+    let original = UserRepository::create(&mut *tx, "reuse@example.com", "Original", "UTC").await?;
+    UserRepository::delete(&mut *tx, original.id).await?;
+
+    let replacement = UserRepository::create(&mut *tx, "reuse@example.com", "Replacement", "UTC").await?;
+
+    let found = UserRepository::find_by_email(&mut *tx, "reuse@example.com").await?;
+    assert_eq!(found.unwrap().id, replacement.id);
+
+    tx.rollback().await?;
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../migrations")]
+async fn test_find_by_ids(pool: PgPool) -> Result<(), DomainError> {
+    let mut tx = pool.begin().await?;
+
+    let alice = UserRepository::create(&mut *tx, "ids-alice@example.com", "Alice", "UTC").await?;
+    let bob = UserRepository::create(&mut *tx, "ids-bob@example.com", "Bob", "UTC").await?;
+    UserRepository::create(&mut *tx, "ids-carol@example.com", "Carol", "UTC").await?;
+
+    let found = UserRepository::find_by_ids(&mut *tx, &[alice.id, bob.id, Uuid::new_v4()]).await?;
+
+    // Only the requested users that exist are returned
+    assert_eq!(found.len(), 2);
+    assert!(found.iter().any(|u| u.id == alice.id));
+    assert!(found.iter().any(|u| u.id == bob.id));
+
+    tx.rollback().await?;
+    Ok(())
+}
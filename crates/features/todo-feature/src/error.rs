@@ -1,3 +1,4 @@
+use domain::Transient;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -8,6 +9,39 @@ pub enum TodoFeatureError {
     #[error("Todo not found: {0}")]
     NotFound(uuid::Uuid),
 
+    #[error("Todo item not found: {0}")]
+    ItemNotFound(uuid::Uuid),
+
     #[error("User not found: {0}")]
     UserNotFound(uuid::Uuid),
+
+    #[error("Not allowed to access todo: {0}")]
+    Forbidden(uuid::Uuid),
+
+    #[error("Invalid tag: {0:?}")]
+    InvalidTag(String),
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Todo was changed since it was read: {0}")]
+    Conflict(uuid::Uuid),
+
+    #[error("Queue error: {0}")]
+    Queue(String),
+
+    #[error("Invalid status transition from {} to {}", .from.as_str(), .to.as_str())]
+    InvalidTransition {
+        from: domain::TodoStatus,
+        to: domain::TodoStatus,
+    },
+}
+
+impl Transient for TodoFeatureError {
+    fn is_transient(&self) -> bool {
+        match self {
+            TodoFeatureError::Domain(e) => e.is_transient(),
+            _ => false,
+        }
+    }
 }
@@ -0,0 +1,96 @@
+use std::time::Duration;
+
+use domain::{Todo, TodoRepository};
+use sqlx::PgPool;
+use sqlx::postgres::PgListener;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tracing::{error, warn};
+use uuid::Uuid;
+
+use crate::error::TodoFeatureError;
+
+/// Postgres channel that todo status changes are published on
+pub const TODO_CHANGES_CHANNEL: &str = "todo_changes";
+
+/// How many unread events a slow subscriber may fall behind by
+const EVENT_BUFFER: usize = 256;
+
+/// How long the listener waits after an error before receiving again
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Fan-out of todo status changes to in-process subscribers
+///
+/// Changes are published by a database trigger via `NOTIFY`, so every status
+/// update is seen regardless of which server or code path made it.
+#[derive(Clone)]
+pub struct TodoEvents {
+    sender: broadcast::Sender<Todo>,
+}
+
+impl TodoEvents {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(EVENT_BUFFER);
+        Self { sender }
+    }
+
+    /// Receive every todo whose status changes from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<Todo> {
+        self.sender.subscribe()
+    }
+
+    /// Start a background task that `LISTEN`s for changes and broadcasts them
+    ///
+    /// The listener is connected before this returns, so no change committed
+    /// afterwards is missed.
+    pub async fn listen(&self, pool: &PgPool) -> Result<JoinHandle<()>, TodoFeatureError> {
+        let mut listener = PgListener::connect_with(pool)
+            .await
+            .map_err(domain::DomainError::from)?;
+        listener
+            .listen(TODO_CHANGES_CHANNEL)
+            .await
+            .map_err(domain::DomainError::from)?;
+
+        let pool = pool.clone();
+        let sender = self.sender.clone();
+
+        Ok(tokio::spawn(async move {
+            loop {
+                let notification = match listener.recv().await {
+                    Ok(notification) => notification,
+                    // The listener reconnects on the next `recv`; changes made
+                    // while it was down are lost
+                    Err(e) => {
+                        error!("Todo change listener error: {}", e);
+                        tokio::time::sleep(RETRY_DELAY).await;
+                        continue;
+                    }
+                };
+
+                let Ok(id) = notification.payload().parse::<Uuid>() else {
+                    warn!(
+                        payload = notification.payload(),
+                        "Ignoring malformed todo change"
+                    );
+                    continue;
+                };
+
+                match TodoRepository::find_by_id(&pool, id).await {
+                    // Sending only fails when nobody is subscribed, which is fine
+                    Ok(Some(todo)) => {
+                        let _ = sender.send(todo);
+                    }
+                    Ok(None) => {}
+                    Err(e) => error!(todo_id = %id, "Failed to load changed todo: {}", e),
+                }
+            }
+        }))
+    }
+}
+
+impl Default for TodoEvents {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,151 @@
+use metrics::counter;
+use serde::{Deserialize, Serialize};
+use sqlxmq::{job, CurrentJob, JobRegistry};
+use time::OffsetDateTime;
+use tracing::info;
+use uuid::Uuid;
+
+/// How long before a todo's due date its reminder is sent
+pub const REMINDER_LEAD: time::Duration = time::Duration::hours(1);
+
+/// Arguments for the due date reminder job
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DueReminderArgs {
+    pub todo_id: Uuid,
+    pub user_id: Uuid,
+    pub title: String,
+    pub due_date: OffsetDateTime,
+}
+
+/// Remind a user that one of their todos is nearly due
+#[job(channel_name = "reminders")]
+pub async fn send_due_reminder(
+    mut current_job: CurrentJob,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    // Extract arguments from the job payload
+    let args: DueReminderArgs = current_job.json()?.expect("job arguments");
+
+    info!(
+        todo_id = %args.todo_id,
+        user_id = %args.user_id,
+        title = %args.title,
+        due_date = %args.due_date,
+        "Sending due date reminder"
+    );
+
+    // In a real application, you would notify the user here
+    // For example: notifier.send_reminder(args.user_id, args.title).await?;
+
+    current_job.complete().await?;
+    counter!("jobs_total", "job" => "send_due_reminder", "outcome" => "success").increment(1);
+    Ok(())
+}
+
+/// Arguments for the todo created notification job
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TodoCreatedArgs {
+    pub todo_id: Uuid,
+    pub user_id: Uuid,
+    pub title: String,
+}
+
+/// Notify a user that a todo was created for them
+#[job(name = "todo.created", channel_name = "notifications")]
+pub async fn notify_todo_created(
+    mut current_job: CurrentJob,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let args: TodoCreatedArgs = current_job.json()?.expect("job arguments");
+
+    info!(
+        todo_id = %args.todo_id,
+        user_id = %args.user_id,
+        title = %args.title,
+        "Sending todo created notification"
+    );
+
+    current_job.complete().await?;
+    counter!("jobs_total", "job" => "notify_todo_created", "outcome" => "success").increment(1);
+    Ok(())
+}
+
+/// Registry of all todo-related jobs
+pub struct TodoJobs;
+
+impl TodoJobs {
+    /// Create a job registry containing all todo feature jobs
+    pub fn registry() -> JobRegistry {
+        JobRegistry::new(&[send_due_reminder, notify_todo_created])
+    }
+
+    /// Enqueue a todo created notification within a transaction
+    pub async fn enqueue_todo_created(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        todo_id: Uuid,
+        user_id: Uuid,
+        title: String,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let args = TodoCreatedArgs {
+            todo_id,
+            user_id,
+            title,
+        };
+
+        notify_todo_created
+            .builder()
+            .set_json(&args)?
+            .spawn(&mut **tx)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Schedule a due date reminder within a transaction, returning its job ID
+    ///
+    /// The reminder runs `REMINDER_LEAD` before `due_date`, or straight away
+    /// if that moment has already passed.
+    pub async fn enqueue_due_reminder(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        todo_id: Uuid,
+        user_id: Uuid,
+        title: String,
+        due_date: OffsetDateTime,
+    ) -> Result<Uuid, Box<dyn std::error::Error + Send + Sync>> {
+        // Negative delays (reminder time already passed) become zero, and
+        // Postgres intervals don't take nanoseconds, so round to whole seconds
+        let remind_at = due_date - REMINDER_LEAD;
+        let delay = std::time::Duration::try_from(remind_at - OffsetDateTime::now_utc())
+            .map(|d| std::time::Duration::from_secs(d.as_secs()))
+            .unwrap_or_default();
+
+        let args = DueReminderArgs {
+            todo_id,
+            user_id,
+            title,
+            due_date,
+        };
+
+        let job_id = send_due_reminder
+            .builder()
+            .set_json(&args)?
+            .set_delay(delay)
+            .spawn(&mut **tx)
+            .await?;
+
+        Ok(job_id)
+    }
+
+    /// Remove a scheduled reminder within a transaction
+    ///
+    /// Does nothing if the job has already run.
+    pub async fn cancel_due_reminder(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        job_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("SELECT mq_delete($1)")
+            .bind(vec![job_id])
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -1,5 +1,14 @@
 pub mod error;
+pub mod events;
+pub mod jobs;
+pub mod limits;
 pub mod service;
 
 pub use error::TodoFeatureError;
-pub use service::{CreateTodoInput, UpdateTodoInput, TodoService};
+pub use events::{TODO_CHANGES_CHANNEL, TodoEvents};
+pub use jobs::{notify_todo_created, send_due_reminder, TodoJobs};
+pub use limits::{TodoLimits, TodoLimitsError};
+pub use service::{
+    CreateTodoInput, CreateTodoItem, TodoFilter, TodoPage, TodoService,
+    TodoStatusSummary, UpdateTodoInput,
+};
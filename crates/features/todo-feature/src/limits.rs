@@ -0,0 +1,54 @@
+use std::env;
+use std::sync::OnceLock;
+
+use domain::{LimitsError, parse_limit};
+
+pub type TodoLimitsError = LimitsError;
+
+/// Longest title and description a todo may have, in characters
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TodoLimits {
+    pub title_max: usize,
+    pub description_max: usize,
+}
+
+impl Default for TodoLimits {
+    fn default() -> Self {
+        Self {
+            title_max: 200,
+            description_max: 2000,
+        }
+    }
+}
+
+static LIMITS: OnceLock<TodoLimits> = OnceLock::new();
+
+impl TodoLimits {
+    /// Read the limits from `TODO_TITLE_MAX` and `TODO_DESCRIPTION_MAX`
+    pub fn from_env() -> Result<Self, TodoLimitsError> {
+        Self::from_vars(|name| env::var(name).ok())
+    }
+
+    /// Build the limits from a variable lookup, using defaults for unset ones
+    pub fn from_vars(var: impl Fn(&str) -> Option<String>) -> Result<Self, TodoLimitsError> {
+        let defaults = Self::default();
+
+        Ok(Self {
+            title_max: parse_limit(&var, "TODO_TITLE_MAX")?.unwrap_or(defaults.title_max),
+            description_max: parse_limit(&var, "TODO_DESCRIPTION_MAX")?
+                .unwrap_or(defaults.description_max),
+        })
+    }
+
+    /// Make these the limits `TodoService` enforces
+    ///
+    /// Only the first call has any effect.
+    pub fn install(self) {
+        let _ = LIMITS.set(self);
+    }
+
+    /// The limits in force: the installed ones, or the defaults
+    pub fn current() -> Self {
+        LIMITS.get().copied().unwrap_or_default()
+    }
+}
@@ -1,14 +1,31 @@
-use domain::{Todo, TodoRepository, TodoStatus, UserRepository};
+use std::collections::HashSet;
+
+use domain::{
+    Clock, Cursor, DomainError, EventRepository, Page, TagRepository, Todo, TodoItem,
+    TodoItemRepository, TodoRepository, TodoStatus, UserRepository, retry_on_transient,
+};
+use serde_json::json;
 use sqlx::PgPool;
+use time::OffsetDateTime;
 use uuid::Uuid;
 
 use crate::error::TodoFeatureError;
+use crate::jobs::TodoJobs;
+use crate::limits::TodoLimits;
 
 /// Input for creating a new todo
 pub struct CreateTodoInput {
     pub user_id: Uuid,
     pub title: String,
     pub description: Option<String>,
+    pub due_date: Option<OffsetDateTime>,
+}
+
+/// A single todo in a batch create
+pub struct CreateTodoItem {
+    pub title: String,
+    pub description: Option<String>,
+    pub due_date: Option<OffsetDateTime>,
 }
 
 /// Input for updating a todo
@@ -16,6 +33,36 @@ pub struct UpdateTodoInput {
     pub title: Option<String>,
     pub description: Option<String>,
     pub status: Option<TodoStatus>,
+    /// When set, the update only applies if the todo was last changed at
+    /// exactly this time
+    pub expected_updated_at: Option<OffsetDateTime>,
+}
+
+/// Criteria for finding a user's todos; unset fields don't filter
+#[derive(Default)]
+pub struct TodoFilter {
+    /// Any of these statuses matches; empty means every status
+    pub statuses: Vec<TodoStatus>,
+    pub query: Option<String>,
+    pub tag: Option<String>,
+    pub created_after: Option<OffsetDateTime>,
+    pub created_before: Option<OffsetDateTime>,
+    pub due_before: Option<OffsetDateTime>,
+}
+
+/// A page of todos
+pub struct TodoPage {
+    pub todos: Vec<Todo>,
+    pub has_next_page: bool,
+}
+
+/// How many of a user's todos are in each status
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct TodoStatusSummary {
+    pub pending: i64,
+    pub in_progress: i64,
+    pub completed: i64,
+    pub cancelled: i64,
 }
 
 /// Service for todo-related operations
@@ -23,24 +70,107 @@ pub struct TodoService;
 
 impl TodoService {
     /// Create a new todo for a user
+    ///
+    /// A created notification job is enqueued atomically with the todo
+    /// itself, and so is a reminder job when it has a due date. The title and
+    /// description are trimmed and must fit within `TodoLimits`.
     pub async fn create(pool: &PgPool, input: CreateTodoInput) -> Result<Todo, TodoFeatureError> {
-        // Verify user exists
-        if UserRepository::find_by_id(pool, input.user_id)
-            .await?
-            .is_none()
-        {
-            return Err(TodoFeatureError::UserNotFound(input.user_id));
+        let limits = TodoLimits::current();
+        let title = check_length(&input.title, "title", limits.title_max)?;
+        let description = input
+            .description
+            .as_deref()
+            .map(|d| check_length(d, "description", limits.description_max))
+            .transpose()?;
+
+        let map_conflict = |e| match e {
+            DomainError::Conflict(_) => TodoFeatureError::UserNotFound(input.user_id),
+            e => e.into(),
+        };
+
+        let mut tx = pool.begin().await.map_err(DomainError::from)?;
+
+        // The insert also checks the user exists
+        let todo =
+            TodoRepository::create(&mut *tx, input.user_id, title, description, input.due_date)
+                .await
+                .map_err(map_conflict)?;
+
+        Self::notify_created(&mut tx, &todo).await?;
+        Self::schedule_reminder(&mut tx, &todo).await?;
+
+        tx.commit().await.map_err(DomainError::from)?;
+
+        Ok(todo)
+    }
+
+    /// Create several todos for a user at once
+    ///
+    /// All rows are inserted in a single statement that also checks the user
+    /// exists, so a failure on any item rolls back the whole batch. Each todo
+    /// gets a created notification, and a reminder when it has a due date, in
+    /// the same transaction. An empty batch creates nothing and returns an
+    /// empty list without checking the user.
+    pub async fn create_many(
+        pool: &PgPool,
+        user_id: Uuid,
+        items: Vec<CreateTodoItem>,
+    ) -> Result<Vec<Todo>, TodoFeatureError> {
+        if items.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let limits = TodoLimits::current();
+        let rows = items
+            .iter()
+            .map(|item| {
+                let title = check_length(&item.title, "title", limits.title_max)?;
+                let description = item
+                    .description
+                    .as_deref()
+                    .map(|d| check_length(d, "description", limits.description_max))
+                    .transpose()?;
+                Ok((title, description, item.due_date))
+            })
+            .collect::<Result<Vec<_>, TodoFeatureError>>()?;
+
+        let mut tx = pool.begin().await.map_err(DomainError::from)?;
+
+        // The insert also checks the user exists
+        let todos = TodoRepository::create_many(&mut *tx, user_id, &rows)
+            .await
+            .map_err(|e| match e {
+                DomainError::Conflict(_) => TodoFeatureError::UserNotFound(user_id),
+                e => e.into(),
+            })?;
+        for todo in &todos {
+            Self::notify_created(&mut tx, todo).await?;
+            Self::schedule_reminder(&mut tx, todo).await?;
         }
 
-        let todo = TodoRepository::create(
+        tx.commit().await.map_err(DomainError::from)?;
+
+        Ok(todos)
+    }
+
+    /// Copy a todo's title and description into a new pending todo for the same user
+    ///
+    /// The copy is created like any other todo, so its created notification
+    /// is enqueued too. It gets its own ID and timestamps and no due date, so
+    /// no reminder is scheduled for it.
+    pub async fn duplicate(pool: &PgPool, id: Uuid) -> Result<Todo, TodoFeatureError> {
+        let source = Self::get(pool, id).await?;
+
+        Self::create(
             pool,
-            input.user_id,
-            &input.title,
-            input.description.as_deref(),
+            CreateTodoInput {
+                user_id: source.user_id,
+                title: source.title,
+                description: source.description,
+                due_date: None,
+            },
         )
-        .await?;
-
-        Ok(todo)
+        .await
     }
 
     /// Get a todo by ID
@@ -50,9 +180,117 @@ impl TodoService {
             .ok_or(TodoFeatureError::NotFound(id))
     }
 
-    /// List todos for a user
-    pub async fn list_for_user(pool: &PgPool, user_id: Uuid) -> Result<Vec<Todo>, TodoFeatureError> {
-        Ok(TodoRepository::list_by_user(pool, user_id).await?)
+    /// Get several of a user's todos by ID, in the order the IDs are given
+    ///
+    /// IDs that match none of the user's todos are skipped rather than
+    /// reported, so callers can't learn which IDs exist.
+    pub async fn get_many(
+        pool: &PgPool,
+        user_id: Uuid,
+        ids: &[Uuid],
+    ) -> Result<Vec<Todo>, TodoFeatureError> {
+        let mut todos = TodoRepository::find_by_ids(pool, ids).await?;
+        todos.retain(|todo| todo.user_id == user_id);
+        Ok(todos)
+    }
+
+    /// Get a todo by ID, checking it belongs to `user_id`
+    pub async fn get_owned(
+        pool: &PgPool,
+        id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Todo, TodoFeatureError> {
+        let todo = Self::get(pool, id).await?;
+
+        if todo.user_id != user_id {
+            return Err(TodoFeatureError::Forbidden(id));
+        }
+
+        Ok(todo)
+    }
+
+    /// Get a todo by ID as seen by `user_id`
+    ///
+    /// Unlike `get_owned`, another user's todo is reported as `NotFound`, so
+    /// callers can't learn which IDs exist.
+    pub async fn get_for_user(
+        pool: &PgPool,
+        id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Todo, TodoFeatureError> {
+        TodoRepository::find_by_id(pool, id)
+            .await?
+            .filter(|todo| todo.user_id == user_id)
+            .ok_or(TodoFeatureError::NotFound(id))
+    }
+
+    /// List todos for a user, with deleted ones only if `include_deleted`
+    ///
+    /// Returns `UserNotFound` if there is no such active user, so it can be
+    /// told apart from a user with no todos.
+    pub async fn list_for_user(
+        pool: &PgPool,
+        user_id: Uuid,
+        include_deleted: bool,
+    ) -> Result<Vec<Todo>, TodoFeatureError> {
+        if UserRepository::find_by_id(pool, user_id).await?.is_none() {
+            return Err(TodoFeatureError::UserNotFound(user_id));
+        }
+
+        Ok(TodoRepository::list_by_user(pool, user_id, include_deleted).await?)
+    }
+
+    /// List todos for a user in the order set by `reorder`, with deleted ones
+    /// only if `include_deleted`
+    ///
+    /// Like `list_for_user`, returns `UserNotFound` if there is no such
+    /// active user.
+    pub async fn list_for_user_by_position(
+        pool: &PgPool,
+        user_id: Uuid,
+        include_deleted: bool,
+    ) -> Result<Vec<Todo>, TodoFeatureError> {
+        if UserRepository::find_by_id(pool, user_id).await?.is_none() {
+            return Err(TodoFeatureError::UserNotFound(user_id));
+        }
+
+        Ok(TodoRepository::list_by_user_by_position(pool, user_id, include_deleted).await?)
+    }
+
+    /// Put a user's todos in the order of `ordered_ids`, returning them in
+    /// that order
+    ///
+    /// Every ID must be one of the user's todos, listed once. Todos left out
+    /// keep their position.
+    pub async fn reorder(
+        pool: &PgPool,
+        user_id: Uuid,
+        ordered_ids: Vec<Uuid>,
+    ) -> Result<Vec<Todo>, TodoFeatureError> {
+        let mut seen = HashSet::new();
+        if let Some(id) = ordered_ids.iter().find(|id| !seen.insert(**id)) {
+            return Err(TodoFeatureError::Validation(format!(
+                "todo {id} is listed more than once"
+            )));
+        }
+
+        let mut tx = pool.begin().await.map_err(DomainError::from)?;
+
+        let todos = TodoRepository::find_by_ids(&mut *tx, &ordered_ids).await?;
+        for id in &ordered_ids {
+            match todos.iter().find(|todo| todo.id == *id) {
+                None => return Err(TodoFeatureError::NotFound(*id)),
+                Some(todo) if todo.user_id != user_id => {
+                    return Err(TodoFeatureError::Forbidden(*id));
+                }
+                Some(_) => {}
+            }
+        }
+        TodoRepository::set_positions(&mut *tx, user_id, &ordered_ids).await?;
+
+        tx.commit().await.map_err(DomainError::from)?;
+
+        Ok(todos)
     }
 
     /// List todos for a user filtered by status
@@ -64,56 +302,724 @@ impl TodoService {
         Ok(TodoRepository::list_by_user_and_status(pool, user_id, status).await?)
     }
 
+    /// List a user's todos in one status a page at a time, newest first
+    ///
+    /// `after` is the end cursor of the previous page; a malformed cursor is
+    /// a validation error.
+    pub async fn list_for_user_by_status_paginated(
+        pool: &PgPool,
+        user_id: Uuid,
+        status: TodoStatus,
+        after: Option<&str>,
+        limit: u64,
+    ) -> Result<Page<Todo>, TodoFeatureError> {
+        let after = after.map(Cursor::decode).transpose()?;
+        Ok(TodoRepository::list_by_user_and_status_paginated(
+            pool,
+            user_id,
+            status,
+            after.as_ref(),
+            limit,
+        )
+        .await?)
+    }
+
+    /// List every user's todos a page at a time, newest first
+    ///
+    /// Callers must check the requester is an admin.
+    pub async fn list_all_paginated(
+        pool: &PgPool,
+        after: Option<&str>,
+        limit: u64,
+    ) -> Result<Page<Todo>, TodoFeatureError> {
+        let after = after.map(Cursor::decode).transpose()?;
+        Ok(TodoRepository::list_all_paginated(pool, after.as_ref(), limit).await?)
+    }
+
+    /// List a user's todos carrying `tag`
+    pub async fn list_for_user_by_tag(
+        pool: &PgPool,
+        user_id: Uuid,
+        tag: &str,
+    ) -> Result<Vec<Todo>, TodoFeatureError> {
+        let tag = normalize_tag(tag)?;
+        Ok(TodoRepository::list_by_user_and_tag(pool, user_id, &tag).await?)
+    }
+
+    /// List a user's `limit` most recently updated todos, latest change first
+    pub async fn list_recent(
+        pool: &PgPool,
+        user_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<Todo>, TodoFeatureError> {
+        Ok(TodoRepository::list_recently_updated(pool, user_id, limit).await?)
+    }
+
+    /// List a user's open todos that are past their due date by `clock`,
+    /// soonest first
+    pub async fn list_overdue(
+        pool: &PgPool,
+        clock: &dyn Clock,
+        user_id: Uuid,
+    ) -> Result<Vec<Todo>, TodoFeatureError> {
+        Ok(TodoRepository::list_overdue(pool, user_id, clock.now()).await?)
+    }
+
+    /// List a user's open todos due from `from` up to but not including `to`,
+    /// soonest first
+    pub async fn list_due_between(
+        pool: &PgPool,
+        user_id: Uuid,
+        from: OffsetDateTime,
+        to: OffsetDateTime,
+    ) -> Result<Vec<Todo>, TodoFeatureError> {
+        if to < from {
+            return Err(TodoFeatureError::Validation(
+                "to must not be before from".to_string(),
+            ));
+        }
+
+        Ok(TodoRepository::list_due_between(pool, user_id, from, to).await?)
+    }
+
+    /// Count a user's todos per status, with zero for statuses they have none in
+    pub async fn status_summary(
+        pool: &PgPool,
+        user_id: Uuid,
+    ) -> Result<TodoStatusSummary, TodoFeatureError> {
+        let mut summary = TodoStatusSummary::default();
+
+        for (status, count) in TodoRepository::count_by_status(pool, user_id).await? {
+            match status {
+                TodoStatus::Pending => summary.pending = count,
+                TodoStatus::InProgress => summary.in_progress = count,
+                TodoStatus::Completed => summary.completed = count,
+                TodoStatus::Cancelled => summary.cancelled = count,
+            }
+        }
+
+        Ok(summary)
+    }
+
+    /// Find a user's todos matching every criterion set in `filter`, one page
+    /// at a time
+    ///
+    /// The tag is normalized like tags being added, so `" Work "` finds todos
+    /// tagged `work`.
+    pub async fn find(
+        pool: &PgPool,
+        user_id: Uuid,
+        filter: TodoFilter,
+        offset: u64,
+        limit: u64,
+    ) -> Result<TodoPage, TodoFeatureError> {
+        let filter = domain::TodoFilter {
+            statuses: filter.statuses,
+            query: filter.query,
+            tag: filter.tag.as_deref().map(normalize_tag).transpose()?,
+            created_after: filter.created_after,
+            created_before: filter.created_before,
+            due_before: filter.due_before,
+        };
+
+        // Fetch one extra row to learn whether another page follows
+        let mut todos = TodoRepository::find(pool, user_id, &filter, offset, limit + 1).await?;
+
+        let has_next_page = todos.len() as u64 > limit;
+        todos.truncate(limit as usize);
+
+        Ok(TodoPage {
+            todos,
+            has_next_page,
+        })
+    }
+
     /// Update a todo
+    ///
+    /// Status and content changes are applied in one transaction, so either
+    /// every provided field lands or none do. A status equal to the current
+    /// one is ignored, so it never blocks content changes. A new title or
+    /// description is trimmed and must fit within `TodoLimits`. When
+    /// `expected_updated_at` is set and the todo has changed since then,
+    /// nothing is written and `Conflict` is returned. The transaction is
+    /// rerun from the start if it fails with a transient database error.
     pub async fn update(
         pool: &PgPool,
         id: Uuid,
         input: UpdateTodoInput,
     ) -> Result<Todo, TodoFeatureError> {
+        let limits = TodoLimits::current();
+        let title = input
+            .title
+            .as_deref()
+            .map(|t| check_length(t, "title", limits.title_max))
+            .transpose()?;
+        let description = input
+            .description
+            .as_deref()
+            .map(|d| check_length(d, "description", limits.description_max))
+            .transpose()?;
+
+        retry_on_transient(|| {
+            Self::apply_update(
+                pool,
+                id,
+                input.status,
+                title,
+                description,
+                input.expected_updated_at,
+            )
+        })
+        .await
+    }
+
+    /// Apply validated changes to a todo in a transaction of its own
+    async fn apply_update(
+        pool: &PgPool,
+        id: Uuid,
+        status: Option<TodoStatus>,
+        title: Option<&str>,
+        description: Option<&str>,
+        expected_updated_at: Option<OffsetDateTime>,
+    ) -> Result<Todo, TodoFeatureError> {
+        let mut tx = pool.begin().await.map_err(domain::DomainError::from)?;
+
         // Get existing todo to merge updates
-        let existing = Self::get(pool, id).await?;
+        let mut todo = TodoRepository::find_by_id(&mut *tx, id)
+            .await?
+            .ok_or(TodoFeatureError::NotFound(id))?;
+
+        let mut expected = expected_updated_at;
+        if expected.is_some_and(|at| at != todo.updated_at) {
+            return Err(TodoFeatureError::Conflict(id));
+        }
+        // Only the first write checks the caller's timestamp, since it bumps
+        // `updated_at` for the second
+        let missing = |expected: Option<OffsetDateTime>| match expected {
+            Some(_) => TodoFeatureError::Conflict(id),
+            None => TodoFeatureError::NotFound(id),
+        };
 
         // Update status if provided
-        if let Some(status) = input.status {
-            if status != existing.status {
-                return TodoRepository::update_status(pool, id, status)
-                    .await?
-                    .ok_or(TodoFeatureError::NotFound(id));
+        if let Some(status) = status
+            && status != todo.status
+        {
+            if !todo.status.can_transition_to(status) {
+                return Err(TodoFeatureError::InvalidTransition {
+                    from: todo.status,
+                    to: status,
+                });
+            }
+
+            let expected = expected.take();
+            todo = TodoRepository::update_status(&mut *tx, id, status, expected)
+                .await?
+                .ok_or_else(|| missing(expected))?;
+
+            if status.is_closed() {
+                todo = Self::close(&mut tx, todo).await?;
             }
         }
 
         // Update content if title or description changed
-        if input.title.is_some() || input.description.is_some() {
-            let new_title = input.title.as_deref().unwrap_or(&existing.title);
-            let new_description = input
-                .description
-                .as_deref()
-                .or(existing.description.as_deref());
-
-            return TodoRepository::update_content(pool, id, new_title, new_description)
-                .await?
-                .ok_or(TodoFeatureError::NotFound(id));
+        if title.is_some() || description.is_some() {
+            let new_title = title.unwrap_or(&todo.title);
+            let new_description = description.or(todo.description.as_deref());
+
+            let expected = expected.take();
+            todo =
+                TodoRepository::update_content(&mut *tx, id, new_title, new_description, expected)
+                    .await?
+                    .ok_or_else(|| missing(expected))?;
+        }
+
+        tx.commit().await.map_err(domain::DomainError::from)?;
+
+        Ok(todo)
+    }
+
+    /// Set the status of several of a user's todos at once, returning how
+    /// many changed
+    ///
+    /// Todos the user doesn't own, or whose current status cannot legally
+    /// move to `status`, are left untouched and not counted. Todos that are
+    /// closed this way are closed as `transition` closes one, in the same
+    /// transaction.
+    pub async fn update_status_many(
+        pool: &PgPool,
+        user_id: Uuid,
+        ids: Vec<Uuid>,
+        status: TodoStatus,
+    ) -> Result<u64, TodoFeatureError> {
+        let from: Vec<TodoStatus> = TodoStatus::ALL
+            .into_iter()
+            .filter(|s| s.can_transition_to(status))
+            .collect();
+
+        let todos =
+            retry_on_transient(|| Self::apply_status_many(pool, user_id, &ids, status, &from))
+                .await?;
+        Ok(todos.len() as u64)
+    }
+
+    /// Set the status of several todos in a transaction of its own
+    async fn apply_status_many(
+        pool: &PgPool,
+        user_id: Uuid,
+        ids: &[Uuid],
+        status: TodoStatus,
+        from: &[TodoStatus],
+    ) -> Result<Vec<Todo>, TodoFeatureError> {
+        let mut tx = pool.begin().await.map_err(DomainError::from)?;
+
+        let mut todos = Vec::new();
+        let updated =
+            TodoRepository::update_status_many(&mut *tx, user_id, ids, status, from).await?;
+        for todo in updated {
+            let todo = if status.is_closed() {
+                Self::close(&mut tx, todo).await?
+            } else {
+                todo
+            };
+            todos.push(todo);
         }
 
-        Ok(existing)
+        tx.commit().await.map_err(DomainError::from)?;
+
+        Ok(todos)
     }
 
     /// Mark a todo as completed
+    ///
+    /// A running time-tracking clock is stopped and its time added to the
+    /// total.
     pub async fn complete(pool: &PgPool, id: Uuid) -> Result<Todo, TodoFeatureError> {
-        TodoRepository::update_status(pool, id, TodoStatus::Completed)
-            .await?
-            .ok_or(TodoFeatureError::NotFound(id))
+        Self::transition(pool, id, TodoStatus::Completed).await
     }
 
-    /// Mark a todo as in progress
+    /// Mark a todo as in progress and start its time-tracking clock
+    ///
+    /// Starting a paused in-progress todo resumes its clock; starting one
+    /// whose clock is running is an invalid transition.
     pub async fn start(pool: &PgPool, id: Uuid) -> Result<Todo, TodoFeatureError> {
-        TodoRepository::update_status(pool, id, TodoStatus::InProgress)
+        let mut tx = pool.begin().await.map_err(DomainError::from)?;
+
+        let existing = TodoRepository::find_by_id(&mut *tx, id)
             .await?
-            .ok_or(TodoFeatureError::NotFound(id))
+            .ok_or(TodoFeatureError::NotFound(id))?;
+
+        let paused = existing.status == TodoStatus::InProgress && existing.started_at.is_none();
+        if !paused {
+            if !existing.status.can_transition_to(TodoStatus::InProgress) {
+                return Err(TodoFeatureError::InvalidTransition {
+                    from: existing.status,
+                    to: TodoStatus::InProgress,
+                });
+            }
+
+            TodoRepository::update_status(&mut *tx, id, TodoStatus::InProgress, None).await?;
+        }
+
+        let todo = TodoRepository::start_timer(&mut *tx, id)
+            .await?
+            .ok_or(TodoFeatureError::NotFound(id))?;
+
+        tx.commit().await.map_err(DomainError::from)?;
+
+        Ok(todo)
+    }
+
+    /// Stop a todo's time-tracking clock, adding the time since it was
+    /// started to the total
+    ///
+    /// The todo stays in progress. Pausing a todo whose clock isn't running
+    /// is a validation error.
+    pub async fn pause(pool: &PgPool, id: Uuid) -> Result<Todo, TodoFeatureError> {
+        if let Some(todo) = TodoRepository::stop_timer(pool, id).await? {
+            return Ok(todo);
+        }
+
+        Self::get(pool, id).await?;
+        Err(TodoFeatureError::Validation(format!(
+            "todo {id} is not being timed"
+        )))
+    }
+
+    /// Cancel a todo that will not be completed
+    pub async fn cancel(pool: &PgPool, id: Uuid) -> Result<Todo, TodoFeatureError> {
+        Self::transition(pool, id, TodoStatus::Cancelled).await
+    }
+
+    /// Move a completed todo back to pending
+    ///
+    /// This is the only way out of `Completed`; the regular transition rules
+    /// never allow it. The update only applies while the todo is still
+    /// completed, so a concurrent change of status is reported as an
+    /// `InvalidTransition` rather than overwritten.
+    pub async fn reopen(pool: &PgPool, id: Uuid) -> Result<Todo, TodoFeatureError> {
+        let existing = Self::get(pool, id).await?;
+
+        if existing.status == TodoStatus::Completed {
+            let reopened = retry_on_transient(|| {
+                TodoRepository::update_status_many(
+                    pool,
+                    existing.user_id,
+                    &[id],
+                    TodoStatus::Pending,
+                    &[TodoStatus::Completed],
+                )
+            })
+            .await?;
+            if let Some(todo) = reopened.into_iter().next() {
+                return Ok(todo);
+            }
+        }
+
+        // Not completed, or no longer by the time of the update
+        let current = Self::get(pool, id).await?;
+        Err(TodoFeatureError::InvalidTransition {
+            from: current.status,
+            to: TodoStatus::Pending,
+        })
+    }
+
+    /// Move a todo to a new status, rejecting transitions the state machine forbids
+    ///
+    /// Closing a todo also cancels its pending due date reminder and stops its
+    /// time-tracking clock, and completing one records a `todo.completed`
+    /// event.
+    async fn transition(pool: &PgPool, id: Uuid, to: TodoStatus) -> Result<Todo, TodoFeatureError> {
+        let mut tx = pool.begin().await.map_err(DomainError::from)?;
+
+        let existing = TodoRepository::find_by_id(&mut *tx, id)
+            .await?
+            .ok_or(TodoFeatureError::NotFound(id))?;
+
+        if !existing.status.can_transition_to(to) {
+            return Err(TodoFeatureError::InvalidTransition {
+                from: existing.status,
+                to,
+            });
+        }
+
+        let mut todo = TodoRepository::update_status(&mut *tx, id, to, None)
+            .await?
+            .ok_or(TodoFeatureError::NotFound(id))?;
+
+        if to.is_closed() {
+            todo = Self::close(&mut tx, todo).await?;
+        }
+
+        tx.commit().await.map_err(DomainError::from)?;
+
+        Ok(todo)
     }
 
-    /// Delete a todo
+    /// Soft-delete a todo, cancelling its pending due date reminder
+    ///
+    /// The todo is hidden from everything but `list_for_user` with
+    /// `include_deleted` until it is restored.
     pub async fn delete(pool: &PgPool, id: Uuid) -> Result<bool, TodoFeatureError> {
-        Ok(TodoRepository::delete(pool, id).await?)
+        let mut tx = pool.begin().await.map_err(DomainError::from)?;
+
+        Self::cancel_reminder(&mut tx, id).await?;
+        let deleted = TodoRepository::delete(&mut *tx, id).await?;
+
+        tx.commit().await.map_err(DomainError::from)?;
+
+        Ok(deleted)
+    }
+
+    /// Restore a soft-deleted todo
+    ///
+    /// The reminder cancelled by the delete is not scheduled again.
+    pub async fn restore(pool: &PgPool, id: Uuid) -> Result<Todo, TodoFeatureError> {
+        TodoRepository::restore(pool, id)
+            .await?
+            .ok_or(TodoFeatureError::NotFound(id))
+    }
+
+    /// Move a todo to another user
+    ///
+    /// Returns `UserNotFound` if there is no such active user and `NotFound`
+    /// if the todo is missing or deleted. The todo loses its position among
+    /// its old owner's todos, and an open todo's reminder is rescheduled for
+    /// the new owner, all in one transaction.
+    pub async fn reassign(
+        pool: &PgPool,
+        id: Uuid,
+        new_user_id: Uuid,
+    ) -> Result<Todo, TodoFeatureError> {
+        let mut tx = pool.begin().await.map_err(DomainError::from)?;
+
+        if UserRepository::find_by_id(&mut *tx, new_user_id)
+            .await?
+            .is_none()
+        {
+            return Err(TodoFeatureError::UserNotFound(new_user_id));
+        }
+
+        // The queued reminder names the old owner
+        Self::cancel_reminder(&mut tx, id).await?;
+
+        let todo = TodoRepository::reassign(&mut *tx, id, new_user_id)
+            .await
+            .map_err(|e| match e {
+                // The user was deleted since the check
+                DomainError::Conflict(_) => TodoFeatureError::UserNotFound(new_user_id),
+                e => e.into(),
+            })?
+            .ok_or(TodoFeatureError::NotFound(id))?;
+
+        if !todo.status.is_closed() {
+            Self::schedule_reminder(&mut tx, &todo).await?;
+        }
+
+        tx.commit().await.map_err(DomainError::from)?;
+
+        Ok(todo)
     }
+
+    /// Get a todo by ID, even if deleted, checking it belongs to `user_id`
+    pub async fn get_owned_including_deleted(
+        pool: &PgPool,
+        id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Todo, TodoFeatureError> {
+        let todo = TodoRepository::find_by_id_including_deleted(pool, id)
+            .await?
+            .ok_or(TodoFeatureError::NotFound(id))?;
+
+        if todo.user_id != user_id {
+            return Err(TodoFeatureError::Forbidden(id));
+        }
+
+        Ok(todo)
+    }
+
+    /// Tag a todo, returning its tags afterwards
+    ///
+    /// Tags are case-insensitive and stored lowercase; adding one the todo
+    /// already has changes nothing.
+    pub async fn add_tag(
+        pool: &PgPool,
+        id: Uuid,
+        tag: &str,
+    ) -> Result<Vec<String>, TodoFeatureError> {
+        let tag = normalize_tag(tag)?;
+        let mut tx = pool.begin().await.map_err(DomainError::from)?;
+
+        TagRepository::attach(&mut *tx, id, &tag)
+            .await
+            .map_err(|e| match e {
+                DomainError::NotFound(_) => TodoFeatureError::NotFound(id),
+                e => e.into(),
+            })?;
+        let tags = TagRepository::list_for_todo(&mut *tx, id).await?;
+
+        tx.commit().await.map_err(DomainError::from)?;
+
+        Ok(tags)
+    }
+
+    /// Remove a tag from a todo, returning its remaining tags
+    pub async fn remove_tag(
+        pool: &PgPool,
+        id: Uuid,
+        tag: &str,
+    ) -> Result<Vec<String>, TodoFeatureError> {
+        let tag = normalize_tag(tag)?;
+        let mut tx = pool.begin().await.map_err(DomainError::from)?;
+
+        TagRepository::detach(&mut *tx, id, &tag).await?;
+        let tags = TagRepository::list_for_todo(&mut *tx, id).await?;
+
+        tx.commit().await.map_err(DomainError::from)?;
+
+        Ok(tags)
+    }
+
+    /// Replace a todo's tags with `tags`, returning its tags afterwards
+    ///
+    /// Tags are normalized like `add_tag`, so duplicates that differ only in
+    /// case collapse into one. An empty list clears every tag.
+    pub async fn set_tags(
+        pool: &PgPool,
+        id: Uuid,
+        tags: &[String],
+    ) -> Result<Vec<String>, TodoFeatureError> {
+        let tags = tags
+            .iter()
+            .map(|tag| normalize_tag(tag))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut tx = pool.begin().await.map_err(DomainError::from)?;
+
+        TagRepository::set_tags(&mut *tx, id, &tags)
+            .await
+            .map_err(|e| match e {
+                DomainError::NotFound(_) => TodoFeatureError::NotFound(id),
+                e => e.into(),
+            })?;
+        let tags = TagRepository::list_for_todo(&mut *tx, id).await?;
+
+        tx.commit().await.map_err(DomainError::from)?;
+
+        Ok(tags)
+    }
+
+    /// Add a checklist item to the end of a todo's items
+    ///
+    /// The text is trimmed, must not be empty, and must fit within the
+    /// title limit.
+    pub async fn add_item(
+        pool: &PgPool,
+        todo_id: Uuid,
+        text: &str,
+    ) -> Result<TodoItem, TodoFeatureError> {
+        let text = check_length(text, "text", TodoLimits::current().title_max)?;
+        if text.is_empty() {
+            return Err(TodoFeatureError::Validation(
+                "text must not be empty".to_string(),
+            ));
+        }
+
+        TodoItemRepository::add(pool, todo_id, text)
+            .await
+            .map_err(|e| match e {
+                DomainError::NotFound(_) => TodoFeatureError::NotFound(todo_id),
+                e => e.into(),
+            })
+    }
+
+    /// Get a checklist item by ID
+    pub async fn get_item(pool: &PgPool, id: Uuid) -> Result<TodoItem, TodoFeatureError> {
+        TodoItemRepository::find_by_id(pool, id)
+            .await?
+            .ok_or(TodoFeatureError::ItemNotFound(id))
+    }
+
+    /// Mark a checklist item done, or not done if it already was
+    pub async fn toggle_item(pool: &PgPool, id: Uuid) -> Result<TodoItem, TodoFeatureError> {
+        TodoItemRepository::toggle(pool, id)
+            .await?
+            .ok_or(TodoFeatureError::ItemNotFound(id))
+    }
+
+    /// Delete a checklist item
+    pub async fn delete_item(pool: &PgPool, id: Uuid) -> Result<(), TodoFeatureError> {
+        if !TodoItemRepository::delete(pool, id).await? {
+            return Err(TodoFeatureError::ItemNotFound(id));
+        }
+
+        Ok(())
+    }
+
+    /// Delete all of a user's completed todos, returning how many were removed
+    pub async fn clear_completed(pool: &PgPool, user_id: Uuid) -> Result<u64, TodoFeatureError> {
+        Ok(TodoRepository::delete_completed_by_user(pool, user_id).await?)
+    }
+
+    /// Finish closing a todo whose status was just set to a closed one
+    ///
+    /// Its pending due date reminder is cancelled, a running time-tracking
+    /// clock is stopped with its time added to the total, and a completed
+    /// todo gets a `todo.completed` event.
+    async fn close(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        mut todo: Todo,
+    ) -> Result<Todo, TodoFeatureError> {
+        Self::cancel_reminder(tx, todo.id).await?;
+
+        if let Some(stopped) = TodoRepository::stop_timer(&mut **tx, todo.id).await? {
+            todo = stopped;
+        }
+
+        if todo.status == TodoStatus::Completed {
+            Self::record_completed(tx, &todo).await?;
+        }
+
+        Ok(todo)
+    }
+
+    /// Schedule the due date reminder for a todo that has a due date
+    async fn schedule_reminder(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        todo: &Todo,
+    ) -> Result<(), TodoFeatureError> {
+        let Some(due_date) = todo.due_date else {
+            return Ok(());
+        };
+
+        let job_id =
+            TodoJobs::enqueue_due_reminder(tx, todo.id, todo.user_id, todo.title.clone(), due_date)
+                .await
+                .map_err(|e| TodoFeatureError::Queue(e.to_string()))?;
+
+        TodoRepository::set_reminder_job_id(&mut **tx, todo.id, Some(job_id)).await?;
+
+        Ok(())
+    }
+
+    /// Remove the reminder job scheduled for a todo, if it still has one
+    async fn cancel_reminder(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        id: Uuid,
+    ) -> Result<(), TodoFeatureError> {
+        if let Some(job_id) = TodoRepository::take_reminder_job_id(&mut **tx, id).await? {
+            TodoJobs::cancel_due_reminder(tx, job_id)
+                .await
+                .map_err(DomainError::from)?;
+        }
+
+        Ok(())
+    }
+
+    /// Enqueue the created notification for a todo within a transaction
+    async fn notify_created(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        todo: &Todo,
+    ) -> Result<(), TodoFeatureError> {
+        TodoJobs::enqueue_todo_created(tx, todo.id, todo.user_id, todo.title.clone())
+            .await
+            .map_err(|e| TodoFeatureError::Queue(e.to_string()))
+    }
+
+    /// Record a `todo.completed` event in the outbox
+    async fn record_completed(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        todo: &Todo,
+    ) -> Result<(), TodoFeatureError> {
+        EventRepository::record(
+            &mut **tx,
+            "todo.completed",
+            &json!({ "todo_id": todo.id, "user_id": todo.user_id }),
+        )
+        .await?;
+
+        Ok(())
+    }
+}
+
+/// Trim a title or description, rejecting it if still longer than `max` characters
+fn check_length<'a>(value: &'a str, field: &str, max: usize) -> Result<&'a str, TodoFeatureError> {
+    let trimmed = value.trim();
+
+    if trimmed.chars().count() > max {
+        return Err(TodoFeatureError::Validation(format!(
+            "{field} must be at most {max} characters"
+        )));
+    }
+
+    Ok(trimmed)
+}
+
+/// Trim and lowercase a tag so "Work" and " work" are the same tag
+fn normalize_tag(tag: &str) -> Result<String, TodoFeatureError> {
+    let normalized = tag.trim().to_lowercase();
+
+    if normalized.is_empty() {
+        return Err(TodoFeatureError::InvalidTag(tag.to_string()));
+    }
+
+    Ok(normalized)
 }
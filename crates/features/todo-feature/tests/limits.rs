@@ -0,0 +1,42 @@
+//! Length limit configuration tests
+
+use std::collections::HashMap;
+
+use todo_feature::{TodoLimits, TodoLimitsError};
+
+/// Parse limits from the given variables only
+fn parse(vars: &[(&str, &str)]) -> Result<TodoLimits, TodoLimitsError> {
+    let vars: HashMap<String, String> = vars
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    TodoLimits::from_vars(|name| vars.get(name).cloned())
+}
+
+#[test]
+fn unset_variables_use_defaults() {
+    let limits = parse(&[]).unwrap();
+
+    assert_eq!(limits, TodoLimits::default());
+    assert_eq!((limits.title_max, limits.description_max), (200, 2000));
+}
+
+#[test]
+fn variables_override_defaults() {
+    let limits = parse(&[("TODO_TITLE_MAX", "80"), ("TODO_DESCRIPTION_MAX", " 500 ")]).unwrap();
+
+    assert_eq!((limits.title_max, limits.description_max), (80, 500));
+}
+
+#[test]
+fn non_positive_limits_are_rejected() {
+    for value in ["0", "-1", "lots"] {
+        assert_eq!(
+            parse(&[("TODO_TITLE_MAX", value)]),
+            Err(TodoLimitsError::InvalidNumber {
+                name: "TODO_TITLE_MAX",
+                value: value.to_string(),
+            })
+        );
+    }
+}
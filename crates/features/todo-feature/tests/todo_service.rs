@@ -3,9 +3,13 @@
 //! These tests verify todo-related business behaviors work correctly.
 //! Focus on workflows and business rules, not implementation details.
 
-use domain::TodoStatus;
+use domain::{FixedClock, SystemClock, Todo, TodoStatus};
 use sqlx::PgPool;
-use todo_feature::{CreateTodoInput, TodoFeatureError, TodoService, UpdateTodoInput};
+use time::{Date, Duration, Month, OffsetDateTime};
+use todo_feature::{
+    CreateTodoInput, CreateTodoItem, TodoFeatureError, TodoFilter, TodoLimits, TodoService,
+    UpdateTodoInput,
+};
 use user_feature::{CreateUserInput, UserService};
 use uuid::Uuid;
 
@@ -16,6 +20,8 @@ async fn create_test_user(pool: &PgPool, email: &str) -> Uuid {
         CreateUserInput {
             email: email.to_string(),
             name: "Test User".to_string(),
+            timezone: None,
+            idempotency_key: None,
         },
     )
     .await
@@ -41,6 +47,7 @@ async fn user_can_create_todo_with_title_and_description(
             user_id,
             title: "My Task".to_string(),
             description: Some("A description".to_string()),
+            due_date: None,
         },
     )
     .await?;
@@ -64,6 +71,7 @@ async fn user_can_create_todo_without_description(pool: PgPool) -> Result<(), To
             user_id,
             title: "Simple Task".to_string(),
             description: None,
+            due_date: None,
         },
     )
     .await?;
@@ -73,6 +81,94 @@ async fn user_can_create_todo_without_description(pool: PgPool) -> Result<(), To
     Ok(())
 }
 
+/// Create a todo with the given title and description
+async fn create_with_text(
+    pool: &PgPool,
+    user_id: Uuid,
+    title: String,
+    description: Option<String>,
+) -> Result<Todo, TodoFeatureError> {
+    TodoService::create(
+        pool,
+        CreateTodoInput {
+            user_id,
+            title,
+            description,
+            due_date: None,
+        },
+    )
+    .await
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn title_and_description_are_trimmed(pool: PgPool) -> Result<(), TodoFeatureError> {
+    let user_id = create_test_user(&pool, "trim@example.com").await;
+
+    let todo = create_with_text(
+        &pool,
+        user_id,
+        "  Padded  ".to_string(),
+        Some("\tDetails\n".to_string()),
+    )
+    .await?;
+
+    assert_eq!(todo.title, "Padded");
+    assert_eq!(todo.description.as_deref(), Some("Details"));
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn title_may_be_exactly_the_maximum_length(pool: PgPool) -> Result<(), TodoFeatureError> {
+    let user_id = create_test_user(&pool, "title-max@example.com").await;
+    let max = TodoLimits::default().title_max;
+
+    // Surrounding whitespace doesn't count towards the limit
+    let title = format!(" {} ", "t".repeat(max));
+    let todo = create_with_text(&pool, user_id, title, None).await?;
+
+    assert_eq!(todo.title.chars().count(), max);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn title_over_the_maximum_length_is_rejected(pool: PgPool) {
+    let user_id = create_test_user(&pool, "title-long@example.com").await;
+    let max = TodoLimits::default().title_max;
+
+    let result = create_with_text(&pool, user_id, "t".repeat(max + 1), None).await;
+
+    assert!(matches!(result, Err(TodoFeatureError::Validation(_))));
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn description_may_be_exactly_the_maximum_length(
+    pool: PgPool,
+) -> Result<(), TodoFeatureError> {
+    let user_id = create_test_user(&pool, "desc-max@example.com").await;
+    let max = TodoLimits::default().description_max;
+
+    let todo = create_with_text(&pool, user_id, "Task".to_string(), Some("d".repeat(max))).await?;
+
+    assert_eq!(todo.description.unwrap().chars().count(), max);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn description_over_the_maximum_length_is_rejected(pool: PgPool) {
+    let user_id = create_test_user(&pool, "desc-long@example.com").await;
+    let max = TodoLimits::default().description_max;
+
+    let result = create_with_text(
+        &pool,
+        user_id,
+        "Task".to_string(),
+        Some("d".repeat(max + 1)),
+    )
+    .await;
+
+    assert!(matches!(result, Err(TodoFeatureError::Validation(_))));
+}
+
 #[sqlx::test(migrations = "../../../migrations")]
 async fn creating_todo_for_nonexistent_user_fails(pool: PgPool) -> Result<(), TodoFeatureError> {
     let result = TodoService::create(
@@ -81,14 +177,431 @@ async fn creating_todo_for_nonexistent_user_fails(pool: PgPool) -> Result<(), To
             user_id: Uuid::new_v4(),
             title: "Task".to_string(),
             description: None,
+            due_date: None,
+        },
+    )
+    .await;
+
+    assert!(matches!(result, Err(TodoFeatureError::UserNotFound(_))));
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn creating_todo_for_deleted_user_fails(pool: PgPool) -> Result<(), TodoFeatureError> {
+    // Given a user who has been deleted
+    let user_id = create_test_user(&pool, "gone@example.com").await;
+    UserService::delete(&pool, user_id).await.unwrap();
+
+    // When creating a todo for them
+    let result = TodoService::create(
+        &pool,
+        CreateTodoInput {
+            user_id,
+            title: "Too late".to_string(),
+            description: None,
+            due_date: None,
+        },
+    )
+    .await;
+
+    // Then the user is reported missing
+    assert!(matches!(result, Err(TodoFeatureError::UserNotFound(_))));
+    Ok(())
+}
+
+/// Count queued jobs on the reminders channel
+async fn reminder_job_count(pool: &PgPool) -> i64 {
+    // mq_msgs has a dummy row with uuid_nil(), so we exclude it
+    sqlx::query_scalar(
+        "SELECT COUNT(*) FROM mq_msgs WHERE channel_name = 'reminders' AND id != uuid_nil()",
+    )
+    .fetch_one(pool)
+    .await
+    .expect("Failed to count reminder jobs")
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn todo_with_due_date_schedules_a_reminder(pool: PgPool) -> Result<(), TodoFeatureError> {
+    // Given a registered user
+    let user_id = create_test_user(&pool, "reminder@example.com").await;
+
+    // When creating a todo with a due date
+    TodoService::create(
+        &pool,
+        CreateTodoInput {
+            user_id,
+            title: "Due soon".to_string(),
+            description: None,
+            due_date: Some(OffsetDateTime::now_utc() + Duration::days(1)),
+        },
+    )
+    .await?;
+
+    // Then a reminder job is queued
+    assert_eq!(reminder_job_count(&pool).await, 1);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn completing_todo_cancels_its_reminder(pool: PgPool) -> Result<(), TodoFeatureError> {
+    // Given a todo with a scheduled reminder
+    let user_id = create_test_user(&pool, "complete-reminder@example.com").await;
+    let todo = TodoService::create(
+        &pool,
+        CreateTodoInput {
+            user_id,
+            title: "Due soon".to_string(),
+            description: None,
+            due_date: Some(OffsetDateTime::now_utc() + Duration::days(1)),
+        },
+    )
+    .await?;
+    assert_eq!(reminder_job_count(&pool).await, 1);
+
+    // When the todo is completed
+    TodoService::complete(&pool, todo.id).await?;
+
+    // Then the reminder is no longer queued
+    assert_eq!(reminder_job_count(&pool).await, 0);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn deleting_todo_cancels_its_reminder(pool: PgPool) -> Result<(), TodoFeatureError> {
+    // Given a todo with a scheduled reminder
+    let user_id = create_test_user(&pool, "delete-reminder@example.com").await;
+    let todo = TodoService::create(
+        &pool,
+        CreateTodoInput {
+            user_id,
+            title: "Due soon".to_string(),
+            description: None,
+            due_date: Some(OffsetDateTime::now_utc() + Duration::days(1)),
+        },
+    )
+    .await?;
+
+    // When the todo is deleted
+    assert!(TodoService::delete(&pool, todo.id).await?);
+
+    // Then the reminder is no longer queued
+    assert_eq!(reminder_job_count(&pool).await, 0);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn bulk_closing_todos_cancels_their_reminders(pool: PgPool) -> Result<(), TodoFeatureError> {
+    // Given two todos with scheduled reminders
+    let user_id = create_test_user(&pool, "bulk-reminder@example.com").await;
+    let mut ids = Vec::new();
+    for title in ["Due soon", "Also due soon"] {
+        let todo = TodoService::create(
+            &pool,
+            CreateTodoInput {
+                user_id,
+                title: title.to_string(),
+                description: None,
+                due_date: Some(OffsetDateTime::now_utc() + Duration::days(1)),
+            },
+        )
+        .await?;
+        ids.push(todo.id);
+    }
+    assert_eq!(reminder_job_count(&pool).await, 2);
+
+    // When one is completed and the other cancelled in bulk
+    TodoService::update_status_many(&pool, user_id, vec![ids[0]], TodoStatus::Completed).await?;
+    TodoService::update_status_many(&pool, user_id, vec![ids[1]], TodoStatus::Cancelled).await?;
+
+    // Then neither reminder is still queued
+    assert_eq!(reminder_job_count(&pool).await, 0);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn deleting_a_user_cancels_their_reminders(pool: PgPool) -> Result<(), TodoFeatureError> {
+    // Given a user with a todo that has a scheduled reminder
+    let user_id = create_test_user(&pool, "leaving@example.com").await;
+    TodoService::create(
+        &pool,
+        CreateTodoInput {
+            user_id,
+            title: "Due soon".to_string(),
+            description: None,
+            due_date: Some(OffsetDateTime::now_utc() + Duration::days(1)),
+        },
+    )
+    .await?;
+
+    // When the user is deleted
+    assert!(UserService::delete(&pool, user_id).await.unwrap());
+
+    // Then the reminder is no longer queued
+    assert_eq!(reminder_job_count(&pool).await, 0);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn todo_without_due_date_schedules_no_reminder(pool: PgPool) -> Result<(), TodoFeatureError> {
+    let user_id = create_test_user(&pool, "no-reminder@example.com").await;
+
+    TodoService::create(
+        &pool,
+        CreateTodoInput {
+            user_id,
+            title: "Someday".to_string(),
+            description: None,
+            due_date: None,
+        },
+    )
+    .await?;
+
+    assert_eq!(reminder_job_count(&pool).await, 0);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn creating_todo_enqueues_a_notification(pool: PgPool) -> Result<(), TodoFeatureError> {
+    // Given a registered user
+    let user_id = create_test_user(&pool, "notify@example.com").await;
+
+    // When they create a todo
+    let todo = TodoService::create(
+        &pool,
+        CreateTodoInput {
+            user_id,
+            title: "Water plants".to_string(),
+            description: None,
+            due_date: None,
         },
     )
+    .await?;
+
+    // Then exactly one notification job is enqueued
+    let payloads: Vec<serde_json::Value> = sqlx::query_scalar(
+        "SELECT p.payload_json FROM mq_payloads p
+         JOIN mq_msgs m ON p.id = m.id
+         WHERE m.channel_name = 'notifications'",
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(domain::DomainError::from)?;
+    assert_eq!(payloads.len(), 1);
+    // And it names the todo
+    assert_eq!(payloads[0]["todo_id"], todo.id.to_string());
+    assert_eq!(payloads[0]["title"], "Water plants");
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn user_can_create_several_todos_at_once(pool: PgPool) -> Result<(), TodoFeatureError> {
+    // Given a registered user
+    let user_id = create_test_user(&pool, "batch@example.com").await;
+
+    // When creating three todos in one batch
+    let todos = TodoService::create_many(
+        &pool,
+        user_id,
+        vec![
+            CreateTodoItem {
+                title: "First".to_string(),
+                description: None,
+                due_date: None,
+            },
+            CreateTodoItem {
+                title: "Second".to_string(),
+                description: Some("With details".to_string()),
+                due_date: None,
+            },
+            CreateTodoItem {
+                title: "Third".to_string(),
+                description: None,
+                due_date: None,
+            },
+        ],
+    )
+    .await?;
+
+    // Then all of them are created as pending todos for that user
+    assert_eq!(todos.len(), 3);
+    assert!(todos.iter().all(|t| t.user_id == user_id));
+    assert!(todos.iter().all(|t| t.status == TodoStatus::Pending));
+    let second = todos.iter().find(|t| t.title == "Second").unwrap();
+    assert_eq!(second.description, Some("With details".to_string()));
+    assert_eq!(
+        TodoService::list_for_user(&pool, user_id, false)
+            .await?
+            .len(),
+        3
+    );
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn batch_create_rolls_back_when_any_item_fails(pool: PgPool) -> Result<(), TodoFeatureError> {
+    // Given a registered user
+    let user_id = create_test_user(&pool, "batch-fail@example.com").await;
+
+    // When one item in the batch cannot be stored (Postgres rejects NUL bytes)
+    let result = TodoService::create_many(
+        &pool,
+        user_id,
+        vec![
+            CreateTodoItem {
+                title: "Valid".to_string(),
+                description: None,
+                due_date: None,
+            },
+            CreateTodoItem {
+                title: "Invalid\0".to_string(),
+                description: None,
+                due_date: None,
+            },
+        ],
+    )
+    .await;
+
+    // Then the batch fails
+    assert!(matches!(result, Err(TodoFeatureError::Domain(_))));
+    // And none of the todos were created
+    assert!(
+        TodoService::list_for_user(&pool, user_id, false)
+            .await?
+            .is_empty()
+    );
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn batch_create_for_deleted_user_fails(pool: PgPool) -> Result<(), TodoFeatureError> {
+    // Given a user who has been deleted
+    let user_id = create_test_user(&pool, "batch-gone@example.com").await;
+    UserService::delete(&pool, user_id).await.unwrap();
+
+    // When creating a batch of todos for them
+    let result = TodoService::create_many(
+        &pool,
+        user_id,
+        vec![CreateTodoItem {
+            title: "Too late".to_string(),
+            description: None,
+            due_date: None,
+        }],
+    )
     .await;
 
+    // Then the user is reported missing
     assert!(matches!(result, Err(TodoFeatureError::UserNotFound(_))));
     Ok(())
 }
 
+#[sqlx::test(migrations = "../../../migrations")]
+async fn batch_created_todo_with_due_date_schedules_a_reminder(
+    pool: PgPool,
+) -> Result<(), TodoFeatureError> {
+    // Given a registered user
+    let user_id = create_test_user(&pool, "batch-reminder@example.com").await;
+
+    // When creating a batch where one todo has a due date
+    let todos = TodoService::create_many(
+        &pool,
+        user_id,
+        vec![
+            CreateTodoItem {
+                title: "Due soon".to_string(),
+                description: None,
+                due_date: Some(OffsetDateTime::now_utc() + Duration::days(1)),
+            },
+            CreateTodoItem {
+                title: "Whenever".to_string(),
+                description: None,
+                due_date: None,
+            },
+        ],
+    )
+    .await?;
+
+    // Then the due date is stored
+    assert!(todos[0].due_date.is_some());
+    assert!(todos[1].due_date.is_none());
+    // And only that todo gets a reminder
+    assert_eq!(reminder_job_count(&pool).await, 1);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn todo_can_be_duplicated_as_a_new_pending_todo(
+    pool: PgPool,
+) -> Result<(), TodoFeatureError> {
+    // Given a completed todo
+    let user_id = create_test_user(&pool, "duplicate@example.com").await;
+    let source = TodoService::create(
+        &pool,
+        CreateTodoInput {
+            user_id,
+            title: "Weekly report".to_string(),
+            description: Some("Send to the team".to_string()),
+            due_date: None,
+        },
+    )
+    .await?;
+    TodoService::complete(&pool, source.id).await?;
+
+    // When duplicating it
+    let copy = TodoService::duplicate(&pool, source.id).await?;
+
+    // Then the copy is a new pending todo with the same content and owner
+    assert_ne!(copy.id, source.id);
+    assert_eq!(copy.user_id, user_id);
+    assert_eq!(copy.title, "Weekly report");
+    assert_eq!(copy.description, Some("Send to the team".to_string()));
+    assert_eq!(copy.status, TodoStatus::Pending);
+    // And the source is left as it was
+    let source = TodoService::get(&pool, source.id).await?;
+    assert_eq!(source.status, TodoStatus::Completed);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn duplicating_a_todo_enqueues_a_notification(pool: PgPool) -> Result<(), TodoFeatureError> {
+    // Given a todo
+    let user_id = create_test_user(&pool, "duplicate-notify@example.com").await;
+    let source = TodoService::create(
+        &pool,
+        CreateTodoInput {
+            user_id,
+            title: "Water plants".to_string(),
+            description: None,
+            due_date: None,
+        },
+    )
+    .await?;
+
+    // When duplicating it
+    let copy = TodoService::duplicate(&pool, source.id).await?;
+
+    // Then the copy gets a notification of its own
+    let notified: Vec<String> = sqlx::query_scalar(
+        "SELECT p.payload_json->>'todo_id' FROM mq_payloads p
+         JOIN mq_msgs m ON p.id = m.id
+         WHERE m.channel_name = 'notifications'",
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(domain::DomainError::from)?;
+    assert_eq!(notified.len(), 2);
+    assert!(notified.contains(&copy.id.to_string()));
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn duplicating_nonexistent_todo_fails(pool: PgPool) -> Result<(), TodoFeatureError> {
+    let result = TodoService::duplicate(&pool, Uuid::new_v4()).await;
+
+    assert!(matches!(result, Err(TodoFeatureError::NotFound(_))));
+    Ok(())
+}
+
 // =============================================================================
 // Todo Query Behaviors
 // =============================================================================
@@ -103,6 +616,7 @@ async fn todo_can_be_found_by_id(pool: PgPool) -> Result<(), TodoFeatureError> {
             user_id,
             title: "Find Me".to_string(),
             description: None,
+            due_date: None,
         },
     )
     .await?;
@@ -125,318 +639,2018 @@ async fn querying_nonexistent_todo_returns_not_found(pool: PgPool) -> Result<(),
 }
 
 #[sqlx::test(migrations = "../../../migrations")]
-async fn user_can_list_their_todos(pool: PgPool) -> Result<(), TodoFeatureError> {
-    // Given a user with multiple todos
-    let user_id = create_test_user(&pool, "list-todos@example.com").await;
+async fn several_todos_can_be_fetched_by_id(pool: PgPool) -> Result<(), TodoFeatureError> {
+    // Given two todos
+    let user_id = create_test_user(&pool, "get-many@example.com").await;
+    let mut ids = Vec::new();
+    for title in ["First", "Second"] {
+        let todo = TodoService::create(
+            &pool,
+            CreateTodoInput {
+                user_id,
+                title: title.to_string(),
+                description: None,
+                due_date: None,
+            },
+        )
+        .await?;
+        ids.push(todo.id);
+    }
+
+    // And another user's todo
+    let other_id = create_test_user(&pool, "get-many-other@example.com").await;
+    let foreign = TodoService::create(
+        &pool,
+        CreateTodoInput {
+            user_id: other_id,
+            title: "Theirs".to_string(),
+            description: None,
+            due_date: None,
+        },
+    )
+    .await?;
 
-    TodoService::create(
+    // When fetching them alongside an ID that matches nothing and the
+    // other user's todo
+    let requested = [ids[1], Uuid::new_v4(), foreign.id, ids[0]];
+    let found = TodoService::get_many(&pool, user_id, &requested).await?;
+
+    // Then only the user's two todos come back, in the order they were asked for
+    let found_ids: Vec<Uuid> = found.iter().map(|t| t.id).collect();
+    assert_eq!(found_ids, vec![ids[1], ids[0]]);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn owner_can_get_their_own_todo(pool: PgPool) -> Result<(), TodoFeatureError> {
+    // Given two users, one of whom owns a todo
+    let owner_id = create_test_user(&pool, "owner@example.com").await;
+    let other_id = create_test_user(&pool, "other@example.com").await;
+    let todo = TodoService::create(
+        &pool,
+        CreateTodoInput {
+            user_id: owner_id,
+            title: "Mine".to_string(),
+            description: None,
+            due_date: None,
+        },
+    )
+    .await?;
+
+    // When each user asks for it
+    let owned = TodoService::get_owned(&pool, todo.id, owner_id).await?;
+    let other = TodoService::get_owned(&pool, todo.id, other_id).await;
+
+    // Then only the owner gets it
+    assert_eq!(owned.id, todo.id);
+    assert!(matches!(other, Err(TodoFeatureError::Forbidden(id)) if id == todo.id));
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn todos_read_for_a_user_hide_whether_others_exist(
+    pool: PgPool,
+) -> Result<(), TodoFeatureError> {
+    // Given two users, one of whom owns a todo
+    let owner_id = create_test_user(&pool, "scoped-owner@example.com").await;
+    let other_id = create_test_user(&pool, "scoped-other@example.com").await;
+    let todo = TodoService::create(
+        &pool,
+        CreateTodoInput {
+            user_id: owner_id,
+            title: "Private".to_string(),
+            description: None,
+            due_date: None,
+        },
+    )
+    .await?;
+
+    // When the owner, another user, and anyone asking for a missing ID read it
+    let owned = TodoService::get_for_user(&pool, todo.id, owner_id).await?;
+    let other = TodoService::get_for_user(&pool, todo.id, other_id).await;
+    let missing_id = Uuid::new_v4();
+    let missing = TodoService::get_for_user(&pool, missing_id, owner_id).await;
+
+    // Then only the owner gets it, and the other two can't tell the difference
+    assert_eq!(owned.id, todo.id);
+    assert!(matches!(other, Err(TodoFeatureError::NotFound(id)) if id == todo.id));
+    assert!(matches!(missing, Err(TodoFeatureError::NotFound(id)) if id == missing_id));
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn user_can_list_their_todos(pool: PgPool) -> Result<(), TodoFeatureError> {
+    // Given a user with multiple todos
+    let user_id = create_test_user(&pool, "list-todos@example.com").await;
+
+    TodoService::create(
+        &pool,
+        CreateTodoInput {
+            user_id,
+            title: "Task 1".to_string(),
+            description: None,
+            due_date: None,
+        },
+    )
+    .await?;
+
+    TodoService::create(
+        &pool,
+        CreateTodoInput {
+            user_id,
+            title: "Task 2".to_string(),
+            description: None,
+            due_date: None,
+        },
+    )
+    .await?;
+
+    TodoService::create(
+        &pool,
+        CreateTodoInput {
+            user_id,
+            title: "Task 3".to_string(),
+            description: None,
+            due_date: None,
+        },
+    )
+    .await?;
+
+    // When listing todos for the user
+    let todos = TodoService::list_for_user(&pool, user_id, false).await?;
+
+    // Then all todos are returned
+    assert_eq!(todos.len(), 3);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn listing_todos_when_none_exist_returns_empty(pool: PgPool) -> Result<(), TodoFeatureError> {
+    let user_id = create_test_user(&pool, "empty-todos@example.com").await;
+
+    let todos = TodoService::list_for_user(&pool, user_id, false).await?;
+
+    assert!(todos.is_empty());
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn listing_todos_for_an_unknown_user_is_user_not_found(pool: PgPool) {
+    // Given an ID that belongs to no user
+    let unknown = Uuid::new_v4();
+
+    // When listing their todos, newest first or by position
+    let newest = TodoService::list_for_user(&pool, unknown, false).await;
+    let by_position = TodoService::list_for_user_by_position(&pool, unknown, false).await;
+
+    // Then the missing user is reported rather than an empty list
+    assert!(matches!(newest, Err(TodoFeatureError::UserNotFound(id)) if id == unknown));
+    assert!(matches!(by_position, Err(TodoFeatureError::UserNotFound(id)) if id == unknown));
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn users_only_see_their_own_todos(pool: PgPool) -> Result<(), TodoFeatureError> {
+    // Given two users with their own todos
+    let user1 = create_test_user(&pool, "user1@example.com").await;
+    let user2 = create_test_user(&pool, "user2@example.com").await;
+
+    TodoService::create(
+        &pool,
+        CreateTodoInput {
+            user_id: user1,
+            title: "User 1 Task".to_string(),
+            description: None,
+            due_date: None,
+        },
+    )
+    .await?;
+
+    TodoService::create(
+        &pool,
+        CreateTodoInput {
+            user_id: user2,
+            title: "User 2 Task".to_string(),
+            description: None,
+            due_date: None,
+        },
+    )
+    .await?;
+
+    // When each user lists their todos
+    let user1_todos = TodoService::list_for_user(&pool, user1, false).await?;
+    let user2_todos = TodoService::list_for_user(&pool, user2, false).await?;
+
+    // Then they only see their own
+    assert_eq!(user1_todos.len(), 1);
+    assert_eq!(user1_todos[0].title, "User 1 Task");
+    assert_eq!(user2_todos.len(), 1);
+    assert_eq!(user2_todos[0].title, "User 2 Task");
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn todo_can_be_reassigned_to_another_user(pool: PgPool) -> Result<(), TodoFeatureError> {
+    // Given a todo belonging to one user
+    let from = create_test_user(&pool, "hand-off@example.com").await;
+    let to = create_test_user(&pool, "pick-up@example.com").await;
+    let todo = TodoService::create(
+        &pool,
+        CreateTodoInput {
+            user_id: from,
+            title: "Handed over".to_string(),
+            description: None,
+            due_date: None,
+        },
+    )
+    .await?;
+
+    // When it is reassigned
+    let reassigned = TodoService::reassign(&pool, todo.id, to).await?;
+
+    // Then it belongs to the new user
+    assert_eq!(reassigned.user_id, to);
+    let to_todos = TodoService::list_for_user(&pool, to, false).await?;
+    assert_eq!(to_todos.len(), 1);
+    assert_eq!(to_todos[0].id, todo.id);
+    // And is gone from the old user's list
+    assert!(
+        TodoService::list_for_user(&pool, from, false)
+            .await?
+            .is_empty()
+    );
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn reassigning_a_todo_moves_its_reminder_to_the_new_owner(
+    pool: PgPool,
+) -> Result<(), TodoFeatureError> {
+    // Given a todo with a due date, and so a queued reminder
+    let from = create_test_user(&pool, "remind-from@example.com").await;
+    let to = create_test_user(&pool, "remind-to@example.com").await;
+    let todo = TodoService::create(
+        &pool,
+        CreateTodoInput {
+            user_id: from,
+            title: "Renew passport".to_string(),
+            description: None,
+            due_date: Some(OffsetDateTime::now_utc() + Duration::days(1)),
+        },
+    )
+    .await?;
+
+    // When it is reassigned
+    TodoService::reassign(&pool, todo.id, to).await?;
+
+    // Then exactly one reminder is queued, and it is for the new owner
+    let payloads: Vec<serde_json::Value> = sqlx::query_scalar(
+        "SELECT p.payload_json FROM mq_payloads p
+         JOIN mq_msgs m ON p.id = m.id
+         WHERE m.channel_name = 'reminders'",
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(domain::DomainError::from)?;
+    assert_eq!(payloads.len(), 1);
+    assert_eq!(payloads[0]["todo_id"], todo.id.to_string());
+    assert_eq!(payloads[0]["user_id"], to.to_string());
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn reassigning_a_todo_to_a_missing_user_fails(pool: PgPool) -> Result<(), TodoFeatureError> {
+    // Given a todo
+    let owner = create_test_user(&pool, "keeper@example.com").await;
+    let todo = TodoService::create(
+        &pool,
+        CreateTodoInput {
+            user_id: owner,
+            title: "Stays put".to_string(),
+            description: None,
+            due_date: None,
+        },
+    )
+    .await?;
+
+    // When it is reassigned to nobody
+    let unknown = Uuid::new_v4();
+    let result = TodoService::reassign(&pool, todo.id, unknown).await;
+
+    // Then the missing user is reported and the todo keeps its owner
+    assert!(matches!(result, Err(TodoFeatureError::UserNotFound(id)) if id == unknown));
+    assert_eq!(TodoService::get(&pool, todo.id).await?.user_id, owner);
+
+    // And a missing todo is reported as such
+    let missing = Uuid::new_v4();
+    let result = TodoService::reassign(&pool, missing, owner).await;
+    assert!(matches!(result, Err(TodoFeatureError::NotFound(id)) if id == missing));
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn todos_can_be_filtered_by_status(pool: PgPool) -> Result<(), TodoFeatureError> {
+    // Given a user with todos in different statuses
+    let user_id = create_test_user(&pool, "status-list@example.com").await;
+
+    let todo1 = TodoService::create(
+        &pool,
+        CreateTodoInput {
+            user_id,
+            title: "Pending".to_string(),
+            description: None,
+            due_date: None,
+        },
+    )
+    .await?;
+
+    let todo2 = TodoService::create(
+        &pool,
+        CreateTodoInput {
+            user_id,
+            title: "In Progress".to_string(),
+            description: None,
+            due_date: None,
+        },
+    )
+    .await?;
+    TodoService::start(&pool, todo2.id).await?;
+
+    let todo3 = TodoService::create(
+        &pool,
+        CreateTodoInput {
+            user_id,
+            title: "Completed".to_string(),
+            description: None,
+            due_date: None,
+        },
+    )
+    .await?;
+    TodoService::complete(&pool, todo3.id).await?;
+
+    // When filtering by each status
+    let pending = TodoService::list_for_user_by_status(&pool, user_id, TodoStatus::Pending).await?;
+    let in_progress =
+        TodoService::list_for_user_by_status(&pool, user_id, TodoStatus::InProgress).await?;
+    let completed =
+        TodoService::list_for_user_by_status(&pool, user_id, TodoStatus::Completed).await?;
+
+    // Then only matching todos are returned
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].id, todo1.id);
+    assert_eq!(in_progress.len(), 1);
+    assert_eq!(in_progress[0].id, todo2.id);
+    assert_eq!(completed.len(), 1);
+    assert_eq!(completed[0].id, todo3.id);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn only_open_past_due_todos_are_overdue(pool: PgPool) -> Result<(), TodoFeatureError> {
+    // Given a user with past-due, completed past-due, future and undated todos
+    let user_id = create_test_user(&pool, "overdue@example.com").await;
+    let now = OffsetDateTime::now_utc();
+
+    let late = TodoService::create(
+        &pool,
+        CreateTodoInput {
+            user_id,
+            title: "Late".to_string(),
+            description: None,
+            due_date: Some(now - Duration::days(1)),
+        },
+    )
+    .await?;
+    let done = TodoService::create(
+        &pool,
+        CreateTodoInput {
+            user_id,
+            title: "Late but done".to_string(),
+            description: None,
+            due_date: Some(now - Duration::days(2)),
+        },
+    )
+    .await?;
+    TodoService::complete(&pool, done.id).await?;
+    TodoService::create(
+        &pool,
+        CreateTodoInput {
+            user_id,
+            title: "Upcoming".to_string(),
+            description: None,
+            due_date: Some(now + Duration::days(1)),
+        },
+    )
+    .await?;
+    TodoService::create(
+        &pool,
+        CreateTodoInput {
+            user_id,
+            title: "Whenever".to_string(),
+            description: None,
+            due_date: None,
+        },
+    )
+    .await?;
+
+    // When listing overdue todos
+    let overdue = TodoService::list_overdue(&pool, &SystemClock, user_id).await?;
+
+    // Then only the open past-due todo is returned
+    assert_eq!(overdue.len(), 1);
+    assert_eq!(overdue[0].id, late.id);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn todos_become_overdue_once_the_clock_passes_their_due_date(
+    pool: PgPool,
+) -> Result<(), TodoFeatureError> {
+    // Given a todo due at noon on a fixed day
+    let user_id = create_test_user(&pool, "overdue-clock@example.com").await;
+    let due = Date::from_calendar_date(2030, Month::June, 1)
+        .unwrap()
+        .with_hms(12, 0, 0)
+        .unwrap()
+        .assume_utc();
+    let todo = TodoService::create(
+        &pool,
+        CreateTodoInput {
+            user_id,
+            title: "Due at noon".to_string(),
+            description: None,
+            due_date: Some(due),
+        },
+    )
+    .await?;
+
+    // When the clock reads exactly its due date
+    let at_due = TodoService::list_overdue(&pool, &FixedClock(due), user_id).await?;
+
+    // Then it is not yet overdue
+    assert!(at_due.is_empty());
+    assert!(!todo.is_overdue(due));
+
+    // And one second later it is
+    let later = due + Duration::seconds(1);
+    let overdue = TodoService::list_overdue(&pool, &FixedClock(later), user_id).await?;
+    assert_eq!(overdue.len(), 1);
+    assert_eq!(overdue[0].id, todo.id);
+    assert!(todo.is_overdue(later));
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn todos_due_in_a_window_exclude_its_end_and_completed_ones(
+    pool: PgPool,
+) -> Result<(), TodoFeatureError> {
+    // Given todos due inside a window, exactly at its end, and completed inside it
+    let user_id = create_test_user(&pool, "due-between@example.com").await;
+    let from = OffsetDateTime::now_utc();
+    let to = from + Duration::days(1);
+
+    let due = |title: &str, due_date| CreateTodoInput {
+        user_id,
+        title: title.to_string(),
+        description: None,
+        due_date: Some(due_date),
+    };
+    let inside = TodoService::create(&pool, due("Inside", from + Duration::hours(2))).await?;
+    TodoService::create(&pool, due("At the end", to)).await?;
+    let done = TodoService::create(&pool, due("Done", from + Duration::hours(1))).await?;
+    TodoService::complete(&pool, done.id).await?;
+
+    // When listing todos due in the window
+    let todos = TodoService::list_due_between(&pool, user_id, from, to).await?;
+
+    // Then only the open todo strictly inside it is returned
+    assert_eq!(todos.len(), 1);
+    assert_eq!(todos[0].id, inside.id);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn todos_can_be_searched_by_text_and_status_together(
+    pool: PgPool,
+) -> Result<(), TodoFeatureError> {
+    // Given a user with matching todos in different statuses
+    let user_id = create_test_user(&pool, "search@example.com").await;
+
+    let pending = TodoService::create(
+        &pool,
+        CreateTodoInput {
+            user_id,
+            title: "Call the plumber".to_string(),
+            description: None,
+            due_date: None,
+        },
+    )
+    .await?;
+
+    let done = TodoService::create(
+        &pool,
+        CreateTodoInput {
+            user_id,
+            title: "Call the bank".to_string(),
+            description: None,
+            due_date: None,
+        },
+    )
+    .await?;
+    TodoService::complete(&pool, done.id).await?;
+
+    TodoService::create(
+        &pool,
+        CreateTodoInput {
+            user_id,
+            title: "Water the plants".to_string(),
+            description: None,
+            due_date: None,
+        },
+    )
+    .await?;
+
+    // When searching by text and status at once
+    let filter = TodoFilter {
+        statuses: vec![TodoStatus::Pending],
+        query: Some("call".to_string()),
+        ..Default::default()
+    };
+    let page = TodoService::find(&pool, user_id, filter, 0, 10).await?;
+
+    // Then only todos matching both filters are returned
+    assert_eq!(page.todos.len(), 1);
+    assert_eq!(page.todos[0].id, pending.id);
+    assert!(!page.has_next_page);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn search_reports_when_more_pages_follow(pool: PgPool) -> Result<(), TodoFeatureError> {
+    let user_id = create_test_user(&pool, "search-pages@example.com").await;
+
+    for title in ["Task 1", "Task 2", "Task 3"] {
+        TodoService::create(
+            &pool,
+            CreateTodoInput {
+                user_id,
+                title: title.to_string(),
+                description: None,
+                due_date: None,
+            },
+        )
+        .await?;
+    }
+
+    let first = TodoService::find(&pool, user_id, TodoFilter::default(), 0, 2).await?;
+    assert_eq!(first.todos.len(), 2);
+    assert!(first.has_next_page);
+
+    let last = TodoService::find(&pool, user_id, TodoFilter::default(), 2, 2).await?;
+    assert_eq!(last.todos.len(), 1);
+    assert!(!last.has_next_page);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn paging_by_status_visits_every_matching_todo_once(
+    pool: PgPool,
+) -> Result<(), TodoFeatureError> {
+    // Given a user with many pending todos, all created in one statement so
+    // they share a timestamp, and one completed todo
+    let user_id = create_test_user(&pool, "status-pages@example.com").await;
+    let items = (1..=12)
+        .map(|i| CreateTodoItem {
+            title: format!("Task {i}"),
+            description: None,
+            due_date: None,
+        })
+        .collect();
+    let todos = TodoService::create_many(&pool, user_id, items).await?;
+    TodoService::complete(&pool, todos[0].id).await?;
+
+    // When paging through their pending todos five at a time
+    let mut seen = Vec::new();
+    let mut after = None;
+    let mut pages = 0;
+    loop {
+        let page = TodoService::list_for_user_by_status_paginated(
+            &pool,
+            user_id,
+            TodoStatus::Pending,
+            after.as_deref(),
+            5,
+        )
+        .await?;
+        pages += 1;
+        seen.extend(page.items.iter().map(|todo| todo.id));
+        if !page.has_next_page {
+            break;
+        }
+        after = page.end_cursor().map(|cursor| cursor.encode());
+    }
+
+    // Then every pending todo comes back exactly once, across three pages
+    let mut expected: Vec<Uuid> = todos[1..].iter().map(|todo| todo.id).collect();
+    expected.sort();
+    let mut unique = seen.clone();
+    unique.sort();
+    unique.dedup();
+    assert_eq!(seen.len(), 11);
+    assert_eq!(unique, expected);
+    assert_eq!(pages, 3);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn status_summary_counts_todos_in_each_status(pool: PgPool) -> Result<(), TodoFeatureError> {
+    // Given a user with three pending, one in-progress and two completed todos
+    let user_id = create_test_user(&pool, "summary@example.com").await;
+    let items = (1..=6)
+        .map(|i| CreateTodoItem {
+            title: format!("Task {i}"),
+            description: None,
+            due_date: None,
+        })
+        .collect();
+    let todos = TodoService::create_many(&pool, user_id, items).await?;
+    TodoService::start(&pool, todos[0].id).await?;
+    TodoService::complete(&pool, todos[1].id).await?;
+    TodoService::complete(&pool, todos[2].id).await?;
+    // And another user's todo that must not be counted
+    let other_id = create_test_user(&pool, "summary-other@example.com").await;
+    TodoService::create(
+        &pool,
+        CreateTodoInput {
+            user_id: other_id,
+            title: "Not mine".to_string(),
+            description: None,
+            due_date: None,
+        },
+    )
+    .await?;
+
+    // When summarizing their todos
+    let summary = TodoService::status_summary(&pool, user_id).await?;
+
+    // Then each status has its count, and unused statuses are zero
+    assert_eq!(summary.pending, 3);
+    assert_eq!(summary.in_progress, 1);
+    assert_eq!(summary.completed, 2);
+    assert_eq!(summary.cancelled, 0);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn recently_updated_todo_comes_first(pool: PgPool) -> Result<(), TodoFeatureError> {
+    // Given a user with three todos, created oldest first
+    let user_id = create_test_user(&pool, "recent@example.com").await;
+    let mut todos = Vec::new();
+    for title in ["Oldest", "Middle", "Newest"] {
+        todos.push(create_with_text(&pool, user_id, title.to_string(), None).await?);
+    }
+
+    // When the oldest one is updated
+    TodoService::update(
+        &pool,
+        todos[0].id,
+        UpdateTodoInput {
+            title: Some("Oldest, edited".to_string()),
+            description: None,
+            status: None,
+            expected_updated_at: None,
+        },
+    )
+    .await?;
+
+    // Then it leads the recent list, ahead of newer todos
+    let recent = TodoService::list_recent(&pool, user_id, 10).await?;
+    let titles: Vec<&str> = recent.iter().map(|t| t.title.as_str()).collect();
+    assert_eq!(titles, ["Oldest, edited", "Newest", "Middle"]);
+    Ok(())
+}
+
+// =============================================================================
+// Todo Filter Behaviors
+// =============================================================================
+
+/// Todos created oldest to newest for the filter tests
+struct FilterFixture {
+    user_id: Uuid,
+    milk: domain::Todo,
+    report: domain::Todo,
+    stamps: domain::Todo,
+}
+
+/// A user with a pending "Buy milk" tagged groceries and due tomorrow, an
+/// in-progress "Write report" due in ten days, and a completed "Buy stamps"
+/// with no due date
+async fn filter_fixture(pool: &PgPool) -> Result<FilterFixture, TodoFeatureError> {
+    let user_id = create_test_user(pool, "filter@example.com").await;
+    let now = OffsetDateTime::now_utc();
+    let create = |title: &str, due_date| CreateTodoInput {
+        user_id,
+        title: title.to_string(),
+        description: None,
+        due_date,
+    };
+
+    let milk = TodoService::create(pool, create("Buy milk", Some(now + Duration::days(1)))).await?;
+    TodoService::add_tag(pool, milk.id, "groceries").await?;
+    let report =
+        TodoService::create(pool, create("Write report", Some(now + Duration::days(10)))).await?;
+    TodoService::start(pool, report.id).await?;
+    let stamps = TodoService::create(pool, create("Buy stamps", None)).await?;
+    TodoService::complete(pool, stamps.id).await?;
+
+    Ok(FilterFixture {
+        user_id,
+        milk,
+        report,
+        stamps,
+    })
+}
+
+/// Titles of the user's todos matching `filter`, newest first
+async fn find_titles(
+    pool: &PgPool,
+    user_id: Uuid,
+    filter: TodoFilter,
+) -> Result<Vec<String>, TodoFeatureError> {
+    let page = TodoService::find(pool, user_id, filter, 0, 20).await?;
+    Ok(page.todos.into_iter().map(|todo| todo.title).collect())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn empty_filter_finds_every_todo(pool: PgPool) -> Result<(), TodoFeatureError> {
+    let fixture = filter_fixture(&pool).await?;
+
+    let titles = find_titles(&pool, fixture.user_id, TodoFilter::default()).await?;
+
+    assert_eq!(titles, ["Buy stamps", "Write report", "Buy milk"]);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn find_filters_by_status(pool: PgPool) -> Result<(), TodoFeatureError> {
+    let fixture = filter_fixture(&pool).await?;
+
+    let filter = TodoFilter {
+        statuses: vec![TodoStatus::InProgress],
+        ..Default::default()
+    };
+    let titles = find_titles(&pool, fixture.user_id, filter).await?;
+
+    assert_eq!(titles, ["Write report"]);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn find_filters_by_text(pool: PgPool) -> Result<(), TodoFeatureError> {
+    let fixture = filter_fixture(&pool).await?;
+
+    let filter = TodoFilter {
+        query: Some("BUY".to_string()),
+        ..Default::default()
+    };
+    let titles = find_titles(&pool, fixture.user_id, filter).await?;
+
+    assert_eq!(titles, ["Buy stamps", "Buy milk"]);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn find_filters_by_tag(pool: PgPool) -> Result<(), TodoFeatureError> {
+    let fixture = filter_fixture(&pool).await?;
+
+    // The tag is normalized the same way as when it was added
+    let filter = TodoFilter {
+        tag: Some(" Groceries ".to_string()),
+        ..Default::default()
+    };
+    let titles = find_titles(&pool, fixture.user_id, filter).await?;
+
+    assert_eq!(titles, ["Buy milk"]);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn find_filters_by_creation_date(pool: PgPool) -> Result<(), TodoFeatureError> {
+    let fixture = filter_fixture(&pool).await?;
+
+    // The lower bound is inclusive and the upper bound exclusive
+    let after = TodoFilter {
+        created_after: Some(fixture.report.created_at),
+        ..Default::default()
+    };
+    let before = TodoFilter {
+        created_before: Some(fixture.stamps.created_at),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        find_titles(&pool, fixture.user_id, after).await?,
+        ["Buy stamps", "Write report"]
+    );
+    assert_eq!(
+        find_titles(&pool, fixture.user_id, before).await?,
+        ["Write report", "Buy milk"]
+    );
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn find_filters_by_due_date(pool: PgPool) -> Result<(), TodoFeatureError> {
+    let fixture = filter_fixture(&pool).await?;
+
+    // Todos without a due date never match
+    let filter = TodoFilter {
+        due_before: Some(OffsetDateTime::now_utc() + Duration::days(5)),
+        ..Default::default()
+    };
+    let titles = find_titles(&pool, fixture.user_id, filter).await?;
+
+    assert_eq!(titles, ["Buy milk"]);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn text_and_status_filters_combine(pool: PgPool) -> Result<(), TodoFeatureError> {
+    let fixture = filter_fixture(&pool).await?;
+
+    let filter = TodoFilter {
+        statuses: vec![TodoStatus::Pending],
+        query: Some("buy".to_string()),
+        ..Default::default()
+    };
+    let titles = find_titles(&pool, fixture.user_id, filter).await?;
+
+    assert_eq!(titles, ["Buy milk"]);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn creation_and_due_date_filters_combine(pool: PgPool) -> Result<(), TodoFeatureError> {
+    let fixture = filter_fixture(&pool).await?;
+
+    let filter = TodoFilter {
+        created_after: Some(fixture.report.created_at),
+        due_before: Some(OffsetDateTime::now_utc() + Duration::days(30)),
+        ..Default::default()
+    };
+    let titles = find_titles(&pool, fixture.user_id, filter).await?;
+
+    assert_eq!(titles, ["Write report"]);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn blank_tag_filter_is_rejected(pool: PgPool) {
+    let user_id = create_test_user(&pool, "filter-blank@example.com").await;
+
+    let filter = TodoFilter {
+        tag: Some("  ".to_string()),
+        ..Default::default()
+    };
+    let result = TodoService::find(&pool, user_id, filter, 0, 20).await;
+
+    assert!(matches!(result, Err(TodoFeatureError::InvalidTag(_))));
+}
+
+// =============================================================================
+// Todo Update Behaviors
+// =============================================================================
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn todo_title_can_be_updated(pool: PgPool) -> Result<(), TodoFeatureError> {
+    // Given a user with a todo
+    let user_id = create_test_user(&pool, "update-title@example.com").await;
+    let created = TodoService::create(
+        &pool,
+        CreateTodoInput {
+            user_id,
+            title: "Original".to_string(),
+            description: Some("Original desc".to_string()),
+            due_date: None,
+        },
+    )
+    .await?;
+
+    // When updating the title
+    let updated = TodoService::update(
+        &pool,
+        created.id,
+        UpdateTodoInput {
+            title: Some("New Title".to_string()),
+            description: None,
+            status: None,
+            expected_updated_at: None,
+        },
+    )
+    .await?;
+
+    // Then the title is changed
+    assert_eq!(updated.title, "New Title");
+    // And the description is preserved
+    assert_eq!(updated.description, Some("Original desc".to_string()));
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn todo_description_can_be_updated(pool: PgPool) -> Result<(), TodoFeatureError> {
+    let user_id = create_test_user(&pool, "update-desc@example.com").await;
+    let created = TodoService::create(
+        &pool,
+        CreateTodoInput {
+            user_id,
+            title: "Title".to_string(),
+            description: Some("Old".to_string()),
+            due_date: None,
+        },
+    )
+    .await?;
+
+    let updated = TodoService::update(
+        &pool,
+        created.id,
+        UpdateTodoInput {
+            title: None,
+            description: Some("New description".to_string()),
+            status: None,
+            expected_updated_at: None,
+        },
+    )
+    .await?;
+
+    assert_eq!(updated.title, "Title"); // Unchanged
+    assert_eq!(updated.description, Some("New description".to_string()));
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn todo_status_can_be_updated_directly(pool: PgPool) -> Result<(), TodoFeatureError> {
+    let user_id = create_test_user(&pool, "update-status@example.com").await;
+    let created = TodoService::create(
+        &pool,
+        CreateTodoInput {
+            user_id,
+            title: "Status Task".to_string(),
+            description: None,
+            due_date: None,
+        },
+    )
+    .await?;
+
+    assert_eq!(created.status, TodoStatus::Pending);
+
+    let updated = TodoService::update(
+        &pool,
+        created.id,
+        UpdateTodoInput {
+            title: None,
+            description: None,
+            status: Some(TodoStatus::InProgress),
+            expected_updated_at: None,
+        },
+    )
+    .await?;
+
+    assert_eq!(updated.status, TodoStatus::InProgress);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn todo_content_and_status_can_be_updated_together(
+    pool: PgPool,
+) -> Result<(), TodoFeatureError> {
+    // Given a pending todo
+    let user_id = create_test_user(&pool, "update-all@example.com").await;
+    let created = TodoService::create(
+        &pool,
+        CreateTodoInput {
+            user_id,
+            title: "Old title".to_string(),
+            description: Some("Old description".to_string()),
+            due_date: None,
+        },
+    )
+    .await?;
+
+    // When updating title, description and status in one call
+    let updated = TodoService::update(
+        &pool,
+        created.id,
+        UpdateTodoInput {
+            title: Some("New title".to_string()),
+            description: Some("New description".to_string()),
+            status: Some(TodoStatus::InProgress),
+            expected_updated_at: None,
+        },
+    )
+    .await?;
+
+    // Then every field changed
+    assert_eq!(updated.title, "New title");
+    assert_eq!(updated.description, Some("New description".to_string()));
+    assert_eq!(updated.status, TodoStatus::InProgress);
+    // And the changes were persisted
+    let stored = TodoService::get(&pool, created.id).await?;
+    assert_eq!(stored, updated);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn update_rejects_overlong_title(pool: PgPool) -> Result<(), TodoFeatureError> {
+    // Given a todo
+    let user_id = create_test_user(&pool, "update-long@example.com").await;
+    let created = create_with_text(&pool, user_id, "Short".to_string(), None).await?;
+
+    // When its title is changed to one past the limit
+    let result = TodoService::update(
+        &pool,
+        created.id,
+        UpdateTodoInput {
+            title: Some("t".repeat(TodoLimits::default().title_max + 1)),
+            description: None,
+            status: None,
+            expected_updated_at: None,
+        },
+    )
+    .await;
+
+    // Then the update is rejected and the title is unchanged
+    assert!(matches!(result, Err(TodoFeatureError::Validation(_))));
+    assert_eq!(TodoService::get(&pool, created.id).await?.title, "Short");
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn unchanged_status_does_not_block_content_update(
+    pool: PgPool,
+) -> Result<(), TodoFeatureError> {
+    // Given an in-progress todo
+    let user_id = create_test_user(&pool, "same-status-title@example.com").await;
+    let created = create_with_text(&pool, user_id, "Draft".to_string(), None).await?;
+    TodoService::start(&pool, created.id).await?;
+
+    // When updating its title while passing the status it already has
+    let updated = TodoService::update(
+        &pool,
+        created.id,
+        UpdateTodoInput {
+            title: Some("Final".to_string()),
+            description: Some("Ready".to_string()),
+            status: Some(TodoStatus::InProgress),
+            expected_updated_at: None,
+        },
+    )
+    .await?;
+
+    // Then the content changes and the status stays put
+    assert_eq!(updated.title, "Final");
+    assert_eq!(updated.description.as_deref(), Some("Ready"));
+    assert_eq!(updated.status, TodoStatus::InProgress);
+    assert_eq!(TodoService::get(&pool, created.id).await?, updated);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn unchanged_status_alone_is_a_no_op(pool: PgPool) -> Result<(), TodoFeatureError> {
+    // Given a pending todo
+    let user_id = create_test_user(&pool, "same-status-only@example.com").await;
+    let created = create_with_text(&pool, user_id, "Idle".to_string(), None).await?;
+
+    // When updating it with only the status it already has
+    let updated = TodoService::update(
+        &pool,
+        created.id,
+        UpdateTodoInput {
+            title: None,
+            description: None,
+            status: Some(TodoStatus::Pending),
+            expected_updated_at: None,
+        },
+    )
+    .await?;
+
+    // Then nothing is written, not even the update time
+    assert_eq!(updated, created);
+    assert_eq!(
+        TodoService::get(&pool, created.id).await?.updated_at,
+        created.updated_at
+    );
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn updating_nonexistent_todo_fails(pool: PgPool) -> Result<(), TodoFeatureError> {
+    let result = TodoService::update(
+        &pool,
+        Uuid::new_v4(),
+        UpdateTodoInput {
+            title: Some("New".to_string()),
+            description: None,
+            status: None,
+            expected_updated_at: None,
+        },
+    )
+    .await;
+
+    assert!(matches!(result, Err(TodoFeatureError::NotFound(_))));
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn update_with_stale_timestamp_is_a_conflict(pool: PgPool) -> Result<(), TodoFeatureError> {
+    // Given a todo that was changed after it was read
+    let user_id = create_test_user(&pool, "stale@example.com").await;
+    let created = TodoService::create(
+        &pool,
+        CreateTodoInput {
+            user_id,
+            title: "First".to_string(),
+            description: None,
+            due_date: None,
+        },
+    )
+    .await?;
+    let stale = created.updated_at - Duration::seconds(1);
+
+    // When updating with the stale timestamp
+    let result = TodoService::update(
+        &pool,
+        created.id,
+        UpdateTodoInput {
+            title: Some("Second".to_string()),
+            description: None,
+            status: None,
+            expected_updated_at: Some(stale),
+        },
+    )
+    .await;
+
+    // Then the update is rejected and the title is unchanged
+    assert!(matches!(result, Err(TodoFeatureError::Conflict(id)) if id == created.id));
+    assert_eq!(TodoService::get(&pool, created.id).await?.title, "First");
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn update_with_current_timestamp_succeeds(pool: PgPool) -> Result<(), TodoFeatureError> {
+    // Given a todo
+    let user_id = create_test_user(&pool, "current@example.com").await;
+    let created = TodoService::create(
+        &pool,
+        CreateTodoInput {
+            user_id,
+            title: "First".to_string(),
+            description: None,
+            due_date: None,
+        },
+    )
+    .await?;
+
+    // When updating status and title with its current timestamp
+    let updated = TodoService::update(
+        &pool,
+        created.id,
+        UpdateTodoInput {
+            title: Some("Second".to_string()),
+            description: None,
+            status: Some(TodoStatus::InProgress),
+            expected_updated_at: Some(created.updated_at),
+        },
+    )
+    .await?;
+
+    // Then both changes land
+    assert_eq!(updated.title, "Second");
+    assert_eq!(updated.status, TodoStatus::InProgress);
+    Ok(())
+}
+
+// =============================================================================
+// Todo Status Transition Behaviors
+// =============================================================================
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn todo_can_be_marked_as_completed(pool: PgPool) -> Result<(), TodoFeatureError> {
+    let user_id = create_test_user(&pool, "complete@example.com").await;
+    let created = TodoService::create(
+        &pool,
+        CreateTodoInput {
+            user_id,
+            title: "Complete Me".to_string(),
+            description: None,
+            due_date: None,
+        },
+    )
+    .await?;
+
+    let completed = TodoService::complete(&pool, created.id).await?;
+
+    assert_eq!(completed.status, TodoStatus::Completed);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn completing_nonexistent_todo_fails(pool: PgPool) -> Result<(), TodoFeatureError> {
+    let result = TodoService::complete(&pool, Uuid::new_v4()).await;
+
+    assert!(matches!(result, Err(TodoFeatureError::NotFound(_))));
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn completing_todo_records_completed_event(pool: PgPool) -> Result<(), TodoFeatureError> {
+    // Given a pending todo
+    let user_id = create_test_user(&pool, "complete-event@example.com").await;
+    let created = TodoService::create(
+        &pool,
+        CreateTodoInput {
+            user_id,
+            title: "Announce Me".to_string(),
+            description: None,
+            due_date: None,
+        },
+    )
+    .await?;
+
+    // When it is completed
+    TodoService::complete(&pool, created.id).await?;
+
+    // Then a todo.completed event waits in the outbox
+    let payloads: Vec<serde_json::Value> =
+        sqlx::query_scalar("SELECT payload_json FROM outbox WHERE event_type = 'todo.completed'")
+            .fetch_all(&pool)
+            .await
+            .map_err(domain::DomainError::from)?;
+
+    assert_eq!(
+        payloads,
+        vec![serde_json::json!({ "todo_id": created.id, "user_id": user_id })]
+    );
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn bulk_completing_todos_records_an_event_for_each(
+    pool: PgPool,
+) -> Result<(), TodoFeatureError> {
+    // Given two pending todos
+    let user_id = create_test_user(&pool, "bulk-event@example.com").await;
+    let mut ids = Vec::new();
+    for title in ["First", "Second"] {
+        let todo = TodoService::create(
+            &pool,
+            CreateTodoInput {
+                user_id,
+                title: title.to_string(),
+                description: None,
+                due_date: None,
+            },
+        )
+        .await?;
+        ids.push(todo.id);
+    }
+
+    // When both are completed at once
+    TodoService::update_status_many(&pool, user_id, ids.clone(), TodoStatus::Completed).await?;
+
+    // Then each has a todo.completed event in the outbox
+    let mut completed: Vec<Uuid> = sqlx::query_scalar(
+        "SELECT (payload_json->>'todo_id')::uuid FROM outbox WHERE event_type = 'todo.completed'",
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(domain::DomainError::from)?;
+    completed.sort();
+    ids.sort();
+    assert_eq!(completed, ids);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn todo_can_be_started(pool: PgPool) -> Result<(), TodoFeatureError> {
+    let user_id = create_test_user(&pool, "start@example.com").await;
+    let created = TodoService::create(
+        &pool,
+        CreateTodoInput {
+            user_id,
+            title: "Start Me".to_string(),
+            description: None,
+            due_date: None,
+        },
+    )
+    .await?;
+
+    let started = TodoService::start(&pool, created.id).await?;
+
+    assert_eq!(started.status, TodoStatus::InProgress);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn starting_nonexistent_todo_fails(pool: PgPool) -> Result<(), TodoFeatureError> {
+    let result = TodoService::start(&pool, Uuid::new_v4()).await;
+
+    assert!(matches!(result, Err(TodoFeatureError::NotFound(_))));
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn in_progress_todo_can_be_completed(pool: PgPool) -> Result<(), TodoFeatureError> {
+    // Given a todo that has been started
+    let user_id = create_test_user(&pool, "progress-complete@example.com").await;
+    let created = TodoService::create(
+        &pool,
+        CreateTodoInput {
+            user_id,
+            title: "Finish Me".to_string(),
+            description: None,
+            due_date: None,
+        },
+    )
+    .await?;
+    TodoService::start(&pool, created.id).await?;
+
+    // When completing it
+    let completed = TodoService::complete(&pool, created.id).await?;
+
+    // Then it is completed
+    assert_eq!(completed.status, TodoStatus::Completed);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn completed_todo_cannot_be_started(pool: PgPool) -> Result<(), TodoFeatureError> {
+    // Given a completed todo
+    let user_id = create_test_user(&pool, "restart@example.com").await;
+    let created = TodoService::create(
+        &pool,
+        CreateTodoInput {
+            user_id,
+            title: "Done".to_string(),
+            description: None,
+            due_date: None,
+        },
+    )
+    .await?;
+    TodoService::complete(&pool, created.id).await?;
+
+    // When trying to start it again
+    let result = TodoService::start(&pool, created.id).await;
+
+    // Then the transition is rejected
+    assert!(matches!(
+        result,
+        Err(TodoFeatureError::InvalidTransition {
+            from: TodoStatus::Completed,
+            to: TodoStatus::InProgress,
+        })
+    ));
+    // And the status is unchanged
+    let todo = TodoService::get(&pool, created.id).await?;
+    assert_eq!(todo.status, TodoStatus::Completed);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn completed_todo_cannot_be_completed_again(pool: PgPool) -> Result<(), TodoFeatureError> {
+    let user_id = create_test_user(&pool, "double-complete@example.com").await;
+    let created = TodoService::create(
+        &pool,
+        CreateTodoInput {
+            user_id,
+            title: "Done".to_string(),
+            description: None,
+            due_date: None,
+        },
+    )
+    .await?;
+    TodoService::complete(&pool, created.id).await?;
+
+    let result = TodoService::complete(&pool, created.id).await;
+
+    assert!(matches!(
+        result,
+        Err(TodoFeatureError::InvalidTransition { .. })
+    ));
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn status_update_cannot_move_todo_backwards(pool: PgPool) -> Result<(), TodoFeatureError> {
+    // Given a todo that is in progress
+    let user_id = create_test_user(&pool, "backwards@example.com").await;
+    let created = TodoService::create(
+        &pool,
+        CreateTodoInput {
+            user_id,
+            title: "Working".to_string(),
+            description: None,
+            due_date: None,
+        },
+    )
+    .await?;
+    TodoService::start(&pool, created.id).await?;
+
+    // When updating its status back to pending
+    let result = TodoService::update(
+        &pool,
+        created.id,
+        UpdateTodoInput {
+            title: None,
+            description: None,
+            status: Some(TodoStatus::Pending),
+            expected_updated_at: None,
+        },
+    )
+    .await;
+
+    // Then the transition is rejected
+    assert!(matches!(
+        result,
+        Err(TodoFeatureError::InvalidTransition {
+            from: TodoStatus::InProgress,
+            to: TodoStatus::Pending,
+        })
+    ));
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn status_update_cannot_reset_completed_todo(pool: PgPool) -> Result<(), TodoFeatureError> {
+    // Given a completed todo
+    let user_id = create_test_user(&pool, "reset-completed@example.com").await;
+    let created = TodoService::create(
+        &pool,
+        CreateTodoInput {
+            user_id,
+            title: "Done".to_string(),
+            description: None,
+            due_date: None,
+        },
+    )
+    .await?;
+    TodoService::complete(&pool, created.id).await?;
+
+    // When updating its status straight back to pending
+    let result = TodoService::update(
+        &pool,
+        created.id,
+        UpdateTodoInput {
+            title: None,
+            description: None,
+            status: Some(TodoStatus::Pending),
+            expected_updated_at: None,
+        },
+    )
+    .await;
+
+    // Then the transition is rejected; only `reopen` may do that
+    assert!(matches!(
+        result,
+        Err(TodoFeatureError::InvalidTransition {
+            from: TodoStatus::Completed,
+            to: TodoStatus::Pending,
+        })
+    ));
+    // And the status is unchanged
+    let todo = TodoService::get(&pool, created.id).await?;
+    assert_eq!(todo.status, TodoStatus::Completed);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn completed_todo_can_be_reopened(pool: PgPool) -> Result<(), TodoFeatureError> {
+    // Given a completed todo
+    let user_id = create_test_user(&pool, "reopen@example.com").await;
+    let created = TodoService::create(
+        &pool,
+        CreateTodoInput {
+            user_id,
+            title: "Not quite done".to_string(),
+            description: None,
+            due_date: None,
+        },
+    )
+    .await?;
+    TodoService::complete(&pool, created.id).await?;
+
+    // When reopening it
+    let reopened = TodoService::reopen(&pool, created.id).await?;
+
+    // Then it is pending again
+    assert_eq!(reopened.status, TodoStatus::Pending);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn pending_todo_cannot_be_reopened(pool: PgPool) -> Result<(), TodoFeatureError> {
+    let user_id = create_test_user(&pool, "reopen-pending@example.com").await;
+    let created = TodoService::create(
         &pool,
         CreateTodoInput {
             user_id,
-            title: "Task 1".to_string(),
+            title: "Still open".to_string(),
             description: None,
+            due_date: None,
         },
     )
     .await?;
 
-    TodoService::create(
+    let result = TodoService::reopen(&pool, created.id).await;
+
+    assert!(matches!(
+        result,
+        Err(TodoFeatureError::InvalidTransition {
+            from: TodoStatus::Pending,
+            to: TodoStatus::Pending,
+        })
+    ));
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn concurrent_reopens_reopen_once(pool: PgPool) -> Result<(), TodoFeatureError> {
+    // Given a completed todo
+    let user_id = create_test_user(&pool, "reopen-race@example.com").await;
+    let created = TodoService::create(
         &pool,
         CreateTodoInput {
             user_id,
-            title: "Task 2".to_string(),
+            title: "Done twice".to_string(),
             description: None,
+            due_date: None,
         },
     )
     .await?;
+    TodoService::complete(&pool, created.id).await?;
+
+    // When two reopens race
+    let (first, second) = tokio::join!(
+        TodoService::reopen(&pool, created.id),
+        TodoService::reopen(&pool, created.id)
+    );
+
+    // Then one reopens it and the other sees it is already pending
+    let (ok, err): (Vec<_>, Vec<_>) = [first, second].into_iter().partition(Result::is_ok);
+    assert_eq!(ok.len(), 1);
+    assert!(matches!(
+        err[0],
+        Err(TodoFeatureError::InvalidTransition {
+            from: TodoStatus::Pending,
+            to: TodoStatus::Pending,
+        })
+    ));
+    Ok(())
+}
 
-    TodoService::create(
+#[sqlx::test(migrations = "../../../migrations")]
+async fn pending_todo_can_be_cancelled(pool: PgPool) -> Result<(), TodoFeatureError> {
+    // Given a pending todo
+    let user_id = create_test_user(&pool, "cancel@example.com").await;
+    let created = TodoService::create(
         &pool,
         CreateTodoInput {
             user_id,
-            title: "Task 3".to_string(),
+            title: "Abandon Me".to_string(),
             description: None,
+            due_date: None,
         },
     )
     .await?;
 
-    // When listing todos for the user
-    let todos = TodoService::list_for_user(&pool, user_id).await?;
-
-    // Then all todos are returned
-    assert_eq!(todos.len(), 3);
-    Ok(())
-}
-
-#[sqlx::test(migrations = "../../../migrations")]
-async fn listing_todos_when_none_exist_returns_empty(pool: PgPool) -> Result<(), TodoFeatureError> {
-    let user_id = create_test_user(&pool, "empty-todos@example.com").await;
-
-    let todos = TodoService::list_for_user(&pool, user_id).await?;
+    // When cancelling it
+    let cancelled = TodoService::cancel(&pool, created.id).await?;
 
-    assert!(todos.is_empty());
+    // Then it is cancelled
+    assert_eq!(cancelled.status, TodoStatus::Cancelled);
     Ok(())
 }
 
 #[sqlx::test(migrations = "../../../migrations")]
-async fn users_only_see_their_own_todos(pool: PgPool) -> Result<(), TodoFeatureError> {
-    // Given two users with their own todos
-    let user1 = create_test_user(&pool, "user1@example.com").await;
-    let user2 = create_test_user(&pool, "user2@example.com").await;
-
-    TodoService::create(
+async fn cancelled_todos_can_be_listed_by_status(pool: PgPool) -> Result<(), TodoFeatureError> {
+    // Given a user with one cancelled and one pending todo
+    let user_id = create_test_user(&pool, "cancel-list@example.com").await;
+    let cancelled = TodoService::create(
         &pool,
         CreateTodoInput {
-            user_id: user1,
-            title: "User 1 Task".to_string(),
+            user_id,
+            title: "Dropped".to_string(),
             description: None,
+            due_date: None,
         },
     )
     .await?;
-
+    TodoService::cancel(&pool, cancelled.id).await?;
     TodoService::create(
         &pool,
         CreateTodoInput {
-            user_id: user2,
-            title: "User 2 Task".to_string(),
+            user_id,
+            title: "Kept".to_string(),
             description: None,
+            due_date: None,
         },
     )
     .await?;
 
-    // When each user lists their todos
-    let user1_todos = TodoService::list_for_user(&pool, user1).await?;
-    let user2_todos = TodoService::list_for_user(&pool, user2).await?;
+    // When listing cancelled todos
+    let todos = TodoService::list_for_user_by_status(&pool, user_id, TodoStatus::Cancelled).await?;
 
-    // Then they only see their own
-    assert_eq!(user1_todos.len(), 1);
-    assert_eq!(user1_todos[0].title, "User 1 Task");
-    assert_eq!(user2_todos.len(), 1);
-    assert_eq!(user2_todos[0].title, "User 2 Task");
+    // Then only the cancelled one is returned
+    assert_eq!(todos.len(), 1);
+    assert_eq!(todos[0].id, cancelled.id);
+    assert_eq!(todos[0].status, TodoStatus::Cancelled);
     Ok(())
 }
 
 #[sqlx::test(migrations = "../../../migrations")]
-async fn todos_can_be_filtered_by_status(pool: PgPool) -> Result<(), TodoFeatureError> {
-    // Given a user with todos in different statuses
-    let user_id = create_test_user(&pool, "status-list@example.com").await;
+async fn several_todos_can_be_completed_at_once(pool: PgPool) -> Result<(), TodoFeatureError> {
+    // Given a user with four pending todos
+    let user_id = create_test_user(&pool, "bulk-status@example.com").await;
+    let mut ids = Vec::new();
+    for title in ["One", "Two", "Three", "Four"] {
+        let todo = TodoService::create(
+            &pool,
+            CreateTodoInput {
+                user_id,
+                title: title.to_string(),
+                description: None,
+                due_date: None,
+            },
+        )
+        .await?;
+        ids.push(todo.id);
+    }
+
+    // When marking the first three as completed
+    let count =
+        TodoService::update_status_many(&pool, user_id, ids[..3].to_vec(), TodoStatus::Completed)
+            .await?;
+
+    // Then three todos were updated
+    assert_eq!(count, 3);
+    for id in &ids[..3] {
+        assert_eq!(
+            TodoService::get(&pool, *id).await?.status,
+            TodoStatus::Completed
+        );
+    }
+    // And the fourth is still pending
+    assert_eq!(
+        TodoService::get(&pool, ids[3]).await?.status,
+        TodoStatus::Pending
+    );
+    Ok(())
+}
 
-    let todo1 = TodoService::create(
+#[sqlx::test(migrations = "../../../migrations")]
+async fn bulk_status_update_skips_illegal_transitions(
+    pool: PgPool,
+) -> Result<(), TodoFeatureError> {
+    // Given one completed and one pending todo
+    let user_id = create_test_user(&pool, "bulk-illegal@example.com").await;
+    let done = TodoService::create(
         &pool,
         CreateTodoInput {
             user_id,
-            title: "Pending".to_string(),
+            title: "Done".to_string(),
             description: None,
+            due_date: None,
         },
     )
     .await?;
-
-    let todo2 = TodoService::create(
+    TodoService::complete(&pool, done.id).await?;
+    let open = TodoService::create(
         &pool,
         CreateTodoInput {
             user_id,
-            title: "In Progress".to_string(),
+            title: "Open".to_string(),
             description: None,
+            due_date: None,
         },
     )
     .await?;
-    TodoService::start(&pool, todo2.id).await?;
 
-    let todo3 = TodoService::create(
+    // When starting both
+    let count = TodoService::update_status_many(
         &pool,
-        CreateTodoInput {
-            user_id,
-            title: "Completed".to_string(),
-            description: None,
-        },
+        user_id,
+        vec![done.id, open.id],
+        TodoStatus::InProgress,
     )
     .await?;
-    TodoService::complete(&pool, todo3.id).await?;
-
-    // When filtering by each status
-    let pending = TodoService::list_for_user_by_status(&pool, user_id, TodoStatus::Pending).await?;
-    let in_progress =
-        TodoService::list_for_user_by_status(&pool, user_id, TodoStatus::InProgress).await?;
-    let completed =
-        TodoService::list_for_user_by_status(&pool, user_id, TodoStatus::Completed).await?;
 
-    // Then only matching todos are returned
-    assert_eq!(pending.len(), 1);
-    assert_eq!(pending[0].id, todo1.id);
-    assert_eq!(in_progress.len(), 1);
-    assert_eq!(in_progress[0].id, todo2.id);
-    assert_eq!(completed.len(), 1);
-    assert_eq!(completed[0].id, todo3.id);
+    // Then only the pending todo moved
+    assert_eq!(count, 1);
+    assert_eq!(
+        TodoService::get(&pool, done.id).await?.status,
+        TodoStatus::Completed
+    );
+    assert_eq!(
+        TodoService::get(&pool, open.id).await?.status,
+        TodoStatus::InProgress
+    );
     Ok(())
 }
 
 // =============================================================================
-// Todo Update Behaviors
+// Todo Time Tracking Behaviors
 // =============================================================================
 
-#[sqlx::test(migrations = "../../../migrations")]
-async fn todo_title_can_be_updated(pool: PgPool) -> Result<(), TodoFeatureError> {
-    // Given a user with a todo
-    let user_id = create_test_user(&pool, "update-title@example.com").await;
-    let created = TodoService::create(
-        &pool,
-        CreateTodoInput {
-            user_id,
-            title: "Original".to_string(),
-            description: Some("Original desc".to_string()),
-        },
+/// Pretend a todo's running clock was started `seconds` ago
+async fn backdate_clock(pool: &PgPool, id: Uuid, seconds: f64) {
+    sqlx::query(
+        "UPDATE todos SET started_at = started_at - make_interval(secs => $1) WHERE id = $2",
     )
-    .await?;
+    .bind(seconds)
+    .bind(id)
+    .execute(pool)
+    .await
+    .expect("Failed to backdate clock");
+}
 
-    // When updating the title
-    let updated = TodoService::update(
-        &pool,
-        created.id,
-        UpdateTodoInput {
-            title: Some("New Title".to_string()),
+/// Create a pending todo for a new user
+async fn create_timed_todo(pool: &PgPool, email: &str) -> Result<Todo, TodoFeatureError> {
+    let user_id = create_test_user(pool, email).await;
+    TodoService::create(
+        pool,
+        CreateTodoInput {
+            user_id,
+            title: "Timed".to_string(),
             description: None,
-            status: None,
+            due_date: None,
         },
     )
-    .await?;
+    .await
+}
 
-    // Then the title is changed
-    assert_eq!(updated.title, "New Title");
-    // And the description is preserved
-    assert_eq!(updated.description, Some("Original desc".to_string()));
+#[sqlx::test(migrations = "../../../migrations")]
+async fn pausing_adds_elapsed_time_to_total(pool: PgPool) -> Result<(), TodoFeatureError> {
+    // Given a todo that was started
+    let todo = create_timed_todo(&pool, "timer-pause@example.com").await?;
+    let started = TodoService::start(&pool, todo.id).await?;
+    assert!(started.started_at.is_some());
+    assert_eq!(started.total_seconds, 0);
+
+    // When some time passes and it is paused
+    backdate_clock(&pool, todo.id, 90.0).await;
+    let paused = TodoService::pause(&pool, todo.id).await?;
+
+    // Then the time is added to the total and the clock is stopped
+    assert!(paused.total_seconds >= 90);
+    assert_eq!(paused.started_at, None);
+    assert_eq!(paused.status, TodoStatus::InProgress);
     Ok(())
 }
 
 #[sqlx::test(migrations = "../../../migrations")]
-async fn todo_description_can_be_updated(pool: PgPool) -> Result<(), TodoFeatureError> {
-    let user_id = create_test_user(&pool, "update-desc@example.com").await;
-    let created = TodoService::create(
-        &pool,
-        CreateTodoInput {
-            user_id,
-            title: "Title".to_string(),
-            description: Some("Old".to_string()),
-        },
-    )
-    .await?;
+async fn completing_running_todo_finalizes_interval(pool: PgPool) -> Result<(), TodoFeatureError> {
+    // Given a todo whose clock has been running for a while
+    let todo = create_timed_todo(&pool, "timer-complete@example.com").await?;
+    TodoService::start(&pool, todo.id).await?;
+    backdate_clock(&pool, todo.id, 60.0).await;
 
-    let updated = TodoService::update(
-        &pool,
-        created.id,
-        UpdateTodoInput {
-            title: None,
-            description: Some("New description".to_string()),
-            status: None,
-        },
-    )
-    .await?;
+    // When it is completed
+    let completed = TodoService::complete(&pool, todo.id).await?;
 
-    assert_eq!(updated.title, "Title"); // Unchanged
-    assert_eq!(updated.description, Some("New description".to_string()));
+    // Then the running interval is counted and the clock stopped
+    assert_eq!(completed.status, TodoStatus::Completed);
+    assert!(completed.total_seconds >= 60);
+    assert_eq!(completed.started_at, None);
     Ok(())
 }
 
 #[sqlx::test(migrations = "../../../migrations")]
-async fn todo_status_can_be_updated_directly(pool: PgPool) -> Result<(), TodoFeatureError> {
-    let user_id = create_test_user(&pool, "update-status@example.com").await;
-    let created = TodoService::create(
+async fn bulk_completing_running_todo_finalizes_interval(
+    pool: PgPool,
+) -> Result<(), TodoFeatureError> {
+    // Given a todo whose clock has been running for a while
+    let todo = create_timed_todo(&pool, "timer-bulk@example.com").await?;
+    TodoService::start(&pool, todo.id).await?;
+    backdate_clock(&pool, todo.id, 60.0).await;
+
+    // When it is completed in bulk
+    TodoService::update_status_many(&pool, todo.user_id, vec![todo.id], TodoStatus::Completed)
+        .await?;
+
+    // Then the running interval is counted and the clock stopped
+    let completed = TodoService::get(&pool, todo.id).await?;
+    assert_eq!(completed.status, TodoStatus::Completed);
+    assert!(completed.total_seconds >= 60);
+    assert_eq!(completed.started_at, None);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn paused_todo_can_be_resumed(pool: PgPool) -> Result<(), TodoFeatureError> {
+    // Given a todo that was started and paused after some time
+    let todo = create_timed_todo(&pool, "timer-resume@example.com").await?;
+    TodoService::start(&pool, todo.id).await?;
+    backdate_clock(&pool, todo.id, 30.0).await;
+    TodoService::pause(&pool, todo.id).await?;
+
+    // When it is started again
+    let resumed = TodoService::start(&pool, todo.id).await?;
+
+    // Then the clock runs again and the earlier time is kept
+    assert!(resumed.started_at.is_some());
+    assert!(resumed.total_seconds >= 30);
+    assert_eq!(resumed.status, TodoStatus::InProgress);
+
+    // And starting it while running is rejected
+    let result = TodoService::start(&pool, todo.id).await;
+    assert!(matches!(
+        result,
+        Err(TodoFeatureError::InvalidTransition { .. })
+    ));
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn pausing_todo_that_is_not_running_fails(pool: PgPool) -> Result<(), TodoFeatureError> {
+    // Given a todo that was never started
+    let todo = create_timed_todo(&pool, "timer-idle@example.com").await?;
+
+    // When pausing it
+    let result = TodoService::pause(&pool, todo.id).await;
+
+    // Then it is rejected and nothing is tracked
+    assert!(matches!(result, Err(TodoFeatureError::Validation(_))));
+    assert_eq!(TodoService::get(&pool, todo.id).await?.total_seconds, 0);
+
+    // And pausing a missing todo is not found
+    let missing = Uuid::new_v4();
+    let result = TodoService::pause(&pool, missing).await;
+    assert!(matches!(result, Err(TodoFeatureError::NotFound(id)) if id == missing));
+    Ok(())
+}
+
+// =============================================================================
+// Todo Tag Behaviors
+// =============================================================================
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn todo_can_be_tagged_with_normalized_tags(pool: PgPool) -> Result<(), TodoFeatureError> {
+    // Given a todo
+    let user_id = create_test_user(&pool, "tag-todo@example.com").await;
+    let todo = TodoService::create(
         &pool,
         CreateTodoInput {
             user_id,
-            title: "Status Task".to_string(),
+            title: "Buy milk".to_string(),
             description: None,
+            due_date: None,
         },
     )
     .await?;
 
-    assert_eq!(created.status, TodoStatus::Pending);
+    // When adding two tags, one of them twice in different case
+    TodoService::add_tag(&pool, todo.id, "Errands").await?;
+    TodoService::add_tag(&pool, todo.id, "home").await?;
+    let tags = TodoService::add_tag(&pool, todo.id, " ERRANDS ").await?;
 
-    let updated = TodoService::update(
+    // Then the todo has both tags, lowercase and without duplicates
+    assert_eq!(tags, vec!["errands", "home"]);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn todos_can_be_filtered_by_tag(pool: PgPool) -> Result<(), TodoFeatureError> {
+    // Given two todos tagged differently
+    let user_id = create_test_user(&pool, "tag-filter@example.com").await;
+    let todos = TodoService::create_many(
         &pool,
-        created.id,
-        UpdateTodoInput {
-            title: None,
-            description: None,
-            status: Some(TodoStatus::InProgress),
-        },
+        user_id,
+        vec![
+            CreateTodoItem {
+                title: "Buy milk".to_string(),
+                description: None,
+                due_date: None,
+            },
+            CreateTodoItem {
+                title: "Write report".to_string(),
+                description: None,
+                due_date: None,
+            },
+        ],
     )
     .await?;
+    TodoService::add_tag(&pool, todos[0].id, "errands").await?;
+    TodoService::add_tag(&pool, todos[0].id, "home").await?;
+    TodoService::add_tag(&pool, todos[1].id, "work").await?;
 
-    assert_eq!(updated.status, TodoStatus::InProgress);
+    // When listing todos tagged "Errands"
+    let tagged = TodoService::list_for_user_by_tag(&pool, user_id, "Errands").await?;
+
+    // Then only the matching todo is returned
+    assert_eq!(tagged.len(), 1);
+    assert_eq!(tagged[0].id, todos[0].id);
     Ok(())
 }
 
 #[sqlx::test(migrations = "../../../migrations")]
-async fn updating_nonexistent_todo_fails(pool: PgPool) -> Result<(), TodoFeatureError> {
-    let result = TodoService::update(
+async fn tag_can_be_removed_from_todo(pool: PgPool) -> Result<(), TodoFeatureError> {
+    // Given a todo with two tags
+    let user_id = create_test_user(&pool, "tag-remove@example.com").await;
+    let todo = TodoService::create(
         &pool,
-        Uuid::new_v4(),
-        UpdateTodoInput {
-            title: Some("New".to_string()),
+        CreateTodoInput {
+            user_id,
+            title: "Buy milk".to_string(),
             description: None,
-            status: None,
+            due_date: None,
         },
     )
-    .await;
+    .await?;
+    TodoService::add_tag(&pool, todo.id, "errands").await?;
+    TodoService::add_tag(&pool, todo.id, "home").await?;
 
-    assert!(matches!(result, Err(TodoFeatureError::NotFound(_))));
+    // When removing one of them
+    let tags = TodoService::remove_tag(&pool, todo.id, "Errands").await?;
+
+    // Then only the other remains, and the todo no longer matches the removed tag
+    assert_eq!(tags, vec!["home"]);
+    let tagged = TodoService::list_for_user_by_tag(&pool, user_id, "errands").await?;
+    assert!(tagged.is_empty());
     Ok(())
 }
 
-// =============================================================================
-// Todo Status Transition Behaviors
-// =============================================================================
-
 #[sqlx::test(migrations = "../../../migrations")]
-async fn todo_can_be_marked_as_completed(pool: PgPool) -> Result<(), TodoFeatureError> {
-    let user_id = create_test_user(&pool, "complete@example.com").await;
-    let created = TodoService::create(
+async fn blank_tag_is_rejected(pool: PgPool) -> Result<(), TodoFeatureError> {
+    // Given a todo
+    let user_id = create_test_user(&pool, "tag-blank@example.com").await;
+    let todo = TodoService::create(
         &pool,
         CreateTodoInput {
             user_id,
-            title: "Complete Me".to_string(),
+            title: "Buy milk".to_string(),
             description: None,
+            due_date: None,
         },
     )
     .await?;
 
-    let completed = TodoService::complete(&pool, created.id).await?;
+    // When adding a blank tag
+    let result = TodoService::add_tag(&pool, todo.id, "   ").await;
 
-    assert_eq!(completed.status, TodoStatus::Completed);
+    // Then it is rejected
+    assert!(matches!(result, Err(TodoFeatureError::InvalidTag(_))));
     Ok(())
 }
 
 #[sqlx::test(migrations = "../../../migrations")]
-async fn completing_nonexistent_todo_fails(pool: PgPool) -> Result<(), TodoFeatureError> {
-    let result = TodoService::complete(&pool, Uuid::new_v4()).await;
+async fn tagging_nonexistent_todo_fails(pool: PgPool) -> Result<(), TodoFeatureError> {
+    let result = TodoService::add_tag(&pool, Uuid::new_v4(), "work").await;
 
     assert!(matches!(result, Err(TodoFeatureError::NotFound(_))));
     Ok(())
 }
 
-#[sqlx::test(migrations = "../../../migrations")]
-async fn todo_can_be_started(pool: PgPool) -> Result<(), TodoFeatureError> {
-    let user_id = create_test_user(&pool, "start@example.com").await;
-    let created = TodoService::create(
-        &pool,
-        CreateTodoInput {
-            user_id,
-            title: "Start Me".to_string(),
-            description: None,
-        },
-    )
-    .await?;
-
-    let started = TodoService::start(&pool, created.id).await?;
+// =============================================================================
+// Todo Ordering Behaviors
+// =============================================================================
 
-    assert_eq!(started.status, TodoStatus::InProgress);
+#[sqlx::test(migrations = "../../../migrations")]
+async fn todos_can_be_reordered(pool: PgPool) -> Result<(), TodoFeatureError> {
+    // Given a user with three todos
+    let user_id = create_test_user(&pool, "reorder@example.com").await;
+    let first = create_with_text(&pool, user_id, "First".to_string(), None).await?;
+    let second = create_with_text(&pool, user_id, "Second".to_string(), None).await?;
+    let third = create_with_text(&pool, user_id, "Third".to_string(), None).await?;
+
+    // When they are put in a new order
+    let reordered =
+        TodoService::reorder(&pool, user_id, vec![second.id, third.id, first.id]).await?;
+
+    // Then listing by position returns that order
+    let expected = vec![second.id, third.id, first.id];
+    assert_eq!(
+        reordered.iter().map(|todo| todo.id).collect::<Vec<_>>(),
+        expected
+    );
+    let listed = TodoService::list_for_user_by_position(&pool, user_id, false).await?;
+    assert_eq!(
+        listed.iter().map(|todo| todo.id).collect::<Vec<_>>(),
+        expected
+    );
     Ok(())
 }
 
 #[sqlx::test(migrations = "../../../migrations")]
-async fn starting_nonexistent_todo_fails(pool: PgPool) -> Result<(), TodoFeatureError> {
-    let result = TodoService::start(&pool, Uuid::new_v4()).await;
-
-    assert!(matches!(result, Err(TodoFeatureError::NotFound(_))));
+async fn reordering_another_users_todo_is_forbidden(pool: PgPool) -> Result<(), TodoFeatureError> {
+    // Given a todo of the user's and one of someone else's
+    let user_id = create_test_user(&pool, "reorder-own@example.com").await;
+    let other_id = create_test_user(&pool, "reorder-other@example.com").await;
+    let own = create_with_text(&pool, user_id, "Mine".to_string(), None).await?;
+    let foreign = create_with_text(&pool, other_id, "Theirs".to_string(), None).await?;
+
+    // When the user tries to reorder both
+    let result = TodoService::reorder(&pool, user_id, vec![own.id, foreign.id]).await;
+
+    // Then the foreign todo is rejected
+    assert!(matches!(result, Err(TodoFeatureError::Forbidden(id)) if id == foreign.id));
     Ok(())
 }
 
@@ -453,6 +2667,7 @@ async fn todo_can_be_deleted(pool: PgPool) -> Result<(), TodoFeatureError> {
             user_id,
             title: "Delete Me".to_string(),
             description: None,
+            due_date: None,
         },
     )
     .await?;
@@ -466,6 +2681,97 @@ async fn todo_can_be_deleted(pool: PgPool) -> Result<(), TodoFeatureError> {
     Ok(())
 }
 
+#[sqlx::test(migrations = "../../../migrations")]
+async fn clearing_completed_removes_only_completed_todos(
+    pool: PgPool,
+) -> Result<(), TodoFeatureError> {
+    // Given a user with two completed todos and one pending todo
+    let user_id = create_test_user(&pool, "clear-completed@example.com").await;
+    let items = ["Done 1", "Done 2", "Still to do"]
+        .into_iter()
+        .map(|title| CreateTodoItem {
+            title: title.to_string(),
+            description: None,
+            due_date: None,
+        })
+        .collect();
+    let todos = TodoService::create_many(&pool, user_id, items).await?;
+    TodoService::complete(&pool, todos[0].id).await?;
+    TodoService::complete(&pool, todos[1].id).await?;
+
+    // When clearing completed todos
+    let removed = TodoService::clear_completed(&pool, user_id).await?;
+
+    // Then both completed todos are deleted and the pending one remains
+    assert_eq!(removed, 2);
+    let remaining = TodoService::list_for_user(&pool, user_id, false).await?;
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].id, todos[2].id);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn deleted_todo_is_hidden_unless_asked_for(pool: PgPool) -> Result<(), TodoFeatureError> {
+    // Given a user with a deleted todo and a live one
+    let user_id = create_test_user(&pool, "soft-delete@example.com").await;
+    let items = ["Deleted", "Kept"]
+        .into_iter()
+        .map(|title| CreateTodoItem {
+            title: title.to_string(),
+            description: None,
+            due_date: None,
+        })
+        .collect();
+    let todos = TodoService::create_many(&pool, user_id, items).await?;
+    TodoService::delete(&pool, todos[0].id).await?;
+
+    // When listing normally, only the live todo is returned
+    let listed = TodoService::list_for_user(&pool, user_id, false).await?;
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].id, todos[1].id);
+
+    // And when including deleted todos, both are, with the deletion marked
+    let listed = TodoService::list_for_user(&pool, user_id, true).await?;
+    assert_eq!(listed.len(), 2);
+    let deleted = listed.iter().find(|t| t.id == todos[0].id).unwrap();
+    assert!(deleted.deleted_at.is_some());
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn restored_todo_reappears(pool: PgPool) -> Result<(), TodoFeatureError> {
+    // Given a deleted todo
+    let user_id = create_test_user(&pool, "restore@example.com").await;
+    let created = TodoService::create(
+        &pool,
+        CreateTodoInput {
+            user_id,
+            title: "Restore Me".to_string(),
+            description: None,
+            due_date: None,
+        },
+    )
+    .await?;
+    TodoService::delete(&pool, created.id).await?;
+
+    // When it is restored
+    let restored = TodoService::restore(&pool, created.id).await?;
+
+    // Then it is back in normal queries
+    assert_eq!(restored.deleted_at, None);
+    assert_eq!(
+        TodoService::get(&pool, created.id).await?.title,
+        "Restore Me"
+    );
+    let listed = TodoService::list_for_user(&pool, user_id, false).await?;
+    assert_eq!(listed.len(), 1);
+
+    // And restoring a todo that isn't deleted is not found
+    let result = TodoService::restore(&pool, created.id).await;
+    assert!(matches!(result, Err(TodoFeatureError::NotFound(_))));
+    Ok(())
+}
+
 #[sqlx::test(migrations = "../../../migrations")]
 async fn deleting_nonexistent_todo_returns_false(pool: PgPool) -> Result<(), TodoFeatureError> {
     let deleted = TodoService::delete(&pool, Uuid::new_v4()).await?;
@@ -15,6 +15,8 @@ async fn create_user(pool: &PgPool, email: &str) -> uuid::Uuid {
         CreateUserInput {
             email: email.to_string(),
             name: "Test User".to_string(),
+            timezone: None,
+            idempotency_key: None,
         },
     )
     .await
@@ -36,6 +38,7 @@ async fn todo_lifecycle_pending_to_completed(pool: PgPool) -> Result<(), TodoFea
             user_id,
             title: "Lifecycle Task".to_string(),
             description: Some("Track this through its lifecycle".to_string()),
+            due_date: None,
         },
     )
     .await?;
@@ -69,6 +72,7 @@ async fn todo_can_be_completed_directly_from_pending(pool: PgPool) -> Result<(),
             user_id,
             title: "Quick Task".to_string(),
             description: None,
+            due_date: None,
         },
     )
     .await?;
@@ -95,6 +99,7 @@ async fn user_manages_multiple_todos(pool: PgPool) -> Result<(), TodoFeatureErro
             user_id,
             title: "Task 1".to_string(),
             description: None,
+            due_date: None,
         },
     )
     .await?;
@@ -105,6 +110,7 @@ async fn user_manages_multiple_todos(pool: PgPool) -> Result<(), TodoFeatureErro
             user_id,
             title: "Task 2".to_string(),
             description: None,
+            due_date: None,
         },
     )
     .await?;
@@ -115,6 +121,7 @@ async fn user_manages_multiple_todos(pool: PgPool) -> Result<(), TodoFeatureErro
             user_id,
             title: "Task 3".to_string(),
             description: None,
+            due_date: None,
         },
     )
     .await?;
@@ -168,6 +175,7 @@ async fn todo_details_can_be_refined(pool: PgPool) -> Result<(), TodoFeatureErro
             user_id,
             title: "Vague Task".to_string(),
             description: None,
+            due_date: None,
         },
     )
     .await?;
@@ -180,6 +188,7 @@ async fn todo_details_can_be_refined(pool: PgPool) -> Result<(), TodoFeatureErro
             title: None,
             description: Some("Now with more details".to_string()),
             status: None,
+            expected_updated_at: None,
         },
     )
     .await?;
@@ -198,6 +207,7 @@ async fn todo_details_can_be_refined(pool: PgPool) -> Result<(), TodoFeatureErro
             title: Some("Clear Task".to_string()),
             description: None,
             status: None,
+            expected_updated_at: None,
         },
     )
     .await?;
@@ -229,6 +239,7 @@ async fn users_todos_are_completely_isolated(pool: PgPool) -> Result<(), TodoFea
             user_id: alice_id,
             title: "Alice Task 1".to_string(),
             description: None,
+            due_date: None,
         },
     )
     .await?;
@@ -239,6 +250,7 @@ async fn users_todos_are_completely_isolated(pool: PgPool) -> Result<(), TodoFea
             user_id: alice_id,
             title: "Alice Task 2".to_string(),
             description: None,
+            due_date: None,
         },
     )
     .await?;
@@ -250,17 +262,18 @@ async fn users_todos_are_completely_isolated(pool: PgPool) -> Result<(), TodoFea
             user_id: bob_id,
             title: "Bob Task".to_string(),
             description: None,
+            due_date: None,
         },
     )
     .await?;
 
     // Alice only sees her todos
-    let alice_todos = TodoService::list_for_user(&pool, alice_id).await?;
+    let alice_todos = TodoService::list_for_user(&pool, alice_id, false).await?;
     assert_eq!(alice_todos.len(), 2);
     assert!(alice_todos.iter().all(|t| t.user_id == alice_id));
 
     // Bob only sees his todos
-    let bob_todos = TodoService::list_for_user(&pool, bob_id).await?;
+    let bob_todos = TodoService::list_for_user(&pool, bob_id, false).await?;
     assert_eq!(bob_todos.len(), 1);
     assert_eq!(bob_todos[0].id, bob_todo.id);
 
@@ -287,6 +300,8 @@ async fn complete_user_and_todo_workflow(pool: PgPool) -> Result<(), TodoFeature
         CreateUserInput {
             email: "complete@example.com".to_string(),
             name: "Complete User".to_string(),
+            timezone: None,
+            idempotency_key: None,
         },
     )
     .await
@@ -299,6 +314,7 @@ async fn complete_user_and_todo_workflow(pool: PgPool) -> Result<(), TodoFeature
             user_id: user.id,
             title: "First Task".to_string(),
             description: Some("My first todo".to_string()),
+            due_date: None,
         },
     )
     .await?;
@@ -309,6 +325,7 @@ async fn complete_user_and_todo_workflow(pool: PgPool) -> Result<(), TodoFeature
             user_id: user.id,
             title: "Second Task".to_string(),
             description: None,
+            due_date: None,
         },
     )
     .await?;
@@ -325,6 +342,7 @@ async fn complete_user_and_todo_workflow(pool: PgPool) -> Result<(), TodoFeature
             title: Some("Updated Second Task".to_string()),
             description: Some("Added description".to_string()),
             status: None,
+            expected_updated_at: None,
         },
     )
     .await?;
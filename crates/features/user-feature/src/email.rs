@@ -0,0 +1,185 @@
+use std::env;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use lettre::message::Mailbox;
+use lettre::message::header::ContentType;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::transport::smtp::client::{Tls, TlsParameters};
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use thiserror::Error;
+use tracing::info;
+
+/// A plain text email ready to send
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmailMessage {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+#[derive(Error, Debug)]
+pub enum EmailError {
+    /// Sending may succeed if tried again later, e.g. the server was unreachable
+    #[error("Temporary email failure: {0}")]
+    Transient(String),
+
+    /// Retrying won't help, e.g. the recipient was rejected
+    #[error("Email rejected: {0}")]
+    Permanent(String),
+}
+
+/// Something that can deliver emails
+///
+/// Jobs receive it through the job registry context, so tests can swap in
+/// a sender that records or fails messages.
+#[async_trait]
+pub trait EmailSender: Send + Sync {
+    async fn send(&self, message: EmailMessage) -> Result<(), EmailError>;
+}
+
+/// Shared sender handle stored in the job registry context
+pub type Mailer = Arc<dyn EmailSender>;
+
+/// Sender that only logs messages, for development without a mail server
+pub struct LogEmailSender;
+
+#[async_trait]
+impl EmailSender for LogEmailSender {
+    async fn send(&self, message: EmailMessage) -> Result<(), EmailError> {
+        info!(to = %message.to, subject = %message.subject, "Email not sent (logging only)");
+        Ok(())
+    }
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum SmtpConfigError {
+    #[error("{0} must be set when SMTP_HOST is set")]
+    Missing(&'static str),
+
+    #[error("SMTP_PORT must be a port number, got {0:?}")]
+    InvalidPort(String),
+
+    #[error("FROM_ADDRESS is not a valid mailbox: {0:?}")]
+    InvalidFromAddress(String),
+
+    #[error("SMTP_USER and SMTP_PASS must be set together")]
+    PartialCredentials,
+}
+
+/// Where and as whom to send email over SMTP
+#[derive(Debug, Clone, PartialEq)]
+pub struct SmtpConfig {
+    pub host: String,
+    pub port: u16,
+    pub credentials: Option<(String, String)>,
+    pub from: Mailbox,
+}
+
+impl SmtpConfig {
+    /// Port used when `SMTP_PORT` is unset
+    pub const DEFAULT_PORT: u16 = 587;
+
+    /// Read the config from `SMTP_HOST`, `SMTP_PORT`, `SMTP_USER`,
+    /// `SMTP_PASS` and `FROM_ADDRESS`
+    ///
+    /// Returns `None` when `SMTP_HOST` is unset, meaning SMTP is not configured.
+    pub fn from_env() -> Result<Option<Self>, SmtpConfigError> {
+        Self::from_vars(|name| env::var(name).ok())
+    }
+
+    /// Build the config from a variable lookup
+    pub fn from_vars(
+        var: impl Fn(&str) -> Option<String>,
+    ) -> Result<Option<Self>, SmtpConfigError> {
+        let Some(host) = var("SMTP_HOST").filter(|h| !h.trim().is_empty()) else {
+            return Ok(None);
+        };
+
+        let port = match var("SMTP_PORT") {
+            Some(value) => value
+                .trim()
+                .parse()
+                .map_err(|_| SmtpConfigError::InvalidPort(value))?,
+            None => Self::DEFAULT_PORT,
+        };
+
+        let credentials = match (var("SMTP_USER"), var("SMTP_PASS")) {
+            (Some(user), Some(pass)) => Some((user, pass)),
+            (None, None) => None,
+            _ => return Err(SmtpConfigError::PartialCredentials),
+        };
+
+        let from = var("FROM_ADDRESS").ok_or(SmtpConfigError::Missing("FROM_ADDRESS"))?;
+        let from = from
+            .parse()
+            .map_err(|_| SmtpConfigError::InvalidFromAddress(from))?;
+
+        Ok(Some(Self {
+            host: host.trim().to_string(),
+            port,
+            credentials,
+            from,
+        }))
+    }
+}
+
+/// Sender that delivers email through an SMTP server
+///
+/// Port 465 uses implicit TLS; any other port upgrades with STARTTLS when the
+/// server offers it.
+pub struct SmtpEmailSender {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl SmtpEmailSender {
+    pub fn new(config: SmtpConfig) -> Result<Self, lettre::transport::smtp::Error> {
+        let tls = TlsParameters::new(config.host.clone())?;
+        let tls = if config.port == 465 {
+            Tls::Wrapper(tls)
+        } else {
+            Tls::Opportunistic(tls)
+        };
+
+        let mut builder = AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.host)
+            .port(config.port)
+            .tls(tls);
+        if let Some((user, pass)) = config.credentials {
+            builder = builder.credentials(Credentials::new(user, pass));
+        }
+
+        Ok(Self {
+            transport: builder.build(),
+            from: config.from,
+        })
+    }
+}
+
+#[async_trait]
+impl EmailSender for SmtpEmailSender {
+    async fn send(&self, message: EmailMessage) -> Result<(), EmailError> {
+        let to: Mailbox = message
+            .to
+            .parse()
+            .map_err(|e| EmailError::Permanent(format!("invalid recipient: {e}")))?;
+
+        let email = Message::builder()
+            .from(self.from.clone())
+            .to(to)
+            .subject(message.subject)
+            .header(ContentType::TEXT_PLAIN)
+            .body(message.body)
+            .map_err(|e| EmailError::Permanent(e.to_string()))?;
+
+        // Only a 5xx reply means the server will never accept this message;
+        // connection and 4xx failures are worth retrying
+        self.transport.send(email).await.map(drop).map_err(|e| {
+            if e.is_permanent() {
+                EmailError::Permanent(e.to_string())
+            } else {
+                EmailError::Transient(e.to_string())
+            }
+        })
+    }
+}
@@ -1,5 +1,41 @@
+use std::fmt;
+
+use domain::Transient;
 use thiserror::Error;
 
+/// One rejected input field and why
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldError {
+    /// Path to the field, such as `name`, or `1.email` for the second input
+    /// of a batch
+    pub field: String,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.field, self.message)
+    }
+}
+
+/// Join field errors into one line for the error message
+fn describe(errors: &[FieldError]) -> String {
+    errors
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
 #[derive(Error, Debug)]
 pub enum UserFeatureError {
     #[error("Domain error: {0}")]
@@ -13,4 +49,32 @@ pub enum UserFeatureError {
 
     #[error("Email already exists: {0}")]
     EmailExists(String),
+
+    #[error("Invalid email: {0}")]
+    InvalidEmail(String),
+
+    #[error("Unknown timezone: {0:?}")]
+    InvalidTimezone(String),
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Invalid email or password")]
+    InvalidCredentials,
+
+    #[error("Password hashing failed: {0}")]
+    PasswordHash(String),
+
+    /// Every field that failed validation, not just the first
+    #[error("Validation error: {}", describe(.0))]
+    InvalidFields(Vec<FieldError>),
+}
+
+impl Transient for UserFeatureError {
+    fn is_transient(&self) -> bool {
+        match self {
+            UserFeatureError::Domain(e) => e.is_transient(),
+            _ => false,
+        }
+    }
 }
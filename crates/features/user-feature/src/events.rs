@@ -0,0 +1,97 @@
+use std::time::Duration;
+
+use domain::{User, UserRepository};
+use sqlx::PgPool;
+use sqlx::postgres::PgListener;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tracing::{error, warn};
+use uuid::Uuid;
+
+use crate::error::UserFeatureError;
+
+/// Postgres channel that newly registered users are published on
+pub const USER_EVENTS_CHANNEL: &str = "user_events";
+
+/// How many unread events a slow subscriber may fall behind by
+const EVENT_BUFFER: usize = 256;
+
+/// How long the listener waits after an error before receiving again
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Fan-out of newly registered users to in-process subscribers
+///
+/// Registrations are published by a database trigger via `NOTIFY`, so every
+/// new user is seen regardless of which server created it.
+#[derive(Clone)]
+pub struct UserEvents {
+    sender: broadcast::Sender<User>,
+}
+
+impl UserEvents {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(EVENT_BUFFER);
+        Self { sender }
+    }
+
+    /// Receive every user registered from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<User> {
+        self.sender.subscribe()
+    }
+
+    /// Start a background task that `LISTEN`s for registrations and
+    /// broadcasts them
+    ///
+    /// The listener is connected before this returns, so no registration
+    /// committed afterwards is missed.
+    pub async fn listen(&self, pool: &PgPool) -> Result<JoinHandle<()>, UserFeatureError> {
+        let mut listener = PgListener::connect_with(pool)
+            .await
+            .map_err(domain::DomainError::from)?;
+        listener
+            .listen(USER_EVENTS_CHANNEL)
+            .await
+            .map_err(domain::DomainError::from)?;
+
+        let pool = pool.clone();
+        let sender = self.sender.clone();
+
+        Ok(tokio::spawn(async move {
+            loop {
+                let notification = match listener.recv().await {
+                    Ok(notification) => notification,
+                    // The listener reconnects on the next `recv`; events sent
+                    // while it was down are lost
+                    Err(e) => {
+                        error!("User event listener error: {}", e);
+                        tokio::time::sleep(RETRY_DELAY).await;
+                        continue;
+                    }
+                };
+
+                let Ok(id) = notification.payload().parse::<Uuid>() else {
+                    warn!(
+                        payload = notification.payload(),
+                        "Ignoring malformed user event"
+                    );
+                    continue;
+                };
+
+                match UserRepository::find_by_id(&pool, id).await {
+                    // Sending only fails when nobody is subscribed, which is fine
+                    Ok(Some(user)) => {
+                        let _ = sender.send(user);
+                    }
+                    Ok(None) => {}
+                    Err(e) => error!(user_id = %id, "Failed to load registered user: {}", e),
+                }
+            }
+        }))
+    }
+}
+
+impl Default for UserEvents {
+    fn default() -> Self {
+        Self::new()
+    }
+}
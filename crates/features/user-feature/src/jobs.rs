@@ -1,8 +1,15 @@
+use std::time::Duration;
+
+use metrics::counter;
 use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
 use sqlxmq::{job, CurrentJob, JobRegistry};
-use tracing::info;
+use time::OffsetDateTime;
+use tracing::{info, warn};
 use uuid::Uuid;
 
+use crate::email::{EmailError, EmailMessage, Mailer};
+
 /// Arguments for the welcome email job
 #[derive(Debug, Serialize, Deserialize)]
 pub struct WelcomeEmailArgs {
@@ -11,10 +18,28 @@ pub struct WelcomeEmailArgs {
     pub name: String,
 }
 
+/// Retries after the first attempt before a welcome email is given up on
+pub const WELCOME_EMAIL_RETRIES: u32 = 5;
+
+/// Wait before the first retry; sqlxmq doubles it after each attempt
+pub const WELCOME_EMAIL_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Template name recorded in `email_sends` for welcome emails
+pub const WELCOME_TEMPLATE: &str = "welcome";
+
 /// Send a welcome email to a newly registered user
+///
+/// The send is recorded in `email_sends` in the transaction that completes
+/// the job, and a job finding it already recorded completes without sending,
+/// so a rerun job doesn't email the user twice; an explicit resend clears
+/// the record first. Transient send failures
+/// return an error without completing the job, so sqlxmq retries it with
+/// backoff. Permanent failures, and transient ones on the last attempt, are
+/// moved to `failed_jobs`.
 #[job(channel_name = "emails")]
 pub async fn send_welcome_email(
     mut current_job: CurrentJob,
+    mailer: Mailer,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
     // Extract arguments from the job payload
     let args: WelcomeEmailArgs = current_job.json()?.expect("job arguments");
@@ -26,23 +51,227 @@ pub async fn send_welcome_email(
         "Sending welcome email"
     );
 
-    // In a real application, you would call an email service here
-    // For example: email_client.send_welcome(args.email, args.name).await?;
+    // Holding the claim until the job completes makes a concurrent run of a
+    // duplicate job wait, then see the send recorded
+    let mut tx = current_job.pool().begin().await?;
+    if !claim_send(&mut tx, args.user_id, WELCOME_TEMPLATE).await? {
+        info!(user_id = %args.user_id, "Welcome email already sent; skipping");
+        current_job.complete_with_transaction(tx).await?;
+        record_outcome("duplicate");
+        return Ok(());
+    }
+
+    let error = match mailer.send(welcome_message(&args)).await {
+        Ok(()) => {
+            current_job.complete_with_transaction(tx).await?;
+            record_outcome("success");
+            return Ok(());
+        }
+        Err(e) => e,
+    };
+    // Release the claim so a retry can send
+    tx.rollback().await?;
 
+    if matches!(error, EmailError::Transient(_)) && attempts_left(&current_job).await? > 0 {
+        // Leave the job incomplete so sqlxmq tries again after the backoff
+        record_outcome("retry");
+        return Err(error.into());
+    }
+
+    warn!(user_id = %args.user_id, error = %error, "Giving up on welcome email");
+    move_to_failed_jobs(&mut current_job, &error).await?;
+    record_outcome("failure");
+    Ok(())
+}
+
+/// How far before its start a digest run leaves the next one to pick up
+///
+/// Registrations still committing when a run reads users are caught by the
+/// next run; recorded sends keep the overlap from emailing anyone twice.
+pub const WELCOME_DIGEST_OVERLAP: time::Duration = time::Duration::minutes(1);
+
+/// Send welcome emails to everyone who registered since the last digest
+///
+/// Queued periodically when `WelcomeEmailMode::Digest` is configured.
+#[job(channel_name = "emails")]
+pub async fn send_welcome_digest(
+    mut current_job: CurrentJob,
+    mailer: Mailer,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let sent = run_welcome_digest(current_job.pool(), &mailer).await?;
+
+    info!(sent, "Sent welcome digest");
     current_job.complete().await?;
+    counter!("jobs_total", "job" => "send_welcome_digest", "outcome" => "success").increment(1);
     Ok(())
 }
 
+/// Send a welcome email to each active user registered since the last run
+/// who hasn't had one, returning how many were sent
+///
+/// Each send is recorded in `email_sends` as it succeeds. Failed sends are
+/// logged and left for the next run, which starts from the earliest of them.
+pub async fn run_welcome_digest(pool: &PgPool, mailer: &Mailer) -> Result<usize, sqlx::Error> {
+    // Hold the state row until the run is over so runs don't overlap
+    let mut state = pool.begin().await?;
+    let since: OffsetDateTime = sqlx::query_scalar("SELECT since FROM welcome_digest FOR UPDATE")
+        .fetch_one(&mut *state)
+        .await?;
+    let started = OffsetDateTime::now_utc();
+
+    let users: Vec<(Uuid, String, String, OffsetDateTime)> = sqlx::query_as(
+        "SELECT id, email, name, created_at FROM users
+         WHERE created_at >= $1 AND deleted_at IS NULL
+           AND NOT EXISTS (
+               SELECT 1 FROM email_sends
+               WHERE email_sends.user_id = users.id AND template = $2
+           )
+         ORDER BY created_at ASC, id ASC",
+    )
+    .bind(since)
+    .bind(WELCOME_TEMPLATE)
+    .fetch_all(pool)
+    .await?;
+
+    let mut sent = 0;
+    let mut first_failed = None;
+    for (user_id, email, name, created_at) in users {
+        let mut tx = pool.begin().await?;
+        if !claim_send(&mut tx, user_id, WELCOME_TEMPLATE).await? {
+            continue;
+        }
+
+        let args = WelcomeEmailArgs {
+            user_id,
+            email,
+            name,
+        };
+        match mailer.send(welcome_message(&args)).await {
+            Ok(()) => {
+                tx.commit().await?;
+                sent += 1;
+            }
+            Err(e) => {
+                tx.rollback().await?;
+                warn!(%user_id, error = %e, "Welcome email in digest failed");
+                first_failed.get_or_insert(created_at);
+            }
+        }
+    }
+
+    sqlx::query("UPDATE welcome_digest SET since = $1")
+        .bind(first_failed.unwrap_or(started - WELCOME_DIGEST_OVERLAP))
+        .execute(&mut *state)
+        .await?;
+    state.commit().await?;
+
+    Ok(sent)
+}
+
+/// Count a welcome email job run in `jobs_total`
+fn record_outcome(outcome: &'static str) {
+    counter!("jobs_total", "job" => "send_welcome_email", "outcome" => outcome).increment(1);
+}
+
+/// Build the welcome email for a new user
+pub fn welcome_message(args: &WelcomeEmailArgs) -> EmailMessage {
+    EmailMessage {
+        to: args.email.clone(),
+        subject: "Welcome to Apex Stack".to_string(),
+        body: format!(
+            "Hi {},\n\nThanks for signing up. Your account is ready to use.\n",
+            args.name
+        ),
+    }
+}
+
+/// Record that `template` is being sent to `user_id`, returning false if it
+/// already was
+async fn claim_send(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    user_id: Uuid,
+    template: &str,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        "INSERT INTO email_sends (user_id, template) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+    )
+    .bind(user_id)
+    .bind(template)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(result.rows_affected() == 1)
+}
+
+/// Forget that `template` was sent to `user_id`, so the next job sending it
+/// goes ahead
+pub(crate) async fn forget_send(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    user_id: Uuid,
+    template: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM email_sends WHERE user_id = $1 AND template = $2")
+        .bind(user_id)
+        .bind(template)
+        .execute(&mut **tx)
+        .await?;
+
+    Ok(())
+}
+
+/// How many more times sqlxmq will run the current job after this attempt
+async fn attempts_left(job: &CurrentJob) -> Result<i32, sqlx::Error> {
+    // sqlxmq decrements attempts when it hands the job out
+    sqlx::query_scalar("SELECT attempts FROM mq_msgs WHERE id = $1")
+        .bind(job.id())
+        .fetch_one(job.pool())
+        .await
+}
+
+/// Record a job in `failed_jobs` and complete it in the same transaction
+async fn move_to_failed_jobs(job: &mut CurrentJob, error: &EmailError) -> Result<(), sqlx::Error> {
+    let mut tx = job.pool().begin().await?;
+
+    sqlx::query(
+        "INSERT INTO failed_jobs (id, name, payload_json, error) VALUES ($1, $2, $3::jsonb, $4)",
+    )
+    .bind(job.id())
+    .bind(job.name())
+    .bind(job.raw_json())
+    .bind(error.to_string())
+    .execute(&mut *tx)
+    .await?;
+
+    job.complete_with_transaction(tx).await
+}
+
 /// Registry of all user-related jobs
 pub struct UserJobs;
 
 impl UserJobs {
     /// Create a job registry containing all user feature jobs
-    pub fn registry() -> JobRegistry {
-        JobRegistry::new(&[send_welcome_email])
+    ///
+    /// Emails are delivered through `mailer`.
+    pub fn registry(mailer: Mailer) -> JobRegistry {
+        let mut registry = JobRegistry::new(&[send_welcome_email, send_welcome_digest]);
+        registry.set_context(mailer);
+        registry
+    }
+
+    /// Queue a welcome email digest
+    pub async fn enqueue_welcome_digest(
+        pool: &PgPool,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        send_welcome_digest.builder().spawn(pool).await?;
+
+        Ok(())
     }
 
     /// Spawn a welcome email job within a transaction
+    ///
+    /// Failed sends are retried up to `WELCOME_EMAIL_RETRIES` times, waiting
+    /// `WELCOME_EMAIL_BACKOFF` before the first retry and twice as long each
+    /// time after.
     pub async fn enqueue_welcome_email(
         tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
         user_id: Uuid,
@@ -58,6 +287,8 @@ impl UserJobs {
         send_welcome_email
             .builder()
             .set_json(&args)?
+            .set_retries(WELCOME_EMAIL_RETRIES)
+            .set_retry_backoff(WELCOME_EMAIL_BACKOFF)
             .spawn(&mut **tx)
             .await?;
 
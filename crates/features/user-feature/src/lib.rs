@@ -1,7 +1,20 @@
+pub mod email;
 pub mod error;
+pub mod events;
 pub mod jobs;
+pub mod limits;
 pub mod service;
+pub mod welcome;
 
-pub use error::UserFeatureError;
-pub use jobs::{send_welcome_email, UserJobs};
-pub use service::{CreateUserInput, UpdateUserInput, UserService};
+pub use email::{
+    EmailError, EmailMessage, EmailSender, LogEmailSender, Mailer, SmtpConfig, SmtpConfigError,
+    SmtpEmailSender,
+};
+pub use error::{FieldError, UserFeatureError};
+pub use events::{UserEvents, USER_EVENTS_CHANNEL};
+pub use jobs::{send_welcome_digest, send_welcome_email, UserJobs};
+pub use limits::{UserLimits, UserLimitsError};
+pub use service::{
+    is_valid_email, CreateUserInput, TodoExport, UpdateUserInput, UserExport, UserService,
+};
+pub use welcome::{WelcomeEmailConfig, WelcomeEmailConfigError, WelcomeEmailMode};
@@ -0,0 +1,46 @@
+use std::env;
+use std::sync::OnceLock;
+
+use domain::{LimitsError, parse_limit};
+
+pub type UserLimitsError = LimitsError;
+
+/// Longest name a user may have, in characters
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UserLimits {
+    pub name_max: usize,
+}
+
+impl Default for UserLimits {
+    fn default() -> Self {
+        Self { name_max: 100 }
+    }
+}
+
+static LIMITS: OnceLock<UserLimits> = OnceLock::new();
+
+impl UserLimits {
+    /// Read the limits from `USER_NAME_MAX`
+    pub fn from_env() -> Result<Self, UserLimitsError> {
+        Self::from_vars(|name| env::var(name).ok())
+    }
+
+    /// Build the limits from a variable lookup, using defaults for unset ones
+    pub fn from_vars(var: impl Fn(&str) -> Option<String>) -> Result<Self, UserLimitsError> {
+        Ok(Self {
+            name_max: parse_limit(var, "USER_NAME_MAX")?.unwrap_or(Self::default().name_max),
+        })
+    }
+
+    /// Make these the limits `UserService` enforces
+    ///
+    /// Only the first call has any effect.
+    pub fn install(self) {
+        let _ = LIMITS.set(self);
+    }
+
+    /// The limits in force: the installed ones, or the defaults
+    pub fn current() -> Self {
+        LIMITS.get().copied().unwrap_or_default()
+    }
+}
@@ -1,61 +1,349 @@
-use domain::{User, UserRepository};
-use sqlx::{Executor, PgPool, Postgres};
+use std::collections::HashMap;
+
+use argon2::Argon2;
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{self, PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use domain::{
+    EventRepository, JobsRepository, Page, Todo, TodoRepository, User, UserFilter, UserRepository,
+    UserRole, UserSort, retry_on_transient,
+};
+use serde::Serialize;
+use serde_json::json;
+use sqlx::{Executor, PgPool, Postgres, Transaction};
+use time::{Date, OffsetDateTime};
 use uuid::Uuid;
 
-use crate::error::UserFeatureError;
-use crate::jobs::UserJobs;
+use crate::error::{FieldError, UserFeatureError};
+use crate::jobs::{self, UserJobs, WELCOME_TEMPLATE};
+use crate::limits::UserLimits;
+use crate::welcome::{WelcomeEmailConfig, WelcomeEmailMode};
 
 /// Input for creating a new user
+#[derive(Clone)]
 pub struct CreateUserInput {
     pub email: String,
     pub name: String,
+    /// IANA timezone name; defaults to UTC
+    pub timezone: Option<String>,
+    /// Client-chosen key that makes the registration safe to retry
+    pub idempotency_key: Option<String>,
 }
 
 /// Input for updating a user
 pub struct UpdateUserInput {
     pub name: Option<String>,
+    pub email: Option<String>,
+    pub timezone: Option<String>,
+}
+
+/// Everything stored about a user, for handing back to them on request
+#[derive(Debug, Clone, Serialize)]
+pub struct UserExport {
+    pub id: Uuid,
+    pub email: String,
+    pub name: String,
+    pub timezone: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub updated_at: OffsetDateTime,
+    pub todos: Vec<TodoExport>,
+}
+
+/// A todo as it appears in a `UserExport`
+#[derive(Debug, Clone, Serialize)]
+pub struct TodoExport {
+    pub id: Uuid,
+    pub title: String,
+    pub description: Option<String>,
+    pub status: &'static str,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub due_date: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub updated_at: OffsetDateTime,
+}
+
+impl From<Todo> for TodoExport {
+    fn from(todo: Todo) -> Self {
+        Self {
+            id: todo.id,
+            title: todo.title,
+            description: todo.description,
+            status: todo.status.as_str(),
+            due_date: todo.due_date,
+            created_at: todo.created_at,
+            updated_at: todo.updated_at,
+        }
+    }
 }
 
+/// Timezone given to users who don't choose one
+const DEFAULT_TIMEZONE: &str = "UTC";
+
+/// Most days `registrations_by_day` reports on at once
+const MAX_STATS_DAYS: i64 = 366;
+
+/// Fewest characters `set_password` accepts
+const MIN_PASSWORD_LENGTH: usize = 8;
+
+/// A hash no password matches, made with the default Argon2 parameters so
+/// checking against it costs the same as checking a real one
+const DUMMY_PASSWORD_HASH: &str = "$argon2id$v=19$m=19456,t=2,p=1$ZHVtbXktcGFzc3dvcmQtc2FsdA$h6EMVtTSHm87FEPBRDUEw8p0qwkvcG4SnzWa+W59S4w";
+
 /// Service for user-related operations
 pub struct UserService;
 
 impl UserService {
     /// Register a new user and enqueue a welcome email atomically
-    /// 
-    /// Requires a Pool to manage the transaction internally.
+    ///
+    /// Requires a Pool to manage the transaction internally. When an
+    /// idempotency key is given and a user was already registered under it,
+    /// that user is returned and nothing new is created. The name is trimmed
+    /// and must be non-empty and fit within `UserLimits`. Every field is
+    /// checked before anything is written, and all failures are reported
+    /// together as `InvalidFields`. Under `WelcomeEmailMode::Digest` no
+    /// email is enqueued; the next welcome digest sends it.
     pub async fn register(pool: &PgPool, input: CreateUserInput) -> Result<User, UserFeatureError> {
-        // Check if email already exists
-        // Note: We use the pool here, effectively a separate read. 
-        // In high concurrency, a race condition exists here, but the DB constraint will catch it.
-        if UserRepository::find_by_email(pool, &input.email)
+        retry_on_transient(|| Self::register_once(pool, input.clone())).await
+    }
+
+    /// One attempt at `register`, in a transaction of its own
+    async fn register_once(
+        pool: &PgPool,
+        input: CreateUserInput,
+    ) -> Result<User, UserFeatureError> {
+        let key = input.idempotency_key.clone();
+
+        let mut tx = pool.begin().await.map_err(domain::DomainError::from)?;
+        match Self::register_tx(&mut tx, input).await {
+            Ok(user) => {
+                // Commit the user and the welcome email together
+                tx.commit().await.map_err(domain::DomainError::from)?;
+                Ok(user)
+            }
+            // A concurrent registration with the same key may have won the
+            // race; this transaction may be aborted, so look it up afresh
+            Err(
+                e @ (UserFeatureError::EmailExists(_)
+                | UserFeatureError::Domain(domain::DomainError::Conflict(_))),
+            ) => match key {
+                Some(key) => {
+                    drop(tx);
+                    UserRepository::find_by_idempotency_key(pool, &key)
+                        .await?
+                        .ok_or(e)
+                }
+                None => Err(e),
+            },
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Register a new user and enqueue a welcome email on the caller's
+    /// transaction
+    ///
+    /// Behaves like `register` but leaves committing to the caller, so the
+    /// registration can be composed with further work such as creating the
+    /// user's first todo. Nothing is visible to others until the caller
+    /// commits, and rolling back undoes the user, event and email alike.
+    pub async fn register_tx(
+        tx: &mut Transaction<'_, Postgres>,
+        input: CreateUserInput,
+    ) -> Result<User, UserFeatureError> {
+        let (name, timezone) =
+            check_registration(&input).map_err(UserFeatureError::InvalidFields)?;
+
+        if let Some(key) = &input.idempotency_key
+            && let Some(user) = UserRepository::find_by_idempotency_key(&mut **tx, key).await?
+        {
+            return Ok(user);
+        }
+
+        Self::create_with_welcome_email(tx, &input, name, timezone).await
+    }
+
+    /// Register several users at once, for data imports
+    ///
+    /// Every input is validated before anything is written, and the fields
+    /// of all invalid inputs are reported together, prefixed with the
+    /// input's index. As with `register`, an input whose idempotency key was
+    /// already used gets that user back instead of creating one. The rest are
+    /// inserted with one statement and each gets its idempotency key,
+    /// `user.registered` event and welcome email in the same transaction. If
+    /// any email is already taken, or repeated within the batch, nothing is
+    /// created and `EmailExists` names the first offender. The users are
+    /// returned in input order.
+    pub async fn register_many(
+        pool: &PgPool,
+        inputs: Vec<CreateUserInput>,
+    ) -> Result<Vec<User>, UserFeatureError> {
+        let mut rows = Vec::with_capacity(inputs.len());
+        let mut errors = Vec::new();
+        for (i, input) in inputs.iter().enumerate() {
+            match check_registration(input) {
+                Ok((name, timezone)) => rows.push((input.email.as_str(), name, timezone)),
+                Err(fields) => errors.extend(
+                    fields
+                        .into_iter()
+                        .map(|e| FieldError::new(format!("{i}.{}", e.field), e.message)),
+                ),
+            }
+        }
+        if !errors.is_empty() {
+            return Err(UserFeatureError::InvalidFields(errors));
+        }
+
+        if rows.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut tx = pool.begin().await.map_err(domain::DomainError::from)?;
+
+        let mut existing = Vec::with_capacity(inputs.len());
+        for input in &inputs {
+            let user = match &input.idempotency_key {
+                Some(key) => UserRepository::find_by_idempotency_key(&mut *tx, key).await?,
+                None => None,
+            };
+            existing.push(user);
+        }
+        let (new_inputs, new_rows): (Vec<&CreateUserInput>, Vec<_>) = inputs
+            .iter()
+            .zip(rows)
+            .zip(&existing)
+            .filter(|(_, user)| user.is_none())
+            .map(|(pair, _)| pair)
+            .unzip();
+
+        // Colliding emails are skipped by the insert; dropping the
+        // transaction rolls back the rest of the batch
+        let created = if new_rows.is_empty() {
+            Vec::new()
+        } else {
+            UserRepository::create_many(&mut *tx, &new_rows).await?
+        };
+        if created.len() < new_inputs.len() {
+            let offender = new_inputs
+                .iter()
+                .enumerate()
+                .find(|(i, input)| {
+                    created
+                        .get(*i)
+                        .is_none_or(|u| u.email != domain::normalize_email(&input.email))
+                })
+                .map(|(_, input)| input.email.clone())
+                .unwrap_or_default();
+            return Err(UserFeatureError::EmailExists(offender));
+        }
+
+        let immediate = WelcomeEmailConfig::current().mode == WelcomeEmailMode::Immediate;
+        for (user, input) in created.iter().zip(&new_inputs) {
+            if let Some(key) = &input.idempotency_key {
+                UserRepository::record_idempotency_key(&mut *tx, key, user.id).await?;
+            }
+
+            EventRepository::record(
+                &mut *tx,
+                "user.registered",
+                &json!({ "user_id": user.id, "email": user.email, "name": user.name }),
+            )
+            .await?;
+
+            if immediate {
+                UserJobs::enqueue_welcome_email(
+                    &mut tx,
+                    user.id,
+                    user.email.clone(),
+                    user.name.clone(),
+                )
+                .await
+                .map_err(|e| UserFeatureError::Queue(e.to_string()))?;
+            }
+        }
+
+        tx.commit().await.map_err(domain::DomainError::from)?;
+
+        let mut created = created.into_iter();
+        Ok(existing
+            .into_iter()
+            .filter_map(|user| user.or_else(|| created.next()))
+            .collect())
+    }
+
+    /// Create the user, its idempotency key if any, its `user.registered`
+    /// event and the welcome email job on `tx`
+    async fn create_with_welcome_email(
+        tx: &mut Transaction<'_, Postgres>,
+        input: &CreateUserInput,
+        name: &str,
+        timezone: &str,
+    ) -> Result<User, UserFeatureError> {
+        // Check the email is free while holding its lock, so a concurrent
+        // registration of the same address waits for this one to finish
+        UserRepository::lock_email(&mut **tx, &input.email).await?;
+        if UserRepository::find_by_email(&mut **tx, &input.email)
             .await?
             .is_some()
         {
-            return Err(UserFeatureError::EmailExists(input.email));
+            return Err(UserFeatureError::EmailExists(input.email.clone()));
         }
 
-        // Start transaction for atomic user creation + job enqueue
-        let mut tx = pool.begin().await.map_err(domain::DomainError::from)?;
+        // The unique email index stays as a backstop
+        let user = UserRepository::create(&mut **tx, &input.email, name, timezone)
+            .await
+            .map_err(|e| match e {
+                domain::DomainError::Conflict(_) => {
+                    UserFeatureError::EmailExists(input.email.clone())
+                }
+                e => e.into(),
+            })?;
 
-        // Create the user
-        let user = UserRepository::create(&mut *tx, &input.email, &input.name).await?;
+        if let Some(key) = &input.idempotency_key {
+            UserRepository::record_idempotency_key(&mut **tx, key, user.id).await?;
+        }
 
-        // Enqueue the welcome email job within the same transaction
-        UserJobs::enqueue_welcome_email(
-            &mut tx,
-            user.id,
-            user.email.clone(),
-            user.name.clone(),
+        EventRepository::record(
+            &mut **tx,
+            "user.registered",
+            &json!({ "user_id": user.id, "email": user.email, "name": user.name }),
         )
-        .await
-        .map_err(|e| UserFeatureError::Queue(e.to_string()))?;
+        .await?;
 
-        // Commit both the user and the job together
-        tx.commit().await.map_err(domain::DomainError::from)?;
+        // Enqueue the welcome email job within the same transaction, unless
+        // a digest will pick the user up
+        if WelcomeEmailConfig::current().mode == WelcomeEmailMode::Immediate {
+            UserJobs::enqueue_welcome_email(tx, user.id, user.email.clone(), user.name.clone())
+                .await
+                .map_err(|e| UserFeatureError::Queue(e.to_string()))?;
+        }
 
         Ok(user)
     }
 
+    /// Enqueue another welcome email for an existing user
+    ///
+    /// The record of the earlier welcome email is cleared in the same
+    /// transaction, so the job sends it again rather than skipping it as a
+    /// duplicate.
+    pub async fn resend_welcome_email(pool: &PgPool, id: Uuid) -> Result<(), UserFeatureError> {
+        let user = Self::get(pool, id).await?;
+
+        let mut tx = pool.begin().await.map_err(domain::DomainError::from)?;
+
+        jobs::forget_send(&mut tx, user.id, WELCOME_TEMPLATE)
+            .await
+            .map_err(domain::DomainError::from)?;
+        UserJobs::enqueue_welcome_email(&mut tx, user.id, user.email, user.name)
+            .await
+            .map_err(|e| UserFeatureError::Queue(e.to_string()))?;
+
+        tx.commit().await.map_err(domain::DomainError::from)?;
+
+        Ok(())
+    }
+
     /// Get a user by ID
     pub async fn get<'e, E>(executor: E, id: Uuid) -> Result<User, UserFeatureError>
     where
@@ -66,6 +354,13 @@ impl UserService {
             .ok_or(UserFeatureError::NotFound(id))
     }
 
+    /// Get several users by ID, in the order the IDs are given
+    ///
+    /// IDs that match no active user are skipped rather than reported.
+    pub async fn get_many(pool: &PgPool, ids: &[Uuid]) -> Result<Vec<User>, UserFeatureError> {
+        Ok(UserRepository::find_by_ids(pool, ids).await?)
+    }
+
     /// Get a user by email
     pub async fn get_by_email<'e, E>(
         executor: E,
@@ -85,29 +380,409 @@ impl UserService {
         Ok(UserRepository::list(executor).await?)
     }
 
-    /// Update a user
-    pub async fn update<'e, E>(
+    /// List users registered from `after` (inclusive) until `before`
+    /// (exclusive), newest first
+    pub async fn list_created_between<'e, E>(
         executor: E,
-        id: Uuid,
-        input: UpdateUserInput,
-    ) -> Result<User, UserFeatureError>
+        after: Option<OffsetDateTime>,
+        before: Option<OffsetDateTime>,
+    ) -> Result<Vec<User>, UserFeatureError>
     where
         E: Executor<'e, Database = Postgres>,
     {
-        if let Some(name) = input.name {
-            UserRepository::update_name(executor, id, &name)
+        Ok(UserRepository::list_created_between(executor, after, before).await?)
+    }
+
+    /// Find users matching `filter`, in `sort` order, one page at a time
+    ///
+    /// Not scoped to the caller, so only for admin tooling.
+    pub async fn find(
+        pool: &PgPool,
+        filter: UserFilter,
+        sort: UserSort,
+        offset: u64,
+        limit: u64,
+    ) -> Result<Page<User>, UserFeatureError> {
+        // Fetch one extra row to learn whether another page follows
+        let users = UserRepository::find(pool, &filter, sort, offset, limit + 1).await?;
+
+        Ok(Page::from_rows(users, limit))
+    }
+
+    /// Count registrations on each UTC day from `from` to `to`, both
+    /// inclusive, with zero for days nobody registered on
+    ///
+    /// The range may span at most `MAX_STATS_DAYS` days.
+    pub async fn registrations_by_day(
+        pool: &PgPool,
+        from: Date,
+        to: Date,
+    ) -> Result<Vec<(Date, i64)>, UserFeatureError> {
+        if to < from {
+            return Err(UserFeatureError::Validation(
+                "to must not be before from".to_string(),
+            ));
+        }
+        if (to - from).whole_days() >= MAX_STATS_DAYS {
+            return Err(UserFeatureError::Validation(format!(
+                "range must not exceed {MAX_STATS_DAYS} days"
+            )));
+        }
+
+        let counts: HashMap<Date, i64> = UserRepository::registrations_by_day(pool, from, to)
+            .await?
+            .into_iter()
+            .collect();
+
+        let mut buckets = Vec::new();
+        let mut day = Some(from);
+        while let Some(current) = day.filter(|&d| d <= to) {
+            buckets.push((current, counts.get(&current).copied().unwrap_or(0)));
+            day = current.next_day();
+        }
+
+        Ok(buckets)
+    }
+
+    /// Gather a user and all their todos, deleted ones included, for export
+    ///
+    /// Both are read in one transaction so the export is consistent.
+    pub async fn export_data(pool: &PgPool, id: Uuid) -> Result<UserExport, UserFeatureError> {
+        let mut tx = pool.begin().await.map_err(domain::DomainError::from)?;
+
+        let user = Self::get(&mut *tx, id).await?;
+        let todos = TodoRepository::list_by_user(&mut *tx, id, true).await?;
+
+        tx.commit().await.map_err(domain::DomainError::from)?;
+
+        Ok(UserExport {
+            id: user.id,
+            email: user.email,
+            name: user.name,
+            timezone: user.timezone,
+            created_at: user.created_at,
+            updated_at: user.updated_at,
+            todos: todos.into_iter().map(Into::into).collect(),
+        })
+    }
+
+    /// Update a user
+    ///
+    /// A new email is validated and stored lowercase; it must not belong to
+    /// another user. A new name is trimmed and must be non-empty and fit
+    /// within `UserLimits`. All changes are applied in one transaction, which
+    /// is rerun from the start if it fails with a transient database error.
+    pub async fn update(
+        pool: &PgPool,
+        id: Uuid,
+        input: UpdateUserInput,
+    ) -> Result<User, UserFeatureError> {
+        let name = input.name.as_deref().map(check_name).transpose()?;
+        let email = input.email.as_deref().map(normalize_email).transpose()?;
+        let timezone = input
+            .timezone
+            .as_deref()
+            .map(validate_timezone)
+            .transpose()?;
+
+        retry_on_transient(|| Self::apply_update(pool, id, name, email.as_deref(), timezone)).await
+    }
+
+    /// Apply validated changes to a user in a transaction of its own
+    async fn apply_update(
+        pool: &PgPool,
+        id: Uuid,
+        name: Option<&str>,
+        email: Option<&str>,
+        timezone: Option<&str>,
+    ) -> Result<User, UserFeatureError> {
+        let mut tx = pool.begin().await.map_err(domain::DomainError::from)?;
+
+        let mut user = UserRepository::find_by_id(&mut *tx, id)
+            .await?
+            .ok_or(UserFeatureError::NotFound(id))?;
+
+        if let Some(name) = name {
+            user = UserRepository::update_name(&mut *tx, id, name)
                 .await?
-                .ok_or(UserFeatureError::NotFound(id))
-        } else {
-            Self::get(executor, id).await
+                .ok_or(UserFeatureError::NotFound(id))?;
+        }
+
+        if let Some(email) = email
+            && email != user.email
+        {
+            user = UserRepository::update_email(&mut *tx, id, email)
+                .await
+                .map_err(|e| match e {
+                    domain::DomainError::Conflict(_) => {
+                        UserFeatureError::EmailExists(email.to_string())
+                    }
+                    e => e.into(),
+                })?
+                .ok_or(UserFeatureError::NotFound(id))?;
+        }
+
+        if let Some(timezone) = timezone {
+            user = UserRepository::update_timezone(&mut *tx, id, timezone)
+                .await?
+                .ok_or(UserFeatureError::NotFound(id))?;
         }
+
+        tx.commit().await.map_err(domain::DomainError::from)?;
+
+        Ok(user)
     }
 
-    /// Delete a user
-    pub async fn delete<'e, E>(executor: E, id: Uuid) -> Result<bool, UserFeatureError>
-    where
-        E: Executor<'e, Database = Postgres>,
-    {
-        Ok(UserRepository::delete(executor, id).await?)
+    /// Change a user's role
+    pub async fn set_role(
+        pool: &PgPool,
+        id: Uuid,
+        role: UserRole,
+    ) -> Result<User, UserFeatureError> {
+        retry_on_transient(|| UserRepository::set_role(pool, id, role))
+            .await?
+            .ok_or(UserFeatureError::NotFound(id))
+    }
+
+    /// Delete a user along with all of their todos
+    ///
+    /// Requires a Pool so the todos, their pending due date reminders and the
+    /// user are removed in one transaction. The idempotency keys the user was
+    /// registered under are released with them, so retrying one registers a
+    /// new user.
+    pub async fn delete(pool: &PgPool, id: Uuid) -> Result<bool, UserFeatureError> {
+        let mut tx = pool.begin().await.map_err(domain::DomainError::from)?;
+
+        let reminders = TodoRepository::take_reminder_job_ids_by_user(&mut *tx, id).await?;
+        JobsRepository::delete(&mut *tx, &reminders).await?;
+        TodoRepository::delete_by_user(&mut *tx, id).await?;
+        UserRepository::release_idempotency_keys(&mut *tx, id).await?;
+        let deleted = UserRepository::delete(&mut *tx, id).await?;
+
+        tx.commit().await.map_err(domain::DomainError::from)?;
+
+        Ok(deleted)
+    }
+
+    /// Bring back a soft-deleted user, recording who restored them
+    ///
+    /// Returns false if the user isn't deleted, and `NotFound` if there was
+    /// never such a user. A `user.restored` event naming `restored_by` is
+    /// recorded in the same transaction. Their todos, removed on delete, stay
+    /// gone.
+    pub async fn restore(
+        pool: &PgPool,
+        id: Uuid,
+        restored_by: Uuid,
+    ) -> Result<bool, UserFeatureError> {
+        let mut tx = pool.begin().await.map_err(domain::DomainError::from)?;
+
+        let user = UserRepository::find_by_id_including_deleted(&mut *tx, id)
+            .await?
+            .ok_or(UserFeatureError::NotFound(id))?;
+        if user.deleted_at.is_none() {
+            return Ok(false);
+        }
+
+        let restored = UserRepository::restore(&mut *tx, id)
+            .await
+            .map_err(|e| match e {
+                domain::DomainError::Conflict(_) => UserFeatureError::EmailExists(user.email),
+                e => e.into(),
+            })?;
+
+        if restored {
+            EventRepository::record(
+                &mut *tx,
+                "user.restored",
+                &json!({ "user_id": id, "restored_by": restored_by }),
+            )
+            .await?;
+        }
+
+        tx.commit().await.map_err(domain::DomainError::from)?;
+
+        Ok(restored)
+    }
+
+    /// Set or replace a user's password
+    ///
+    /// The password must be at least `MIN_PASSWORD_LENGTH` characters. Only
+    /// an Argon2 hash with a random salt is stored. Registration doesn't take
+    /// a password, so users can still be created without one.
+    pub async fn set_password(
+        pool: &PgPool,
+        id: Uuid,
+        password: &str,
+    ) -> Result<(), UserFeatureError> {
+        if password.chars().count() < MIN_PASSWORD_LENGTH {
+            return Err(UserFeatureError::Validation(format!(
+                "password must be at least {MIN_PASSWORD_LENGTH} characters"
+            )));
+        }
+
+        let hash = hash_password(password.to_string()).await?;
+        if !UserRepository::set_password_hash(pool, id, &hash).await? {
+            return Err(UserFeatureError::NotFound(id));
+        }
+
+        Ok(())
+    }
+
+    /// Check an email and password, returning the user they belong to
+    ///
+    /// An unknown email, a user without a password and a wrong password are
+    /// all `InvalidCredentials`, so callers can't tell which accounts exist.
+    /// Without a stored hash the password is checked against a dummy one, so
+    /// those cases take as long as a wrong password.
+    pub async fn verify_password(
+        pool: &PgPool,
+        email: &str,
+        password: &str,
+    ) -> Result<User, UserFeatureError> {
+        let user = UserRepository::find_by_email(pool, email).await?;
+        let hash = match &user {
+            Some(user) => UserRepository::find_password_hash(pool, user.id).await?,
+            None => None,
+        };
+
+        let has_hash = hash.is_some();
+        let hash = hash.unwrap_or_else(|| DUMMY_PASSWORD_HASH.to_string());
+        let matches = password_matches(password.to_string(), hash).await?;
+
+        match user {
+            Some(user) if has_hash && matches => Ok(user),
+            _ => Err(UserFeatureError::InvalidCredentials),
+        }
+    }
+
+    /// Anonymize a user instead of deleting their data
+    ///
+    /// Their email, name and password are scrubbed and they are soft-deleted,
+    /// but their todos are kept for analytics. As with `delete`, the
+    /// idempotency keys they were registered under are released in the same
+    /// transaction. Returns false if there was no such user.
+    pub async fn anonymize(pool: &PgPool, id: Uuid) -> Result<bool, UserFeatureError> {
+        let mut tx = pool.begin().await.map_err(domain::DomainError::from)?;
+
+        UserRepository::release_idempotency_keys(&mut *tx, id).await?;
+        let anonymized = UserRepository::anonymize(&mut *tx, id).await?;
+
+        tx.commit().await.map_err(domain::DomainError::from)?;
+
+        Ok(anonymized)
+    }
+}
+
+/// Hash a password with Argon2 and a fresh salt
+///
+/// Hashing is deliberately slow, so it runs on a blocking thread.
+async fn hash_password(password: String) -> Result<String, UserFeatureError> {
+    tokio::task::spawn_blocking(move || {
+        let salt = SaltString::generate(&mut OsRng);
+        Argon2::default()
+            .hash_password(password.as_bytes(), &salt)
+            .map(|hash| hash.to_string())
+    })
+    .await
+    .map_err(|e| UserFeatureError::PasswordHash(e.to_string()))?
+    .map_err(|e| UserFeatureError::PasswordHash(e.to_string()))
+}
+
+/// Whether `password` matches a stored Argon2 hash, checked on a blocking
+/// thread
+async fn password_matches(password: String, hash: String) -> Result<bool, UserFeatureError> {
+    tokio::task::spawn_blocking(move || {
+        let hash = PasswordHash::new(&hash)?;
+        match Argon2::default().verify_password(password.as_bytes(), &hash) {
+            Ok(()) => Ok(true),
+            Err(password_hash::Error::Password) => Ok(false),
+            Err(e) => Err(e),
+        }
+    })
+    .await
+    .map_err(|e| UserFeatureError::PasswordHash(e.to_string()))?
+    .map_err(|e| UserFeatureError::PasswordHash(e.to_string()))
+}
+
+/// Trim a name, rejecting it if empty or still longer than the configured
+/// maximum
+fn check_name(name: &str) -> Result<&str, UserFeatureError> {
+    trim_name(name).map_err(|message| UserFeatureError::Validation(format!("name {message}")))
+}
+
+/// Trim a name, or say what is wrong with it
+fn trim_name(name: &str) -> Result<&str, String> {
+    let trimmed = name.trim();
+    let max = UserLimits::current().name_max;
+
+    if trimmed.is_empty() {
+        return Err("must not be empty".to_string());
+    }
+    if trimmed.chars().count() > max {
+        return Err(format!("must be at most {max} characters"));
+    }
+
+    Ok(trimmed)
+}
+
+/// Check every field of a registration, returning the trimmed name and the
+/// timezone, or one error per invalid field
+fn check_registration(input: &CreateUserInput) -> Result<(&str, &str), Vec<FieldError>> {
+    let mut errors = Vec::new();
+
+    if !is_valid_email(&domain::normalize_email(&input.email)) {
+        errors.push(FieldError::new("email", "must be a valid email address"));
+    }
+
+    let name = trim_name(&input.name)
+        .map_err(|message| errors.push(FieldError::new("name", message)))
+        .ok();
+
+    let timezone = match &input.timezone {
+        Some(timezone) => validate_timezone(timezone)
+            .map_err(|_| errors.push(FieldError::new("timezone", "must be an IANA timezone name")))
+            .ok(),
+        None => Some(DEFAULT_TIMEZONE),
+    };
+
+    match (name, timezone) {
+        (Some(name), Some(timezone)) if errors.is_empty() => Ok((name, timezone)),
+        _ => Err(errors),
+    }
+}
+
+/// Whether `email` looks like a deliverable address: one `@`, something
+/// before it, a dotted domain after it and no whitespace
+pub fn is_valid_email(email: &str) -> bool {
+    match email.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty()
+                && !domain.contains('@')
+                && domain.contains('.')
+                && !domain.starts_with('.')
+                && !domain.ends_with('.')
+                && !email.chars().any(char::is_whitespace)
+        }
+        None => false,
+    }
+}
+
+/// Validate an email address and normalize it to lowercase
+fn normalize_email(email: &str) -> Result<String, UserFeatureError> {
+    let email = domain::normalize_email(email);
+
+    if is_valid_email(&email) {
+        Ok(email)
+    } else {
+        Err(UserFeatureError::InvalidEmail(email))
+    }
+}
+
+/// Check `timezone` names a zone in the IANA database
+fn validate_timezone(timezone: &str) -> Result<&str, UserFeatureError> {
+    match time_tz::timezones::get_by_name(timezone) {
+        Some(_) => Ok(timezone),
+        None => Err(UserFeatureError::InvalidTimezone(timezone.to_string())),
     }
 }
@@ -0,0 +1,93 @@
+use std::env;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum WelcomeEmailConfigError {
+    #[error("WELCOME_EMAIL_MODE must be \"immediate\" or \"digest\", got {0:?}")]
+    InvalidMode(String),
+
+    #[error("{name} must be a positive integer, got {value:?}")]
+    InvalidNumber { name: &'static str, value: String },
+}
+
+/// When new users are sent their welcome email
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WelcomeEmailMode {
+    /// A job per user, queued as they register
+    #[default]
+    Immediate,
+    /// A periodic `send_welcome_digest` job for everyone who registered since
+    /// the last one
+    Digest,
+}
+
+/// How welcome emails are sent
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WelcomeEmailConfig {
+    pub mode: WelcomeEmailMode,
+    /// How often a digest is sent in `Digest` mode
+    pub digest_interval: Duration,
+}
+
+impl Default for WelcomeEmailConfig {
+    fn default() -> Self {
+        Self {
+            mode: WelcomeEmailMode::default(),
+            digest_interval: Duration::from_secs(300),
+        }
+    }
+}
+
+static CONFIG: OnceLock<WelcomeEmailConfig> = OnceLock::new();
+
+impl WelcomeEmailConfig {
+    /// Read the config from `WELCOME_EMAIL_MODE` and `WELCOME_DIGEST_EVERY_MS`
+    pub fn from_env() -> Result<Self, WelcomeEmailConfigError> {
+        Self::from_vars(|name| env::var(name).ok())
+    }
+
+    /// Build the config from a variable lookup, using defaults for unset ones
+    pub fn from_vars(
+        var: impl Fn(&str) -> Option<String>,
+    ) -> Result<Self, WelcomeEmailConfigError> {
+        let defaults = Self::default();
+
+        let mode = match var("WELCOME_EMAIL_MODE") {
+            Some(value) => match value.trim() {
+                "immediate" => WelcomeEmailMode::Immediate,
+                "digest" => WelcomeEmailMode::Digest,
+                _ => return Err(WelcomeEmailConfigError::InvalidMode(value)),
+            },
+            None => defaults.mode,
+        };
+
+        let name = "WELCOME_DIGEST_EVERY_MS";
+        let digest_interval = match var(name) {
+            Some(value) => match value.trim().parse() {
+                Ok(ms) if ms > 0 => Duration::from_millis(ms),
+                _ => return Err(WelcomeEmailConfigError::InvalidNumber { name, value }),
+            },
+            None => defaults.digest_interval,
+        };
+
+        Ok(Self {
+            mode,
+            digest_interval,
+        })
+    }
+
+    /// Make this the config registration follows
+    ///
+    /// Only the first call has any effect.
+    pub fn install(self) {
+        let _ = CONFIG.set(self);
+    }
+
+    /// The config in force: the installed one, or the default
+    pub fn current() -> Self {
+        CONFIG.get().copied().unwrap_or_default()
+    }
+}
@@ -0,0 +1,84 @@
+//! SMTP configuration tests
+
+use std::collections::HashMap;
+
+use user_feature::{SmtpConfig, SmtpConfigError};
+
+/// Parse a config from the given variables only
+fn parse(vars: &[(&str, &str)]) -> Result<Option<SmtpConfig>, SmtpConfigError> {
+    let vars: HashMap<String, String> = vars
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    SmtpConfig::from_vars(|name| vars.get(name).cloned())
+}
+
+#[test]
+fn smtp_is_off_without_a_host() {
+    assert_eq!(parse(&[("FROM_ADDRESS", "noreply@example.com")]), Ok(None));
+}
+
+#[test]
+fn full_config_is_read() {
+    let config = parse(&[
+        ("SMTP_HOST", "smtp.example.com"),
+        ("SMTP_PORT", "465"),
+        ("SMTP_USER", "apex"),
+        ("SMTP_PASS", "secret"),
+        ("FROM_ADDRESS", "Apex Stack <noreply@example.com>"),
+    ])
+    .unwrap()
+    .unwrap();
+
+    assert_eq!(config.host, "smtp.example.com");
+    assert_eq!(config.port, 465);
+    assert_eq!(
+        config.credentials,
+        Some(("apex".to_string(), "secret".to_string()))
+    );
+    assert_eq!(config.from.email.to_string(), "noreply@example.com");
+}
+
+#[test]
+fn port_defaults_without_credentials() {
+    let config = parse(&[
+        ("SMTP_HOST", "localhost"),
+        ("FROM_ADDRESS", "noreply@example.com"),
+    ])
+    .unwrap()
+    .unwrap();
+
+    assert_eq!(config.port, SmtpConfig::DEFAULT_PORT);
+    assert_eq!(config.credentials, None);
+}
+
+#[test]
+fn from_address_is_required_with_a_host() {
+    assert_eq!(
+        parse(&[("SMTP_HOST", "localhost")]),
+        Err(SmtpConfigError::Missing("FROM_ADDRESS"))
+    );
+}
+
+#[test]
+fn invalid_values_are_rejected() {
+    let base = [
+        ("SMTP_HOST", "localhost"),
+        ("FROM_ADDRESS", "noreply@example.com"),
+    ];
+
+    let bad_port = parse(&[base[0], base[1], ("SMTP_PORT", "smtp")]);
+    assert_eq!(
+        bad_port,
+        Err(SmtpConfigError::InvalidPort("smtp".to_string()))
+    );
+
+    let bad_from = parse(&[base[0], ("FROM_ADDRESS", "not an address")]);
+    assert!(matches!(
+        bad_from,
+        Err(SmtpConfigError::InvalidFromAddress(_))
+    ));
+
+    let user_only = parse(&[base[0], base[1], ("SMTP_USER", "apex")]);
+    assert_eq!(user_only, Err(SmtpConfigError::PartialCredentials));
+}
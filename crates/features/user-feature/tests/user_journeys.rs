@@ -18,6 +18,8 @@ async fn complete_user_registration_journey(pool: PgPool) -> Result<(), UserFeat
         CreateUserInput {
             email: "journey@example.com".to_string(),
             name: "Journey User".to_string(),
+            timezone: None,
+            idempotency_key: None,
         },
     )
     .await?;
@@ -60,6 +62,8 @@ async fn user_can_update_their_profile(pool: PgPool) -> Result<(), UserFeatureEr
         CreateUserInput {
             email: "profile@example.com".to_string(),
             name: "Original Name".to_string(),
+            timezone: None,
+            idempotency_key: None,
         },
     )
     .await?;
@@ -70,13 +74,15 @@ async fn user_can_update_their_profile(pool: PgPool) -> Result<(), UserFeatureEr
         original.id,
         UpdateUserInput {
             name: Some("New Name".to_string()),
+            email: None,
+            timezone: None,
         },
     )
     .await?;
 
     // Then the name is changed
     assert_eq!(updated.name, "New Name");
-    // And the email remains unchanged (email is immutable)
+    // And the email remains unchanged
     assert_eq!(updated.email, "profile@example.com");
     // And the ID remains the same
     assert_eq!(updated.id, original.id);
@@ -100,6 +106,8 @@ async fn user_account_deletion_journey(pool: PgPool) -> Result<(), UserFeatureEr
         CreateUserInput {
             email: "todelete@example.com".to_string(),
             name: "To Delete".to_string(),
+            timezone: None,
+            idempotency_key: None,
         },
     )
     .await?;
@@ -139,6 +147,8 @@ async fn multiple_users_can_coexist(pool: PgPool) -> Result<(), UserFeatureError
         CreateUserInput {
             email: "alice@example.com".to_string(),
             name: "Alice".to_string(),
+            timezone: None,
+            idempotency_key: None,
         },
     )
     .await?;
@@ -148,6 +158,8 @@ async fn multiple_users_can_coexist(pool: PgPool) -> Result<(), UserFeatureError
         CreateUserInput {
             email: "bob@example.com".to_string(),
             name: "Bob".to_string(),
+            timezone: None,
+            idempotency_key: None,
         },
     )
     .await?;
@@ -157,6 +169,8 @@ async fn multiple_users_can_coexist(pool: PgPool) -> Result<(), UserFeatureError
         CreateUserInput {
             email: "charlie@example.com".to_string(),
             name: "Charlie".to_string(),
+            timezone: None,
+            idempotency_key: None,
         },
     )
     .await?;
@@ -176,6 +190,8 @@ async fn multiple_users_can_coexist(pool: PgPool) -> Result<(), UserFeatureError
         alice.id,
         UpdateUserInput {
             name: Some("Alice Updated".to_string()),
+            email: None,
+            timezone: None,
         },
     )
     .await?;
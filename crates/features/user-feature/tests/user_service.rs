@@ -3,8 +3,12 @@
 //! These tests verify user-related business behaviors work correctly.
 //! Focus on user journeys and business rules, not implementation details.
 
+use domain::{TodoRepository, User, UserFilter, UserRepository, UserRole, UserSort};
 use sqlx::PgPool;
-use user_feature::{CreateUserInput, UpdateUserInput, UserFeatureError, UserService};
+use time::{Date, Month};
+use user_feature::{
+    CreateUserInput, FieldError, UpdateUserInput, UserFeatureError, UserLimits, UserService,
+};
 use uuid::Uuid;
 
 // =============================================================================
@@ -18,15 +22,71 @@ async fn user_can_register_with_email_and_name(pool: PgPool) -> Result<(), UserF
         CreateUserInput {
             email: "register@example.com".to_string(),
             name: "Register Test".to_string(),
+            timezone: None,
+            idempotency_key: None,
         },
     )
     .await?;
 
     assert_eq!(user.email, "register@example.com");
     assert_eq!(user.name, "Register Test");
+    // Users who don't pick a timezone get UTC
+    assert_eq!(user.timezone, "UTC");
     Ok(())
 }
 
+#[sqlx::test(migrations = "../../../migrations")]
+async fn registration_with_unknown_timezone_is_rejected(pool: PgPool) {
+    let result = UserService::register(
+        &pool,
+        CreateUserInput {
+            email: "nowhere@example.com".to_string(),
+            name: "Nowhere".to_string(),
+            timezone: Some("Mars/Olympus_Mons".to_string()),
+            idempotency_key: None,
+        },
+    )
+    .await;
+
+    match result {
+        Err(UserFeatureError::InvalidFields(fields)) => {
+            assert_eq!(fields.len(), 1);
+            assert_eq!(fields[0].field, "timezone");
+        }
+        other => panic!("expected InvalidFields, got {other:?}"),
+    }
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn registration_reports_every_invalid_field_at_once(pool: PgPool) {
+    // When a registration has both a bad email and an empty name
+    let result = UserService::register(
+        &pool,
+        CreateUserInput {
+            email: "not-an-email".to_string(),
+            name: "".to_string(),
+            timezone: None,
+            idempotency_key: None,
+        },
+    )
+    .await;
+
+    // Then both fields are reported, in input order
+    match result {
+        Err(UserFeatureError::InvalidFields(fields)) => assert_eq!(
+            fields,
+            vec![
+                FieldError::new("email", "must be a valid email address"),
+                FieldError::new("name", "must not be empty"),
+            ]
+        ),
+        other => panic!("expected InvalidFields, got {other:?}"),
+    }
+
+    // And nobody was registered
+    assert!(UserService::list(&pool).await.unwrap().is_empty());
+}
+
 #[sqlx::test(migrations = "../../../migrations")]
 async fn registered_user_receives_welcome_email_job(pool: PgPool) -> Result<(), UserFeatureError> {
     // When a user registers
@@ -35,6 +95,8 @@ async fn registered_user_receives_welcome_email_job(pool: PgPool) -> Result<(),
         CreateUserInput {
             email: "welcome@example.com".to_string(),
             name: "Welcome Test".to_string(),
+            timezone: None,
+            idempotency_key: None,
         },
     )
     .await?;
@@ -79,6 +141,54 @@ async fn registered_user_receives_welcome_email_job(pool: PgPool) -> Result<(),
     Ok(())
 }
 
+#[sqlx::test(migrations = "../../../migrations")]
+async fn registration_records_user_registered_event(pool: PgPool) -> Result<(), UserFeatureError> {
+    // When a user registers
+    let user = UserService::register(
+        &pool,
+        CreateUserInput {
+            email: "event@example.com".to_string(),
+            name: "Event".to_string(),
+            timezone: None,
+            idempotency_key: None,
+        },
+    )
+    .await?;
+
+    // Then a user.registered event is waiting in the outbox
+    let events: Vec<(String, serde_json::Value)> =
+        sqlx::query_as("SELECT event_type, payload_json FROM outbox WHERE processed_at IS NULL")
+            .fetch_all(&pool)
+            .await
+            .map_err(domain::DomainError::from)?;
+
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].0, "user.registered");
+    assert_eq!(events[0].1["user_id"], user.id.to_string());
+    assert_eq!(events[0].1["email"], "event@example.com");
+
+    // And a rejected registration records no event
+    let duplicate = UserService::register(
+        &pool,
+        CreateUserInput {
+            email: "event@example.com".to_string(),
+            name: "Again".to_string(),
+            timezone: None,
+            idempotency_key: None,
+        },
+    )
+    .await;
+    assert!(matches!(duplicate, Err(UserFeatureError::EmailExists(_))));
+
+    let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM outbox")
+        .fetch_one(&pool)
+        .await
+        .map_err(domain::DomainError::from)?;
+    assert_eq!(total, 1);
+
+    Ok(())
+}
+
 #[sqlx::test(migrations = "../../../migrations")]
 async fn duplicate_email_registration_is_rejected(pool: PgPool) -> Result<(), UserFeatureError> {
     // Given an existing user
@@ -87,6 +197,8 @@ async fn duplicate_email_registration_is_rejected(pool: PgPool) -> Result<(), Us
         CreateUserInput {
             email: "duplicate@example.com".to_string(),
             name: "First".to_string(),
+            timezone: None,
+            idempotency_key: None,
         },
     )
     .await?;
@@ -97,6 +209,8 @@ async fn duplicate_email_registration_is_rejected(pool: PgPool) -> Result<(), Us
         CreateUserInput {
             email: "duplicate@example.com".to_string(),
             name: "Second".to_string(),
+            timezone: None,
+            idempotency_key: None,
         },
     )
     .await;
@@ -106,6 +220,412 @@ async fn duplicate_email_registration_is_rejected(pool: PgPool) -> Result<(), Us
     Ok(())
 }
 
+#[sqlx::test(migrations = "../../../migrations")]
+async fn concurrent_registrations_with_same_email_admit_one(
+    pool: PgPool,
+) -> Result<(), UserFeatureError> {
+    // When two registrations for the same email race
+    let (first, second) = tokio::join!(
+        UserService::register(
+            &pool,
+            CreateUserInput {
+                email: "race@example.com".to_string(),
+                name: "First".to_string(),
+                timezone: None,
+                idempotency_key: None,
+            },
+        ),
+        UserService::register(
+            &pool,
+            CreateUserInput {
+                email: "race@example.com".to_string(),
+                name: "Second".to_string(),
+                timezone: None,
+                idempotency_key: None,
+            },
+        ),
+    );
+
+    // Then exactly one succeeds and the other is told the email exists
+    let results = [first, second];
+    assert_eq!(results.iter().filter(|r| r.is_ok()).count(), 1);
+    assert!(
+        results
+            .iter()
+            .any(|r| matches!(r, Err(UserFeatureError::EmailExists(_))))
+    );
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn racing_registrations_are_serialized_without_the_unique_index(
+    pool: PgPool,
+) -> Result<(), UserFeatureError> {
+    // Given the unique email index is gone, so only the email lock can help
+    sqlx::query("DROP INDEX users_email_active_idx")
+        .execute(&pool)
+        .await
+        .map_err(domain::DomainError::from)?;
+
+    // When two registrations for the same email run as separate tasks
+    let register = |name: &str| {
+        let pool = pool.clone();
+        let input = CreateUserInput {
+            email: "locked@example.com".to_string(),
+            name: name.to_string(),
+            timezone: None,
+            idempotency_key: None,
+        };
+        tokio::spawn(async move { UserService::register(&pool, input).await })
+    };
+    let (first, second) = (register("First"), register("Second"));
+    let results = [first.await.unwrap(), second.await.unwrap()];
+
+    // Then exactly one succeeds and only one user exists
+    assert_eq!(results.iter().filter(|r| r.is_ok()).count(), 1);
+    assert!(
+        results
+            .iter()
+            .any(|r| matches!(r, Err(UserFeatureError::EmailExists(_))))
+    );
+    assert_eq!(UserService::list(&pool).await?.len(), 1);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn welcome_email_can_be_resent(pool: PgPool) -> Result<(), UserFeatureError> {
+    // Given a registered user with one welcome email queued
+    let user = UserService::register(
+        &pool,
+        CreateUserInput {
+            email: "resend@example.com".to_string(),
+            name: "Resend Test".to_string(),
+            timezone: None,
+            idempotency_key: None,
+        },
+    )
+    .await?;
+
+    // When the welcome email is resent
+    UserService::resend_welcome_email(&pool, user.id).await?;
+
+    // Then a second job is queued on the emails channel
+    let email_job_count: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM mq_msgs WHERE channel_name = 'emails' AND id != uuid_nil()",
+    )
+    .fetch_one(&pool)
+    .await
+    .map_err(domain::DomainError::from)?;
+
+    assert_eq!(email_job_count, 2);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn resending_welcome_email_to_unknown_user_fails(
+    pool: PgPool,
+) -> Result<(), UserFeatureError> {
+    let result = UserService::resend_welcome_email(&pool, Uuid::new_v4()).await;
+
+    assert!(matches!(result, Err(UserFeatureError::NotFound(_))));
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn retried_registration_with_same_key_returns_same_user(
+    pool: PgPool,
+) -> Result<(), UserFeatureError> {
+    let input = || CreateUserInput {
+        email: "retry@example.com".to_string(),
+        name: "Retry".to_string(),
+        timezone: None,
+        idempotency_key: Some("signup-retry-1".to_string()),
+    };
+
+    // Given a registration made with an idempotency key
+    let first = UserService::register(&pool, input()).await?;
+
+    // When the client retries it with the same key
+    let second = UserService::register(&pool, input()).await?;
+
+    // Then the same user comes back
+    assert_eq!(second.id, first.id);
+
+    // And only one user and one welcome email were created
+    let users: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users")
+        .fetch_one(&pool)
+        .await
+        .map_err(domain::DomainError::from)?;
+    assert_eq!(users, 1);
+
+    let email_jobs: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM mq_msgs WHERE channel_name = 'emails' AND id != uuid_nil()",
+    )
+    .fetch_one(&pool)
+    .await
+    .map_err(domain::DomainError::from)?;
+    assert_eq!(email_jobs, 1);
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn registrations_with_different_keys_create_distinct_users(
+    pool: PgPool,
+) -> Result<(), UserFeatureError> {
+    // When two different registrations each carry their own key
+    let ann = UserService::register(
+        &pool,
+        CreateUserInput {
+            email: "ann@example.com".to_string(),
+            name: "Ann".to_string(),
+            timezone: None,
+            idempotency_key: Some("signup-ann".to_string()),
+        },
+    )
+    .await?;
+    let ben = UserService::register(
+        &pool,
+        CreateUserInput {
+            email: "ben@example.com".to_string(),
+            name: "Ben".to_string(),
+            timezone: None,
+            idempotency_key: Some("signup-ben".to_string()),
+        },
+    )
+    .await?;
+
+    // Then each gets their own user
+    assert_ne!(ann.id, ben.id);
+
+    let keys: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM idempotency_keys")
+        .fetch_one(&pool)
+        .await
+        .map_err(domain::DomainError::from)?;
+    assert_eq!(keys, 2);
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn deleting_a_user_releases_their_idempotency_key(
+    pool: PgPool,
+) -> Result<(), UserFeatureError> {
+    let input = || CreateUserInput {
+        email: "again@example.com".to_string(),
+        name: "Again".to_string(),
+        timezone: None,
+        idempotency_key: Some("signup-again".to_string()),
+    };
+
+    // Given a user registered with an idempotency key, then deleted
+    let first = UserService::register(&pool, input()).await?;
+    UserService::delete(&pool, first.id).await?;
+
+    // When a registration reuses the key
+    let second = UserService::register(&pool, input()).await?;
+
+    // Then a new user is created under it
+    assert_ne!(second.id, first.id);
+    assert!(second.deleted_at.is_none());
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn anonymizing_a_user_releases_their_idempotency_key(
+    pool: PgPool,
+) -> Result<(), UserFeatureError> {
+    let input = || CreateUserInput {
+        email: "anon-again@example.com".to_string(),
+        name: "Again".to_string(),
+        timezone: None,
+        idempotency_key: Some("signup-anon-again".to_string()),
+    };
+
+    // Given a user registered with an idempotency key, then anonymized
+    let first = UserService::register(&pool, input()).await?;
+    UserService::anonymize(&pool, first.id).await?;
+
+    // When a registration reuses the key
+    let second = UserService::register(&pool, input()).await?;
+
+    // Then a new user is created under it
+    assert_ne!(second.id, first.id);
+    assert!(second.deleted_at.is_none());
+
+    Ok(())
+}
+
+/// Registration input for a batch, without timezone or idempotency key
+fn batch_input(email: &str, name: &str) -> CreateUserInput {
+    CreateUserInput {
+        email: email.to_string(),
+        name: name.to_string(),
+        timezone: None,
+        idempotency_key: None,
+    }
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn batch_of_users_can_be_registered(pool: PgPool) -> Result<(), UserFeatureError> {
+    // When registering three users in one batch
+    let users = UserService::register_many(
+        &pool,
+        vec![
+            batch_input("one@example.com", "One"),
+            batch_input("two@example.com", "Two"),
+            batch_input("three@example.com", "Three"),
+        ],
+    )
+    .await?;
+
+    // Then all three are created, in order
+    let emails: Vec<&str> = users.iter().map(|u| u.email.as_str()).collect();
+    assert_eq!(
+        emails,
+        ["one@example.com", "two@example.com", "three@example.com"]
+    );
+    assert_eq!(UserService::list(&pool).await?.len(), 3);
+
+    // And each is sent a welcome email
+    let email_jobs: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM mq_msgs WHERE channel_name = 'emails' AND id != uuid_nil()",
+    )
+    .fetch_one(&pool)
+    .await
+    .map_err(domain::DomainError::from)?;
+    assert_eq!(email_jobs, 3);
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn batch_with_taken_email_rolls_back(pool: PgPool) -> Result<(), UserFeatureError> {
+    // Given an existing user
+    UserService::register(&pool, batch_input("taken@example.com", "Existing")).await?;
+
+    // When a batch includes their email
+    let result = UserService::register_many(
+        &pool,
+        vec![
+            batch_input("fresh@example.com", "Fresh"),
+            batch_input("taken@example.com", "Duplicate"),
+            batch_input("other@example.com", "Other"),
+        ],
+    )
+    .await;
+
+    // Then the batch fails naming the duplicate
+    assert!(matches!(
+        result,
+        Err(UserFeatureError::EmailExists(ref email)) if email == "taken@example.com"
+    ));
+    // And none of its users were created
+    let users = UserService::list(&pool).await?;
+    assert_eq!(users.len(), 1);
+    assert_eq!(users[0].name, "Existing");
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn batch_repeating_an_email_rolls_back(pool: PgPool) -> Result<(), UserFeatureError> {
+    let result = UserService::register_many(
+        &pool,
+        vec![
+            batch_input("twice@example.com", "First"),
+            batch_input("twice@example.com", "Second"),
+        ],
+    )
+    .await;
+
+    assert!(matches!(
+        result,
+        Err(UserFeatureError::EmailExists(ref email)) if email == "twice@example.com"
+    ));
+    assert!(UserService::list(&pool).await?.is_empty());
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn batch_returns_the_user_already_registered_under_a_key(
+    pool: PgPool,
+) -> Result<(), UserFeatureError> {
+    // Given a user registered with an idempotency key
+    let keyed = || CreateUserInput {
+        idempotency_key: Some("import-row-1".to_string()),
+        ..batch_input("keyed@example.com", "Keyed")
+    };
+    let existing = UserService::register(&pool, keyed()).await?;
+
+    // When a batch retries that registration alongside a new one
+    let users =
+        UserService::register_many(&pool, vec![batch_input("new@example.com", "New"), keyed()])
+            .await?;
+
+    // Then the existing user is returned in its place
+    assert_eq!(users.len(), 2);
+    assert_eq!(users[0].email, "new@example.com");
+    assert_eq!(users[1].id, existing.id);
+    // And only the new user was created
+    assert_eq!(UserService::list(&pool).await?.len(), 2);
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn batch_reports_invalid_fields_by_input_index(pool: PgPool) {
+    let result = UserService::register_many(
+        &pool,
+        vec![
+            batch_input("fine@example.com", "Fine"),
+            batch_input("broken", "Broken"),
+        ],
+    )
+    .await;
+
+    match result {
+        Err(UserFeatureError::InvalidFields(fields)) => {
+            assert_eq!(fields.len(), 1);
+            assert_eq!(fields[0].field, "1.email");
+        }
+        other => panic!("expected InvalidFields, got {other:?}"),
+    }
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn registration_can_share_a_transaction_with_other_work(
+    pool: PgPool,
+) -> Result<(), UserFeatureError> {
+    // Given a registration and a first todo made in one transaction
+    let mut tx = pool.begin().await.map_err(domain::DomainError::from)?;
+    let user = UserService::register_tx(
+        &mut tx,
+        CreateUserInput {
+            email: "composed@example.com".to_string(),
+            name: "Composed".to_string(),
+            timezone: None,
+            idempotency_key: None,
+        },
+    )
+    .await?;
+    let todo = TodoRepository::create(&mut *tx, user.id, "First todo", None, None).await?;
+
+    // When the transaction is rolled back
+    tx.rollback().await.map_err(domain::DomainError::from)?;
+
+    // Then neither the user nor the todo exists
+    assert!(
+        UserService::get_by_email(&pool, "composed@example.com")
+            .await?
+            .is_none()
+    );
+    assert!(TodoRepository::find_by_id(&pool, todo.id).await?.is_none());
+    Ok(())
+}
+
 // =============================================================================
 // User Query Behaviors
 // =============================================================================
@@ -118,6 +638,8 @@ async fn user_can_be_found_by_id(pool: PgPool) -> Result<(), UserFeatureError> {
         CreateUserInput {
             email: "get@example.com".to_string(),
             name: "Get Test".to_string(),
+            timezone: None,
+            idempotency_key: None,
         },
     )
     .await?;
@@ -147,6 +669,8 @@ async fn user_can_be_found_by_email(pool: PgPool) -> Result<(), UserFeatureError
         CreateUserInput {
             email: "byemail@example.com".to_string(),
             name: "Email Test".to_string(),
+            timezone: None,
+            idempotency_key: None,
         },
     )
     .await?;
@@ -175,6 +699,8 @@ async fn all_users_can_be_listed(pool: PgPool) -> Result<(), UserFeatureError> {
         CreateUserInput {
             email: "list1@example.com".to_string(),
             name: "List 1".to_string(),
+            timezone: None,
+            idempotency_key: None,
         },
     )
     .await?;
@@ -184,6 +710,8 @@ async fn all_users_can_be_listed(pool: PgPool) -> Result<(), UserFeatureError> {
         CreateUserInput {
             email: "list2@example.com".to_string(),
             name: "List 2".to_string(),
+            timezone: None,
+            idempotency_key: None,
         },
     )
     .await?;
@@ -203,6 +731,149 @@ async fn listing_users_when_none_exist_returns_empty(pool: PgPool) -> Result<(),
     Ok(())
 }
 
+/// Register a user with the given name and email
+async fn register(pool: &PgPool, name: &str, email: &str) -> Result<User, UserFeatureError> {
+    UserService::register(
+        pool,
+        CreateUserInput {
+            email: email.to_string(),
+            name: name.to_string(),
+            timezone: None,
+            idempotency_key: None,
+        },
+    )
+    .await
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn users_can_be_found_by_name_and_registration_date_a_page_at_a_time(
+    pool: PgPool,
+) -> Result<(), UserFeatureError> {
+    // Given an early Ann, then three more Anns and a Bob
+    register(&pool, "Ann Early", "early@example.com").await?;
+    let one = register(&pool, "Ann One", "one@example.com").await?;
+    register(&pool, "Ann Two", "two@example.com").await?;
+    register(&pool, "Ann Three", "three@example.com").await?;
+    register(&pool, "Bob", "bob@example.com").await?;
+
+    // When finding Anns registered from Ann One on, by name, two at a time
+    let filter = UserFilter {
+        query: Some("ann".to_string()),
+        created_after: Some(one.created_at),
+        include_deleted: false,
+    };
+    let first = UserService::find(&pool, filter.clone(), UserSort::Name, 0, 2).await?;
+    let second = UserService::find(&pool, filter, UserSort::Name, 2, 2).await?;
+
+    // Then the later Anns come back alphabetically across both pages
+    let names = |users: &[User]| users.iter().map(|u| u.name.clone()).collect::<Vec<_>>();
+    assert_eq!(names(&first.items), vec!["Ann One", "Ann Three"]);
+    assert!(first.has_next_page);
+    assert_eq!(names(&second.items), vec!["Ann Two"]);
+    assert!(!second.has_next_page);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn deleted_users_are_only_found_when_asked_for(pool: PgPool) -> Result<(), UserFeatureError> {
+    // Given a deleted user
+    let gone = register(&pool, "Gone", "gone@example.com").await?;
+    UserService::delete(&pool, gone.id).await?;
+
+    // When finding users with and without deleted ones
+    let active = UserService::find(&pool, UserFilter::default(), UserSort::Newest, 0, 10).await?;
+    let all = UserService::find(
+        &pool,
+        UserFilter {
+            include_deleted: true,
+            ..UserFilter::default()
+        },
+        UserSort::Newest,
+        0,
+        10,
+    )
+    .await?;
+
+    // Then only the second includes them
+    assert!(active.items.is_empty());
+    assert_eq!(all.items.len(), 1);
+    assert_eq!(all.items[0].id, gone.id);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn registrations_are_counted_per_day_with_empty_days_as_zero(
+    pool: PgPool,
+) -> Result<(), UserFeatureError> {
+    // Given two users registered on March 1st and one on March 3rd (UTC)
+    let march = |day| Date::from_calendar_date(2025, Month::March, day).unwrap();
+    for (email, day, hour) in [
+        ("early@example.com", 1, 0),
+        ("late@example.com", 1, 23),
+        ("third@example.com", 3, 12),
+    ] {
+        let user = register(&pool, "User", email).await?;
+        sqlx::query("UPDATE users SET created_at = $1 WHERE id = $2")
+            .bind(march(day).with_hms(hour, 30, 0).unwrap().assume_utc())
+            .bind(user.id)
+            .execute(&pool)
+            .await
+            .map_err(domain::DomainError::from)?;
+    }
+
+    // When counting registrations from March 1st to 3rd
+    let buckets = UserService::registrations_by_day(&pool, march(1), march(3)).await?;
+
+    // Then every day is reported, including the empty one
+    assert_eq!(buckets, vec![(march(1), 2), (march(2), 0), (march(3), 1)]);
+
+    // And a reversed range is rejected
+    let reversed = UserService::registrations_by_day(&pool, march(3), march(1)).await;
+    assert!(matches!(reversed, Err(UserFeatureError::Validation(_))));
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn users_can_be_listed_by_registration_time(pool: PgPool) -> Result<(), UserFeatureError> {
+    // Given users registered at 09:00, exactly 10:00 and 11:00 on March 1st (UTC)
+    let at = |hour| {
+        Date::from_calendar_date(2025, Month::March, 1)
+            .unwrap()
+            .with_hms(hour, 0, 0)
+            .unwrap()
+            .assume_utc()
+    };
+    for (email, hour) in [
+        ("nine@example.com", 9),
+        ("ten@example.com", 10),
+        ("eleven@example.com", 11),
+    ] {
+        let user = register(&pool, "User", email).await?;
+        sqlx::query("UPDATE users SET created_at = $1 WHERE id = $2")
+            .bind(at(hour))
+            .bind(user.id)
+            .execute(&pool)
+            .await
+            .map_err(domain::DomainError::from)?;
+    }
+    let emails = |users: Vec<User>| users.into_iter().map(|u| u.email).collect::<Vec<_>>();
+
+    // When listing users registered from 10:00
+    let after = UserService::list_created_between(&pool, Some(at(10)), None).await?;
+
+    // Then the lower bound is inclusive
+    assert_eq!(emails(after), ["eleven@example.com", "ten@example.com"]);
+
+    // And listing users registered before 10:00 excludes the user at the bound
+    let before = UserService::list_created_between(&pool, None, Some(at(10))).await?;
+    assert_eq!(emails(before), ["nine@example.com"]);
+
+    // And both bounds together select the half-open range
+    let between = UserService::list_created_between(&pool, Some(at(10)), Some(at(11))).await?;
+    assert_eq!(emails(between), ["ten@example.com"]);
+    Ok(())
+}
+
 // =============================================================================
 // User Update Behaviors
 // =============================================================================
@@ -215,6 +886,8 @@ async fn user_name_can_be_updated(pool: PgPool) -> Result<(), UserFeatureError>
         CreateUserInput {
             email: "update@example.com".to_string(),
             name: "Original".to_string(),
+            timezone: None,
+            idempotency_key: None,
         },
     )
     .await?;
@@ -225,6 +898,8 @@ async fn user_name_can_be_updated(pool: PgPool) -> Result<(), UserFeatureError>
         created.id,
         UpdateUserInput {
             name: Some("Updated".to_string()),
+            email: None,
+            timezone: None,
         },
     )
     .await?;
@@ -244,18 +919,249 @@ async fn update_with_no_changes_preserves_user(pool: PgPool) -> Result<(), UserF
         CreateUserInput {
             email: "nochange@example.com".to_string(),
             name: "No Change".to_string(),
+            timezone: None,
+            idempotency_key: None,
         },
     )
     .await?;
 
     // When updating with no fields set
-    let updated = UserService::update(&pool, created.id, UpdateUserInput { name: None }).await?;
+    let updated = UserService::update(
+        &pool,
+        created.id,
+        UpdateUserInput {
+            name: None,
+            email: None,
+            timezone: None,
+        },
+    )
+    .await?;
 
     // Then the user is unchanged
     assert_eq!(updated.name, "No Change");
     Ok(())
 }
 
+#[sqlx::test(migrations = "../../../migrations")]
+async fn user_email_can_be_changed(pool: PgPool) -> Result<(), UserFeatureError> {
+    // Given a registered user
+    let created = UserService::register(
+        &pool,
+        CreateUserInput {
+            email: "old-address@example.com".to_string(),
+            name: "Mover".to_string(),
+            timezone: None,
+            idempotency_key: None,
+        },
+    )
+    .await?;
+
+    // When changing to an unused email with mixed case
+    let updated = UserService::update(
+        &pool,
+        created.id,
+        UpdateUserInput {
+            name: None,
+            email: Some("New-Address@Example.com".to_string()),
+            timezone: None,
+        },
+    )
+    .await?;
+
+    // Then the email is stored lowercase
+    assert_eq!(updated.email, "new-address@example.com");
+    // And the user can be found by it
+    let found = UserService::get_by_email(&pool, "new-address@example.com").await?;
+    assert_eq!(found.map(|u| u.id), Some(created.id));
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn email_cannot_be_changed_to_another_users(pool: PgPool) -> Result<(), UserFeatureError> {
+    // Given two registered users
+    UserService::register(
+        &pool,
+        CreateUserInput {
+            email: "taken@example.com".to_string(),
+            name: "Owner".to_string(),
+            timezone: None,
+            idempotency_key: None,
+        },
+    )
+    .await?;
+    let other = UserService::register(
+        &pool,
+        CreateUserInput {
+            email: "other@example.com".to_string(),
+            name: "Other".to_string(),
+            timezone: None,
+            idempotency_key: None,
+        },
+    )
+    .await?;
+
+    // When one tries to take the other's email
+    let result = UserService::update(
+        &pool,
+        other.id,
+        UpdateUserInput {
+            name: None,
+            email: Some("taken@example.com".to_string()),
+            timezone: None,
+        },
+    )
+    .await;
+
+    // Then the change is rejected
+    assert!(matches!(result, Err(UserFeatureError::EmailExists(_))));
+    // And their email is unchanged
+    assert_eq!(
+        UserService::get(&pool, other.id).await?.email,
+        "other@example.com"
+    );
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn malformed_email_is_rejected(pool: PgPool) -> Result<(), UserFeatureError> {
+    let created = UserService::register(
+        &pool,
+        CreateUserInput {
+            email: "valid@example.com".to_string(),
+            name: "Valid".to_string(),
+            timezone: None,
+            idempotency_key: None,
+        },
+    )
+    .await?;
+
+    for email in [
+        "not-an-email",
+        "@example.com",
+        "user@localhost",
+        "a b@example.com",
+    ] {
+        let result = UserService::update(
+            &pool,
+            created.id,
+            UpdateUserInput {
+                name: None,
+                email: Some(email.to_string()),
+                timezone: None,
+            },
+        )
+        .await;
+
+        assert!(
+            matches!(result, Err(UserFeatureError::InvalidEmail(_))),
+            "{email} should be rejected"
+        );
+    }
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn user_name_is_trimmed_and_length_limited(pool: PgPool) -> Result<(), UserFeatureError> {
+    let max = UserLimits::default().name_max;
+
+    // A name padded with whitespace is stored trimmed
+    let created = UserService::register(
+        &pool,
+        CreateUserInput {
+            email: "long-name@example.com".to_string(),
+            name: format!("  {}  ", "n".repeat(max)),
+            timezone: None,
+            idempotency_key: None,
+        },
+    )
+    .await?;
+    assert_eq!(created.name, "n".repeat(max));
+
+    // But a name one past the limit is rejected
+    let result = UserService::update(
+        &pool,
+        created.id,
+        UpdateUserInput {
+            name: Some("n".repeat(max + 1)),
+            email: None,
+            timezone: None,
+        },
+    )
+    .await;
+    assert!(matches!(result, Err(UserFeatureError::Validation(_))));
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn user_timezone_round_trips(pool: PgPool) -> Result<(), UserFeatureError> {
+    // Given a user registered in London
+    let created = UserService::register(
+        &pool,
+        CreateUserInput {
+            email: "london@example.com".to_string(),
+            name: "Londoner".to_string(),
+            timezone: Some("Europe/London".to_string()),
+            idempotency_key: None,
+        },
+    )
+    .await?;
+    assert_eq!(
+        UserService::get(&pool, created.id).await?.timezone,
+        "Europe/London"
+    );
+
+    // When they move to New York
+    UserService::update(
+        &pool,
+        created.id,
+        UpdateUserInput {
+            name: None,
+            email: None,
+            timezone: Some("America/New_York".to_string()),
+        },
+    )
+    .await?;
+
+    // Then the new timezone is stored
+    let found = UserService::get(&pool, created.id).await?;
+    assert_eq!(found.timezone, "America/New_York");
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn unknown_timezone_is_rejected(pool: PgPool) -> Result<(), UserFeatureError> {
+    let created = UserService::register(
+        &pool,
+        CreateUserInput {
+            email: "tz@example.com".to_string(),
+            name: "Timezone".to_string(),
+            timezone: None,
+            idempotency_key: None,
+        },
+    )
+    .await?;
+
+    for timezone in ["Mars/Olympus_Mons", "", "GMT+25"] {
+        let result = UserService::update(
+            &pool,
+            created.id,
+            UpdateUserInput {
+                name: None,
+                email: None,
+                timezone: Some(timezone.to_string()),
+            },
+        )
+        .await;
+
+        assert!(
+            matches!(result, Err(UserFeatureError::InvalidTimezone(_))),
+            "{timezone:?} should be rejected"
+        );
+    }
+    assert_eq!(UserService::get(&pool, created.id).await?.timezone, "UTC");
+    Ok(())
+}
+
 #[sqlx::test(migrations = "../../../migrations")]
 async fn updating_nonexistent_user_fails(pool: PgPool) -> Result<(), UserFeatureError> {
     let result = UserService::update(
@@ -263,6 +1169,8 @@ async fn updating_nonexistent_user_fails(pool: PgPool) -> Result<(), UserFeature
         Uuid::new_v4(),
         UpdateUserInput {
             name: Some("New Name".to_string()),
+            email: None,
+            timezone: None,
         },
     )
     .await;
@@ -271,6 +1179,43 @@ async fn updating_nonexistent_user_fails(pool: PgPool) -> Result<(), UserFeature
     Ok(())
 }
 
+// =============================================================================
+// User Role Behaviors
+// =============================================================================
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn user_can_be_promoted_to_admin(pool: PgPool) -> Result<(), UserFeatureError> {
+    // Given a newly registered user, who starts as a regular user
+    let created = UserService::register(
+        &pool,
+        CreateUserInput {
+            email: "promote@example.com".to_string(),
+            name: "Promote".to_string(),
+            timezone: None,
+            idempotency_key: None,
+        },
+    )
+    .await?;
+    assert_eq!(created.role, UserRole::User);
+
+    // When they are made an admin
+    UserService::set_role(&pool, created.id, UserRole::Admin).await?;
+
+    // Then the role is stored
+    assert_eq!(
+        UserService::get(&pool, created.id).await?.role,
+        UserRole::Admin
+    );
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn setting_role_of_nonexistent_user_fails(pool: PgPool) {
+    let result = UserService::set_role(&pool, Uuid::new_v4(), UserRole::Admin).await;
+
+    assert!(matches!(result, Err(UserFeatureError::NotFound(_))));
+}
+
 // =============================================================================
 // User Deletion Behaviors
 // =============================================================================
@@ -283,6 +1228,8 @@ async fn user_can_be_deleted(pool: PgPool) -> Result<(), UserFeatureError> {
         CreateUserInput {
             email: "delete@example.com".to_string(),
             name: "Delete Test".to_string(),
+            timezone: None,
+            idempotency_key: None,
         },
     )
     .await?;
@@ -297,9 +1244,244 @@ async fn user_can_be_deleted(pool: PgPool) -> Result<(), UserFeatureError> {
     Ok(())
 }
 
+#[sqlx::test(migrations = "../../../migrations")]
+async fn deleting_user_removes_their_todos(pool: PgPool) -> Result<(), UserFeatureError> {
+    // Given a user with three todos
+    let created = UserService::register(
+        &pool,
+        CreateUserInput {
+            email: "delete-with-todos@example.com".to_string(),
+            name: "Busy User".to_string(),
+            timezone: None,
+            idempotency_key: None,
+        },
+    )
+    .await?;
+    for title in ["One", "Two", "Three"] {
+        TodoRepository::create(&pool, created.id, title, None, None).await?;
+    }
+
+    // When deleting the user
+    let deleted = UserService::delete(&pool, created.id).await?;
+    assert!(deleted);
+
+    // Then their todos are gone
+    let todos = TodoRepository::list_by_user(&pool, created.id, false).await?;
+    assert!(todos.is_empty());
+    // And so is the user
+    let result = UserService::get(&pool, created.id).await;
+    assert!(matches!(result, Err(UserFeatureError::NotFound(_))));
+    Ok(())
+}
+
 #[sqlx::test(migrations = "../../../migrations")]
 async fn deleting_nonexistent_user_returns_false(pool: PgPool) -> Result<(), UserFeatureError> {
     let deleted = UserService::delete(&pool, Uuid::new_v4()).await?;
     assert!(!deleted);
     Ok(())
 }
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn anonymizing_user_scrubs_details_but_keeps_todos(
+    pool: PgPool,
+) -> Result<(), UserFeatureError> {
+    // Given a user with two todos
+    let created = UserService::register(
+        &pool,
+        CreateUserInput {
+            email: "forget-me@example.com".to_string(),
+            name: "Forgotten".to_string(),
+            timezone: None,
+            idempotency_key: None,
+        },
+    )
+    .await?;
+    for title in ["One", "Two"] {
+        TodoRepository::create(&pool, created.id, title, None, None).await?;
+    }
+
+    // When anonymizing the user
+    let anonymized = UserService::anonymize(&pool, created.id).await?;
+    assert!(anonymized);
+
+    // Then their email and name are scrubbed
+    let user = UserRepository::find_by_id_including_deleted(&pool, created.id)
+        .await?
+        .expect("anonymized user row is kept");
+    assert_eq!(
+        user.email,
+        format!("deleted-{}@example.invalid", created.id)
+    );
+    assert_eq!(user.name, "");
+    // And they no longer appear in the user list
+    let users = UserService::list(&pool).await?;
+    assert!(users.iter().all(|u| u.id != created.id));
+    // But their todos are untouched
+    let todos = TodoRepository::list_by_user(&pool, created.id, false).await?;
+    assert_eq!(todos.len(), 2);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn anonymizing_nonexistent_user_returns_false(pool: PgPool) -> Result<(), UserFeatureError> {
+    let anonymized = UserService::anonymize(&pool, Uuid::new_v4()).await?;
+    assert!(!anonymized);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn deleted_user_can_be_restored(pool: PgPool) -> Result<(), UserFeatureError> {
+    // Given a deleted user and an admin
+    let gone = register(&pool, "Returning", "returning@example.com").await?;
+    let admin = register(&pool, "Admin", "restorer@example.com").await?;
+    UserService::delete(&pool, gone.id).await?;
+
+    // When the admin restores them
+    let restored = UserService::restore(&pool, gone.id, admin.id).await?;
+
+    // Then they are listed again
+    assert!(restored);
+    let users = UserService::list(&pool).await?;
+    assert!(users.iter().any(|u| u.id == gone.id));
+    // And the outbox records who restored them
+    let payload: serde_json::Value =
+        sqlx::query_scalar("SELECT payload_json FROM outbox WHERE event_type = 'user.restored'")
+            .fetch_one(&pool)
+            .await
+            .map_err(domain::DomainError::from)?;
+    assert_eq!(payload["user_id"], gone.id.to_string());
+    assert_eq!(payload["restored_by"], admin.id.to_string());
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn restoring_a_live_user_returns_false(pool: PgPool) -> Result<(), UserFeatureError> {
+    // Given a user who was never deleted
+    let user = register(&pool, "Live", "live@example.com").await?;
+
+    // When restoring them, or someone who never existed
+    let restored = UserService::restore(&pool, user.id, user.id).await?;
+    let missing = UserService::restore(&pool, Uuid::new_v4(), user.id).await;
+
+    // Then nothing changes for the first and the second isn't found
+    assert!(!restored);
+    assert!(matches!(missing, Err(UserFeatureError::NotFound(_))));
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn restoring_a_user_whose_email_was_reused_is_rejected(
+    pool: PgPool,
+) -> Result<(), UserFeatureError> {
+    // Given a deleted user whose email now belongs to someone else
+    let gone = register(&pool, "Original", "reused@example.com").await?;
+    UserService::delete(&pool, gone.id).await?;
+    let current = register(&pool, "Newcomer", "reused@example.com").await?;
+
+    // When restoring the original
+    let result = UserService::restore(&pool, gone.id, current.id).await;
+
+    // Then the email clash is reported and they stay deleted
+    assert!(matches!(result, Err(UserFeatureError::EmailExists(_))));
+    assert!(matches!(
+        UserService::get(&pool, gone.id).await,
+        Err(UserFeatureError::NotFound(_))
+    ));
+    Ok(())
+}
+
+// =============================================================================
+// User Export Behaviors
+// =============================================================================
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn export_contains_user_and_all_their_todos(pool: PgPool) -> Result<(), UserFeatureError> {
+    // Given a user with two todos
+    let created = UserService::register(
+        &pool,
+        CreateUserInput {
+            email: "export@example.com".to_string(),
+            name: "Exporter".to_string(),
+            timezone: None,
+            idempotency_key: None,
+        },
+    )
+    .await?;
+    for title in ["Pack boxes", "Book movers"] {
+        TodoRepository::create(&pool, created.id, title, None, None).await?;
+    }
+
+    // When exporting their data
+    let export = UserService::export_data(&pool, created.id).await?;
+    let json = serde_json::to_value(&export).expect("export serializes");
+
+    // Then it holds their email
+    assert_eq!(json["email"], "export@example.com");
+    // And both todo titles with their statuses
+    let todos = json["todos"].as_array().expect("todos array");
+    let mut titles: Vec<&str> = todos.iter().filter_map(|t| t["title"].as_str()).collect();
+    titles.sort();
+    assert_eq!(titles, ["Book movers", "Pack boxes"]);
+    assert!(todos.iter().all(|t| t["status"] == "pending"));
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn exporting_nonexistent_user_fails(pool: PgPool) -> Result<(), UserFeatureError> {
+    let result = UserService::export_data(&pool, Uuid::new_v4()).await;
+
+    assert!(matches!(result, Err(UserFeatureError::NotFound(_))));
+    Ok(())
+}
+
+// =============================================================================
+// User Password Behaviors
+// =============================================================================
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn user_can_log_in_with_the_password_they_set(pool: PgPool) -> Result<(), UserFeatureError> {
+    // Given a user who sets a password
+    let user = UserService::register(&pool, batch_input("secret@example.com", "Secret")).await?;
+    UserService::set_password(&pool, user.id, "correct horse battery").await?;
+
+    // When they verify with it, using any case of their email
+    let verified =
+        UserService::verify_password(&pool, "Secret@Example.com", "correct horse battery").await?;
+
+    // Then it is them
+    assert_eq!(verified.id, user.id);
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn wrong_password_is_rejected(pool: PgPool) -> Result<(), UserFeatureError> {
+    // Given a user with a password and one without
+    let user = UserService::register(&pool, batch_input("guarded@example.com", "Guarded")).await?;
+    UserService::set_password(&pool, user.id, "correct horse battery").await?;
+    UserService::register(&pool, batch_input("open@example.com", "Open")).await?;
+
+    // Then a wrong password, a passwordless user and an unknown email are
+    // all the same failure
+    for (email, password) in [
+        ("guarded@example.com", "incorrect horse battery"),
+        ("open@example.com", "correct horse battery"),
+        ("nobody@example.com", "correct horse battery"),
+    ] {
+        let result = UserService::verify_password(&pool, email, password).await;
+        assert!(
+            matches!(result, Err(UserFeatureError::InvalidCredentials)),
+            "{email} should be rejected"
+        );
+    }
+    Ok(())
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn short_passwords_are_rejected(pool: PgPool) -> Result<(), UserFeatureError> {
+    let user = UserService::register(&pool, batch_input("short@example.com", "Short")).await?;
+
+    let result = UserService::set_password(&pool, user.id, "hunter2").await;
+
+    assert!(matches!(result, Err(UserFeatureError::Validation(_))));
+    Ok(())
+}
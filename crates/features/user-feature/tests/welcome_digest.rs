@@ -0,0 +1,151 @@
+//! BDD-style behavior tests for welcome email digests
+//!
+//! Kept apart from the per-user job tests because they install digest mode
+//! for the whole test binary.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use sqlx::PgPool;
+use user_feature::{
+    CreateUserInput, EmailError, EmailMessage, EmailSender, UserJobs, UserService,
+    WelcomeEmailConfig, WelcomeEmailConfigError, WelcomeEmailMode,
+};
+
+/// Sender that records every message
+#[derive(Default)]
+struct MockSender {
+    sent: Mutex<Vec<EmailMessage>>,
+}
+
+#[async_trait]
+impl EmailSender for MockSender {
+    async fn send(&self, message: EmailMessage) -> Result<(), EmailError> {
+        self.sent.lock().unwrap().push(message);
+        Ok(())
+    }
+}
+
+/// Parse a config from the given variables only
+fn parse(vars: &[(&str, &str)]) -> Result<WelcomeEmailConfig, WelcomeEmailConfigError> {
+    let vars: HashMap<String, String> = vars
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    WelcomeEmailConfig::from_vars(|name| vars.get(name).cloned())
+}
+
+fn install_digest_mode() {
+    WelcomeEmailConfig {
+        mode: WelcomeEmailMode::Digest,
+        ..WelcomeEmailConfig::default()
+    }
+    .install();
+}
+
+async fn register_user(pool: &PgPool, email: &str) {
+    UserService::register(
+        pool,
+        CreateUserInput {
+            email: email.to_string(),
+            name: "Digest Test".to_string(),
+            timezone: None,
+            idempotency_key: None,
+        },
+    )
+    .await
+    .expect("Failed to register user");
+}
+
+/// Queue a digest and run it with the given sender
+async fn run_digest(pool: &PgPool, sender: Arc<MockSender>) {
+    UserJobs::enqueue_welcome_digest(pool)
+        .await
+        .expect("Failed to queue digest");
+    UserJobs::registry(sender)
+        .runner(pool)
+        .set_channel_names(&["emails"])
+        .test_one()
+        .await
+        .expect("Failed to run job");
+}
+
+async fn queued_email_job_count(pool: &PgPool) -> i64 {
+    sqlx::query_scalar(
+        "SELECT COUNT(*) FROM mq_msgs WHERE channel_name = 'emails' AND id != uuid_nil()",
+    )
+    .fetch_one(pool)
+    .await
+    .expect("Failed to count email jobs")
+}
+
+// =============================================================================
+// Configuration
+// =============================================================================
+
+#[test]
+fn immediate_mode_is_the_default() {
+    let config = parse(&[]).unwrap();
+
+    assert_eq!(config.mode, WelcomeEmailMode::Immediate);
+    assert_eq!(config.digest_interval, Duration::from_secs(300));
+}
+
+#[test]
+fn digest_mode_and_interval_can_be_chosen() {
+    let config = parse(&[
+        ("WELCOME_EMAIL_MODE", "digest"),
+        ("WELCOME_DIGEST_EVERY_MS", "60000"),
+    ])
+    .unwrap();
+
+    assert_eq!(config.mode, WelcomeEmailMode::Digest);
+    assert_eq!(config.digest_interval, Duration::from_secs(60));
+}
+
+#[test]
+fn unknown_mode_is_rejected() {
+    assert_eq!(
+        parse(&[("WELCOME_EMAIL_MODE", "weekly")]),
+        Err(WelcomeEmailConfigError::InvalidMode("weekly".to_string()))
+    );
+}
+
+// =============================================================================
+// Digest Behaviors
+// =============================================================================
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn one_digest_welcomes_every_recent_registration(pool: PgPool) {
+    // Given three users registered in digest mode
+    install_digest_mode();
+    for email in ["one@example.com", "two@example.com", "three@example.com"] {
+        register_user(&pool, email).await;
+    }
+    assert_eq!(queued_email_job_count(&pool).await, 0);
+    let sender = Arc::new(MockSender::default());
+
+    // When one digest runs
+    run_digest(&pool, sender.clone()).await;
+
+    // Then all three were welcomed and the digest job is gone
+    let mut sent: Vec<String> = sender
+        .sent
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|m| m.to.clone())
+        .collect();
+    sent.sort();
+    assert_eq!(
+        sent,
+        ["one@example.com", "three@example.com", "two@example.com"]
+    );
+    assert_eq!(queued_email_job_count(&pool).await, 0);
+
+    // And the next digest doesn't welcome them again
+    run_digest(&pool, sender.clone()).await;
+    assert_eq!(sender.sent.lock().unwrap().len(), 3);
+}
@@ -0,0 +1,244 @@
+//! BDD-style behavior tests for the welcome email job
+//!
+//! These run the queued job once with an injected email sender and check
+//! what is left in the queue afterwards.
+
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use sqlx::PgPool;
+use user_feature::jobs::WELCOME_EMAIL_RETRIES;
+use user_feature::{CreateUserInput, EmailError, EmailMessage, EmailSender, UserJobs, UserService};
+use uuid::Uuid;
+
+/// Sender that records messages, optionally failing every send
+struct MockSender {
+    fail_with: Option<fn(String) -> EmailError>,
+    sent: Mutex<Vec<EmailMessage>>,
+}
+
+impl MockSender {
+    fn new(fail_with: Option<fn(String) -> EmailError>) -> Arc<Self> {
+        Arc::new(Self {
+            fail_with,
+            sent: Mutex::new(Vec::new()),
+        })
+    }
+}
+
+#[async_trait]
+impl EmailSender for MockSender {
+    async fn send(&self, message: EmailMessage) -> Result<(), EmailError> {
+        if let Some(fail) = self.fail_with {
+            return Err(fail("simulated failure".to_string()));
+        }
+        self.sent.lock().unwrap().push(message);
+        Ok(())
+    }
+}
+
+async fn register_user(pool: &PgPool, email: &str) {
+    register_named_user(pool, email, "Job Test").await;
+}
+
+async fn register_named_user(pool: &PgPool, email: &str, name: &str) {
+    UserService::register(
+        pool,
+        CreateUserInput {
+            email: email.to_string(),
+            name: name.to_string(),
+            timezone: None,
+            idempotency_key: None,
+        },
+    )
+    .await
+    .expect("Failed to register user");
+}
+
+/// Run the next ready welcome email job with the given sender
+async fn run_one_job(pool: &PgPool, sender: Arc<MockSender>) {
+    UserJobs::registry(sender)
+        .runner(pool)
+        .set_channel_names(&["emails"])
+        .test_one()
+        .await
+        .expect("Failed to run job");
+}
+
+/// The queued email job's remaining attempts and whether it is scheduled to run again
+async fn queued_email_job(pool: &PgPool) -> Option<(Uuid, i32, bool)> {
+    sqlx::query_as(
+        "SELECT id, attempts, attempt_at IS NOT NULL FROM mq_msgs
+         WHERE channel_name = 'emails' AND id != uuid_nil()",
+    )
+    .fetch_optional(pool)
+    .await
+    .expect("Failed to query email job")
+}
+
+async fn failed_job_count(pool: &PgPool) -> i64 {
+    sqlx::query_scalar("SELECT COUNT(*) FROM failed_jobs")
+        .fetch_one(pool)
+        .await
+        .expect("Failed to count failed jobs")
+}
+
+// =============================================================================
+// Delivery Behaviors
+// =============================================================================
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn delivered_welcome_email_completes_the_job(pool: PgPool) {
+    // Given a queued welcome email
+    register_user(&pool, "delivered@example.com").await;
+    let sender = MockSender::new(None);
+
+    // When the job runs and the send succeeds
+    run_one_job(&pool, sender.clone()).await;
+
+    // Then the email was sent and the job is gone
+    assert_eq!(sender.sent.lock().unwrap().len(), 1);
+    assert!(queued_email_job(&pool).await.is_none());
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn welcome_email_is_addressed_to_the_new_user(pool: PgPool) {
+    // Given a user who just registered
+    register_named_user(&pool, "ada@example.com", "Ada Lovelace").await;
+    let sender = MockSender::new(None);
+
+    // When the welcome email job runs
+    run_one_job(&pool, sender.clone()).await;
+
+    // Then the email goes to them and greets them by name
+    let sent = sender.sent.lock().unwrap();
+    assert_eq!(sent[0].to, "ada@example.com");
+    assert!(sent[0].body.contains("Ada Lovelace"));
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn welcome_email_is_sent_once_when_its_job_runs_twice(pool: PgPool) {
+    // Given a user whose welcome email was queued twice
+    let user = UserService::register(
+        &pool,
+        CreateUserInput {
+            email: "twice@example.com".to_string(),
+            name: "Twice".to_string(),
+            timezone: None,
+            idempotency_key: None,
+        },
+    )
+    .await
+    .expect("Failed to register user");
+    let mut tx = pool.begin().await.unwrap();
+    UserJobs::enqueue_welcome_email(&mut tx, user.id, user.email, user.name)
+        .await
+        .unwrap();
+    tx.commit().await.unwrap();
+    let sender = MockSender::new(None);
+
+    // When both jobs run
+    run_one_job(&pool, sender.clone()).await;
+    run_one_job(&pool, sender.clone()).await;
+
+    // Then the email was delivered only once and neither job is left
+    assert_eq!(sender.sent.lock().unwrap().len(), 1);
+    assert!(queued_email_job(&pool).await.is_none());
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn resent_welcome_email_is_delivered_again(pool: PgPool) {
+    // Given a user whose welcome email has been sent
+    let user = UserService::register(
+        &pool,
+        CreateUserInput {
+            email: "again@example.com".to_string(),
+            name: "Again".to_string(),
+            timezone: None,
+            idempotency_key: None,
+        },
+    )
+    .await
+    .expect("Failed to register user");
+    let sender = MockSender::new(None);
+    run_one_job(&pool, sender.clone()).await;
+
+    // When it is explicitly resent and the job runs
+    UserService::resend_welcome_email(&pool, user.id)
+        .await
+        .expect("Failed to resend");
+    run_one_job(&pool, sender.clone()).await;
+
+    // Then the user received it twice
+    assert_eq!(sender.sent.lock().unwrap().len(), 2);
+    assert!(queued_email_job(&pool).await.is_none());
+}
+
+// =============================================================================
+// Retry Behaviors
+// =============================================================================
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn transient_failure_leaves_job_for_retry(pool: PgPool) {
+    // Given a queued welcome email
+    register_user(&pool, "transient@example.com").await;
+
+    // When the send fails with a transient error
+    run_one_job(&pool, MockSender::new(Some(EmailError::Transient))).await;
+
+    // Then the job is not complete and is scheduled to run again
+    let (_, attempts, retryable) = queued_email_job(&pool).await.expect("job should remain");
+    assert_eq!(attempts, WELCOME_EMAIL_RETRIES as i32);
+    assert!(retryable);
+    assert_eq!(failed_job_count(&pool).await, 0);
+
+    // And the failed send isn't recorded, so the retry still delivers it
+    let sender = MockSender::new(None);
+    sqlx::query(
+        "UPDATE mq_msgs SET attempt_at = NOW() WHERE channel_name = 'emails' AND id != uuid_nil()",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    run_one_job(&pool, sender.clone()).await;
+    assert_eq!(sender.sent.lock().unwrap().len(), 1);
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn transient_failure_on_last_attempt_moves_job_aside(pool: PgPool) {
+    // Given a queued welcome email with one attempt left
+    register_user(&pool, "exhausted@example.com").await;
+    let (job_id, _, _) = queued_email_job(&pool).await.unwrap();
+    sqlx::query("UPDATE mq_msgs SET attempts = 1 WHERE id = $1")
+        .bind(job_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    // When that attempt fails too
+    run_one_job(&pool, MockSender::new(Some(EmailError::Transient))).await;
+
+    // Then the job leaves the queue for failed_jobs
+    assert!(queued_email_job(&pool).await.is_none());
+    assert_eq!(failed_job_count(&pool).await, 1);
+}
+
+#[sqlx::test(migrations = "../../../migrations")]
+async fn permanent_failure_moves_job_aside_immediately(pool: PgPool) {
+    // Given a queued welcome email
+    register_user(&pool, "rejected@example.com").await;
+    let (job_id, _, _) = queued_email_job(&pool).await.unwrap();
+
+    // When the send is rejected outright
+    run_one_job(&pool, MockSender::new(Some(EmailError::Permanent))).await;
+
+    // Then the job is not retried but kept in failed_jobs with its payload
+    assert!(queued_email_job(&pool).await.is_none());
+    let (id, payload): (Uuid, String) =
+        sqlx::query_as("SELECT id, payload_json::TEXT FROM failed_jobs")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(id, job_id);
+    assert!(payload.contains("rejected@example.com"));
+}
@@ -0,0 +1,7 @@
+-- Soft-delete support for users
+ALTER TABLE users ADD COLUMN deleted_at TIMESTAMPTZ;
+
+-- Emails only need to be unique among active users, so the address of a
+-- deleted user can be registered again
+ALTER TABLE users DROP CONSTRAINT users_email_key;
+CREATE UNIQUE INDEX users_email_active_idx ON users (email) WHERE deleted_at IS NULL;
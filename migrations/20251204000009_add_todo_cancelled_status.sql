@@ -0,0 +1,4 @@
+-- Allow todos to be cancelled (abandoned rather than completed)
+ALTER TABLE todos DROP CONSTRAINT todos_status_check;
+ALTER TABLE todos ADD CONSTRAINT todos_status_check
+    CHECK (status IN ('pending', 'in_progress', 'completed', 'cancelled'));
@@ -0,0 +1,14 @@
+-- Publish todo status changes on the `todo_changes` channel so API servers
+-- can push them to subscribers. The payload is the todo ID.
+CREATE FUNCTION notify_todo_status_change() RETURNS trigger AS $$
+BEGIN
+    PERFORM pg_notify('todo_changes', NEW.id::text);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER todos_status_change_notify
+    AFTER UPDATE OF status ON todos
+    FOR EACH ROW
+    WHEN (OLD.status IS DISTINCT FROM NEW.status)
+    EXECUTE FUNCTION notify_todo_status_change();
@@ -0,0 +1,5 @@
+-- Optional deadline for todos
+ALTER TABLE todos ADD COLUMN due_date TIMESTAMPTZ;
+
+-- Index for overdue lookups
+CREATE INDEX todos_user_due_date_idx ON todos (user_id, due_date) WHERE due_date IS NOT NULL;
@@ -0,0 +1,2 @@
+-- Pending reminder job for a todo, so it can be cancelled once no longer needed
+ALTER TABLE todos ADD COLUMN reminder_job_id UUID;
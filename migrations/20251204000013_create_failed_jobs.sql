@@ -0,0 +1,8 @@
+-- Jobs that failed permanently, set aside for inspection or manual replay
+CREATE TABLE failed_jobs (
+    id UUID PRIMARY KEY,
+    name TEXT NOT NULL,
+    payload_json JSONB,
+    error TEXT NOT NULL,
+    failed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,15 @@
+-- Tags for categorizing todos, stored lowercase so each name appears once
+CREATE TABLE tags (
+    id UUID PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE CHECK (name = LOWER(name) AND name <> '')
+);
+
+-- Which tags each todo carries
+CREATE TABLE todo_tags (
+    todo_id UUID NOT NULL REFERENCES todos(id) ON DELETE CASCADE,
+    tag_id UUID NOT NULL REFERENCES tags(id) ON DELETE CASCADE,
+    PRIMARY KEY (todo_id, tag_id)
+);
+
+-- Index for finding todos by tag
+CREATE INDEX todo_tags_tag_id_idx ON todo_tags (tag_id);
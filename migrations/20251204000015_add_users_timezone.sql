@@ -0,0 +1,2 @@
+-- IANA timezone name used to show a user's due dates in their local time
+ALTER TABLE users ADD COLUMN timezone TEXT NOT NULL DEFAULT 'UTC';
@@ -0,0 +1,3 @@
+-- What a user is allowed to do; admins may use admin-only fields
+ALTER TABLE users ADD COLUMN role TEXT NOT NULL DEFAULT 'user'
+    CHECK (role IN ('user', 'admin'));
@@ -0,0 +1,6 @@
+-- Client-supplied keys that make user registration safe to retry
+CREATE TABLE idempotency_keys (
+    key TEXT PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,11 @@
+-- Domain events recorded alongside the changes that caused them, waiting to
+-- be dispatched
+CREATE TABLE outbox (
+    id UUID PRIMARY KEY,
+    event_type TEXT NOT NULL,
+    payload_json JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    processed_at TIMESTAMPTZ
+);
+
+CREATE INDEX outbox_unprocessed_idx ON outbox (created_at) WHERE processed_at IS NULL;
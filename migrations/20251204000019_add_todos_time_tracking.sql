@@ -0,0 +1,4 @@
+-- Time tracking: the start of the running interval, if any, and the seconds
+-- accumulated by finished intervals
+ALTER TABLE todos ADD COLUMN started_at TIMESTAMPTZ;
+ALTER TABLE todos ADD COLUMN total_seconds BIGINT NOT NULL DEFAULT 0;
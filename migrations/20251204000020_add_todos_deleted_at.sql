@@ -0,0 +1,3 @@
+-- Soft delete: deleted todos keep their row, with deleted_at set, so they can
+-- be restored
+ALTER TABLE todos ADD COLUMN deleted_at TIMESTAMPTZ;
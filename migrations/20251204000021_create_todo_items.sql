@@ -0,0 +1,11 @@
+-- Checklist items within a todo, shown in `position` order
+CREATE TABLE todo_items (
+    id UUID PRIMARY KEY,
+    todo_id UUID NOT NULL REFERENCES todos(id) ON DELETE CASCADE,
+    text TEXT NOT NULL,
+    done BOOLEAN NOT NULL DEFAULT FALSE,
+    position INTEGER NOT NULL
+);
+
+-- Index for listing a todo's items in order
+CREATE INDEX todo_items_todo_id_position_idx ON todo_items (todo_id, position);
@@ -0,0 +1,5 @@
+-- Manual ordering set by reorderTodos; todos never reordered have no position
+ALTER TABLE todos ADD COLUMN position INTEGER;
+
+-- Index for listing a user's todos by position
+CREATE INDEX todos_user_position_idx ON todos (user_id, position);
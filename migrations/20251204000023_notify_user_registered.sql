@@ -0,0 +1,13 @@
+-- Publish newly registered users on the `user_events` channel so API servers
+-- can push them to subscribers. The payload is the user ID.
+CREATE FUNCTION notify_user_registered() RETURNS trigger AS $$
+BEGIN
+    PERFORM pg_notify('user_events', NEW.id::text);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER users_registered_notify
+    AFTER INSERT ON users
+    FOR EACH ROW
+    EXECUTE FUNCTION notify_user_registered();
@@ -0,0 +1,8 @@
+-- Emails already delivered to a user, one row per template, so a job that
+-- runs again after a restart doesn't send them twice
+CREATE TABLE email_sends (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    template TEXT NOT NULL,
+    sent_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, template)
+);
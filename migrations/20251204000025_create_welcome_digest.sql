@@ -0,0 +1,9 @@
+-- Where the welcome email digest picks up: its next run considers users
+-- registered from `since` onwards. The single row is locked while a digest
+-- runs, so runs never overlap.
+CREATE TABLE welcome_digest (
+    id BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (id),
+    since TIMESTAMPTZ NOT NULL
+);
+
+INSERT INTO welcome_digest (since) VALUES (NOW());
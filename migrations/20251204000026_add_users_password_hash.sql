@@ -0,0 +1,2 @@
+-- Optional password login; users registered without one keep a NULL hash
+ALTER TABLE users ADD COLUMN password_hash TEXT;